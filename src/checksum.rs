@@ -0,0 +1,90 @@
+/// An incremental computation of the Internet checksum (RFC 1071), the ones' complement of the ones' complement sum of
+/// 16-bit words, which is used by IPv4, ICMP, UDP, TCP and others.
+///
+/// Data can be added in several chunks, e.g. a pseudo-header followed by the segment. Chunks of odd length are handled as
+/// if all chunks were concatenated.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Checksum {
+    sum: u64,
+    /// The first byte of a word which was split between two chunks.
+    pending: Option<u8>,
+}
+
+impl Checksum {
+    /// Constructs an empty checksum computation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the bytes to the sum.
+    pub fn add_bytes(&mut self, mut bytes: &[u8]) -> &mut Self {
+        if let Some(high) = self.pending.take() {
+            match bytes.split_first() {
+                Some((low, rest)) => {
+                    self.sum += u16::from_be_bytes([high, *low]) as u64;
+                    bytes = rest;
+                }
+                None => self.pending = Some(high),
+            }
+        }
+
+        let mut words = bytes.chunks_exact(2);
+        for word in &mut words {
+            self.sum += u16::from_be_bytes([word[0], word[1]]) as u64;
+        }
+
+        if let [last] = words.remainder() {
+            self.pending = Some(*last);
+        }
+
+        self
+    }
+
+    /// Adds a 16-bit word to the sum.
+    pub fn add_u16(&mut self, value: u16) -> &mut Self {
+        self.add_bytes(&value.to_be_bytes())
+    }
+
+    /// Adds a 32-bit value to the sum as two 16-bit words.
+    pub fn add_u32(&mut self, value: u32) -> &mut Self {
+        self.add_bytes(&value.to_be_bytes())
+    }
+
+    /// Returns the checksum of the data added so far. An odd trailing byte is padded with zero.
+    pub fn finish(&self) -> u16 {
+        let mut sum = self.sum + self.pending.map_or(0, |high| (high as u64) << 8);
+
+        while sum > 0xffff {
+            sum = (sum & 0xffff) + (sum >> 16);
+        }
+
+        !(sum as u16)
+    }
+}
+
+/// Computes the Internet checksum of the bytes.
+pub fn internet_checksum(bytes: &[u8]) -> u16 {
+    Checksum::new().add_bytes(bytes).finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum() {
+        // The example from RFC 1071, section 3
+        let bytes = [0x00, 0x01, 0xf2, 0x03, 0xf4, 0xf5, 0xf6, 0xf7];
+        assert_eq!(internet_checksum(&bytes), !0xddf2);
+        assert_eq!(internet_checksum(&[]), 0xffff);
+        assert_eq!(internet_checksum(&[0x01]), !0x0100);
+
+        let split = Checksum::new().add_bytes(&bytes[..3]).add_bytes(&[]).add_bytes(&bytes[3..]).finish();
+        assert_eq!(split, internet_checksum(&bytes));
+
+        // The checksum of data which includes its own checksum is zero
+        let mut with_checksum = bytes.to_vec();
+        with_checksum.extend_from_slice(&internet_checksum(&bytes).to_be_bytes());
+        assert_eq!(internet_checksum(&with_checksum), 0);
+    }
+}
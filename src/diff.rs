@@ -0,0 +1,288 @@
+use crate::{DynLayer, Field, FieldValue, LayerType, Packet};
+
+/// The kind of difference between two packets.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DiffKind {
+    Changed,
+    /// The field is only present in the new packet.
+    Added,
+    /// The field is only present in the old packet.
+    Removed,
+}
+
+/// A difference in a single field between two packets.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FieldDiff {
+    pub kind: DiffKind,
+    /// The name of the layer which contains the field.
+    pub layer: &'static str,
+    /// The path of the field, e.g. `eth.vlan.vid`.
+    pub field: String,
+    pub old: Option<FieldValue>,
+    pub new: Option<FieldValue>,
+    /// The byte offset of the field in the serialized old packet.
+    pub old_offset: Option<usize>,
+    /// The byte offset of the field in the serialized new packet.
+    pub new_offset: Option<usize>,
+}
+
+/// Options which control how two packets are compared.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct DiffOptions {
+    /// Skips fields which are derived from the other layers, such as lengths and checksums.
+    pub ignore_computed: bool,
+}
+
+/// A field together with its byte offset in the serialized packet.
+struct LocatedField {
+    field: Field,
+    offset: usize,
+}
+
+impl Packet {
+    /// Compares the fields of two packets, see [`Packet::diff_with`].
+    pub fn diff(&self, other: &Packet) -> Vec<FieldDiff> {
+        self.diff_with(other, &DiffOptions::default())
+    }
+
+    /// Compares the fields of `self` (old) with those of `other` (new).
+    ///
+    /// Both packets are finalized before the comparison. Their layers are aligned by type, so that a layer inserted into
+    /// or removed from one of the stacks is reported as a set of added or removed fields rather than as changes to every
+    /// following layer.
+    pub fn diff_with(&self, other: &Packet, options: &DiffOptions) -> Vec<FieldDiff> {
+        let mut old = self.clone();
+        old.finalize();
+        let mut new = other.clone();
+        new.finalize();
+
+        let old_fields = located_fields(&old, options);
+        let new_fields = located_fields(&new, options);
+        let old_types: Vec<LayerType> = old.layers().iter().map(|layer| layer.layer_type()).collect();
+        let new_types: Vec<LayerType> = new.layers().iter().map(|layer| layer.layer_type()).collect();
+
+        let mut diffs = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        for (matched_i, matched_j) in align(&old_types, &new_types).into_iter().chain([(old_types.len(), new_types.len())]) {
+            for (layer, fields) in old.layers().iter().zip(&old_fields).take(matched_i).skip(i) {
+                diffs.extend(fields.iter().map(|field| removed(layer.as_ref(), field)));
+            }
+
+            for (layer, fields) in new.layers().iter().zip(&new_fields).take(matched_j).skip(j) {
+                diffs.extend(fields.iter().map(|field| added(layer.as_ref(), field)));
+            }
+
+            if matched_i < old_types.len() {
+                diffs.extend(diff_layer(
+                    old.layers()[matched_i].as_ref(),
+                    &old_fields[matched_i],
+                    &new_fields[matched_j],
+                ));
+            }
+
+            (i, j) = (matched_i + 1, matched_j + 1);
+        }
+
+        diffs
+    }
+}
+
+/// Returns the fields of every layer of the packet together with their byte offsets.
+fn located_fields(packet: &Packet, options: &DiffOptions) -> Vec<Vec<LocatedField>> {
+    let mut offset = 0;
+
+    packet
+        .layers()
+        .iter()
+        .map(|layer| {
+            let fields = layer
+                .as_reflect()
+                .map(|layer| layer.fields())
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|field| !(options.ignore_computed && field.computed))
+                .map(|field| LocatedField {
+                    offset: offset + field.bit_offset / 8,
+                    field,
+                })
+                .collect();
+
+            offset += layer.header_len();
+            fields
+        })
+        .collect()
+}
+
+/// Aligns two sequences of layer types using their longest common subsequence and returns the indices of the matched pairs.
+fn align(old: &[LayerType], new: &[LayerType]) -> Vec<(usize, usize)> {
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = match old[i] == new[j] {
+                true => lengths[i + 1][j + 1] + 1,
+                false => lengths[i + 1][j].max(lengths[i][j + 1]),
+            };
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    pairs
+}
+
+/// Compares the fields of two layers of the same type.
+fn diff_layer(layer: &dyn DynLayer, old: &[LocatedField], new: &[LocatedField]) -> Vec<FieldDiff> {
+    let mut diffs = Vec::new();
+
+    for old_field in old {
+        match new.iter().find(|new_field| new_field.field.name == old_field.field.name) {
+            Some(new_field) if new_field.field.value != old_field.field.value => diffs.push(FieldDiff {
+                kind: DiffKind::Changed,
+                layer: layer.name(),
+                field: old_field.field.name.clone(),
+                old: Some(old_field.field.value.clone()),
+                new: Some(new_field.field.value.clone()),
+                old_offset: Some(old_field.offset),
+                new_offset: Some(new_field.offset),
+            }),
+            Some(_) => {}
+            None => diffs.push(removed(layer, old_field)),
+        }
+    }
+
+    for new_field in new {
+        if !old.iter().any(|old_field| old_field.field.name == new_field.field.name) {
+            diffs.push(added(layer, new_field));
+        }
+    }
+
+    diffs
+}
+
+fn removed(layer: &dyn DynLayer, field: &LocatedField) -> FieldDiff {
+    FieldDiff {
+        kind: DiffKind::Removed,
+        layer: layer.name(),
+        field: field.field.name.clone(),
+        old: Some(field.field.value.clone()),
+        new: None,
+        old_offset: Some(field.offset),
+        new_offset: None,
+    }
+}
+
+fn added(layer: &dyn DynLayer, field: &LocatedField) -> FieldDiff {
+    FieldDiff {
+        kind: DiffKind::Added,
+        layer: layer.name(),
+        field: field.field.name.clone(),
+        old: None,
+        new: Some(field.field.value.clone()),
+        old_offset: None,
+        new_offset: Some(field.offset),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    fn eth() -> EthLayer {
+        EthLayer::new(MacAddr::new(0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff), MacAddr::broadcast(), EtherType::IPv4, vec![])
+    }
+
+    #[test]
+    fn identical() {
+        let packet = eth() / b"abc";
+        assert_eq!(packet.diff(&packet), []);
+    }
+
+    #[test]
+    fn changed_fields() {
+        let old = eth() / b"abc";
+        let mut new = old.clone();
+        new.set_field("eth.src", "11:22:33:44:55:66").unwrap();
+        new.set_field("raw.load", "616264").unwrap();
+
+        assert_eq!(
+            old.diff(&new),
+            [
+                FieldDiff {
+                    kind: DiffKind::Changed,
+                    layer: "Ethernet",
+                    field: "eth.src".to_string(),
+                    old: Some(FieldValue::Mac(MacAddr::new(0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff))),
+                    new: Some(FieldValue::Mac(MacAddr::new(0x11, 0x22, 0x33, 0x44, 0x55, 0x66))),
+                    old_offset: Some(6),
+                    new_offset: Some(6),
+                },
+                FieldDiff {
+                    kind: DiffKind::Changed,
+                    layer: "Raw",
+                    field: "raw.load".to_string(),
+                    old: Some(FieldValue::Bytes(b"abc".to_vec())),
+                    new: Some(FieldValue::Bytes(b"abd".to_vec())),
+                    old_offset: Some(14),
+                    new_offset: Some(14),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn added_and_removed_fields() {
+        let old = eth() / b"abc";
+        let mut new = eth() / PaddingLayer::zeroed(2) / b"abc";
+        new.set_field("eth.vlan.vid", "10").unwrap();
+
+        let diffs = old.diff(&new);
+        let summary: Vec<(DiffKind, &str, Option<usize>)> =
+            diffs.iter().map(|diff| (diff.kind, diff.field.as_str(), diff.new_offset)).collect();
+
+        assert_eq!(
+            summary,
+            [
+                (DiffKind::Added, "eth.vlan.tpid", Some(12)),
+                (DiffKind::Added, "eth.vlan.pcp", Some(14)),
+                (DiffKind::Added, "eth.vlan.dei", Some(14)),
+                (DiffKind::Added, "eth.vlan.vid", Some(14)),
+                (DiffKind::Added, "padding.load", Some(18)),
+            ]
+        );
+
+        let reverse = new.diff(&old);
+        assert_eq!(reverse.iter().filter(|diff| diff.kind == DiffKind::Removed).count(), 5);
+    }
+
+    #[test]
+    fn ignore_computed() {
+        let old = eth() / b"abc";
+        let mut new = old.clone();
+        new.get_mut::<EthLayer>().unwrap().ether_type = None;
+
+        // A raw payload does not determine an EtherType, so the field stays unset after finalization
+        let diffs = old.diff(&new);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].field, "eth.type");
+        assert_eq!(diffs[0].new, Some(FieldValue::Unset));
+
+        let options = DiffOptions { ignore_computed: true };
+        assert_eq!(old.diff_with(&new, &options), []);
+    }
+}
@@ -0,0 +1,612 @@
+use std::{
+    collections::HashMap,
+    sync::{OnceLock, PoisonError, RwLock},
+};
+
+use crate::{layer::*, Packet};
+
+/// The link-layer type of a captured frame, which determines how its first layer is decoded.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum LinkType {
+    Ethernet,
+    Ipv4,
+    Ipv6,
+}
+
+/// A value in a layer header which identifies the type of the next layer.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Discriminator {
+    LinkType(LinkType),
+    EtherType(u16),
+    /// The payload of an IEEE 802.3 frame, whose Length/Type field holds a length instead of an EtherType.
+    Ieee8023,
+    /// The destination service access point of an IEEE 802.2 LLC header.
+    LlcSap(u8),
+    /// The OUI and protocol ID of a SNAP header with a non-zero OUI.
+    SnapProtocol(u32, u16),
+    IpProtocol(u8),
+    UdpPort(u16),
+    TcpPort(u16),
+}
+
+/// A function which parses a layer from the start of a buffer and returns it together with the number of bytes consumed.
+pub type LayerParser = fn(&[u8]) -> Result<(Box<dyn DynLayer>, usize), LayerError>;
+
+/// Parses a layer of type `L` into a box. Its instantiations can be used as a [`LayerParser`].
+pub fn parse_boxed<L: DynLayer + 'static>(bytes: &[u8]) -> Result<(Box<dyn DynLayer>, usize), LayerError> {
+    let (layer, len) = L::parse(bytes)?;
+    Ok((Box::new(layer), len))
+}
+
+/// A registry which maps discriminators to the parsers of the layers they identify.
+#[derive(Debug, Clone)]
+pub struct DissectorRegistry {
+    parsers: HashMap<Discriminator, LayerParser>,
+}
+
+impl DissectorRegistry {
+    /// Constructs a registry populated with the parsers of the built-in layers.
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        registry.register::<EthLayer>(Discriminator::LinkType(LinkType::Ethernet));
+        registry.register::<Ipv4Layer>(Discriminator::LinkType(LinkType::Ipv4));
+        registry.register::<Ipv4Layer>(Discriminator::EtherType(EtherType::IPv4.value()));
+        registry.register::<Ipv4Layer>(Discriminator::IpProtocol(IpProtocol::IPv4.value()));
+        registry.register::<Ipv6Layer>(Discriminator::LinkType(LinkType::Ipv6));
+        registry.register::<Ipv6Layer>(Discriminator::EtherType(EtherType::IPv6.value()));
+        registry.register::<Ipv6Layer>(Discriminator::IpProtocol(IpProtocol::IPv6.value()));
+        registry.register::<IcmpLayer>(Discriminator::IpProtocol(IpProtocol::Icmp.value()));
+        registry.register::<Icmpv6Layer>(Discriminator::IpProtocol(IpProtocol::Icmpv6.value()));
+        registry.register::<IgmpLayer>(Discriminator::IpProtocol(IpProtocol::Igmp.value()));
+        registry.register::<GreLayer>(Discriminator::IpProtocol(IpProtocol::Gre.value()));
+        registry.register::<EthLayer>(Discriminator::EtherType(EtherType::TransparentEthernetBridging.value()));
+        registry.register::<UdpLayer>(Discriminator::IpProtocol(IpProtocol::Udp.value()));
+        registry.register::<TcpLayer>(Discriminator::IpProtocol(IpProtocol::Tcp.value()));
+        registry.register::<DnsLayer>(Discriminator::UdpPort(DnsLayer::PORT));
+        registry.register::<DnsLayer>(Discriminator::UdpPort(DnsLayer::MDNS_PORT));
+        registry.register_parser(Discriminator::TcpPort(DnsLayer::PORT), |bytes| {
+            let (layer, len) = DnsLayer::parse_stream(bytes)?;
+            Ok((Box::new(layer), len))
+        });
+        registry.register::<DhcpLayer>(Discriminator::UdpPort(DhcpLayer::SERVER_PORT));
+        registry.register::<DhcpLayer>(Discriminator::UdpPort(DhcpLayer::CLIENT_PORT));
+        registry.register::<Dhcpv6Layer>(Discriminator::UdpPort(Dhcpv6Layer::CLIENT_PORT));
+        registry.register::<Dhcpv6Layer>(Discriminator::UdpPort(Dhcpv6Layer::SERVER_PORT));
+        registry.register::<VxlanLayer>(Discriminator::UdpPort(VxlanLayer::PORT));
+        registry.register::<VxlanLayer>(Discriminator::UdpPort(VxlanLayer::GPE_PORT));
+        registry.register::<GeneveLayer>(Discriminator::UdpPort(GeneveLayer::PORT));
+        registry.register::<ArpLayer>(Discriminator::EtherType(EtherType::Arp.value()));
+        registry.register::<LlcLayer>(Discriminator::Ieee8023);
+        registry.register::<SnapLayer>(Discriminator::LlcSap(Sap::Snap.value()));
+        registry
+    }
+
+    /// Constructs a registry without any parsers.
+    pub fn empty() -> Self {
+        Self {
+            parsers: HashMap::new(),
+        }
+    }
+
+    /// Returns the process-wide registry used by [`Packet::parse`].
+    pub fn global() -> &'static RwLock<DissectorRegistry> {
+        static GLOBAL: OnceLock<RwLock<DissectorRegistry>> = OnceLock::new();
+        GLOBAL.get_or_init(|| RwLock::new(DissectorRegistry::new()))
+    }
+
+    /// Binds the layer type `L` to the discriminator in the global registry, replacing any previous binding.
+    pub fn register_global<L: DynLayer + 'static>(discriminator: Discriminator) {
+        Self::register_global_parser(discriminator, parse_boxed::<L>);
+    }
+
+    /// Binds a parser to the discriminator in the global registry, replacing any previous binding.
+    pub fn register_global_parser(discriminator: Discriminator, parser: LayerParser) {
+        // The registry is left consistent by a panicking writer, so a poisoned lock is recovered
+        Self::global().write().unwrap_or_else(PoisonError::into_inner).register_parser(discriminator, parser);
+    }
+
+    /// Binds the layer type `L` to the discriminator, replacing any previous binding.
+    pub fn register<L: DynLayer + 'static>(&mut self, discriminator: Discriminator) {
+        self.register_parser(discriminator, parse_boxed::<L>);
+    }
+
+    /// Binds a parser to the discriminator, replacing any previous binding.
+    pub fn register_parser(&mut self, discriminator: Discriminator, parser: LayerParser) {
+        self.parsers.insert(discriminator, parser);
+    }
+
+    /// Removes the binding of the discriminator and returns its parser.
+    pub fn unregister(&mut self, discriminator: Discriminator) -> Option<LayerParser> {
+        self.parsers.remove(&discriminator)
+    }
+
+    /// Returns the parser bound to the discriminator.
+    pub fn parser(&self, discriminator: Discriminator) -> Option<LayerParser> {
+        self.parsers.get(&discriminator).copied()
+    }
+
+    /// Decodes as many layers from the buffer as the registered parsers allow.
+    ///
+    /// Decoding stops once the buffer is exhausted, no parser is bound to the discriminators of the last decoded layer
+    /// or an upper layer fails to parse. The undecoded bytes are kept in a [`RawLayer`] and the bytes past the payload
+    /// of a layer, as specified by its header, are kept in a [`PaddingLayer`]. Only a failure to parse the first layer is
+    /// reported as an error.
+    pub fn dissect(&self, bytes: &[u8], link_type: LinkType) -> Result<Packet, LayerError> {
+        let mut packet = Packet::new();
+        let mut discriminators = vec![Discriminator::LinkType(link_type)];
+        let mut rest = bytes;
+        let mut trailers = Vec::new();
+
+        while !rest.is_empty() {
+            let Some(parser) = discriminators.iter().find_map(|d| self.parser(*d)) else {
+                break;
+            };
+
+            let (layer, header_len) = match parser(rest) {
+                Ok(parsed) => parsed,
+                Err(err) if packet.is_empty() => return Err(err),
+                Err(_) => break,
+            };
+
+            let end = match layer.payload_len() {
+                Some(payload_len) => rest.len().min(header_len + payload_len),
+                None => rest.len(),
+            };
+
+            // A layer which consumes no bytes could lead back to its own parser and be decoded forever
+            let stalled = header_len == 0 && end == rest.len();
+
+            trailers.push(&rest[end..]);
+            discriminators = layer.next_discriminators();
+            rest = &rest[header_len.min(end)..end];
+            packet.push_boxed(layer);
+
+            if stalled {
+                break;
+            }
+        }
+
+        if !rest.is_empty() {
+            packet.push(RawLayer::new(rest));
+        }
+
+        for trailer in trailers.into_iter().rev().filter(|trailer| !trailer.is_empty()) {
+            packet.push(PaddingLayer::new(trailer));
+        }
+
+        Ok(packet)
+    }
+}
+
+impl Default for DissectorRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Ipv4Addr, Ipv6Addr, MacAddr};
+
+    /// A proprietary layer consisting of a single tag byte.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct TagLayer {
+        tag: u8,
+    }
+
+    impl Layer for TagLayer {
+        const NAME: &'static str = "Tag";
+        const TYPE: LayerType = LayerType::Custom;
+        const OSI_LEVEL: OsiLevel = OsiLevel::Network;
+    }
+
+    impl DynLayer for TagLayer {
+        fn header_len(&self) -> usize {
+            1
+        }
+
+        fn write(&self, buf: &mut Vec<u8>) {
+            buf.push(self.tag);
+        }
+
+        fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+            match bytes.first() {
+                Some(tag) => Ok((Self { tag: *tag }, 1)),
+                None => Err(LayerError::BufferTooShort(Self::NAME)),
+            }
+        }
+    }
+
+    fn eth() -> EthLayer {
+        EthLayer::new(MacAddr::new(0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff), MacAddr::broadcast(), EtherType::IPv4, vec![])
+    }
+
+    #[test]
+    fn dissect_builtin() {
+        let bytes = (eth() / TagLayer { tag: 7 }).to_bytes();
+        let packet = Packet::parse(&bytes, LinkType::Ethernet).unwrap();
+
+        assert_eq!(packet.len(), 2);
+        assert_eq!(packet.get::<EthLayer>(), Some(&eth()));
+        assert_eq!(packet.get::<RawLayer>(), Some(&RawLayer::new([7])));
+        assert_eq!(packet.to_bytes(), bytes);
+
+        let packet = DissectorRegistry::empty().dissect(&bytes, LinkType::Ethernet).unwrap();
+        assert_eq!(packet.len(), 1);
+        assert_eq!(packet.get::<RawLayer>().unwrap().data, bytes);
+
+        assert_eq!(
+            Packet::parse(&bytes[..6], LinkType::Ethernet).unwrap_err(),
+            LayerError::BufferTooShort("Ethernet")
+        );
+    }
+
+    #[test]
+    fn dissect_custom() {
+        let bytes = (eth() / TagLayer { tag: 7 } / TagLayer { tag: 9 }).to_bytes();
+
+        let mut registry = DissectorRegistry::new();
+        registry.register::<TagLayer>(Discriminator::EtherType(EtherType::IPv4.value()));
+        let packet = Packet::parse_with(&bytes, LinkType::Ethernet, &registry).unwrap();
+
+        assert_eq!(packet.len(), 3);
+        assert_eq!(packet.get::<TagLayer>(), Some(&TagLayer { tag: 7 }));
+        assert_eq!(packet.get::<RawLayer>(), Some(&RawLayer::new([9])));
+        assert_eq!(packet.to_bytes(), bytes);
+
+        assert!(registry.unregister(Discriminator::EtherType(EtherType::IPv4.value())).is_some());
+        assert!(registry.parser(Discriminator::EtherType(EtherType::IPv4.value())).is_none());
+        assert_eq!(registry.dissect(&bytes, LinkType::Ethernet).unwrap().len(), 2);
+    }
+
+    /// A layer whose header specifies the length of its payload in a single byte.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct LengthLayer {
+        length: u8,
+    }
+
+    impl Layer for LengthLayer {
+        const NAME: &'static str = "Length";
+        const TYPE: LayerType = LayerType::Custom;
+        const OSI_LEVEL: OsiLevel = OsiLevel::Network;
+    }
+
+    impl DynLayer for LengthLayer {
+        fn header_len(&self) -> usize {
+            1
+        }
+
+        fn payload_len(&self) -> Option<usize> {
+            Some(self.length as usize)
+        }
+
+        fn write(&self, buf: &mut Vec<u8>) {
+            buf.push(self.length);
+        }
+
+        fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+            match bytes.first() {
+                Some(length) => Ok((Self { length: *length }, 1)),
+                None => Err(LayerError::BufferTooShort(Self::NAME)),
+            }
+        }
+    }
+
+    /// A layer without a header which is followed by another instance of itself.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct EmptyLayer;
+
+    impl Layer for EmptyLayer {
+        const NAME: &'static str = "Empty";
+        const TYPE: LayerType = LayerType::Custom;
+        const OSI_LEVEL: OsiLevel = OsiLevel::Network;
+    }
+
+    impl DynLayer for EmptyLayer {
+        fn header_len(&self) -> usize {
+            0
+        }
+
+        fn write(&self, _buf: &mut Vec<u8>) {}
+
+        fn next_discriminators(&self) -> Vec<Discriminator> {
+            vec![Discriminator::EtherType(EtherType::IPv4.value())]
+        }
+
+        fn parse(_bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+            Ok((Self, 0))
+        }
+    }
+
+    #[test]
+    fn dissect_stalled() {
+        let bytes = (eth() / RawLayer::new([1, 2, 3])).to_bytes();
+
+        let mut registry = DissectorRegistry::new();
+        registry.register::<EmptyLayer>(Discriminator::EtherType(EtherType::IPv4.value()));
+        let packet = registry.dissect(&bytes, LinkType::Ethernet).unwrap();
+
+        assert_eq!(packet.to_string(), "Ethernet / Empty / Raw");
+        assert_eq!(packet.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn global_registry() {
+        // A writer which panics poisons the lock, which must not break later parsing or registration
+        let poisoned = std::thread::spawn(|| {
+            let _registry = DissectorRegistry::global().write().unwrap();
+            panic!("registration failed");
+        });
+        assert!(poisoned.join().is_err());
+
+        DissectorRegistry::register_global_parser(Discriminator::TcpPort(0xffff), parse_boxed::<RawLayer>);
+        DissectorRegistry::register_global::<RawLayer>(Discriminator::TcpPort(0xffff));
+        let bytes = Packet::from(eth()).to_bytes();
+        assert_eq!(Packet::parse(&bytes, LinkType::Ethernet).unwrap().to_string(), "Ethernet");
+    }
+
+    #[test]
+    fn dissect_padding() {
+        let bytes = (eth() / LengthLayer { length: 2 } / RawLayer::new([1, 2]) / PaddingLayer::zeroed(3)).to_bytes();
+
+        let mut registry = DissectorRegistry::new();
+        registry.register::<LengthLayer>(Discriminator::EtherType(EtherType::IPv4.value()));
+        let packet = registry.dissect(&bytes, LinkType::Ethernet).unwrap();
+
+        assert_eq!(packet.to_string(), "Ethernet / Length / Raw / Padding");
+        assert_eq!(packet.get::<RawLayer>(), Some(&RawLayer::new([1, 2])));
+        assert_eq!(packet.get::<PaddingLayer>(), Some(&PaddingLayer::zeroed(3)));
+        assert_eq!(packet.to_bytes(), bytes);
+
+        // Upper layers which fail to parse are kept as raw bytes
+        registry.register_parser(Discriminator::EtherType(EtherType::IPv4.value()), |_| {
+            Err(LayerError::BufferTooShort("Broken"))
+        });
+        let packet = registry.dissect(&bytes, LinkType::Ethernet).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / Raw");
+        assert_eq!(packet.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn dissect_ipv4() {
+        let ip = Ipv4Layer::new(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2));
+        let bytes = (eth() / ip / b"abc" / PaddingLayer::zeroed(23)).to_bytes();
+
+        let packet = Packet::parse(&bytes, LinkType::Ethernet).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / IPv4 / Raw / Padding");
+        assert_eq!(packet.get::<Ipv4Layer>().unwrap().total_length, Some(23));
+        assert_eq!(packet.to_bytes(), bytes);
+
+        let packet = Packet::parse(&bytes[14..37], LinkType::Ipv4).unwrap();
+        assert_eq!(packet.to_string(), "IPv4 / Raw");
+    }
+
+    #[test]
+    fn dissect_ipv6() {
+        let mut ip = Ipv6Layer::new(Ipv6Addr::loopback(), Ipv6Addr::loopback());
+        ip.push_extension(Ipv6Extension::Destination(vec![]));
+        let bytes = (EthLayer { ether_type: None, ..eth() } / ip / Ipv4Layer::default() / b"abc").to_bytes();
+
+        let packet = Packet::parse(&bytes, LinkType::Ethernet).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / IPv6 / IPv4 / Raw");
+        assert_eq!(packet.get::<Ipv6Layer>().unwrap().upper_protocol(), Some(IpProtocol::IPv4));
+        assert_eq!(packet.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn dissect_icmp() {
+        let ip = Ipv4Layer::new(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2));
+        let bytes = (eth() / ip.clone() / IcmpLayer::echo_request(1, 2) / b"abc" / PaddingLayer::zeroed(15)).to_bytes();
+
+        let packet = Packet::parse(&bytes, LinkType::Ethernet).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / IPv4 / ICMP / Raw / Padding");
+        assert!(packet.get::<IcmpLayer>().unwrap().is_checksum_valid(b"abc"));
+        assert_eq!(packet.to_bytes(), bytes);
+
+        let error = IcmpLayer::time_exceeded(0, &Packet::parse(&bytes[14..45], LinkType::Ipv4).unwrap());
+        let bytes = (ip / error).to_bytes();
+        let packet = Packet::parse(&bytes, LinkType::Ipv4).unwrap();
+        assert_eq!(packet.to_string(), "IPv4 / ICMP");
+        assert_eq!(packet.get::<IcmpLayer>().unwrap().original_packet().unwrap().to_string(), "IPv4 / ICMP");
+    }
+
+    #[test]
+    fn dissect_icmpv6() {
+        let mac = MacAddr::new(0, 0x11, 0x22, 0x33, 0x44, 0x55);
+        let bytes = Icmpv6Layer::neighbor_solicitation_frame(mac, Ipv6Addr::loopback(), Ipv6Addr::loopback()).to_bytes();
+
+        let packet = Packet::parse(&bytes, LinkType::Ethernet).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / IPv6 / ICMPv6");
+        let ip = packet.get::<Ipv6Layer>().unwrap();
+        assert!(packet.get::<Icmpv6Layer>().unwrap().is_checksum_valid(ip, &[]));
+        assert_eq!(packet.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn dissect_udp() {
+        let ip = Ipv4Layer::new(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2));
+        let bytes = (eth() / ip / UdpLayer::new(40000, 7) / b"echo" / PaddingLayer::zeroed(14)).to_bytes();
+
+        let packet = Packet::parse(&bytes, LinkType::Ethernet).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / IPv4 / UDP / Raw / Padding");
+        assert_eq!(packet.to_bytes(), bytes);
+
+        // The payload is dispatched by the lower port
+        let mut registry = DissectorRegistry::new();
+        registry.register::<TagLayer>(Discriminator::UdpPort(7));
+        let packet = Packet::parse_with(&bytes, LinkType::Ethernet, &registry).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / IPv4 / UDP / Tag / Raw / Padding");
+    }
+
+    #[test]
+    fn dissect_tcp() {
+        let ip = Ipv4Layer::new(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2));
+        let tcp = TcpLayer {
+            options: vec![TcpOption::Mss(1460)],
+            ..TcpLayer::new(50000, 80, TcpFlags::SYN)
+        };
+        let bytes = (eth() / ip / tcp / PaddingLayer::zeroed(2)).to_bytes();
+
+        let packet = Packet::parse(&bytes, LinkType::Ethernet).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / IPv4 / TCP / Padding");
+        assert_eq!(packet.get::<TcpLayer>().unwrap().options, [TcpOption::Mss(1460)]);
+        assert_eq!(packet.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn dissect_dns() {
+        let ip = Ipv4Layer::new(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2));
+        let query = DnsLayer::query(1, "example.com", DnsType::A);
+        let bytes = (eth() / ip.clone() / UdpLayer::new(40000, 53) / query.clone()).to_bytes();
+
+        let packet = Packet::parse(&bytes, LinkType::Ethernet).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / IPv4 / UDP / DNS");
+        assert_eq!(packet.get::<DnsLayer>(), Some(&query));
+        assert_eq!(packet.to_bytes(), bytes);
+
+        // Over TCP, every message is preceded by its length
+        let query = DnsLayer { framing: DnsFraming::Stream, ..query };
+        let bytes = (ip / TcpLayer::new(40000, 53, TcpFlags::PSH_ACK) / query.clone() / query).to_bytes();
+        let packet = Packet::parse(&bytes, LinkType::Ipv4).unwrap();
+        assert_eq!(packet.to_string(), "IPv4 / TCP / DNS / DNS");
+        assert_eq!(packet.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn dissect_dhcp() {
+        let ip = Ipv4Layer::new(Ipv4Addr::new(0, 0, 0, 0), Ipv4Addr::new(255, 255, 255, 255));
+        let discover = DhcpLayer::discover(0x3903f326, MacAddr::new(0x00, 0x0b, 0x82, 0x01, 0xfc, 0x42));
+        let bytes = (eth() / ip / UdpLayer::new(68, 67) / discover.clone() / PaddingLayer::zeroed(39)).to_bytes();
+
+        let packet = Packet::parse(&bytes, LinkType::Ethernet).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / IPv4 / UDP / DHCP / Padding");
+        assert_eq!(packet.get::<DhcpLayer>(), Some(&discover));
+        assert_eq!(packet.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn dissect_dhcpv6() {
+        let ip = Ipv6Layer::new(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1), Dhcpv6Layer::all_relay_agents_and_servers());
+        let solicit = Dhcpv6Layer::solicit(0x123456, Duid::link_layer(MacAddr::new(0, 0x11, 0x22, 0x33, 0x44, 0x55)), 1);
+        let relayed = Dhcpv6Layer::relay_forward(solicit.clone(), Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1), ip.src);
+        let bytes = (ip / UdpLayer::new(547, 547) / relayed).to_bytes();
+
+        let packet = Packet::parse(&bytes, LinkType::Ipv6).unwrap();
+        assert_eq!(packet.to_string(), "IPv6 / UDP / DHCPv6");
+        assert_eq!(packet.get::<Dhcpv6Layer>().unwrap().innermost(), &solicit);
+        assert_eq!(packet.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn dissect_igmp() {
+        let mac = MacAddr::new(0, 0x11, 0x22, 0x33, 0x44, 0x55);
+        let record = IgmpGroupRecord::new(GroupRecordType::ModeIsExclude, Ipv4Addr::new(239, 1, 1, 1), vec![]);
+        let frame = IgmpLayer::report_v3(vec![record]).into_frame(mac, Ipv4Addr::new(10, 0, 0, 1)) / PaddingLayer::zeroed(6);
+        let bytes = frame.to_bytes();
+
+        let packet = Packet::parse(&bytes, LinkType::Ethernet).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / IPv4 / IGMP / Padding");
+        assert!(packet.get::<IgmpLayer>().unwrap().is_checksum_valid());
+        assert_eq!(packet.get::<Ipv4Layer>().unwrap().dst, Ipv4Addr::all_igmpv3_routers());
+        assert_eq!(packet.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn dissect_mld() {
+        let mac = MacAddr::new(0, 0x11, 0x22, 0x33, 0x44, 0x55);
+        let query = Icmpv6Layer::multicast_listener_query_v2(Ipv6Addr::unspecified(), vec![]);
+        let bytes = query.into_mld_frame(mac, Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)).to_bytes();
+
+        let packet = Packet::parse(&bytes, LinkType::Ethernet).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / IPv6 / ICMPv6");
+        let ip = packet.get::<Ipv6Layer>().unwrap();
+        assert_eq!(ip.dst, Ipv6Addr::all_nodes());
+        let icmp = packet.get::<Icmpv6Layer>().unwrap();
+        assert!(icmp.is_checksum_valid(ip, &[]));
+        assert!(matches!(icmp.message, Icmpv6Message::MulticastListenerQueryV2(_)));
+        assert_eq!(packet.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn dissect_gre() {
+        let outer = Ipv4Layer::new(Ipv4Addr::new(192, 0, 2, 1), Ipv4Addr::new(192, 0, 2, 2));
+        let inner = EthLayer::new(MacAddr::new(2, 0, 0, 0, 0, 1), MacAddr::new(2, 0, 0, 0, 0, 2), EtherType::IPv4, vec![]);
+        let gre = GreLayer { sequence: Some(1), ..GreLayer::nvgre(5001, 0) };
+        let inner_ip = Ipv4Layer::new(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2));
+        let bytes = (eth() / outer / gre / inner / inner_ip / UdpLayer::new(1234, 4321) / b"data").to_bytes();
+
+        let packet = Packet::parse(&bytes, LinkType::Ethernet).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / IPv4 / GRE / Ethernet / IPv4 / UDP / Raw");
+        let gre = packet.get::<GreLayer>().unwrap();
+        assert_eq!((gre.vsid(), gre.sequence), (Some(5001), Some(1)));
+        assert_eq!(packet.to_bytes(), bytes);
+
+        let bytes = (eth() / Ipv4Layer::default() / GreLayer::default() / Ipv6Layer::default()).to_bytes();
+        let packet = Packet::parse(&bytes, LinkType::Ethernet).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / IPv4 / GRE / IPv6");
+    }
+
+    #[test]
+    fn dissect_overlays() {
+        let outer = Ipv4Layer::new(Ipv4Addr::new(192, 0, 2, 1), Ipv4Addr::new(192, 0, 2, 2));
+        let inner = EthLayer::new(MacAddr::new(2, 0, 0, 0, 0, 1), MacAddr::new(2, 0, 0, 0, 0, 2), EtherType::IPv4, vec![]);
+        let inner_ip = Ipv4Layer::new(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2));
+        let inner = inner / inner_ip / UdpLayer::new(1234, 4321) / b"data";
+
+        let bytes = (eth() / outer.clone() / UdpLayer::new(54321, VxlanLayer::PORT) / VxlanLayer::new(42) / inner.clone()).to_bytes();
+        let packet = Packet::parse(&bytes, LinkType::Ethernet).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / IPv4 / UDP / VXLAN / Ethernet / IPv4 / UDP / Raw");
+        assert_eq!(packet.get::<VxlanLayer>().unwrap().vni, 42);
+        assert_eq!(packet.to_bytes(), bytes);
+
+        let gpe = UdpLayer::new(54321, VxlanLayer::GPE_PORT) / VxlanLayer::gpe(42) / Ipv6Layer::default();
+        let bytes = (eth() / outer.clone() / gpe).to_bytes();
+        let packet = Packet::parse(&bytes, LinkType::Ethernet).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / IPv4 / UDP / VXLAN / IPv6");
+
+        let mut geneve = GeneveLayer::new(7);
+        geneve.push_option(GeneveOption::new(0x0103, 0x80, vec![1, 2, 3, 4])).unwrap();
+        let bytes = (eth() / outer / UdpLayer::new(54321, GeneveLayer::PORT) / geneve / inner).to_bytes();
+        let packet = Packet::parse(&bytes, LinkType::Ethernet).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / IPv4 / UDP / Geneve / Ethernet / IPv4 / UDP / Raw");
+        let geneve = packet.get::<GeneveLayer>().unwrap();
+        assert_eq!(geneve.critical, Some(true));
+        assert_eq!(geneve.options[0].data, [1, 2, 3, 4]);
+        assert_eq!(packet.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn dissect_arp() {
+        let mac = MacAddr::new(0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff);
+        let arp = ArpLayer::request(mac, Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2));
+        let bytes = (arp.clone().into_frame() / PaddingLayer::zeroed(18)).to_bytes();
+
+        let packet = Packet::parse(&bytes, LinkType::Ethernet).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / ARP / Padding");
+        assert_eq!(packet.get::<ArpLayer>(), Some(&arp));
+        assert_eq!(packet.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn dissect_llc_snap() {
+        let eth = EthLayer::new(MacAddr::new(0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff), MacAddr::broadcast(), EtherType::IPv4, vec![]);
+        let eth = EthLayer { ether_type: None, ..eth };
+        let cdp = eth.clone() / LlcLayer::snap() / SnapLayer::new(SnapLayer::OUI_CISCO, 0x2000) / b"cdp" / PaddingLayer::zeroed(35);
+        let bytes = cdp.to_bytes();
+
+        // The length covers the LLC and SNAP headers and the payload, but not the padding
+        assert_eq!(&bytes[12..14], [0x00, 0x0b]);
+
+        let packet = Packet::parse(&bytes, LinkType::Ethernet).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / LLC / SNAP / Raw / Padding");
+        assert_eq!(packet.get::<SnapLayer>(), Some(&SnapLayer::new(SnapLayer::OUI_CISCO, 0x2000)));
+        assert_eq!(packet.get::<RawLayer>(), Some(&RawLayer::new(*b"cdp")));
+        assert_eq!(packet.to_bytes(), bytes);
+
+        let stp = eth / LlcLayer::new(Sap::Stp, Sap::Stp, LlcLayer::UI).unwrap() / [0u8; 35].as_slice();
+        let packet = Packet::parse(&stp.to_bytes(), LinkType::Ethernet).unwrap();
+        assert_eq!(packet.to_string(), "Ethernet / LLC / Raw");
+        assert_eq!(packet.get::<EthLayer>().unwrap().ether_type, Some(TypeOrLength::Length(38)));
+    }
+}
@@ -1,7 +1,6 @@
-use pnet_datalink::DataLinkSender;
-
 use crate::{MacAddr, Ipv4Addr};
 
+#[allow(dead_code)]
 pub struct NetworkInterface {
     dev: pnet_datalink::NetworkInterface,
     tx: Box<dyn pnet_datalink::DataLinkSender>,
@@ -36,9 +35,7 @@ pub struct NetworkInterfaceData {
 
 impl From<pnet_datalink::NetworkInterface> for NetworkInterface {
     fn from(value: pnet_datalink::NetworkInterface) -> Self {
-        let name = value.name.clone();
-        
-        let (mut tx, mut rx) = match pnet_datalink::channel(&value, Default::default()) {
+        let (tx, rx) = match pnet_datalink::channel(&value, Default::default()) {
             Ok(pnet_datalink::Channel::Ethernet(tx, rx)) => (tx, rx),
             Ok(_) => panic!("Unhandled channel type"),
             Err(e) => panic!("An error occurred when creating the datalink channel: {}", e)
@@ -6,36 +6,87 @@ pub struct Ipv4Addr(u8, u8, u8, u8);
 impl Ipv4Addr {
     
     /// Constructs an IPv4 address from four octet values
-    fn new(a: u8 , b: u8 , c: u8, d: u8) -> Self {
+    pub fn new(a: u8 , b: u8 , c: u8, d: u8) -> Self {
         Ipv4Addr(a, b, c, d)
     }
     
-    /// Attempts to construct an IPv4 address from a string.
-    fn from_str(string: &str) -> Result<Self, IpAddressError> {
-        let octets : Vec<&str> = string.split('.').collect();
+    /// Returns the four octets of the address.
+    pub const fn octets(&self) -> [u8; 4] {
+        [self.0, self.1, self.2, self.3]
+    }
 
-        if octets.len() != 4 { return Err(IpAddressError::InvalidLength); }
-        
-        Ok(Ipv4Addr(octets[0].parse()?, octets[1].parse()?, octets[2].parse()?,octets[3].parse()?))
+    /// Constructs the unspecified address `0.0.0.0`.
+    pub const fn unspecified() -> Self {
+        Ipv4Addr(0, 0, 0, 0)
+    }
+
+    /// Constructs the limited broadcast address `255.255.255.255`.
+    pub const fn broadcast() -> Self {
+        Ipv4Addr(255, 255, 255, 255)
+    }
+
+    /// Constructs the all-systems multicast address `224.0.0.1`, to which general IGMP queries are sent.
+    pub const fn all_systems() -> Self {
+        Ipv4Addr(224, 0, 0, 1)
+    }
+
+    /// Constructs the all-routers multicast address `224.0.0.2`, to which IGMPv2 Leave Group messages are sent.
+    pub const fn all_routers() -> Self {
+        Ipv4Addr(224, 0, 0, 2)
+    }
+
+    /// Constructs the all-IGMPv3-routers multicast address `224.0.0.22`, to which IGMPv3 reports are sent.
+    pub const fn all_igmpv3_routers() -> Self {
+        Ipv4Addr(224, 0, 0, 22)
+    }
+
+    /// Checks if the address is a multicast address, i.e. in `224.0.0.0/4`.
+    pub fn is_multicast(&self) -> bool {
+        self.0 & 0xf0 == 0xe0
     }
 
     /// Constructs a loopback address.
-    const fn loopback() -> Self {
+    pub const fn loopback() -> Self {
         Ipv4Addr(127, 0, 0, 1)
     }
 
     /// Checks if an IP address is a loopback address.
-    fn is_loopback(&self) -> bool {
-        return self.is_in_range(Ipv4Addr(127, 0, 0, 0), Ipv4Addr(127, 255, 255, 255))
+    pub fn is_loopback(&self) -> bool {
+        self.is_in_range(Ipv4Addr(127, 0, 0, 0), Ipv4Addr(127, 255, 255, 255))
     }
 
     /// Checks if the IP address is in the specified IP range (inclusively).
-    fn is_in_range(&self, lower_bound: Self, upper_bound:Self) -> bool
+    pub fn is_in_range(&self, lower_bound: Self, upper_bound:Self) -> bool
     {
         *self >= lower_bound && *self <= upper_bound
     }
 }
 
+impl FromStr for Ipv4Addr {
+    type Err = IpAddressError;
+
+    /// Attempts to construct an IPv4 address from a string.
+    fn from_str(string: &str) -> Result<Self, IpAddressError> {
+        let octets : Vec<&str> = string.split('.').collect();
+
+        if octets.len() != 4 { return Err(IpAddressError::InvalidLength); }
+        
+        Ok(Ipv4Addr(octets[0].parse()?, octets[1].parse()?, octets[2].parse()?,octets[3].parse()?))
+    }
+}
+
+impl From<[u8; 4]> for Ipv4Addr {
+    fn from(octets: [u8; 4]) -> Self {
+        Ipv4Addr(octets[0], octets[1], octets[2], octets[3])
+    }
+}
+
+impl From<Ipv4Addr> for u32 {
+    fn from(addr: Ipv4Addr) -> Self {
+        u32::from_be_bytes(addr.octets())
+    }
+}
+
 impl fmt::Display for Ipv4Addr {
         
     /// Display the IP address as a string with the '.' delimiter
@@ -59,4 +110,14 @@ mod tests {
         assert_eq!(Ipv4Addr::loopback(), Ipv4Addr::new(127, 0, 0, 1));
         assert!(Ipv4Addr::new(127,234,255,112).is_loopback());
     }
+
+    #[test]
+    fn octets() {
+        let addr = Ipv4Addr::new(192, 168, 1, 10);
+        assert_eq!(addr.octets(), [192, 168, 1, 10]);
+        assert_eq!(Ipv4Addr::from([192, 168, 1, 10]), addr);
+        assert_eq!(u32::from(addr), 0xc0a8010a);
+        assert!(Ipv4Addr::new(224, 0, 0, 22).is_multicast());
+        assert!(!Ipv4Addr::broadcast().is_multicast());
+    }
 }
\ No newline at end of file
@@ -1,44 +1,224 @@
 use super::*;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash, Ord, PartialOrd)]
-pub struct Ipv6Addr(u16, u16, u16, u16, u16, u16);
+pub struct Ipv6Addr([u16; 8]);
 
 impl Ipv6Addr {
-    
-    /// Constructs an IPv6 address from six hextets.
-    fn new(a: u16, b: u16, c: u16, d: u16, e: u16, f: u16) -> Self {
-        Ipv6Addr(a, b, c, d, e, f)
+
+    /// Constructs an IPv6 address from eight hextets.
+    #[allow(clippy::too_many_arguments)]
+    pub const fn new(a: u16, b: u16, c: u16, d: u16, e: u16, f: u16, g: u16, h: u16) -> Self {
+        Ipv6Addr([a, b, c, d, e, f, g, h])
+    }
+
+    /// Constructs the loopback address `::1`.
+    pub const fn loopback() -> Self {
+        Ipv6Addr([0, 0, 0, 0, 0, 0, 0, 1])
+    }
+
+    /// Constructs the unspecified address `::`.
+    pub const fn unspecified() -> Self {
+        Ipv6Addr([0; 8])
+    }
+
+    /// Constructs the link-local all-nodes multicast address `ff02::1`.
+    pub const fn all_nodes() -> Self {
+        Ipv6Addr([0xff02, 0, 0, 0, 0, 0, 0, 1])
+    }
+
+    /// Constructs the link-local all-routers multicast address `ff02::2`.
+    pub const fn all_routers() -> Self {
+        Ipv6Addr([0xff02, 0, 0, 0, 0, 0, 0, 2])
     }
-    
-    /// Attempts to construct an IPv6 address from a string.
-    fn from_str(string: &str) -> Result<Self, IpAddressError> {
-        let hextets : Vec<&str> = string.split(':').collect();
 
-        if hextets.len() != 6 { return Err(IpAddressError::InvalidLength); }
-        
-        Ok(Ipv6Addr(hextets[0].parse()?, hextets[1].parse()?, hextets[2].parse()?,hextets[3].parse()?, hextets[4].parse()?, hextets[5].parse()?))
+    /// Constructs the all-MLDv2-capable-routers multicast address `ff02::16`, to which MLDv2 reports are sent.
+    pub const fn all_mldv2_routers() -> Self {
+        Ipv6Addr([0xff02, 0, 0, 0, 0, 0, 0, 0x16])
     }
 
-    /// Constructs a loopback address.
-    const fn loopback() -> Self {
-        Ipv6Addr(0, 0, 0, 0, 0, 0)
+    /// Returns the solicited-node multicast address (RFC 4291) to which Neighbor Solicitations for the address are sent.
+    pub const fn solicited_node(&self) -> Self {
+        Ipv6Addr([0xff02, 0, 0, 0, 0, 1, 0xff00 | (self.0[6] & 0xff), self.0[7]])
     }
 
     /// Checks if an IP address is a loopback address
-    fn is_loopback(&self) -> bool {
-        return self == &Self::loopback()
+    pub fn is_loopback(&self) -> bool {
+        self == &Self::loopback()
+    }
+
+    /// Checks if the address is a multicast address, i.e. in `ff00::/8`.
+    pub fn is_multicast(&self) -> bool {
+        self.0[0] & 0xff00 == 0xff00
+    }
+
+    /// Checks if the address is a link-local unicast address, i.e. in `fe80::/10`.
+    pub fn is_link_local(&self) -> bool {
+        self.0[0] & 0xffc0 == 0xfe80
+    }
+
+    /// Returns the eight hextets of the address.
+    pub const fn segments(&self) -> [u16; 8] {
+        self.0
+    }
+
+    /// Returns the sixteen octets of the address in network byte order.
+    pub fn octets(&self) -> [u8; 16] {
+        let mut octets = [0; 16];
+        for (chunk, segment) in octets.chunks_exact_mut(2).zip(self.0) {
+            chunk.copy_from_slice(&segment.to_be_bytes());
+        }
+        octets
+    }
+}
+
+impl From<[u16; 8]> for Ipv6Addr {
+    fn from(segments: [u16; 8]) -> Self {
+        Ipv6Addr(segments)
     }
 }
 
+impl From<[u8; 16]> for Ipv6Addr {
+    fn from(octets: [u8; 16]) -> Self {
+        let mut segments = [0; 8];
+        for (segment, chunk) in segments.iter_mut().zip(octets.chunks_exact(2)) {
+            *segment = u16::from_be_bytes([chunk[0], chunk[1]]);
+        }
+        Ipv6Addr(segments)
+    }
+}
+
+impl FromStr for Ipv6Addr {
+    type Err = IpAddressError;
+
+    /// Attempts to construct an IPv6 address from a string, e.g. `2001:db8::1` or `::ffff:192.0.2.1`.
+    fn from_str(string: &str) -> Result<Self, IpAddressError> {
+        let (head, tail) = match string.split_once("::") {
+            Some((head, tail)) => (head, Some(tail)),
+            None => (string, None),
+        };
+
+        let head = parse_hextets(head)?;
+        let segments = match tail {
+            None if head.len() == 8 => head,
+            None => return Err(IpAddressError::InvalidLength),
+            Some(tail) => {
+                let tail = parse_hextets(tail)?;
+                if head.len() + tail.len() > 7 {
+                    return Err(IpAddressError::InvalidLength);
+                }
+
+                let mut segments = head;
+                segments.resize(8 - tail.len(), 0);
+                segments.extend(tail);
+                segments
+            }
+        };
+
+        Ok(Ipv6Addr(segments.try_into().unwrap()))
+    }
+}
+
+/// Parses colon-separated hextets, the last of which may be an embedded IPv4 address.
+fn parse_hextets(string: &str) -> Result<Vec<u16>, IpAddressError> {
+    if string.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let parts: Vec<&str> = string.split(':').collect();
+    let mut hextets = Vec::with_capacity(8);
+
+    for (i, part) in parts.iter().enumerate() {
+        if i == parts.len() - 1 && part.contains('.') {
+            let octets = Ipv4Addr::from_str(part)?.octets();
+            hextets.push(u16::from_be_bytes([octets[0], octets[1]]));
+            hextets.push(u16::from_be_bytes([octets[2], octets[3]]));
+        } else if part.is_empty() || part.len() > 4 {
+            return Err(IpAddressError::InvalidFormat);
+        } else {
+            hextets.push(u16::from_str_radix(part, 16)?);
+        }
+    }
+
+    Ok(hextets)
+}
+
 impl fmt::Display for Ipv6Addr {
-        
-    /// Display the IPv6 address as a string.
+
+    /// Display the IPv6 address in the canonical form of RFC 5952, which compresses the longest run of zero hextets.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:04x}:{:04x}:{:04x}:{:04x}:{:04x}:{:04x}", self.0, self.1, self.2, self.3, self.4, self.5)
+        let mut longest = (0, 0);
+        let mut current = (0, 0);
+
+        for (i, segment) in self.0.iter().enumerate() {
+            if *segment != 0 {
+                current = (i + 1, 0);
+                continue;
+            }
+
+            current.1 += 1;
+            if current.1 > longest.1 {
+                longest = current;
+            }
+        }
+
+        let write_all = |f: &mut fmt::Formatter, segments: &[u16]| -> fmt::Result {
+            let hextets: Vec<String> = segments.iter().map(|segment| format!("{:x}", segment)).collect();
+            write!(f, "{}", hextets.join(":"))
+        };
+
+        // A single zero hextet is not compressed
+        if longest.1 < 2 {
+            return write_all(f, &self.0);
+        }
+
+        write_all(f, &self.0[..longest.0])?;
+        write!(f, "::")?;
+        write_all(f, &self.0[longest.0 + longest.1..])
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-}
\ No newline at end of file
+
+    #[test]
+    fn construct_from_str() {
+        assert_eq!(Ipv6Addr::from_str("2001:db8:0:0:1:0:0:1"), Ok(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 1, 0, 0, 1)));
+        assert_eq!(Ipv6Addr::from_str("2001:db8::1"), Ok(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)));
+        assert_eq!(Ipv6Addr::from_str("::"), Ok(Ipv6Addr::unspecified()));
+        assert_eq!(Ipv6Addr::from_str("::1"), Ok(Ipv6Addr::loopback()));
+        assert_eq!(Ipv6Addr::from_str("fe80::"), Ok(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 0)));
+        assert_eq!(Ipv6Addr::from_str("::ffff:192.0.2.1"), Ok(Ipv6Addr::new(0, 0, 0, 0, 0, 0xffff, 0xc000, 0x0201)));
+
+        assert_eq!(Ipv6Addr::from_str("1:2:3:4:5:6:7"), Err(IpAddressError::InvalidLength));
+        assert_eq!(Ipv6Addr::from_str("1:2:3:4::5:6:7:8"), Err(IpAddressError::InvalidLength));
+        assert_eq!(Ipv6Addr::from_str("1::2::3"), Err(IpAddressError::InvalidFormat));
+        assert_eq!(Ipv6Addr::from_str("12345::"), Err(IpAddressError::InvalidFormat));
+        assert!(Ipv6Addr::from_str("g::").is_err());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 1, 0, 0, 1).to_string(), "2001:db8::1:0:0:1");
+        assert_eq!(Ipv6Addr::new(0x2001, 0xdb8, 0, 1, 1, 1, 1, 1).to_string(), "2001:db8:0:1:1:1:1:1");
+        assert_eq!(Ipv6Addr::loopback().to_string(), "::1");
+        assert_eq!(Ipv6Addr::unspecified().to_string(), "::");
+        assert_eq!(Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 0).to_string(), "ff02::");
+    }
+
+    #[test]
+    fn octets() {
+        let addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 0x16);
+        let octets = addr.octets();
+        assert_eq!(octets[..2], [0xff, 0x02]);
+        assert_eq!(octets[15], 0x16);
+        assert_eq!(Ipv6Addr::from(octets), addr);
+        assert!(addr.is_multicast());
+        assert!(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1).is_link_local());
+        assert!(Ipv6Addr::loopback().is_loopback());
+        assert_eq!(
+            Ipv6Addr::new(0xfe80, 0, 0, 0, 0x0211, 0x22ff, 0xfe33, 0x4455).solicited_node().to_string(),
+            "ff02::1:ff33:4455"
+        );
+    }
+}
@@ -3,7 +3,7 @@ pub use ipv4::*;
 pub mod ipv6;
 pub use ipv6::*;
 
-use std::{fmt, num::ParseIntError};
+use std::{fmt, num::ParseIntError, str::FromStr};
 
 use thiserror::Error;
 
@@ -14,5 +14,8 @@ pub enum IpAddressError
     InvalidAddress(#[from] ParseIntError),
     
     #[error("Invalid IP address length")]
-    InvalidLength
+    InvalidLength,
+
+    #[error("Invalid IP address format")]
+    InvalidFormat
 }
\ No newline at end of file
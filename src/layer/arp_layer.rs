@@ -0,0 +1,348 @@
+use super::*;
+
+use crate::{reflect::*, Ipv4Addr, MacAddr, Packet};
+
+use thiserror::Error;
+
+registry_enum! {
+    /// The hardware types assigned by IANA which identify the link layer of an ARP message.
+    pub enum HardwareType: u16 {
+        Ethernet = 1,
+        ExperimentalEthernet = 2,
+        Ax25 = 3,
+        ProNet = 4,
+        Chaos = 5,
+        Ieee802 = 6,
+        Arcnet = 7,
+        FrameRelay = 15,
+        Atm = 16,
+        Hdlc = 17,
+        FibreChannel = 18,
+        Eui64 = 27,
+        InfiniBand = 32,
+    }
+}
+
+registry_enum! {
+    /// The ARP operation codes assigned by IANA.
+    pub enum ArpOpcode: u16 {
+        Request = 1,
+        Reply = 2,
+        RarpRequest = 3,
+        RarpReply = 4,
+        InArpRequest = 8,
+        InArpReply = 9,
+        ArpNak = 10,
+    }
+}
+
+/// An ARP message (RFC 826) which maps IPv4 addresses to Ethernet MAC addresses.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ArpLayer {
+    pub hardware_type: HardwareType,
+    pub protocol_type: EtherType,
+    pub opcode: ArpOpcode,
+    pub sender_mac: MacAddr,
+    pub sender_ip: Ipv4Addr,
+    pub target_mac: MacAddr,
+    pub target_ip: Ipv4Addr,
+}
+
+impl ArpLayer {
+    /// The length of a message for Ethernet and IPv4 in bytes.
+    pub const LEN: usize = 28;
+
+    /// Constructs a message with the specified opcode and addresses.
+    pub fn new(opcode: ArpOpcode, sender_mac: MacAddr, sender_ip: Ipv4Addr, target_mac: MacAddr, target_ip: Ipv4Addr) -> Self {
+        Self {
+            hardware_type: HardwareType::Ethernet,
+            protocol_type: EtherType::IPv4,
+            opcode,
+            sender_mac,
+            sender_ip,
+            target_mac,
+            target_ip,
+        }
+    }
+
+    /// Constructs a request which asks who has the target address.
+    pub fn request(sender_mac: MacAddr, sender_ip: Ipv4Addr, target_ip: Ipv4Addr) -> Self {
+        Self::new(ArpOpcode::Request, sender_mac, sender_ip, MacAddr::default(), target_ip)
+    }
+
+    /// Constructs a reply which tells the target that the sender address is at the sender MAC address.
+    pub fn reply(sender_mac: MacAddr, sender_ip: Ipv4Addr, target_mac: MacAddr, target_ip: Ipv4Addr) -> Self {
+        Self::new(ArpOpcode::Reply, sender_mac, sender_ip, target_mac, target_ip)
+    }
+
+    /// Constructs a gratuitous ARP, also known as an ARP announcement (RFC 5227), which announces the binding of the
+    /// address to the MAC address. Both the sender and the target address are set to the announced address.
+    pub fn gratuitous(mac: MacAddr, ip: Ipv4Addr) -> Self {
+        Self::new(ArpOpcode::Request, mac, ip, MacAddr::default(), ip)
+    }
+
+    /// Constructs an ARP probe (RFC 5227), which checks whether the address is in use without announcing the sender.
+    pub fn probe(mac: MacAddr, target_ip: Ipv4Addr) -> Self {
+        Self::new(ArpOpcode::Request, mac, Ipv4Addr::unspecified(), MacAddr::default(), target_ip)
+    }
+
+    /// Checks if the message is a gratuitous ARP, i.e. its sender and target addresses are equal.
+    pub fn is_gratuitous(&self) -> bool {
+        self.sender_ip == self.target_ip && self.sender_ip != Ipv4Addr::unspecified()
+    }
+
+    /// Checks if the message is an ARP probe, i.e. a request with an unspecified sender address.
+    pub fn is_probe(&self) -> bool {
+        self.opcode == ArpOpcode::Request && self.sender_ip == Ipv4Addr::unspecified()
+    }
+
+    /// Wraps the message into an Ethernet frame sent from the sender MAC address. Requests are broadcast and replies
+    /// are sent to the target MAC address.
+    pub fn into_frame(self) -> Packet {
+        let dst_mac = match self.opcode {
+            ArpOpcode::Reply | ArpOpcode::RarpReply | ArpOpcode::InArpReply => self.target_mac,
+            _ => MacAddr::broadcast(),
+        };
+
+        EthLayer::new(self.sender_mac, dst_mac, EtherType::Arp, vec![]) / self
+    }
+}
+
+impl Default for ArpLayer {
+    fn default() -> Self {
+        Self::request(MacAddr::default(), Ipv4Addr::unspecified(), Ipv4Addr::unspecified())
+    }
+}
+
+impl Layer for ArpLayer {
+    const NAME: &'static str = "ARP";
+    const TYPE: LayerType = LayerType::ArpLayer;
+    const OSI_LEVEL: OsiLevel = OsiLevel::Network;
+}
+
+impl DynLayer for ArpLayer {
+    fn header_len(&self) -> usize {
+        Self::LEN
+    }
+
+    /// ARP messages carry no payload, so any following bytes are padding of the frame.
+    fn payload_len(&self) -> Option<usize> {
+        Some(0)
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.hardware_type.value().to_be_bytes());
+        buf.extend_from_slice(&self.protocol_type.value().to_be_bytes());
+        buf.extend_from_slice(&[6, 4]);
+        buf.extend_from_slice(&self.opcode.value().to_be_bytes());
+        buf.extend_from_slice(&self.sender_mac.octets());
+        buf.extend_from_slice(&self.sender_ip.octets());
+        buf.extend_from_slice(&self.target_mac.octets());
+        buf.extend_from_slice(&self.target_ip.octets());
+    }
+
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        let view = ArpView::new(bytes)?;
+        Ok((view.to_layer(), Self::LEN))
+    }
+
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+}
+
+impl Reflect for ArpLayer {
+    fn fields(&self) -> Vec<Field> {
+        vec![
+            Field::new("arp.hwtype", FieldKind::Enum, 0, 16, self.hardware_type.field_value()),
+            Field::new("arp.ptype", FieldKind::Enum, 16, 16, self.protocol_type.field_value()),
+            Field::new("arp.op", FieldKind::Enum, 48, 16, self.opcode.field_value()),
+            Field::new("arp.hwsrc", FieldKind::Mac, 64, 48, FieldValue::Mac(self.sender_mac)),
+            Field::new("arp.psrc", FieldKind::Ipv4, 112, 32, FieldValue::Ipv4(self.sender_ip)),
+            Field::new("arp.hwdst", FieldKind::Mac, 144, 48, FieldValue::Mac(self.target_mac)),
+            Field::new("arp.pdst", FieldKind::Ipv4, 192, 32, FieldValue::Ipv4(self.target_ip)),
+        ]
+    }
+
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        match path {
+            "arp.hwtype" => self.hardware_type = HardwareType::parse_field(value)?,
+            "arp.ptype" => self.protocol_type = EtherType::parse_field(value)?,
+            "arp.op" => self.opcode = ArpOpcode::parse_field(value)?,
+            "arp.hwsrc" => self.sender_mac = parse_str(value)?,
+            "arp.psrc" => self.sender_ip = parse_str(value)?,
+            "arp.hwdst" => self.target_mac = parse_str(value)?,
+            "arp.pdst" => self.target_ip = parse_str(value)?,
+            _ => return Err(FieldError::UnknownField(path.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
+/// A borrowed, zero-copy view of an Ethernet/IPv4 ARP message at the start of a byte slice.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ArpView<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ArpView<'a> {
+    /// Constructs a view of the ARP message at the start of the slice. Fails if the addresses are not 6 and 4 bytes long.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, LayerError> {
+        if bytes.len() < 6 {
+            return Err(LayerError::BufferTooShort(ArpLayer::NAME));
+        }
+
+        if bytes[4] != 6 || bytes[5] != 4 {
+            return Err(ArpError::UnsupportedAddressLength { hardware_len: bytes[4], protocol_len: bytes[5] }.into());
+        }
+
+        if bytes.len() < ArpLayer::LEN {
+            return Err(LayerError::BufferTooShort(ArpLayer::NAME));
+        }
+
+        Ok(Self { bytes })
+    }
+
+    fn u16_at(&self, offset: usize) -> u16 {
+        u16::from_be_bytes([self.bytes[offset], self.bytes[offset + 1]])
+    }
+
+    /// Returns the hardware type.
+    pub fn hardware_type(&self) -> HardwareType {
+        HardwareType::from_value(self.u16_at(0))
+    }
+
+    /// Returns the protocol type.
+    pub fn protocol_type(&self) -> EtherType {
+        EtherType::from_value(self.u16_at(2))
+    }
+
+    /// Returns the operation code.
+    pub fn opcode(&self) -> ArpOpcode {
+        ArpOpcode::from_value(self.u16_at(6))
+    }
+
+    /// Returns the sender MAC address.
+    pub fn sender_mac(&self) -> MacAddr {
+        MacAddr::from(<[u8; 6]>::try_from(&self.bytes[8..14]).unwrap())
+    }
+
+    /// Returns the sender IPv4 address.
+    pub fn sender_ip(&self) -> Ipv4Addr {
+        Ipv4Addr::from(<[u8; 4]>::try_from(&self.bytes[14..18]).unwrap())
+    }
+
+    /// Returns the target MAC address.
+    pub fn target_mac(&self) -> MacAddr {
+        MacAddr::from(<[u8; 6]>::try_from(&self.bytes[18..24]).unwrap())
+    }
+
+    /// Returns the target IPv4 address.
+    pub fn target_ip(&self) -> Ipv4Addr {
+        Ipv4Addr::from(<[u8; 4]>::try_from(&self.bytes[24..28]).unwrap())
+    }
+
+    /// Converts the view into an owned ARP layer.
+    pub fn to_layer(&self) -> ArpLayer {
+        ArpLayer {
+            hardware_type: self.hardware_type(),
+            protocol_type: self.protocol_type(),
+            opcode: self.opcode(),
+            sender_mac: self.sender_mac(),
+            sender_ip: self.sender_ip(),
+            target_mac: self.target_mac(),
+            target_ip: self.target_ip(),
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum ArpError {
+    #[error("ARP messages with {hardware_len}-byte hardware and {protocol_len}-byte protocol addresses are not supported.")]
+    UnsupportedAddressLength { hardware_len: u8, protocol_len: u8 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mac() -> MacAddr {
+        MacAddr::new(0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff)
+    }
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(ArpLayer::NAME, "ARP");
+        assert_eq!(ArpLayer::TYPE, LayerType::ArpLayer);
+        assert_eq!(ArpLayer::OSI_LEVEL, OsiLevel::Network);
+    }
+
+    #[test]
+    fn write_and_parse() {
+        let layer = ArpLayer::request(mac(), Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 2));
+        let mut buf = Vec::new();
+        layer.write(&mut buf);
+
+        assert_eq!(
+            buf,
+            [
+                0x00, 0x01, 0x08, 0x00, 0x06, 0x04, 0x00, 0x01,
+                0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff, 192, 168, 0, 1,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 192, 168, 0, 2,
+            ]
+        );
+        assert_eq!(ArpLayer::parse(&buf), Ok((layer, 28)));
+        assert_eq!(ArpLayer::parse(&buf[..27]), Err(LayerError::BufferTooShort("ARP")));
+
+        buf[4] = 8;
+        assert_eq!(
+            ArpLayer::parse(&buf),
+            Err(LayerError::Arp(ArpError::UnsupportedAddressLength { hardware_len: 8, protocol_len: 4 }))
+        );
+    }
+
+    #[test]
+    fn builders() {
+        let ip = Ipv4Addr::new(10, 0, 0, 1);
+
+        let gratuitous = ArpLayer::gratuitous(mac(), ip);
+        assert!(gratuitous.is_gratuitous());
+        assert!(!gratuitous.is_probe());
+        assert_eq!((gratuitous.sender_ip, gratuitous.target_ip), (ip, ip));
+
+        let probe = ArpLayer::probe(mac(), ip);
+        assert!(probe.is_probe());
+        assert!(!probe.is_gratuitous());
+        assert_eq!(probe.sender_ip, Ipv4Addr::unspecified());
+
+        let reply = ArpLayer::reply(mac(), ip, MacAddr::new(1, 2, 3, 4, 5, 6), Ipv4Addr::new(10, 0, 0, 2));
+        assert_eq!(reply.opcode, ArpOpcode::Reply);
+
+        let frame = reply.into_frame();
+        let eth = frame.get::<EthLayer>().unwrap();
+        assert_eq!((eth.src_mac, eth.dst_mac), (mac(), MacAddr::new(1, 2, 3, 4, 5, 6)));
+        assert_eq!(frame.to_bytes()[12..14], [0x08, 0x06]);
+
+        let frame = ArpLayer::request(mac(), ip, Ipv4Addr::new(10, 0, 0, 2)).into_frame();
+        assert_eq!(frame.get::<EthLayer>().unwrap().dst_mac, MacAddr::broadcast());
+    }
+
+    #[test]
+    fn reflect() {
+        let mut layer = ArpLayer::default();
+        layer.set_field("arp.op", "Reply").unwrap();
+        layer.set_field("arp.pdst", "10.0.0.2").unwrap();
+        layer.set_field("arp.hwsrc", "aa:bb:cc:dd:ee:ff").unwrap();
+
+        assert_eq!(layer.opcode, ArpOpcode::Reply);
+        assert_eq!(layer.target_ip, Ipv4Addr::new(10, 0, 0, 2));
+        assert_eq!(layer.get_field("arp.op").unwrap().to_string(), "ArpOpcode::Reply (0x0002)");
+        assert_eq!(layer.get_field("arp.hwsrc"), Some(FieldValue::Mac(mac())));
+        assert_eq!(layer.set_field("arp.psrc", "10.0.0"), Err(FieldError::InvalidValue("10.0.0".to_string())));
+    }
+}
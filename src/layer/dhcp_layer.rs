@@ -0,0 +1,513 @@
+use super::*;
+
+use crate::{reflect::*, Ipv4Addr, MacAddr};
+
+use thiserror::Error;
+
+registry_enum! {
+    /// The operation codes of BOOTP messages.
+    pub enum BootpOpcode: u8 {
+        BootRequest = 1,
+        BootReply = 2,
+    }
+}
+
+/// A DHCP message (RFC 2131), which consists of a BOOTP header (RFC 951), the magic cookie and the options.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DhcpLayer {
+    pub opcode: BootpOpcode,
+    pub hardware_type: HardwareType,
+    pub hardware_len: u8,
+    /// The number of relay agents the message passed through.
+    pub hops: u8,
+    pub transaction_id: u32,
+    /// The seconds elapsed since the client started to acquire or renew its lease.
+    pub seconds: u16,
+    pub flags: u16,
+    /// The current address of a client which is bound, renewing or rebinding (`ciaddr`).
+    pub client_ip: Ipv4Addr,
+    /// The address assigned to the client (`yiaddr`).
+    pub your_ip: Ipv4Addr,
+    /// The address of the next server to use in bootstrap (`siaddr`).
+    pub server_ip: Ipv4Addr,
+    /// The address of the relay agent (`giaddr`).
+    pub relay_ip: Ipv4Addr,
+    /// The hardware address of the client, padded with zeros (`chaddr`).
+    pub client_hardware_address: [u8; 16],
+    /// The optional server host name, without the trailing zeros of its 64-byte field.
+    pub server_name: Vec<u8>,
+    /// The optional boot file name, without the trailing zeros of its 128-byte field.
+    pub boot_file: Vec<u8>,
+    /// The options, which are followed by the End option when the message is serialized.
+    pub options: Vec<DhcpOption>,
+}
+
+impl DhcpLayer {
+    /// The length of the BOOTP header and the magic cookie in bytes.
+    pub const MIN_LEN: usize = 240;
+
+    /// The value which distinguishes DHCP options from BOOTP vendor extensions.
+    pub const MAGIC_COOKIE: u32 = 0x63825363;
+
+    /// The broadcast bit of the flags, which asks the server to broadcast its replies.
+    pub const FLAG_BROADCAST: u16 = 0x8000;
+
+    pub const SERVER_PORT: u16 = 67;
+    pub const CLIENT_PORT: u16 = 68;
+
+    /// The options requested by the builders: subnet mask, router, DNS servers, domain name, lease time and server ID.
+    pub const DEFAULT_PARAMETERS: [u8; 6] = [
+        DhcpOption::SUBNET_MASK,
+        DhcpOption::ROUTER,
+        DhcpOption::DNS_SERVERS,
+        DhcpOption::DOMAIN_NAME,
+        DhcpOption::LEASE_TIME,
+        DhcpOption::SERVER_ID,
+    ];
+
+    const SERVER_NAME_LEN: usize = 64;
+    const BOOT_FILE_LEN: usize = 128;
+
+    /// Constructs a message of an Ethernet client without options.
+    pub fn new(opcode: BootpOpcode, transaction_id: u32, client_mac: MacAddr) -> Self {
+        let mut client_hardware_address = [0; 16];
+        client_hardware_address[..6].copy_from_slice(&client_mac.octets());
+
+        Self {
+            opcode,
+            hardware_type: HardwareType::Ethernet,
+            hardware_len: 6,
+            hops: 0,
+            transaction_id,
+            seconds: 0,
+            flags: 0,
+            client_ip: Ipv4Addr::new(0, 0, 0, 0),
+            your_ip: Ipv4Addr::new(0, 0, 0, 0),
+            server_ip: Ipv4Addr::new(0, 0, 0, 0),
+            relay_ip: Ipv4Addr::new(0, 0, 0, 0),
+            client_hardware_address,
+            server_name: Vec::new(),
+            boot_file: Vec::new(),
+            options: Vec::new(),
+        }
+    }
+
+    /// Constructs a DHCPDISCOVER which asks for broadcast replies and the default parameters.
+    pub fn discover(transaction_id: u32, client_mac: MacAddr) -> Self {
+        Self {
+            flags: Self::FLAG_BROADCAST,
+            options: vec![
+                DhcpOption::MessageType(DhcpMessageType::Discover),
+                DhcpOption::ClientId(client_id(client_mac)),
+                DhcpOption::ParameterRequestList(Self::DEFAULT_PARAMETERS.to_vec()),
+            ],
+            ..Self::new(BootpOpcode::BootRequest, transaction_id, client_mac)
+        }
+    }
+
+    /// Constructs a DHCPOFFER of the address in reply to the DHCPDISCOVER.
+    pub fn offer(discover: &DhcpLayer, your_ip: Ipv4Addr, server_id: Ipv4Addr, lease_time: u32) -> Self {
+        Self::reply(discover, DhcpMessageType::Offer, your_ip, server_id, lease_time)
+    }
+
+    /// Constructs a DHCPREQUEST which selects the address of the DHCPOFFER.
+    pub fn request(offer: &DhcpLayer) -> Self {
+        let mut options = vec![DhcpOption::MessageType(DhcpMessageType::Request)];
+        options.push(DhcpOption::ClientId(client_id(offer.client_mac())));
+        options.push(DhcpOption::RequestedIp(offer.your_ip));
+        options.extend(offer.server_id().map(DhcpOption::ServerId));
+        options.push(DhcpOption::ParameterRequestList(Self::DEFAULT_PARAMETERS.to_vec()));
+
+        Self {
+            flags: offer.flags,
+            relay_ip: offer.relay_ip,
+            client_hardware_address: offer.client_hardware_address,
+            options,
+            ..Self::new(BootpOpcode::BootRequest, offer.transaction_id, offer.client_mac())
+        }
+    }
+
+    /// Constructs a DHCPACK which confirms the lease of the address in reply to the DHCPREQUEST.
+    pub fn ack(request: &DhcpLayer, your_ip: Ipv4Addr, server_id: Ipv4Addr, lease_time: u32) -> Self {
+        Self::reply(request, DhcpMessageType::Ack, your_ip, server_id, lease_time)
+    }
+
+    /// Constructs a DHCPRELEASE which gives the address of the client back to the server.
+    pub fn release(transaction_id: u32, client_mac: MacAddr, client_ip: Ipv4Addr, server_id: Ipv4Addr) -> Self {
+        Self {
+            client_ip,
+            options: vec![
+                DhcpOption::MessageType(DhcpMessageType::Release),
+                DhcpOption::ClientId(client_id(client_mac)),
+                DhcpOption::ServerId(server_id),
+            ],
+            ..Self::new(BootpOpcode::BootRequest, transaction_id, client_mac)
+        }
+    }
+
+    fn reply(request: &DhcpLayer, message_type: DhcpMessageType, your_ip: Ipv4Addr, server_id: Ipv4Addr, lease_time: u32) -> Self {
+        Self {
+            hardware_type: request.hardware_type,
+            hardware_len: request.hardware_len,
+            flags: request.flags,
+            your_ip,
+            relay_ip: request.relay_ip,
+            client_hardware_address: request.client_hardware_address,
+            options: vec![
+                DhcpOption::MessageType(message_type),
+                DhcpOption::ServerId(server_id),
+                DhcpOption::LeaseTime(lease_time),
+            ],
+            ..Self::new(BootpOpcode::BootReply, request.transaction_id, request.client_mac())
+        }
+    }
+
+    /// Returns the type of the message, or `None` for a plain BOOTP message.
+    pub fn message_type(&self) -> Option<DhcpMessageType> {
+        self.options.iter().find_map(|option| match option {
+            DhcpOption::MessageType(message_type) => Some(*message_type),
+            _ => None,
+        })
+    }
+
+    /// Returns the address of the server given by the Server Identifier option.
+    pub fn server_id(&self) -> Option<Ipv4Addr> {
+        self.options.iter().find_map(|option| match option {
+            DhcpOption::ServerId(addr) => Some(*addr),
+            _ => None,
+        })
+    }
+
+    /// Returns the first option with the code.
+    pub fn option(&self, code: u8) -> Option<&DhcpOption> {
+        self.options.iter().find(|option| option.code() == code)
+    }
+
+    /// Returns the first six bytes of the client hardware address as a MAC address.
+    pub fn client_mac(&self) -> MacAddr {
+        MacAddr::from(<[u8; 6]>::try_from(&self.client_hardware_address[..6]).unwrap())
+    }
+
+    /// Checks if the client asks for broadcast replies.
+    pub fn is_broadcast(&self) -> bool {
+        self.flags & Self::FLAG_BROADCAST != 0
+    }
+
+    fn options_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.options.iter().for_each(|option| option.write(&mut buf));
+        buf.push(DhcpOption::END);
+        buf
+    }
+}
+
+/// Returns a client identifier made of the Ethernet hardware type followed by the address (RFC 2132 section 9.14).
+fn client_id(mac: MacAddr) -> Vec<u8> {
+    [&[HardwareType::Ethernet.value() as u8][..], &mac.octets()].concat()
+}
+
+impl Layer for DhcpLayer {
+    const NAME: &'static str = "DHCP";
+    const TYPE: LayerType = LayerType::DhcpLayer;
+    const OSI_LEVEL: OsiLevel = OsiLevel::Application;
+}
+
+impl DynLayer for DhcpLayer {
+    fn header_len(&self) -> usize {
+        Self::MIN_LEN + self.options.iter().map(DhcpOption::encoded_len).sum::<usize>() + 1
+    }
+
+    /// The bytes following the End option, which clients often add to reach the minimal BOOTP length, are padding.
+    fn payload_len(&self) -> Option<usize> {
+        Some(0)
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&[self.opcode.value(), self.hardware_type.value() as u8, self.hardware_len, self.hops]);
+        buf.extend_from_slice(&self.transaction_id.to_be_bytes());
+        buf.extend_from_slice(&self.seconds.to_be_bytes());
+        buf.extend_from_slice(&self.flags.to_be_bytes());
+        for addr in [self.client_ip, self.your_ip, self.server_ip, self.relay_ip] {
+            buf.extend_from_slice(&addr.octets());
+        }
+        buf.extend_from_slice(&self.client_hardware_address);
+
+        for (field, len) in [(&self.server_name, Self::SERVER_NAME_LEN), (&self.boot_file, Self::BOOT_FILE_LEN)] {
+            let start = buf.len();
+            buf.extend_from_slice(&field[..field.len().min(len)]);
+            buf.resize(start + len, 0);
+        }
+
+        buf.extend_from_slice(&Self::MAGIC_COOKIE.to_be_bytes());
+        buf.extend_from_slice(&self.options_bytes());
+    }
+
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        let view = DhcpView::new(bytes)?;
+        let (options, len) = view.options()?;
+        Ok((view.to_layer(options), DhcpLayer::MIN_LEN + len))
+    }
+
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+}
+
+impl Reflect for DhcpLayer {
+    fn fields(&self) -> Vec<Field> {
+        let uint = |value: u64| FieldValue::UInt(value);
+        let options = self.options_bytes();
+
+        vec![
+            Field::new("dhcp.op", FieldKind::Enum, 0, 8, self.opcode.field_value()),
+            Field::new("dhcp.htype", FieldKind::Enum, 8, 8, self.hardware_type.field_value()),
+            Field::new("dhcp.hlen", FieldKind::UInt, 16, 8, uint(self.hardware_len as u64)),
+            Field::new("dhcp.hops", FieldKind::UInt, 24, 8, uint(self.hops as u64)),
+            Field::new("dhcp.xid", FieldKind::UInt, 32, 32, uint(self.transaction_id as u64)),
+            Field::new("dhcp.secs", FieldKind::UInt, 64, 16, uint(self.seconds as u64)),
+            Field::new("dhcp.flags", FieldKind::UInt, 80, 16, uint(self.flags as u64)),
+            Field::new("dhcp.ciaddr", FieldKind::Ipv4, 96, 32, FieldValue::Ipv4(self.client_ip)),
+            Field::new("dhcp.yiaddr", FieldKind::Ipv4, 128, 32, FieldValue::Ipv4(self.your_ip)),
+            Field::new("dhcp.siaddr", FieldKind::Ipv4, 160, 32, FieldValue::Ipv4(self.server_ip)),
+            Field::new("dhcp.giaddr", FieldKind::Ipv4, 192, 32, FieldValue::Ipv4(self.relay_ip)),
+            Field::new("dhcp.chaddr", FieldKind::Bytes, 224, 128, FieldValue::Bytes(self.client_hardware_address.to_vec())),
+            Field::new("dhcp.sname", FieldKind::Bytes, 352, 512, FieldValue::Bytes(self.server_name.clone())),
+            Field::new("dhcp.file", FieldKind::Bytes, 864, 1024, FieldValue::Bytes(self.boot_file.clone())),
+            Field::new("dhcp.cookie", FieldKind::UInt, 1888, 32, uint(Self::MAGIC_COOKIE as u64)),
+            Field::new("dhcp.options", FieldKind::Bytes, 1920, options.len() * 8, FieldValue::Bytes(options)),
+        ]
+    }
+
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        let invalid = || FieldError::InvalidValue(value.to_string());
+        let bytes = |max: usize| match hex::decode(value.trim()) {
+            Ok(bytes) if bytes.len() <= max => Ok(bytes),
+            _ => Err(invalid()),
+        };
+
+        match path {
+            "dhcp.op" => self.opcode = BootpOpcode::parse_field(value)?,
+            "dhcp.htype" => match HardwareType::parse_field(value)? {
+                hardware_type if hardware_type.value() <= 0xff => self.hardware_type = hardware_type,
+                _ => return Err(invalid()),
+            },
+            "dhcp.hlen" => self.hardware_len = parse_uint_as(value)?,
+            "dhcp.hops" => self.hops = parse_uint_as(value)?,
+            "dhcp.xid" => self.transaction_id = parse_uint_as(value)?,
+            "dhcp.secs" => self.seconds = parse_uint_as(value)?,
+            "dhcp.flags" => self.flags = parse_uint_as(value)?,
+            "dhcp.ciaddr" => self.client_ip = parse_str(value)?,
+            "dhcp.yiaddr" => self.your_ip = parse_str(value)?,
+            "dhcp.siaddr" => self.server_ip = parse_str(value)?,
+            "dhcp.giaddr" => self.relay_ip = parse_str(value)?,
+            "dhcp.chaddr" => {
+                let bytes = bytes(16)?;
+                self.client_hardware_address = [0; 16];
+                self.client_hardware_address[..bytes.len()].copy_from_slice(&bytes);
+            }
+            "dhcp.sname" => self.server_name = bytes(Self::SERVER_NAME_LEN)?,
+            "dhcp.file" => self.boot_file = bytes(Self::BOOT_FILE_LEN)?,
+            "dhcp.options" => {
+                let bytes = hex::decode(value.trim()).map_err(|_| invalid())?;
+                match parse_dhcp_options(&bytes) {
+                    Ok((options, len)) if len == bytes.len() => self.options = options,
+                    _ => return Err(invalid()),
+                }
+            }
+            _ => return Err(FieldError::UnknownField(path.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
+/// A borrowed, zero-copy view of a DHCP message at the start of a byte slice.
+///
+/// The magic cookie is validated once on construction, and the options are only decoded when requested.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DhcpView<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> DhcpView<'a> {
+    /// Constructs a view of the DHCP message at the start of the slice.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, LayerError> {
+        if bytes.len() < DhcpLayer::MIN_LEN {
+            return Err(LayerError::BufferTooShort(DhcpLayer::NAME));
+        }
+
+        let view = Self { bytes };
+        if view.magic_cookie() != DhcpLayer::MAGIC_COOKIE {
+            return Err(DhcpError::InvalidMagicCookie(view.magic_cookie()).into());
+        }
+
+        Ok(view)
+    }
+
+    fn ip_at(&self, offset: usize) -> Ipv4Addr {
+        Ipv4Addr::from(<[u8; 4]>::try_from(&self.bytes[offset..offset + 4]).unwrap())
+    }
+
+    /// Returns the operation code.
+    pub fn opcode(&self) -> BootpOpcode {
+        BootpOpcode::from_value(self.bytes[0])
+    }
+
+    /// Returns the transaction ID.
+    pub fn transaction_id(&self) -> u32 {
+        u32::from_be_bytes(self.bytes[4..8].try_into().unwrap())
+    }
+
+    /// Returns the flags.
+    pub fn flags(&self) -> u16 {
+        u16::from_be_bytes([self.bytes[10], self.bytes[11]])
+    }
+
+    /// Returns the current address of the client.
+    pub fn client_ip(&self) -> Ipv4Addr {
+        self.ip_at(12)
+    }
+
+    /// Returns the address assigned to the client.
+    pub fn your_ip(&self) -> Ipv4Addr {
+        self.ip_at(16)
+    }
+
+    /// Returns the address of the next server.
+    pub fn server_ip(&self) -> Ipv4Addr {
+        self.ip_at(20)
+    }
+
+    /// Returns the address of the relay agent.
+    pub fn relay_ip(&self) -> Ipv4Addr {
+        self.ip_at(24)
+    }
+
+    /// Returns the client hardware address field.
+    pub fn client_hardware_address(&self) -> &'a [u8] {
+        &self.bytes[28..44]
+    }
+
+    /// Returns the magic cookie.
+    pub fn magic_cookie(&self) -> u32 {
+        u32::from_be_bytes(self.bytes[236..240].try_into().unwrap())
+    }
+
+    /// Returns the bytes following the magic cookie, up to the end of the slice.
+    pub fn options_raw(&self) -> &'a [u8] {
+        &self.bytes[DhcpLayer::MIN_LEN..]
+    }
+
+    /// Decodes the options together with the number of bytes they occupy, including the End option.
+    pub fn options(&self) -> Result<(Vec<DhcpOption>, usize), DhcpError> {
+        parse_dhcp_options(self.options_raw())
+    }
+
+    fn to_layer(self, options: Vec<DhcpOption>) -> DhcpLayer {
+        let trimmed = |field: &[u8]| field[..field.iter().rposition(|byte| *byte != 0).map_or(0, |i| i + 1)].to_vec();
+
+        DhcpLayer {
+            opcode: self.opcode(),
+            hardware_type: HardwareType::from_value(self.bytes[1] as u16),
+            hardware_len: self.bytes[2],
+            hops: self.bytes[3],
+            transaction_id: self.transaction_id(),
+            seconds: u16::from_be_bytes([self.bytes[8], self.bytes[9]]),
+            flags: self.flags(),
+            client_ip: self.client_ip(),
+            your_ip: self.your_ip(),
+            server_ip: self.server_ip(),
+            relay_ip: self.relay_ip(),
+            client_hardware_address: self.client_hardware_address().try_into().unwrap(),
+            server_name: trimmed(&self.bytes[44..108]),
+            boot_file: trimmed(&self.bytes[108..236]),
+            options,
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum DhcpError {
+    #[error("The magic cookie {0:#010x} does not start DHCP options.")]
+    InvalidMagicCookie(u32),
+
+    #[error("The DHCP option {0} is truncated.")]
+    MalformedOption(u8),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client() -> MacAddr {
+        MacAddr::new(0x00, 0x11, 0x22, 0x33, 0x44, 0x55)
+    }
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(DhcpLayer::NAME, "DHCP");
+        assert_eq!(DhcpLayer::TYPE, LayerType::DhcpLayer);
+        assert_eq!(DhcpLayer::OSI_LEVEL, OsiLevel::Application);
+    }
+
+    #[test]
+    fn parse_and_write() {
+        let discover = DhcpLayer::discover(0xdeadbeef, client());
+        let mut buf = Vec::new();
+        discover.write(&mut buf);
+        assert_eq!(buf.len(), discover.header_len());
+        assert_eq!(buf[..12], [1, 1, 6, 0, 0xde, 0xad, 0xbe, 0xef, 0, 0, 0x80, 0]);
+        assert_eq!(buf[236..243], [0x63, 0x82, 0x53, 0x63, 53, 1, 1]);
+        assert_eq!(buf.last(), Some(&DhcpOption::END));
+
+        // The zeros which pad the message to 300 bytes are not part of the layer
+        buf.resize(300, 0);
+        assert_eq!(DhcpLayer::parse(&buf), Ok((discover, 261)));
+
+        assert_eq!(DhcpLayer::parse(&buf[..239]), Err(LayerError::BufferTooShort("DHCP")));
+        buf[236] = 0;
+        assert_eq!(DhcpLayer::parse(&buf), Err(LayerError::Dhcp(DhcpError::InvalidMagicCookie(0x00825363))));
+    }
+
+    #[test]
+    fn exchange() {
+        let server = Ipv4Addr::new(192, 168, 1, 1);
+        let discover = DhcpLayer::discover(7, client());
+        assert_eq!(discover.message_type(), Some(DhcpMessageType::Discover));
+        assert!(discover.is_broadcast());
+
+        let offer = DhcpLayer::offer(&discover, Ipv4Addr::new(192, 168, 1, 100), server, 3600);
+        assert_eq!((offer.opcode, offer.transaction_id, offer.client_mac()), (BootpOpcode::BootReply, 7, client()));
+        assert_eq!(offer.server_id(), Some(server));
+
+        let request = DhcpLayer::request(&offer);
+        assert_eq!(request.message_type(), Some(DhcpMessageType::Request));
+        assert_eq!(request.option(DhcpOption::REQUESTED_IP), Some(&DhcpOption::RequestedIp(Ipv4Addr::new(192, 168, 1, 100))));
+        assert_eq!(request.server_id(), Some(server));
+
+        let ack = DhcpLayer::ack(&request, Ipv4Addr::new(192, 168, 1, 100), server, 3600);
+        assert_eq!(ack.message_type(), Some(DhcpMessageType::Ack));
+        assert_eq!(ack.option(DhcpOption::LEASE_TIME), Some(&DhcpOption::LeaseTime(3600)));
+
+        let release = DhcpLayer::release(8, client(), Ipv4Addr::new(192, 168, 1, 100), server);
+        assert_eq!((release.client_ip, release.server_id()), (Ipv4Addr::new(192, 168, 1, 100), Some(server)));
+        assert!(!release.is_broadcast());
+    }
+
+    #[test]
+    fn reflect() {
+        let mut layer = DhcpLayer::discover(1, client());
+        layer.set_field("dhcp.giaddr", "10.0.0.1").unwrap();
+        layer.set_field("dhcp.sname", "626f6f74").unwrap();
+        layer.set_field("dhcp.options", "350102ff").unwrap();
+        assert_eq!(layer.relay_ip, Ipv4Addr::new(10, 0, 0, 1));
+        assert_eq!(layer.server_name, b"boot");
+        assert_eq!(layer.message_type(), Some(DhcpMessageType::Offer));
+        assert_eq!(layer.get_field("dhcp.options"), Some(FieldValue::Bytes(vec![0x35, 1, 2, 0xff])));
+        assert_eq!(layer.set_field("dhcp.options", "3501"), Err(FieldError::InvalidValue("3501".to_string())));
+    }
+}
@@ -0,0 +1,350 @@
+use super::*;
+
+use crate::Ipv4Addr;
+
+registry_enum! {
+    /// The types of DHCP messages, carried by the DHCP Message Type option.
+    pub enum DhcpMessageType: u8 {
+        Discover = 1,
+        Offer = 2,
+        Request = 3,
+        Decline = 4,
+        Ack = 5,
+        Nak = 6,
+        Release = 7,
+        Inform = 8,
+    }
+}
+
+/// The longest data a single option or sub-option can hold in bytes.
+const MAX_DATA_LEN: usize = 255;
+
+/// Returns the length of the data together with the code and length fields, once split into parts of at most 255 bytes.
+fn split_len(data_len: usize) -> usize {
+    data_len + 2 * data_len.div_ceil(MAX_DATA_LEN).max(1)
+}
+
+/// Appends the data split into parts of at most 255 bytes, each of which is preceded by the code and its length.
+fn write_split(buf: &mut Vec<u8>, code: u8, data: &[u8]) {
+    if data.is_empty() {
+        return buf.extend_from_slice(&[code, 0]);
+    }
+
+    for part in data.chunks(MAX_DATA_LEN) {
+        buf.extend_from_slice(&[code, part.len() as u8]);
+        buf.extend_from_slice(part);
+    }
+}
+
+/// A sub-option of the Relay Agent Information option (RFC 3046).
+///
+/// Data longer than 255 bytes is written as consecutive sub-options of the same code.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RelayAgentSubOption {
+    pub code: u8,
+    pub data: Vec<u8>,
+}
+
+impl RelayAgentSubOption {
+    pub const CIRCUIT_ID: u8 = 1;
+    pub const REMOTE_ID: u8 = 2;
+
+    /// Constructs an Agent Circuit ID sub-option, which identifies the port the request was received on.
+    pub fn circuit_id(data: &[u8]) -> Self {
+        Self { code: Self::CIRCUIT_ID, data: data.to_vec() }
+    }
+
+    /// Constructs an Agent Remote ID sub-option, which identifies the remote end of the circuit.
+    pub fn remote_id(data: &[u8]) -> Self {
+        Self { code: Self::REMOTE_ID, data: data.to_vec() }
+    }
+}
+
+/// An option of a DHCP message (RFC 2132).
+///
+/// The End option is not represented, since it is written automatically after the last option. Options longer than 255
+/// bytes are split into several instances of the same code (RFC 3396).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum DhcpOption {
+    Pad,
+    SubnetMask(Ipv4Addr),
+    Router(Vec<Ipv4Addr>),
+    DnsServers(Vec<Ipv4Addr>),
+    HostName(String),
+    DomainName(String),
+    RequestedIp(Ipv4Addr),
+    /// The lease time in seconds, where `u32::MAX` means infinity.
+    LeaseTime(u32),
+    MessageType(DhcpMessageType),
+    ServerId(Ipv4Addr),
+    /// The codes of the options the client asks for.
+    ParameterRequestList(Vec<u8>),
+    /// The time in seconds until the client starts to renew its lease (T1).
+    RenewalTime(u32),
+    /// The time in seconds until the client starts to rebind its lease (T2).
+    RebindingTime(u32),
+    /// An opaque client identifier, which usually starts with a hardware type followed by the hardware address.
+    ClientId(Vec<u8>),
+    /// Information inserted by a relay agent (RFC 3046).
+    RelayAgentInformation(Vec<RelayAgentSubOption>),
+    /// An option which is either not supported or whose contents do not match its code.
+    Unknown { code: u8, data: Vec<u8> },
+}
+
+impl DhcpOption {
+    pub const PAD: u8 = 0;
+    pub const SUBNET_MASK: u8 = 1;
+    pub const ROUTER: u8 = 3;
+    pub const DNS_SERVERS: u8 = 6;
+    pub const HOST_NAME: u8 = 12;
+    pub const DOMAIN_NAME: u8 = 15;
+    pub const REQUESTED_IP: u8 = 50;
+    pub const LEASE_TIME: u8 = 51;
+    pub const MESSAGE_TYPE: u8 = 53;
+    pub const SERVER_ID: u8 = 54;
+    pub const PARAMETER_REQUEST_LIST: u8 = 55;
+    pub const RENEWAL_TIME: u8 = 58;
+    pub const REBINDING_TIME: u8 = 59;
+    pub const CLIENT_ID: u8 = 61;
+    pub const RELAY_AGENT_INFORMATION: u8 = 82;
+    pub const END: u8 = 255;
+
+    /// Returns the option code.
+    pub fn code(&self) -> u8 {
+        match self {
+            DhcpOption::Pad => Self::PAD,
+            DhcpOption::SubnetMask(_) => Self::SUBNET_MASK,
+            DhcpOption::Router(_) => Self::ROUTER,
+            DhcpOption::DnsServers(_) => Self::DNS_SERVERS,
+            DhcpOption::HostName(_) => Self::HOST_NAME,
+            DhcpOption::DomainName(_) => Self::DOMAIN_NAME,
+            DhcpOption::RequestedIp(_) => Self::REQUESTED_IP,
+            DhcpOption::LeaseTime(_) => Self::LEASE_TIME,
+            DhcpOption::MessageType(_) => Self::MESSAGE_TYPE,
+            DhcpOption::ServerId(_) => Self::SERVER_ID,
+            DhcpOption::ParameterRequestList(_) => Self::PARAMETER_REQUEST_LIST,
+            DhcpOption::RenewalTime(_) => Self::RENEWAL_TIME,
+            DhcpOption::RebindingTime(_) => Self::REBINDING_TIME,
+            DhcpOption::ClientId(_) => Self::CLIENT_ID,
+            DhcpOption::RelayAgentInformation(_) => Self::RELAY_AGENT_INFORMATION,
+            DhcpOption::Unknown { code, .. } => *code,
+        }
+    }
+
+    /// Returns the length of the serialized option in bytes, including the headers of the parts it is split into.
+    pub fn encoded_len(&self) -> usize {
+        let data_len = match self {
+            DhcpOption::Pad => return 1,
+            DhcpOption::SubnetMask(_) | DhcpOption::RequestedIp(_) | DhcpOption::ServerId(_) => 4,
+            DhcpOption::LeaseTime(_) | DhcpOption::RenewalTime(_) | DhcpOption::RebindingTime(_) => 4,
+            DhcpOption::Router(addrs) | DhcpOption::DnsServers(addrs) => addrs.len() * 4,
+            DhcpOption::HostName(name) | DhcpOption::DomainName(name) => name.len(),
+            DhcpOption::MessageType(_) => 1,
+            DhcpOption::ParameterRequestList(data) | DhcpOption::ClientId(data) | DhcpOption::Unknown { data, .. } => data.len(),
+            DhcpOption::RelayAgentInformation(sub_options) => sub_options.iter().map(|sub| split_len(sub.data.len())).sum(),
+        };
+
+        split_len(data_len)
+    }
+
+    /// Appends the serialized option to the buffer.
+    pub fn write(&self, buf: &mut Vec<u8>) {
+        match self {
+            DhcpOption::Pad => buf.push(Self::PAD),
+            _ => write_split(buf, self.code(), &self.data()),
+        }
+    }
+
+    /// Returns the encoded data of the option.
+    fn data(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+
+        match self {
+            DhcpOption::Pad => {}
+            DhcpOption::SubnetMask(addr) | DhcpOption::RequestedIp(addr) | DhcpOption::ServerId(addr) => {
+                buf.extend_from_slice(&addr.octets())
+            }
+            DhcpOption::LeaseTime(time) | DhcpOption::RenewalTime(time) | DhcpOption::RebindingTime(time) => {
+                buf.extend_from_slice(&time.to_be_bytes())
+            }
+            DhcpOption::Router(addrs) | DhcpOption::DnsServers(addrs) => {
+                addrs.iter().for_each(|addr| buf.extend_from_slice(&addr.octets()))
+            }
+            DhcpOption::HostName(name) | DhcpOption::DomainName(name) => buf.extend_from_slice(name.as_bytes()),
+            DhcpOption::MessageType(message_type) => buf.push(message_type.value()),
+            DhcpOption::ParameterRequestList(data) | DhcpOption::ClientId(data) | DhcpOption::Unknown { data, .. } => {
+                buf.extend_from_slice(data)
+            }
+            DhcpOption::RelayAgentInformation(sub_options) => {
+                sub_options.iter().for_each(|sub| write_split(&mut buf, sub.code, &sub.data));
+            }
+        }
+
+        buf
+    }
+
+    /// Parses an option other than End from the start of the buffer and returns it together with the number of bytes
+    /// consumed.
+    ///
+    /// Options whose contents do not match their code are returned as [`DhcpOption::Unknown`], so that they are preserved.
+    pub fn parse(bytes: &[u8]) -> Result<(Self, usize), DhcpError> {
+        let (code, data, len) = parse_raw(bytes)?;
+        Ok((Self::decode(code, data), len))
+    }
+
+    /// Decodes the data of an option with the code.
+    fn decode(code: u8, data: &[u8]) -> Self {
+        if code == Self::PAD {
+            return DhcpOption::Pad;
+        }
+
+        let len = data.len();
+        let addr = || Ipv4Addr::from(<[u8; 4]>::try_from(data).unwrap());
+        let time = || u32::from_be_bytes(data.try_into().unwrap());
+        let addrs = || data.chunks_exact(4).map(|chunk| Ipv4Addr::from(<[u8; 4]>::try_from(chunk).unwrap())).collect();
+        let string = || String::from_utf8(data.to_vec()).ok();
+
+        let option = match code {
+            Self::SUBNET_MASK if len == 4 => Some(DhcpOption::SubnetMask(addr())),
+            Self::ROUTER if len > 0 && len.is_multiple_of(4) => Some(DhcpOption::Router(addrs())),
+            Self::DNS_SERVERS if len > 0 && len.is_multiple_of(4) => Some(DhcpOption::DnsServers(addrs())),
+            Self::HOST_NAME => string().map(DhcpOption::HostName),
+            Self::DOMAIN_NAME => string().map(DhcpOption::DomainName),
+            Self::REQUESTED_IP if len == 4 => Some(DhcpOption::RequestedIp(addr())),
+            Self::LEASE_TIME if len == 4 => Some(DhcpOption::LeaseTime(time())),
+            Self::MESSAGE_TYPE if len == 1 => Some(DhcpOption::MessageType(DhcpMessageType::from_value(data[0]))),
+            Self::SERVER_ID if len == 4 => Some(DhcpOption::ServerId(addr())),
+            Self::PARAMETER_REQUEST_LIST => Some(DhcpOption::ParameterRequestList(data.to_vec())),
+            Self::RENEWAL_TIME if len == 4 => Some(DhcpOption::RenewalTime(time())),
+            Self::REBINDING_TIME if len == 4 => Some(DhcpOption::RebindingTime(time())),
+            Self::CLIENT_ID if len > 0 => Some(DhcpOption::ClientId(data.to_vec())),
+            Self::RELAY_AGENT_INFORMATION => parse_sub_options(data).map(DhcpOption::RelayAgentInformation),
+            _ => None,
+        };
+
+        option.unwrap_or_else(|| DhcpOption::Unknown { code, data: data.to_vec() })
+    }
+}
+
+/// Splits the option at the start of the buffer into its code and data, and returns them with the number of bytes
+/// consumed.
+fn parse_raw(bytes: &[u8]) -> Result<(u8, &[u8], usize), DhcpError> {
+    let code = *bytes.first().ok_or(DhcpError::MalformedOption(DhcpOption::END))?;
+    if code == DhcpOption::PAD {
+        return Ok((code, &[], 1));
+    }
+
+    let len = *bytes.get(1).ok_or(DhcpError::MalformedOption(code))? as usize;
+    let data = bytes.get(2..2 + len).ok_or(DhcpError::MalformedOption(code))?;
+    Ok((code, data, 2 + len))
+}
+
+fn parse_sub_options(mut bytes: &[u8]) -> Option<Vec<RelayAgentSubOption>> {
+    let mut sub_options = Vec::new();
+
+    while let [code, len, rest @ ..] = bytes {
+        let data = rest.get(..*len as usize)?;
+        sub_options.push(RelayAgentSubOption { code: *code, data: data.to_vec() });
+        bytes = &rest[data.len()..];
+    }
+
+    bytes.is_empty().then_some(sub_options)
+}
+
+/// Parses the options following the magic cookie up to the End option, and returns them together with the number of
+/// bytes consumed including the End option. A missing End option is tolerated.
+///
+/// Several instances of an option with the same code are concatenated into a single option at the position of the first
+/// one (RFC 3396).
+pub fn parse_dhcp_options(bytes: &[u8]) -> Result<(Vec<DhcpOption>, usize), DhcpError> {
+    let mut parts: Vec<(u8, Vec<u8>)> = Vec::new();
+    let mut offset = 0;
+
+    while offset < bytes.len() && bytes[offset] != DhcpOption::END {
+        let (code, data, len) = parse_raw(&bytes[offset..])?;
+        match parts.iter_mut().find(|(part_code, _)| code != DhcpOption::PAD && *part_code == code) {
+            Some((_, concatenated)) => concatenated.extend_from_slice(data),
+            None => parts.push((code, data.to_vec())),
+        }
+        offset += len;
+    }
+
+    let options = parts.iter().map(|(code, data)| DhcpOption::decode(*code, data)).collect();
+    match offset < bytes.len() {
+        true => Ok((options, offset + 1)),
+        false => Ok((options, offset)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(option: DhcpOption) -> Vec<u8> {
+        let mut buf = Vec::new();
+        option.write(&mut buf);
+        assert_eq!(buf.len(), option.encoded_len());
+        assert_eq!(DhcpOption::parse(&buf), Ok((option, buf.len())));
+        buf
+    }
+
+    #[test]
+    fn options() {
+        assert_eq!(roundtrip(DhcpOption::MessageType(DhcpMessageType::Discover)), [53, 1, 1]);
+        assert_eq!(roundtrip(DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0))), [1, 4, 255, 255, 255, 0]);
+        assert_eq!(roundtrip(DhcpOption::LeaseTime(86400)), [51, 4, 0, 1, 0x51, 0x80]);
+        assert_eq!(roundtrip(DhcpOption::Pad), [0]);
+
+        let servers = vec![Ipv4Addr::new(1, 1, 1, 1), Ipv4Addr::new(8, 8, 8, 8)];
+        assert_eq!(roundtrip(DhcpOption::DnsServers(servers)).len(), 10);
+        assert_eq!(roundtrip(DhcpOption::DomainName("example.com".to_string())).len(), 13);
+        assert_eq!(roundtrip(DhcpOption::ParameterRequestList(vec![1, 3, 6, 15])), [55, 4, 1, 3, 6, 15]);
+        assert_eq!(roundtrip(DhcpOption::ClientId(vec![1, 0, 0x11, 0x22, 0x33, 0x44, 0x55])).len(), 9);
+
+        let relay = DhcpOption::RelayAgentInformation(vec![
+            RelayAgentSubOption::circuit_id(b"eth0/1"),
+            RelayAgentSubOption::remote_id(&[0xaa, 0xbb]),
+        ]);
+        assert_eq!(roundtrip(relay), [82, 12, 1, 6, b'e', b't', b'h', b'0', b'/', b'1', 2, 2, 0xaa, 0xbb]);
+
+        assert_eq!(roundtrip(DhcpOption::Unknown { code: 121, data: vec![24, 10, 0, 0, 10, 0, 0, 1] }).len(), 10);
+    }
+
+    #[test]
+    fn long_options() {
+        // Options longer than 255 bytes are split and concatenated again
+        let option = DhcpOption::Unknown { code: 121, data: (0..=255).chain(0..44).collect() };
+        let mut buf = Vec::new();
+        option.write(&mut buf);
+        assert_eq!(buf.len(), option.encoded_len());
+        assert_eq!((&buf[..2], &buf[257..259]), (&[121, 255][..], &[121, 45][..]));
+
+        buf.extend_from_slice(&[53, 1, 1]);
+        buf.push(DhcpOption::END);
+        let message_type = DhcpOption::MessageType(DhcpMessageType::Discover);
+        assert_eq!(parse_dhcp_options(&buf), Ok((vec![option, message_type], buf.len())));
+
+        // Long sub-options are written as consecutive sub-options of the same code
+        let relay = DhcpOption::RelayAgentInformation(vec![RelayAgentSubOption::circuit_id(&[7; 300])]);
+        let mut buf = Vec::new();
+        relay.write(&mut buf);
+        assert_eq!(buf.len(), relay.encoded_len());
+        assert_eq!(buf.len(), 2 + 255 + 2 + 49);
+
+        let sub_options = vec![RelayAgentSubOption::circuit_id(&[7; 255]), RelayAgentSubOption::circuit_id(&[7; 45])];
+        assert_eq!(parse_dhcp_options(&buf), Ok((vec![DhcpOption::RelayAgentInformation(sub_options)], buf.len())));
+    }
+
+    #[test]
+    fn malformed() {
+        // A subnet mask of the wrong length and truncated relay agent sub-options are preserved as unknown
+        assert_eq!(DhcpOption::parse(&[1, 2, 255, 0]), Ok((DhcpOption::Unknown { code: 1, data: vec![255, 0] }, 4)));
+        assert_eq!(DhcpOption::parse(&[82, 2, 1, 5]), Ok((DhcpOption::Unknown { code: 82, data: vec![1, 5] }, 4)));
+        assert_eq!(DhcpOption::parse(&[51, 4, 0]), Err(DhcpError::MalformedOption(51)));
+
+        let bytes = [53, 1, 3, 0, 255, 0, 0];
+        let options = vec![DhcpOption::MessageType(DhcpMessageType::Request), DhcpOption::Pad];
+        assert_eq!(parse_dhcp_options(&bytes), Ok((options, 5)));
+        assert_eq!(parse_dhcp_options(&bytes[..3]).unwrap().1, 3);
+    }
+}
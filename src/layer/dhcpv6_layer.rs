@@ -0,0 +1,509 @@
+use super::*;
+
+use crate::{reflect::*, Ipv6Addr};
+
+use thiserror::Error;
+
+registry_enum! {
+    /// The types of DHCPv6 messages.
+    pub enum Dhcpv6MessageType: u8 {
+        Solicit = 1,
+        Advertise = 2,
+        Request = 3,
+        Confirm = 4,
+        Renew = 5,
+        Rebind = 6,
+        Reply = 7,
+        Release = 8,
+        Decline = 9,
+        Reconfigure = 10,
+        InformationRequest = 11,
+        RelayForw = 12,
+        RelayRepl = 13,
+    }
+}
+
+/// The header of a DHCPv6 message, which depends on whether it is exchanged between a client and a server or between
+/// relay agents.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Dhcpv6Message {
+    /// A message between a client and a server, identified by a 24-bit transaction ID.
+    Client { transaction_id: u32, options: Vec<Dhcpv6Option> },
+    /// A message between relay agents and servers, which carries another message in a Relay Message option.
+    Relay {
+        hop_count: u8,
+        /// An address which identifies the link of the client.
+        link_address: Ipv6Addr,
+        /// The address of the client or relay agent the message was received from.
+        peer_address: Ipv6Addr,
+        options: Vec<Dhcpv6Option>,
+    },
+}
+
+/// A DHCPv6 message (RFC 8415).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Dhcpv6Layer {
+    pub msg_type: Dhcpv6MessageType,
+    pub message: Dhcpv6Message,
+}
+
+impl Dhcpv6Layer {
+    /// The length of the header of client and server messages in bytes.
+    pub const CLIENT_HEADER_LEN: usize = 4;
+
+    /// The length of the header of relay messages in bytes.
+    pub const RELAY_HEADER_LEN: usize = 34;
+
+    /// The maximum number of relay agents a message may pass (RFC 8415 section 7.6). Relay Message options nested
+    /// deeper are not decoded.
+    pub const HOP_COUNT_LIMIT: usize = 32;
+
+    pub const CLIENT_PORT: u16 = 546;
+    pub const SERVER_PORT: u16 = 547;
+
+    /// The options requested by [`Dhcpv6Layer::solicit`]: DNS servers and the domain search list.
+    pub const DEFAULT_OPTION_REQUEST: [u16; 2] = [Dhcpv6Option::DNS_SERVERS, Dhcpv6Option::DOMAIN_LIST];
+
+    /// The link-scoped multicast address of all relay agents and servers (`ff02::1:2`).
+    pub fn all_relay_agents_and_servers() -> Ipv6Addr {
+        Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 1, 2)
+    }
+
+    /// Constructs a client or server message. The transaction ID is truncated to 24 bits.
+    pub fn new(msg_type: Dhcpv6MessageType, transaction_id: u32, options: Vec<Dhcpv6Option>) -> Self {
+        Self {
+            msg_type,
+            message: Dhcpv6Message::Client { transaction_id: transaction_id & 0xffffff, options },
+        }
+    }
+
+    /// Constructs a Solicit which asks for an address in an IA_NA with the IAID, and for the default options.
+    pub fn solicit(transaction_id: u32, client_id: Duid, iaid: u32) -> Self {
+        let options = vec![
+            Dhcpv6Option::ClientId(client_id),
+            Dhcpv6Option::ElapsedTime(0),
+            Dhcpv6Option::OptionRequest(Self::DEFAULT_OPTION_REQUEST.to_vec()),
+            Dhcpv6Option::IaNa(IdentityAssociation { iaid, ..IdentityAssociation::default() }),
+        ];
+
+        Self::new(Dhcpv6MessageType::Solicit, transaction_id, options)
+    }
+
+    /// Constructs an Information-request, which asks for configuration without addresses.
+    pub fn information_request(transaction_id: u32, client_id: Duid) -> Self {
+        let options = vec![
+            Dhcpv6Option::ClientId(client_id),
+            Dhcpv6Option::ElapsedTime(0),
+            Dhcpv6Option::OptionRequest(Self::DEFAULT_OPTION_REQUEST.to_vec()),
+        ];
+
+        Self::new(Dhcpv6MessageType::InformationRequest, transaction_id, options)
+    }
+
+    /// Constructs a message of the client in reply to a server message, such as a Request after an Advertise or a Renew
+    /// after a Reply. The server ID and the identity associations of the server message are echoed.
+    pub fn client_reply(server_message: &Dhcpv6Layer, msg_type: Dhcpv6MessageType, client_id: Duid) -> Self {
+        let mut options = vec![Dhcpv6Option::ClientId(client_id)];
+        if msg_type != Dhcpv6MessageType::Rebind {
+            options.extend(server_message.server_id().cloned().map(Dhcpv6Option::ServerId));
+        }
+        options.push(Dhcpv6Option::ElapsedTime(0));
+        let associations = server_message.options().iter().filter(|option| {
+            matches!(option, Dhcpv6Option::IaNa(_) | Dhcpv6Option::IaPd(_))
+        });
+        options.extend(associations.cloned());
+
+        Self::new(msg_type, server_message.transaction_id().unwrap_or(0), options)
+    }
+
+    /// Constructs a message of the server in reply to a client message, such as an Advertise after a Solicit or a Reply
+    /// after a Request. The transaction ID and the client ID are echoed, followed by the server ID and the options.
+    pub fn server_reply(
+        client_message: &Dhcpv6Layer,
+        msg_type: Dhcpv6MessageType,
+        server_id: Duid,
+        options: Vec<Dhcpv6Option>,
+    ) -> Self {
+        let mut reply_options = Vec::new();
+        reply_options.extend(client_message.client_id().cloned().map(Dhcpv6Option::ClientId));
+        reply_options.push(Dhcpv6Option::ServerId(server_id));
+        reply_options.extend(options);
+
+        Self::new(msg_type, client_message.transaction_id().unwrap_or(0), reply_options)
+    }
+
+    /// Constructs a Relay-forward which carries the message received from the peer.
+    pub fn relay_forward(message: Dhcpv6Layer, link_address: Ipv6Addr, peer_address: Ipv6Addr) -> Self {
+        let hop_count = match &message.message {
+            Dhcpv6Message::Relay { hop_count, .. } => hop_count.saturating_add(1),
+            Dhcpv6Message::Client { .. } => 0,
+        };
+
+        Self {
+            msg_type: Dhcpv6MessageType::RelayForw,
+            message: Dhcpv6Message::Relay {
+                hop_count,
+                link_address,
+                peer_address,
+                options: vec![Dhcpv6Option::RelayMessage(Box::new(message))],
+            },
+        }
+    }
+
+    /// Constructs a Relay-reply which carries the message to the peer of the Relay-forward, echoing its Interface-Id.
+    pub fn relay_reply(relay_forward: &Dhcpv6Layer, message: Dhcpv6Layer) -> Self {
+        let interface_id = relay_forward.options().iter().filter(|option| matches!(option, Dhcpv6Option::InterfaceId(_)));
+        let mut options: Vec<Dhcpv6Option> = interface_id.cloned().collect();
+        options.push(Dhcpv6Option::RelayMessage(Box::new(message)));
+
+        let (hop_count, link_address, peer_address) = match &relay_forward.message {
+            Dhcpv6Message::Relay { hop_count, link_address, peer_address, .. } => (*hop_count, *link_address, *peer_address),
+            Dhcpv6Message::Client { .. } => (0, Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0), Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0)),
+        };
+
+        Self {
+            msg_type: Dhcpv6MessageType::RelayRepl,
+            message: Dhcpv6Message::Relay { hop_count, link_address, peer_address, options },
+        }
+    }
+
+    /// Checks if the message is exchanged between relay agents and servers.
+    pub fn is_relay(&self) -> bool {
+        matches!(self.message, Dhcpv6Message::Relay { .. })
+    }
+
+    /// Returns the transaction ID of a client or server message.
+    pub fn transaction_id(&self) -> Option<u32> {
+        match self.message {
+            Dhcpv6Message::Client { transaction_id, .. } => Some(transaction_id),
+            Dhcpv6Message::Relay { .. } => None,
+        }
+    }
+
+    /// Returns the options.
+    pub fn options(&self) -> &[Dhcpv6Option] {
+        match &self.message {
+            Dhcpv6Message::Client { options, .. } | Dhcpv6Message::Relay { options, .. } => options,
+        }
+    }
+
+    /// Returns the options mutably.
+    pub fn options_mut(&mut self) -> &mut Vec<Dhcpv6Option> {
+        match &mut self.message {
+            Dhcpv6Message::Client { options, .. } | Dhcpv6Message::Relay { options, .. } => options,
+        }
+    }
+
+    /// Returns the first option with the code.
+    pub fn option(&self, code: u16) -> Option<&Dhcpv6Option> {
+        self.options().iter().find(|option| option.code() == code)
+    }
+
+    /// Returns the DUID of the Client Identifier option.
+    pub fn client_id(&self) -> Option<&Duid> {
+        self.options().iter().find_map(|option| match option {
+            Dhcpv6Option::ClientId(duid) => Some(duid),
+            _ => None,
+        })
+    }
+
+    /// Returns the DUID of the Server Identifier option.
+    pub fn server_id(&self) -> Option<&Duid> {
+        self.options().iter().find_map(|option| match option {
+            Dhcpv6Option::ServerId(duid) => Some(duid),
+            _ => None,
+        })
+    }
+
+    /// Returns the message carried by the Relay Message option of a relay message.
+    pub fn relayed_message(&self) -> Option<&Dhcpv6Layer> {
+        self.options().iter().find_map(|option| match option {
+            Dhcpv6Option::RelayMessage(message) => Some(message.as_ref()),
+            _ => None,
+        })
+    }
+
+    /// Follows the relayed messages down to the client or server message, which may be this message itself.
+    pub fn innermost(&self) -> &Dhcpv6Layer {
+        let mut message = self;
+        while let Some(relayed) = message.relayed_message() {
+            message = relayed;
+        }
+        message
+    }
+
+    fn fixed_header_len(&self) -> usize {
+        match self.message {
+            Dhcpv6Message::Client { .. } => Self::CLIENT_HEADER_LEN,
+            Dhcpv6Message::Relay { .. } => Self::RELAY_HEADER_LEN,
+        }
+    }
+
+    fn options_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.options().iter().for_each(|option| option.write(&mut buf));
+        buf
+    }
+}
+
+impl Layer for Dhcpv6Layer {
+    const NAME: &'static str = "DHCPv6";
+    const TYPE: LayerType = LayerType::Dhcpv6Layer;
+    const OSI_LEVEL: OsiLevel = OsiLevel::Application;
+}
+
+impl DynLayer for Dhcpv6Layer {
+    fn header_len(&self) -> usize {
+        self.fixed_header_len() + self.options().iter().map(Dhcpv6Option::encoded_len).sum::<usize>()
+    }
+
+    fn payload_len(&self) -> Option<usize> {
+        Some(0)
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.push(self.msg_type.value());
+
+        match &self.message {
+            Dhcpv6Message::Client { transaction_id, .. } => buf.extend_from_slice(&transaction_id.to_be_bytes()[1..]),
+            Dhcpv6Message::Relay { hop_count, link_address, peer_address, .. } => {
+                buf.push(*hop_count);
+                buf.extend_from_slice(&link_address.octets());
+                buf.extend_from_slice(&peer_address.octets());
+            }
+        }
+
+        self.options().iter().for_each(|option| option.write(buf));
+    }
+
+    /// The options fill the rest of the buffer. Relay messages are recognized by their type.
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        Self::parse_relayed(bytes, 0)
+    }
+
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+}
+
+impl Dhcpv6Layer {
+    /// Parses a message which is relayed by the specified number of enclosing Relay Message options.
+    pub(crate) fn parse_relayed(bytes: &[u8], relay_depth: usize) -> Result<(Self, usize), LayerError> {
+        let scope = OptionScope::Message { relay_depth };
+        let msg_type = Dhcpv6MessageType::from_value(*bytes.first().ok_or(LayerError::BufferTooShort(Self::NAME))?);
+
+        let message = match msg_type {
+            Dhcpv6MessageType::RelayForw | Dhcpv6MessageType::RelayRepl => {
+                let header = bytes.get(..Self::RELAY_HEADER_LEN).ok_or(LayerError::BufferTooShort(Self::NAME))?;
+                Dhcpv6Message::Relay {
+                    hop_count: header[1],
+                    link_address: Ipv6Addr::from(<[u8; 16]>::try_from(&header[2..18]).unwrap()),
+                    peer_address: Ipv6Addr::from(<[u8; 16]>::try_from(&header[18..34]).unwrap()),
+                    options: parse_options_in(&bytes[Self::RELAY_HEADER_LEN..], scope)?,
+                }
+            }
+            _ => {
+                let header = bytes.get(..Self::CLIENT_HEADER_LEN).ok_or(LayerError::BufferTooShort(Self::NAME))?;
+                Dhcpv6Message::Client {
+                    transaction_id: u32::from_be_bytes([0, header[1], header[2], header[3]]),
+                    options: parse_options_in(&bytes[Self::CLIENT_HEADER_LEN..], scope)?,
+                }
+            }
+        };
+
+        Ok((Self { msg_type, message }, bytes.len()))
+    }
+}
+
+impl Reflect for Dhcpv6Layer {
+    fn fields(&self) -> Vec<Field> {
+        let mut fields = vec![Field::new("dhcpv6.msgtype", FieldKind::Enum, 0, 8, self.msg_type.field_value())];
+
+        match &self.message {
+            Dhcpv6Message::Client { transaction_id, .. } => {
+                fields.push(Field::new("dhcpv6.trid", FieldKind::UInt, 8, 24, FieldValue::UInt(*transaction_id as u64)));
+            }
+            Dhcpv6Message::Relay { hop_count, link_address, peer_address, .. } => {
+                fields.push(Field::new("dhcpv6.hopcount", FieldKind::UInt, 8, 8, FieldValue::UInt(*hop_count as u64)));
+                fields.push(Field::new("dhcpv6.linkaddr", FieldKind::Ipv6, 16, 128, FieldValue::Ipv6(*link_address)));
+                fields.push(Field::new("dhcpv6.peeraddr", FieldKind::Ipv6, 144, 128, FieldValue::Ipv6(*peer_address)));
+            }
+        }
+
+        let options = self.options_bytes();
+        let offset = self.fixed_header_len() * 8;
+        fields.push(Field::new("dhcpv6.options", FieldKind::Bytes, offset, options.len() * 8, FieldValue::Bytes(options)));
+
+        fields
+    }
+
+    /// The message type can only be changed within client and server messages or within relay messages, since the
+    /// header fields differ.
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        let invalid = || FieldError::InvalidValue(value.to_string());
+
+        match (path, &mut self.message) {
+            ("dhcpv6.msgtype", message) => {
+                let msg_type = Dhcpv6MessageType::parse_field(value)?;
+                let relay = matches!(msg_type, Dhcpv6MessageType::RelayForw | Dhcpv6MessageType::RelayRepl);
+                if relay != matches!(message, Dhcpv6Message::Relay { .. }) {
+                    return Err(invalid());
+                }
+                self.msg_type = msg_type;
+            }
+            ("dhcpv6.trid", Dhcpv6Message::Client { transaction_id, .. }) => match parse_uint(value)? {
+                parsed if parsed <= 0xffffff => *transaction_id = parsed as u32,
+                _ => return Err(invalid()),
+            },
+            ("dhcpv6.hopcount", Dhcpv6Message::Relay { hop_count, .. }) => *hop_count = parse_uint_as(value)?,
+            ("dhcpv6.linkaddr", Dhcpv6Message::Relay { link_address, .. }) => *link_address = parse_str(value)?,
+            ("dhcpv6.peeraddr", Dhcpv6Message::Relay { peer_address, .. }) => *peer_address = parse_str(value)?,
+            ("dhcpv6.options", _) => {
+                let bytes = hex::decode(value.trim()).map_err(|_| invalid())?;
+                *self.options_mut() = parse_dhcpv6_options(&bytes).map_err(|_| invalid())?;
+            }
+            _ => return Err(FieldError::UnknownField(path.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum Dhcpv6Error {
+    #[error("The DHCPv6 option {0} is truncated.")]
+    MalformedOption(u16),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MacAddr;
+
+    fn client() -> Duid {
+        Duid::link_layer(MacAddr::new(0x00, 0x11, 0x22, 0x33, 0x44, 0x55))
+    }
+
+    fn server() -> Duid {
+        Duid::Enterprise { enterprise_number: 32473, identifier: vec![0xaa; 4] }
+    }
+
+    fn lease() -> Dhcpv6Option {
+        let address = IaAddress {
+            address: Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0x100),
+            preferred_lifetime: 3600,
+            valid_lifetime: 7200,
+            options: vec![],
+        };
+        Dhcpv6Option::IaNa(IdentityAssociation { iaid: 1, t1: 1800, t2: 2880, options: vec![Dhcpv6Option::IaAddress(address)] })
+    }
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(Dhcpv6Layer::NAME, "DHCPv6");
+        assert_eq!(Dhcpv6Layer::TYPE, LayerType::Dhcpv6Layer);
+        assert_eq!(Dhcpv6Layer::OSI_LEVEL, OsiLevel::Application);
+    }
+
+    #[test]
+    fn parse_and_write() {
+        let solicit = Dhcpv6Layer::solicit(0x1a2b3c4d, client(), 1);
+        assert_eq!(solicit.transaction_id(), Some(0x2b3c4d));
+
+        let mut buf = Vec::new();
+        solicit.write(&mut buf);
+        assert_eq!(buf.len(), solicit.header_len());
+        assert_eq!(buf[..8], [1, 0x2b, 0x3c, 0x4d, 0, 1, 0, 10]);
+        assert_eq!(Dhcpv6Layer::parse(&buf), Ok((solicit, 4 + 14 + 6 + 8 + 16)));
+
+        assert_eq!(Dhcpv6Layer::parse(&[1, 0, 0]), Err(LayerError::BufferTooShort("DHCPv6")));
+        assert_eq!(Dhcpv6Layer::parse(&[1, 0, 0, 0, 0, 8]), Err(LayerError::Dhcpv6(Dhcpv6Error::MalformedOption(0))));
+    }
+
+    #[test]
+    fn exchange() {
+        let solicit = Dhcpv6Layer::solicit(7, client(), 1);
+        let advertise = Dhcpv6Layer::server_reply(&solicit, Dhcpv6MessageType::Advertise, server(), vec![lease()]);
+        assert_eq!((advertise.transaction_id(), advertise.client_id()), (Some(7), Some(&client())));
+
+        let request = Dhcpv6Layer::client_reply(&advertise, Dhcpv6MessageType::Request, client());
+        assert_eq!(request.server_id(), Some(&server()));
+        assert_eq!(request.option(Dhcpv6Option::IA_NA), Some(&lease()));
+
+        let rebind = Dhcpv6Layer::client_reply(&advertise, Dhcpv6MessageType::Rebind, client());
+        assert_eq!(rebind.server_id(), None);
+
+        let info = Dhcpv6Layer::information_request(8, client());
+        assert_eq!(info.option(Dhcpv6Option::IA_NA), None);
+    }
+
+    #[test]
+    fn relay() {
+        let solicit = Dhcpv6Layer::solicit(7, client(), 1);
+        let link = Ipv6Addr::new(0x2001, 0xdb8, 0, 1, 0, 0, 0, 1);
+        let peer = Ipv6Addr::new(0xfe80, 0, 0, 0, 0x0211, 0x22ff, 0xfe33, 0x4455);
+
+        let mut inner = Dhcpv6Layer::relay_forward(solicit.clone(), link, peer);
+        inner.options_mut().insert(0, Dhcpv6Option::InterfaceId(b"eth0".to_vec()));
+        let outer = Dhcpv6Layer::relay_forward(inner.clone(), Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0), link);
+
+        let mut buf = Vec::new();
+        outer.write(&mut buf);
+        assert_eq!(buf[..2], [12, 1]);
+
+        // Relayed messages are decoded recursively
+        let (parsed, _) = Dhcpv6Layer::parse(&buf).unwrap();
+        assert_eq!(parsed, outer);
+        assert_eq!(parsed.relayed_message(), Some(&inner));
+        assert_eq!(parsed.innermost(), &solicit);
+
+        let advertise = Dhcpv6Layer::server_reply(&solicit, Dhcpv6MessageType::Advertise, server(), vec![]);
+        let reply = Dhcpv6Layer::relay_reply(&inner, advertise.clone());
+        assert_eq!(reply.msg_type, Dhcpv6MessageType::RelayRepl);
+        assert_eq!(reply.options()[0], Dhcpv6Option::InterfaceId(b"eth0".to_vec()));
+        assert_eq!(reply.innermost(), &advertise);
+    }
+
+    #[test]
+    fn nested_relays() {
+        let link = Ipv6Addr::new(0x2001, 0xdb8, 0, 1, 0, 0, 0, 1);
+        let mut message = Dhcpv6Layer::solicit(7, client(), 1);
+        for _ in 0..40 {
+            message = Dhcpv6Layer::relay_forward(message, link, link);
+        }
+
+        let mut buf = Vec::new();
+        message.write(&mut buf);
+
+        // Relay messages are decoded up to the hop count limit, deeper ones are kept as they are
+        let (parsed, _) = Dhcpv6Layer::parse(&buf).unwrap();
+        let mut relayed = &parsed;
+        for _ in 0..Dhcpv6Layer::HOP_COUNT_LIMIT {
+            relayed = relayed.relayed_message().unwrap();
+        }
+        assert!(relayed.is_relay());
+        assert!(matches!(relayed.options(), [Dhcpv6Option::Unknown { code: Dhcpv6Option::RELAY_MESSAGE, .. }]));
+
+        let mut written = Vec::new();
+        parsed.write(&mut written);
+        assert_eq!(written, buf);
+    }
+
+    #[test]
+    fn reflect() {
+        let mut layer = Dhcpv6Layer::solicit(7, client(), 1);
+        layer.set_field("dhcpv6.trid", "0xabcdef").unwrap();
+        layer.set_field("dhcpv6.msgtype", "Request").unwrap();
+        layer.set_field("dhcpv6.options", "000e0000").unwrap();
+        assert_eq!((layer.msg_type, layer.transaction_id()), (Dhcpv6MessageType::Request, Some(0xabcdef)));
+        assert_eq!(layer.options(), [Dhcpv6Option::RapidCommit]);
+
+        assert_eq!(layer.set_field("dhcpv6.msgtype", "RelayForw"), Err(FieldError::InvalidValue("RelayForw".to_string())));
+        assert_eq!(layer.set_field("dhcpv6.trid", "0x1000000"), Err(FieldError::InvalidValue("0x1000000".to_string())));
+        assert_eq!(layer.set_field("dhcpv6.hopcount", "1"), Err(FieldError::UnknownField("dhcpv6.hopcount".to_string())));
+    }
+}
@@ -0,0 +1,520 @@
+use super::*;
+
+use crate::{Ipv6Addr, MacAddr};
+
+registry_enum! {
+    /// The status codes of DHCPv6 Status Code options.
+    pub enum Dhcpv6StatusCode: u16 {
+        Success = 0,
+        UnspecFail = 1,
+        NoAddrsAvail = 2,
+        NoBinding = 3,
+        NotOnLink = 4,
+        UseMulticast = 5,
+        NoPrefixAvail = 6,
+    }
+}
+
+/// A DHCP Unique Identifier, which identifies a client or a server (RFC 8415 section 11).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Duid {
+    /// A link-layer address plus the time it was generated, in seconds since midnight of 1 January 2000 UTC (DUID-LLT).
+    LinkLayerTime { hardware_type: HardwareType, time: u32, address: Vec<u8> },
+    /// An identifier assigned by the vendor with the enterprise number (DUID-EN).
+    Enterprise { enterprise_number: u32, identifier: Vec<u8> },
+    /// A link-layer address (DUID-LL).
+    LinkLayer { hardware_type: HardwareType, address: Vec<u8> },
+    /// A UUID (RFC 6355).
+    Uuid([u8; 16]),
+    /// A DUID of an unknown type, or whose contents do not match its type.
+    Unknown { kind: u16, data: Vec<u8> },
+}
+
+impl Duid {
+    pub const LINK_LAYER_TIME: u16 = 1;
+    pub const ENTERPRISE: u16 = 2;
+    pub const LINK_LAYER: u16 = 3;
+    pub const UUID: u16 = 4;
+
+    /// Constructs a DUID-LL from an Ethernet address.
+    pub fn link_layer(mac: MacAddr) -> Self {
+        Duid::LinkLayer {
+            hardware_type: HardwareType::Ethernet,
+            address: mac.octets().to_vec(),
+        }
+    }
+
+    /// Returns the DUID type.
+    pub fn kind(&self) -> u16 {
+        match self {
+            Duid::LinkLayerTime { .. } => Self::LINK_LAYER_TIME,
+            Duid::Enterprise { .. } => Self::ENTERPRISE,
+            Duid::LinkLayer { .. } => Self::LINK_LAYER,
+            Duid::Uuid(_) => Self::UUID,
+            Duid::Unknown { kind, .. } => *kind,
+        }
+    }
+
+    /// Returns the length of the encoded DUID in bytes.
+    pub fn encoded_len(&self) -> usize {
+        match self {
+            Duid::LinkLayerTime { address, .. } => 8 + address.len(),
+            Duid::Enterprise { identifier, .. } => 6 + identifier.len(),
+            Duid::LinkLayer { address, .. } => 4 + address.len(),
+            Duid::Uuid(_) => 18,
+            Duid::Unknown { data, .. } => 2 + data.len(),
+        }
+    }
+
+    /// Appends the encoded DUID to the buffer.
+    pub fn write(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.kind().to_be_bytes());
+
+        match self {
+            Duid::LinkLayerTime { hardware_type, time, address } => {
+                buf.extend_from_slice(&hardware_type.value().to_be_bytes());
+                buf.extend_from_slice(&time.to_be_bytes());
+                buf.extend_from_slice(address);
+            }
+            Duid::Enterprise { enterprise_number, identifier } => {
+                buf.extend_from_slice(&enterprise_number.to_be_bytes());
+                buf.extend_from_slice(identifier);
+            }
+            Duid::LinkLayer { hardware_type, address } => {
+                buf.extend_from_slice(&hardware_type.value().to_be_bytes());
+                buf.extend_from_slice(address);
+            }
+            Duid::Uuid(uuid) => buf.extend_from_slice(uuid),
+            Duid::Unknown { data, .. } => buf.extend_from_slice(data),
+        }
+    }
+
+    /// Parses a DUID which fills the buffer. Returns `None` if the buffer is too short for the type.
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let (kind, data) = bytes.split_first_chunk::<2>()?;
+        let kind = u16::from_be_bytes(*kind);
+        let hardware_type = || HardwareType::from_value(u16::from_be_bytes([data[0], data[1]]));
+
+        let duid = match kind {
+            Self::LINK_LAYER_TIME if data.len() >= 6 => Duid::LinkLayerTime {
+                hardware_type: hardware_type(),
+                time: u32::from_be_bytes(data[2..6].try_into().unwrap()),
+                address: data[6..].to_vec(),
+            },
+            Self::ENTERPRISE if data.len() >= 4 => Duid::Enterprise {
+                enterprise_number: u32::from_be_bytes(data[..4].try_into().unwrap()),
+                identifier: data[4..].to_vec(),
+            },
+            Self::LINK_LAYER if data.len() >= 2 => Duid::LinkLayer { hardware_type: hardware_type(), address: data[2..].to_vec() },
+            Self::UUID if data.len() == 16 => Duid::Uuid(data.try_into().unwrap()),
+            _ => Duid::Unknown { kind, data: data.to_vec() },
+        };
+
+        Some(duid)
+    }
+}
+
+/// An Identity Association for Non-temporary Addresses or for Prefix Delegation.
+///
+/// Its options hold the leased addresses or prefixes and the status of the association.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct IdentityAssociation {
+    /// The identifier chosen by the client.
+    pub iaid: u32,
+    /// The time in seconds until the client renews its leases.
+    pub t1: u32,
+    /// The time in seconds until the client rebinds its leases.
+    pub t2: u32,
+    pub options: Vec<Dhcpv6Option>,
+}
+
+/// An address leased to a client in an IA_NA.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IaAddress {
+    pub address: Ipv6Addr,
+    pub preferred_lifetime: u32,
+    pub valid_lifetime: u32,
+    pub options: Vec<Dhcpv6Option>,
+}
+
+/// A prefix delegated to a client in an IA_PD.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IaPrefix {
+    pub preferred_lifetime: u32,
+    pub valid_lifetime: u32,
+    pub prefix_length: u8,
+    pub prefix: Ipv6Addr,
+    pub options: Vec<Dhcpv6Option>,
+}
+
+/// The part of a message in which options are parsed, which limits the options that are decoded recursively.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum OptionScope {
+    /// The options of a message which is relayed by the specified number of enclosing Relay Message options.
+    Message { relay_depth: usize },
+    /// The options of an IA_NA or IA_PD, which may contain addresses, prefixes and status codes.
+    IdentityAssociation,
+    /// The options of an IA Address or IA Prefix, which may only contain status codes.
+    Lease,
+}
+
+impl OptionScope {
+    /// Checks if an option with the code is decoded in this scope. Other options are kept as
+    /// [`Dhcpv6Option::Unknown`], which bounds the recursion of the parser.
+    fn allows(self, code: u16) -> bool {
+        match self {
+            OptionScope::Message { relay_depth } => code != Dhcpv6Option::RELAY_MESSAGE || relay_depth < Dhcpv6Layer::HOP_COUNT_LIMIT,
+            OptionScope::IdentityAssociation => {
+                matches!(code, Dhcpv6Option::IA_ADDRESS | Dhcpv6Option::IA_PREFIX | Dhcpv6Option::STATUS_CODE)
+            }
+            OptionScope::Lease => code == Dhcpv6Option::STATUS_CODE,
+        }
+    }
+
+    /// Returns the number of relay messages which enclose the scope.
+    fn relay_depth(self) -> usize {
+        match self {
+            OptionScope::Message { relay_depth } => relay_depth,
+            _ => 0,
+        }
+    }
+}
+
+/// An option of a DHCPv6 message (RFC 8415 section 21).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Dhcpv6Option {
+    ClientId(Duid),
+    ServerId(Duid),
+    IaNa(IdentityAssociation),
+    IaAddress(IaAddress),
+    /// The codes of the options the client asks for.
+    OptionRequest(Vec<u16>),
+    Preference(u8),
+    /// The time since the client started the exchange, in hundredths of a second.
+    ElapsedTime(u16),
+    /// The message relayed by a Relay-forward or Relay-reply message.
+    RelayMessage(Box<Dhcpv6Layer>),
+    StatusCode { code: Dhcpv6StatusCode, message: String },
+    RapidCommit,
+    /// An identifier of the interface a relay agent received the message on.
+    InterfaceId(Vec<u8>),
+    /// Recursive DNS servers (RFC 3646).
+    DnsServers(Vec<Ipv6Addr>),
+    /// A domain search list (RFC 3646).
+    DomainList(Vec<String>),
+    IaPd(IdentityAssociation),
+    IaPrefix(IaPrefix),
+    /// An option which is either not supported or whose contents do not match its code.
+    Unknown { code: u16, data: Vec<u8> },
+}
+
+impl Dhcpv6Option {
+    pub const CLIENT_ID: u16 = 1;
+    pub const SERVER_ID: u16 = 2;
+    pub const IA_NA: u16 = 3;
+    pub const IA_ADDRESS: u16 = 5;
+    pub const OPTION_REQUEST: u16 = 6;
+    pub const PREFERENCE: u16 = 7;
+    pub const ELAPSED_TIME: u16 = 8;
+    pub const RELAY_MESSAGE: u16 = 9;
+    pub const STATUS_CODE: u16 = 13;
+    pub const RAPID_COMMIT: u16 = 14;
+    pub const INTERFACE_ID: u16 = 18;
+    pub const DNS_SERVERS: u16 = 23;
+    pub const DOMAIN_LIST: u16 = 24;
+    pub const IA_PD: u16 = 25;
+    pub const IA_PREFIX: u16 = 26;
+
+    /// Returns the option code.
+    pub fn code(&self) -> u16 {
+        match self {
+            Dhcpv6Option::ClientId(_) => Self::CLIENT_ID,
+            Dhcpv6Option::ServerId(_) => Self::SERVER_ID,
+            Dhcpv6Option::IaNa(_) => Self::IA_NA,
+            Dhcpv6Option::IaAddress(_) => Self::IA_ADDRESS,
+            Dhcpv6Option::OptionRequest(_) => Self::OPTION_REQUEST,
+            Dhcpv6Option::Preference(_) => Self::PREFERENCE,
+            Dhcpv6Option::ElapsedTime(_) => Self::ELAPSED_TIME,
+            Dhcpv6Option::RelayMessage(_) => Self::RELAY_MESSAGE,
+            Dhcpv6Option::StatusCode { .. } => Self::STATUS_CODE,
+            Dhcpv6Option::RapidCommit => Self::RAPID_COMMIT,
+            Dhcpv6Option::InterfaceId(_) => Self::INTERFACE_ID,
+            Dhcpv6Option::DnsServers(_) => Self::DNS_SERVERS,
+            Dhcpv6Option::DomainList(_) => Self::DOMAIN_LIST,
+            Dhcpv6Option::IaPd(_) => Self::IA_PD,
+            Dhcpv6Option::IaPrefix(_) => Self::IA_PREFIX,
+            Dhcpv6Option::Unknown { code, .. } => *code,
+        }
+    }
+
+    /// Returns the length of the encoded option in bytes, including nested options and relayed messages.
+    pub fn encoded_len(&self) -> usize {
+        let options_len = |options: &[Dhcpv6Option]| options.iter().map(Dhcpv6Option::encoded_len).sum::<usize>();
+
+        let data_len = match self {
+            Dhcpv6Option::ClientId(duid) | Dhcpv6Option::ServerId(duid) => duid.encoded_len(),
+            Dhcpv6Option::IaNa(ia) | Dhcpv6Option::IaPd(ia) => 12 + options_len(&ia.options),
+            Dhcpv6Option::IaAddress(addr) => 24 + options_len(&addr.options),
+            Dhcpv6Option::OptionRequest(codes) => codes.len() * 2,
+            Dhcpv6Option::Preference(_) => 1,
+            Dhcpv6Option::ElapsedTime(_) => 2,
+            Dhcpv6Option::RelayMessage(message) => message.header_len(),
+            Dhcpv6Option::StatusCode { message, .. } => 2 + message.len(),
+            Dhcpv6Option::RapidCommit => 0,
+            Dhcpv6Option::InterfaceId(data) | Dhcpv6Option::Unknown { data, .. } => data.len(),
+            Dhcpv6Option::DnsServers(servers) => servers.len() * 16,
+            Dhcpv6Option::DomainList(domains) => domains.iter().map(|domain| encoded_name_len(domain)).sum(),
+            Dhcpv6Option::IaPrefix(prefix) => 25 + options_len(&prefix.options),
+        };
+
+        4 + data_len
+    }
+
+    /// Appends the encoded option to the buffer.
+    pub fn write(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.code().to_be_bytes());
+        buf.extend_from_slice(&((self.encoded_len() - 4) as u16).to_be_bytes());
+
+        match self {
+            Dhcpv6Option::ClientId(duid) | Dhcpv6Option::ServerId(duid) => duid.write(buf),
+            Dhcpv6Option::IaNa(ia) | Dhcpv6Option::IaPd(ia) => {
+                [ia.iaid, ia.t1, ia.t2].iter().for_each(|value| buf.extend_from_slice(&value.to_be_bytes()));
+                ia.options.iter().for_each(|option| option.write(buf));
+            }
+            Dhcpv6Option::IaAddress(addr) => {
+                buf.extend_from_slice(&addr.address.octets());
+                buf.extend_from_slice(&addr.preferred_lifetime.to_be_bytes());
+                buf.extend_from_slice(&addr.valid_lifetime.to_be_bytes());
+                addr.options.iter().for_each(|option| option.write(buf));
+            }
+            Dhcpv6Option::OptionRequest(codes) => codes.iter().for_each(|code| buf.extend_from_slice(&code.to_be_bytes())),
+            Dhcpv6Option::Preference(preference) => buf.push(*preference),
+            Dhcpv6Option::ElapsedTime(time) => buf.extend_from_slice(&time.to_be_bytes()),
+            Dhcpv6Option::RelayMessage(message) => message.write(buf),
+            Dhcpv6Option::StatusCode { code, message } => {
+                buf.extend_from_slice(&code.value().to_be_bytes());
+                buf.extend_from_slice(message.as_bytes());
+            }
+            Dhcpv6Option::RapidCommit => {}
+            Dhcpv6Option::InterfaceId(data) | Dhcpv6Option::Unknown { data, .. } => buf.extend_from_slice(data),
+            Dhcpv6Option::DnsServers(servers) => servers.iter().for_each(|server| buf.extend_from_slice(&server.octets())),
+            Dhcpv6Option::DomainList(domains) => domains.iter().for_each(|domain| write_name(buf, domain)),
+            Dhcpv6Option::IaPrefix(prefix) => {
+                buf.extend_from_slice(&prefix.preferred_lifetime.to_be_bytes());
+                buf.extend_from_slice(&prefix.valid_lifetime.to_be_bytes());
+                buf.push(prefix.prefix_length);
+                buf.extend_from_slice(&prefix.prefix.octets());
+                prefix.options.iter().for_each(|option| option.write(buf));
+            }
+        }
+    }
+
+    /// Parses an option from the start of the buffer and returns it together with its length.
+    ///
+    /// Nested options and relayed messages are decoded recursively, up to [`Dhcpv6Layer::HOP_COUNT_LIMIT`] relay
+    /// messages deep. Inside identity associations, only addresses, prefixes and status codes are decoded. Other options
+    /// and options whose contents do not match their code are returned as [`Dhcpv6Option::Unknown`], so that they are
+    /// preserved.
+    pub fn parse(bytes: &[u8]) -> Result<(Self, usize), Dhcpv6Error> {
+        Self::parse_in(bytes, OptionScope::Message { relay_depth: 0 })
+    }
+
+    /// Parses an option which appears in the specified scope.
+    pub(crate) fn parse_in(bytes: &[u8], scope: OptionScope) -> Result<(Self, usize), Dhcpv6Error> {
+        let header = bytes.get(..4).ok_or(Dhcpv6Error::MalformedOption(0))?;
+        let code = u16::from_be_bytes([header[0], header[1]]);
+        let len = u16::from_be_bytes([header[2], header[3]]) as usize;
+        let data = bytes.get(4..4 + len).ok_or(Dhcpv6Error::MalformedOption(code))?;
+
+        let u32_at = |offset: usize| u32::from_be_bytes(data[offset..offset + 4].try_into().unwrap());
+        let ipv6_at = |offset: usize| Ipv6Addr::from(<[u8; 16]>::try_from(&data[offset..offset + 16]).unwrap());
+        let ia = || {
+            let options = parse_options_in(&data[12..], OptionScope::IdentityAssociation).ok()?;
+            Some(IdentityAssociation { iaid: u32_at(0), t1: u32_at(4), t2: u32_at(8), options })
+        };
+
+        let option = match code {
+            _ if !scope.allows(code) => None,
+            Self::CLIENT_ID => Duid::parse(data).map(Dhcpv6Option::ClientId),
+            Self::SERVER_ID => Duid::parse(data).map(Dhcpv6Option::ServerId),
+            Self::IA_NA if len >= 12 => ia().map(Dhcpv6Option::IaNa),
+            Self::IA_PD if len >= 12 => ia().map(Dhcpv6Option::IaPd),
+            Self::IA_ADDRESS if len >= 24 => parse_options_in(&data[24..], OptionScope::Lease).ok().map(|options| {
+                Dhcpv6Option::IaAddress(IaAddress {
+                    address: ipv6_at(0),
+                    preferred_lifetime: u32_at(16),
+                    valid_lifetime: u32_at(20),
+                    options,
+                })
+            }),
+            Self::OPTION_REQUEST if len.is_multiple_of(2) => Some(Dhcpv6Option::OptionRequest(
+                data.chunks_exact(2).map(|chunk| u16::from_be_bytes([chunk[0], chunk[1]])).collect(),
+            )),
+            Self::PREFERENCE if len == 1 => Some(Dhcpv6Option::Preference(data[0])),
+            Self::ELAPSED_TIME if len == 2 => Some(Dhcpv6Option::ElapsedTime(u16::from_be_bytes([data[0], data[1]]))),
+            Self::RELAY_MESSAGE => match Dhcpv6Layer::parse_relayed(data, scope.relay_depth() + 1) {
+                Ok((message, consumed)) if consumed == len => Some(Dhcpv6Option::RelayMessage(Box::new(message))),
+                _ => None,
+            },
+            Self::STATUS_CODE if len >= 2 => String::from_utf8(data[2..].to_vec()).ok().map(|message| Dhcpv6Option::StatusCode {
+                code: Dhcpv6StatusCode::from_value(u16::from_be_bytes([data[0], data[1]])),
+                message,
+            }),
+            Self::RAPID_COMMIT if len == 0 => Some(Dhcpv6Option::RapidCommit),
+            Self::INTERFACE_ID => Some(Dhcpv6Option::InterfaceId(data.to_vec())),
+            Self::DNS_SERVERS if len.is_multiple_of(16) => Some(Dhcpv6Option::DnsServers((0..len).step_by(16).map(ipv6_at).collect())),
+            Self::DOMAIN_LIST => parse_domain_list(data).map(Dhcpv6Option::DomainList),
+            Self::IA_PREFIX if len >= 25 => parse_options_in(&data[25..], OptionScope::Lease).ok().map(|options| {
+                Dhcpv6Option::IaPrefix(IaPrefix {
+                    preferred_lifetime: u32_at(0),
+                    valid_lifetime: u32_at(4),
+                    prefix_length: data[8],
+                    prefix: ipv6_at(9),
+                    options,
+                })
+            }),
+            _ => None,
+        };
+
+        let option = option.unwrap_or_else(|| Dhcpv6Option::Unknown { code, data: data.to_vec() });
+        Ok((option, 4 + len))
+    }
+}
+
+/// Returns the length of a domain name encoded as a sequence of labels without compression.
+fn encoded_name_len(name: &str) -> usize {
+    let mut buf = Vec::new();
+    write_name(&mut buf, name);
+    buf.len()
+}
+
+/// Parses a list of uncompressed domain names which fills the buffer.
+fn parse_domain_list(bytes: &[u8]) -> Option<Vec<String>> {
+    let mut domains = Vec::new();
+    let mut offset = 0;
+
+    while offset < bytes.len() {
+        // Compression is not allowed, so a name which occupies fewer bytes than its full encoding used a pointer
+        let (name, len) = read_name(bytes, offset).ok()?;
+        if len != encoded_name_len(&name) {
+            return None;
+        }
+
+        domains.push(name);
+        offset += len;
+    }
+
+    Some(domains)
+}
+
+/// Parses the options of a message which fill the buffer.
+pub fn parse_dhcpv6_options(bytes: &[u8]) -> Result<Vec<Dhcpv6Option>, Dhcpv6Error> {
+    parse_options_in(bytes, OptionScope::Message { relay_depth: 0 })
+}
+
+/// Parses the options which fill the buffer and appear in the specified scope.
+pub(crate) fn parse_options_in(mut bytes: &[u8], scope: OptionScope) -> Result<Vec<Dhcpv6Option>, Dhcpv6Error> {
+    let mut options = Vec::new();
+
+    while !bytes.is_empty() {
+        let (option, len) = Dhcpv6Option::parse_in(bytes, scope)?;
+        options.push(option);
+        bytes = &bytes[len..];
+    }
+
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(option: Dhcpv6Option) -> Vec<u8> {
+        let mut buf = Vec::new();
+        option.write(&mut buf);
+        assert_eq!(buf.len(), option.encoded_len());
+        assert_eq!(Dhcpv6Option::parse(&buf), Ok((option, buf.len())));
+        buf
+    }
+
+    #[test]
+    fn duids() {
+        let mac = MacAddr::new(0x00, 0x11, 0x22, 0x33, 0x44, 0x55);
+        let buf = roundtrip(Dhcpv6Option::ClientId(Duid::link_layer(mac)));
+        assert_eq!(buf, [0, 1, 0, 10, 0, 3, 0, 1, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+
+        let llt = Duid::LinkLayerTime { hardware_type: HardwareType::Ethernet, time: 0x2a2b2c2d, address: mac.octets().to_vec() };
+        assert_eq!(roundtrip(Dhcpv6Option::ServerId(llt)).len(), 18);
+        roundtrip(Dhcpv6Option::ServerId(Duid::Enterprise { enterprise_number: 32473, identifier: vec![1, 2, 3] }));
+        roundtrip(Dhcpv6Option::ClientId(Duid::Uuid([0xab; 16])));
+        roundtrip(Dhcpv6Option::ClientId(Duid::Unknown { kind: 4, data: vec![1, 2] }));
+
+        // An empty DUID is preserved as an unknown option
+        assert_eq!(Dhcpv6Option::parse(&[0, 1, 0, 0]), Ok((Dhcpv6Option::Unknown { code: 1, data: vec![] }, 4)));
+    }
+
+    #[test]
+    fn identity_associations() {
+        let address = IaAddress {
+            address: Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0x100),
+            preferred_lifetime: 3600,
+            valid_lifetime: 7200,
+            options: vec![],
+        };
+        let status = Dhcpv6Option::StatusCode { code: Dhcpv6StatusCode::Success, message: "ok".to_string() };
+        let ia_na = IdentityAssociation { iaid: 1, t1: 1800, t2: 2880, options: vec![Dhcpv6Option::IaAddress(address), status] };
+        let buf = roundtrip(Dhcpv6Option::IaNa(ia_na));
+        assert_eq!(buf.len(), 4 + 12 + 28 + 8);
+        assert_eq!(buf[16..20], [0, 5, 0, 24]);
+
+        let prefix = IaPrefix {
+            preferred_lifetime: 3600,
+            valid_lifetime: 7200,
+            prefix_length: 56,
+            prefix: Ipv6Addr::new(0x2001, 0xdb8, 0xab00, 0, 0, 0, 0, 0),
+            options: vec![],
+        };
+        let ia_pd = IdentityAssociation { iaid: 2, t1: 0, t2: 0, options: vec![Dhcpv6Option::IaPrefix(prefix)] };
+        assert_eq!(roundtrip(Dhcpv6Option::IaPd(ia_pd)).len(), 4 + 12 + 29);
+    }
+
+    #[test]
+    fn options() {
+        assert_eq!(roundtrip(Dhcpv6Option::OptionRequest(vec![23, 24])), [0, 6, 0, 4, 0, 23, 0, 24]);
+        assert_eq!(roundtrip(Dhcpv6Option::ElapsedTime(100)), [0, 8, 0, 2, 0, 100]);
+        assert_eq!(roundtrip(Dhcpv6Option::RapidCommit), [0, 14, 0, 0]);
+        assert_eq!(roundtrip(Dhcpv6Option::Preference(255)), [0, 7, 0, 1, 255]);
+
+        let servers = vec![Ipv6Addr::new(0x2001, 0x4860, 0x4860, 0, 0, 0, 0, 0x8888)];
+        assert_eq!(roundtrip(Dhcpv6Option::DnsServers(servers)).len(), 20);
+
+        let domains = vec!["example.com".to_string(), "lab".to_string()];
+        assert_eq!(roundtrip(Dhcpv6Option::DomainList(domains)).len(), 4 + 13 + 5);
+
+        assert_eq!(roundtrip(Dhcpv6Option::Unknown { code: 39, data: vec![0, 4, b'h', b'o', b's', b't'] }).len(), 10);
+        assert_eq!(Dhcpv6Option::parse(&[0, 8, 0, 2, 0]), Err(Dhcpv6Error::MalformedOption(8)));
+    }
+
+    #[test]
+    fn nested_identity_associations() {
+        // Thousands of IA_NA options nested in each other
+        let mut buf = Vec::new();
+        for _ in 0..4000 {
+            let mut option = vec![0, 3];
+            option.extend_from_slice(&(12 + buf.len() as u16).to_be_bytes());
+            option.extend_from_slice(&[0; 12]);
+            option.extend_from_slice(&buf);
+            buf = option;
+        }
+
+        // Only addresses, prefixes and status codes are decoded inside an IA
+        let (option, len) = Dhcpv6Option::parse(&buf).unwrap();
+        assert_eq!(len, buf.len());
+        let Dhcpv6Option::IaNa(ia) = &option else { panic!("expected IA_NA, got {option:?}") };
+        assert!(matches!(ia.options[..], [Dhcpv6Option::Unknown { code: 3, .. }]));
+
+        let mut written = Vec::new();
+        option.write(&mut written);
+        assert_eq!(written, buf);
+    }
+}
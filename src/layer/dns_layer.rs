@@ -0,0 +1,538 @@
+use super::*;
+
+use crate::{reflect::*, Discriminator};
+
+use thiserror::Error;
+
+registry_enum! {
+    /// The kinds of DNS messages.
+    pub enum DnsOpcode: u8 {
+        Query = 0,
+        IQuery = 1,
+        Status = 2,
+        Notify = 4,
+        Update = 5,
+        Dso = 6,
+    }
+}
+
+registry_enum! {
+    /// The response codes of the DNS header. EDNS extends them with eight more bits (RFC 6891).
+    pub enum DnsRcode: u8 {
+        NoError = 0,
+        FormErr = 1,
+        ServFail = 2,
+        NxDomain = 3,
+        NotImp = 4,
+        Refused = 5,
+        YxDomain = 6,
+        YxRrset = 7,
+        NxRrset = 8,
+        NotAuth = 9,
+        NotZone = 10,
+    }
+}
+
+/// The flags and codes in the second 16-bit word of a DNS header.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DnsFlags {
+    /// The message is a response.
+    pub response: bool,
+    pub opcode: DnsOpcode,
+    pub authoritative: bool,
+    pub truncated: bool,
+    pub recursion_desired: bool,
+    pub recursion_available: bool,
+    /// The reserved bit, which must be zero.
+    pub zero: bool,
+    /// The data was authenticated with DNSSEC (RFC 4035).
+    pub authentic_data: bool,
+    /// DNSSEC validation is disabled (RFC 4035).
+    pub checking_disabled: bool,
+    pub rcode: DnsRcode,
+}
+
+impl DnsFlags {
+    /// Constructs the flags from the second word of the header.
+    pub const fn from_bits(bits: u16) -> Self {
+        Self {
+            response: bits & 0x8000 != 0,
+            opcode: DnsOpcode::from_value((bits >> 11) as u8 & 0x0f),
+            authoritative: bits & 0x0400 != 0,
+            truncated: bits & 0x0200 != 0,
+            recursion_desired: bits & 0x0100 != 0,
+            recursion_available: bits & 0x0080 != 0,
+            zero: bits & 0x0040 != 0,
+            authentic_data: bits & 0x0020 != 0,
+            checking_disabled: bits & 0x0010 != 0,
+            rcode: DnsRcode::from_value(bits as u8 & 0x0f),
+        }
+    }
+
+    /// Returns the flags as the second word of the header.
+    pub const fn bits(self) -> u16 {
+        (self.response as u16) << 15
+            | (self.opcode.value() as u16 & 0x0f) << 11
+            | (self.authoritative as u16) << 10
+            | (self.truncated as u16) << 9
+            | (self.recursion_desired as u16) << 8
+            | (self.recursion_available as u16) << 7
+            | (self.zero as u16) << 6
+            | (self.authentic_data as u16) << 5
+            | (self.checking_disabled as u16) << 4
+            | self.rcode.value() as u16 & 0x0f
+    }
+}
+
+impl Default for DnsFlags {
+    fn default() -> Self {
+        Self::from_bits(0)
+    }
+}
+
+/// How a DNS message is delimited by the transport.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum DnsFraming {
+    /// The message fills a datagram, as over UDP.
+    #[default]
+    Datagram,
+    /// The message is preceded by its 16-bit length, as over TCP (RFC 1035 section 4.2.2).
+    Stream,
+}
+
+/// A DNS message (RFC 1035).
+///
+/// The section counts of the header are derived from the sections, and the message is encoded as a whole, so the layer
+/// has no payload.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct DnsLayer {
+    pub id: u16,
+    pub flags: DnsFlags,
+    pub questions: Vec<DnsQuestion>,
+    pub answers: Vec<DnsRecord>,
+    pub authorities: Vec<DnsRecord>,
+    pub additionals: Vec<DnsRecord>,
+    /// Whether repeated names are compressed when the message is encoded. Parsed messages are always re-encoded with
+    /// compression enabled, so messages which were compressed differently are not reproduced byte for byte.
+    pub compression: bool,
+    pub framing: DnsFraming,
+}
+
+impl DnsLayer {
+    /// The length of the header in bytes.
+    pub const HEADER_LEN: usize = 12;
+
+    /// The length of the length prefix of messages over TCP in bytes.
+    pub const LENGTH_PREFIX_LEN: usize = 2;
+
+    /// The port of DNS over both UDP and TCP.
+    pub const PORT: u16 = 53;
+
+    /// The port of multicast DNS (RFC 6762).
+    pub const MDNS_PORT: u16 = 5353;
+
+    /// Constructs a recursive query for a single name.
+    pub fn query(id: u16, name: &str, qtype: DnsType) -> Self {
+        Self {
+            id,
+            flags: DnsFlags {
+                recursion_desired: true,
+                ..DnsFlags::default()
+            },
+            questions: vec![DnsQuestion::new(name, qtype)],
+            compression: true,
+            ..Self::default()
+        }
+    }
+
+    /// Constructs a successful response to the query, which echoes its ID, questions and framing.
+    pub fn response(query: &DnsLayer, answers: Vec<DnsRecord>) -> Self {
+        Self {
+            id: query.id,
+            flags: DnsFlags {
+                response: true,
+                opcode: query.flags.opcode,
+                recursion_desired: query.flags.recursion_desired,
+                recursion_available: true,
+                checking_disabled: query.flags.checking_disabled,
+                ..DnsFlags::default()
+            },
+            questions: query.questions.clone(),
+            answers,
+            compression: true,
+            framing: query.framing,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the EDNS OPT pseudo-record of the additional section, if any.
+    pub fn edns(&self) -> Option<&DnsRecord> {
+        self.additionals.iter().find(|record| record.rtype() == DnsType::Opt)
+    }
+
+    /// Returns the 12-bit response code, which is extended by the OPT pseudo-record if present.
+    pub fn rcode(&self) -> u16 {
+        let extended = self.edns().map_or(0, |opt| opt.extended_rcode());
+        (extended as u16) << 4 | self.flags.rcode.value() as u16
+    }
+
+    /// Encodes the message without the length prefix.
+    pub fn to_message(&self) -> Vec<u8> {
+        let mut message = Vec::with_capacity(512);
+        let mut names = NameCompressor::new(self.compression);
+
+        message.extend_from_slice(&self.id.to_be_bytes());
+        message.extend_from_slice(&self.flags.bits().to_be_bytes());
+        for count in [self.questions.len(), self.answers.len(), self.authorities.len(), self.additionals.len()] {
+            message.extend_from_slice(&(count as u16).to_be_bytes());
+        }
+
+        self.questions.iter().for_each(|question| question.write(&mut message, &mut names));
+        for record in self.answers.iter().chain(&self.authorities).chain(&self.additionals) {
+            record.write(&mut message, &mut names);
+        }
+
+        message
+    }
+
+    /// Parses a message preceded by its 16-bit length, as sent over TCP, and returns it together with the number of
+    /// bytes consumed.
+    pub fn parse_stream(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        let prefix = bytes.get(..Self::LENGTH_PREFIX_LEN).ok_or(LayerError::BufferTooShort(Self::NAME))?;
+        let len = u16::from_be_bytes([prefix[0], prefix[1]]) as usize;
+        let message = bytes.get(Self::LENGTH_PREFIX_LEN..Self::LENGTH_PREFIX_LEN + len).ok_or(LayerError::BufferTooShort(Self::NAME))?;
+
+        let layer = DnsView::new(message)?.to_layer()?;
+        Ok((DnsLayer { framing: DnsFraming::Stream, ..layer }, Self::LENGTH_PREFIX_LEN + len))
+    }
+}
+
+impl Layer for DnsLayer {
+    const NAME: &'static str = "DNS";
+    const TYPE: LayerType = LayerType::DnsLayer;
+    const OSI_LEVEL: OsiLevel = OsiLevel::Application;
+}
+
+impl DynLayer for DnsLayer {
+    fn header_len(&self) -> usize {
+        match self.framing {
+            DnsFraming::Datagram => self.to_message().len(),
+            DnsFraming::Stream => Self::LENGTH_PREFIX_LEN + self.to_message().len(),
+        }
+    }
+
+    /// A datagram holds a single message, whereas a stream may continue with further messages.
+    fn payload_len(&self) -> Option<usize> {
+        match self.framing {
+            DnsFraming::Datagram => Some(0),
+            DnsFraming::Stream => None,
+        }
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        let message = self.to_message();
+        if self.framing == DnsFraming::Stream {
+            buf.extend_from_slice(&(message.len() as u16).to_be_bytes());
+        }
+        buf.extend_from_slice(&message);
+    }
+
+    fn next_discriminators(&self) -> Vec<Discriminator> {
+        match self.framing {
+            DnsFraming::Datagram => Vec::new(),
+            DnsFraming::Stream => vec![Discriminator::TcpPort(Self::PORT)],
+        }
+    }
+
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        let view = DnsView::new(bytes)?;
+        let layer = view.to_layer()?;
+        Ok((layer, view.message_len()?))
+    }
+
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+}
+
+impl Reflect for DnsLayer {
+    fn fields(&self) -> Vec<Field> {
+        let uint = |value: u64| FieldValue::UInt(value);
+        let bool = |value: bool| FieldValue::Bool(value);
+
+        let mut fields = Vec::new();
+        let mut offset = 0;
+        if self.framing == DnsFraming::Stream {
+            let len = self.to_message().len() as u64;
+            fields.push(Field::new("dns.length", FieldKind::UInt, 0, 16, uint(len)).computed());
+            offset = 16;
+        }
+
+        let counts = [
+            ("dns.qdcount", self.questions.len()),
+            ("dns.ancount", self.answers.len()),
+            ("dns.nscount", self.authorities.len()),
+            ("dns.arcount", self.additionals.len()),
+        ];
+
+        fields.extend([
+            Field::new("dns.id", FieldKind::UInt, offset, 16, uint(self.id as u64)),
+            Field::new("dns.qr", FieldKind::Bool, offset + 16, 1, bool(self.flags.response)),
+            Field::new("dns.opcode", FieldKind::Enum, offset + 17, 4, self.flags.opcode.field_value()),
+            Field::new("dns.aa", FieldKind::Bool, offset + 21, 1, bool(self.flags.authoritative)),
+            Field::new("dns.tc", FieldKind::Bool, offset + 22, 1, bool(self.flags.truncated)),
+            Field::new("dns.rd", FieldKind::Bool, offset + 23, 1, bool(self.flags.recursion_desired)),
+            Field::new("dns.ra", FieldKind::Bool, offset + 24, 1, bool(self.flags.recursion_available)),
+            Field::new("dns.z", FieldKind::Bool, offset + 25, 1, bool(self.flags.zero)),
+            Field::new("dns.ad", FieldKind::Bool, offset + 26, 1, bool(self.flags.authentic_data)),
+            Field::new("dns.cd", FieldKind::Bool, offset + 27, 1, bool(self.flags.checking_disabled)),
+            Field::new("dns.rcode", FieldKind::Enum, offset + 28, 4, self.flags.rcode.field_value()),
+        ]);
+
+        for (i, (name, count)) in counts.into_iter().enumerate() {
+            fields.push(Field::new(name, FieldKind::UInt, offset + 32 + i * 16, 16, uint(count as u64)).computed());
+        }
+
+        fields
+    }
+
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        let flags = &mut self.flags;
+
+        match path {
+            "dns.id" => self.id = parse_uint_as(value)?,
+            "dns.qr" => flags.response = parse_bool(value)?,
+            "dns.opcode" => match DnsOpcode::parse_field(value)? {
+                opcode if opcode.value() <= 0x0f => flags.opcode = opcode,
+                _ => return Err(FieldError::InvalidValue(value.to_string())),
+            },
+            "dns.aa" => flags.authoritative = parse_bool(value)?,
+            "dns.tc" => flags.truncated = parse_bool(value)?,
+            "dns.rd" => flags.recursion_desired = parse_bool(value)?,
+            "dns.ra" => flags.recursion_available = parse_bool(value)?,
+            "dns.z" => flags.zero = parse_bool(value)?,
+            "dns.ad" => flags.authentic_data = parse_bool(value)?,
+            "dns.cd" => flags.checking_disabled = parse_bool(value)?,
+            "dns.rcode" => match DnsRcode::parse_field(value)? {
+                rcode if rcode.value() <= 0x0f => flags.rcode = rcode,
+                _ => return Err(FieldError::InvalidValue(value.to_string())),
+            },
+            _ => return Err(FieldError::UnknownField(path.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
+/// A borrowed view of a DNS message at the start of a byte slice, without a length prefix.
+///
+/// The fixed header is decoded on access, whereas the sections are only decoded when the view is converted to a layer.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DnsView<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> DnsView<'a> {
+    /// Constructs a view of the DNS message at the start of the slice.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, LayerError> {
+        if bytes.len() < DnsLayer::HEADER_LEN {
+            return Err(LayerError::BufferTooShort(DnsLayer::NAME));
+        }
+
+        Ok(Self { bytes })
+    }
+
+    fn u16_at(&self, offset: usize) -> u16 {
+        u16::from_be_bytes([self.bytes[offset], self.bytes[offset + 1]])
+    }
+
+    /// Returns the message ID.
+    pub fn id(&self) -> u16 {
+        self.u16_at(0)
+    }
+
+    /// Returns the flags.
+    pub fn flags(&self) -> DnsFlags {
+        DnsFlags::from_bits(self.u16_at(2))
+    }
+
+    /// Returns the number of entries of the question section.
+    pub fn question_count(&self) -> u16 {
+        self.u16_at(4)
+    }
+
+    /// Returns the number of records of the answer section.
+    pub fn answer_count(&self) -> u16 {
+        self.u16_at(6)
+    }
+
+    /// Returns the number of records of the authority section.
+    pub fn authority_count(&self) -> u16 {
+        self.u16_at(8)
+    }
+
+    /// Returns the number of records of the additional section.
+    pub fn additional_count(&self) -> u16 {
+        self.u16_at(10)
+    }
+
+    /// Returns the length of the message by walking its sections.
+    pub fn message_len(&self) -> Result<usize, DnsError> {
+        self.sections().map(|(_, len)| len)
+    }
+
+    /// Decodes the sections and converts the view into an owned DNS layer.
+    pub fn to_layer(&self) -> Result<DnsLayer, LayerError> {
+        let ((questions, answers, authorities, additionals), _) = self.sections()?;
+
+        Ok(DnsLayer {
+            id: self.id(),
+            flags: self.flags(),
+            questions,
+            answers,
+            authorities,
+            additionals,
+            compression: true,
+            framing: DnsFraming::Datagram,
+        })
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn sections(&self) -> Result<((Vec<DnsQuestion>, Vec<DnsRecord>, Vec<DnsRecord>, Vec<DnsRecord>), usize), DnsError> {
+        let mut offset = DnsLayer::HEADER_LEN;
+
+        let mut questions = Vec::new();
+        for _ in 0..self.question_count() {
+            let (question, len) = DnsQuestion::parse(self.bytes, offset)?;
+            questions.push(question);
+            offset += len;
+        }
+
+        let mut records = |count: u16| -> Result<Vec<DnsRecord>, DnsError> {
+            let mut records = Vec::new();
+            for _ in 0..count {
+                let (record, len) = DnsRecord::parse(self.bytes, offset)?;
+                records.push(record);
+                offset += len;
+            }
+            Ok(records)
+        };
+
+        let answers = records(self.answer_count())?;
+        let authorities = records(self.authority_count())?;
+        let additionals = records(self.additional_count())?;
+
+        Ok(((questions, answers, authorities, additionals), offset))
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum DnsError {
+    #[error("The DNS message ends in the middle of a name or record.")]
+    Truncated,
+
+    #[error("The domain name at offset {0} contains a loop of compression pointers.")]
+    CompressionLoop(usize),
+
+    #[error("The domain name is longer than 255 bytes.")]
+    NameTooLong,
+
+    #[error("The label type {0:#04x} is not supported.")]
+    InvalidLabel(u8),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Ipv4Addr, Packet};
+
+    /// A response for the A records of example.com, as sent by a public resolver.
+    const RESPONSE: [u8; 56] = [
+        0x12, 0x34, 0x81, 0x80, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e',
+        0x03, b'c', b'o', b'm', 0x00, 0x00, 0x01, 0x00, 0x01, 0xc0, 0x0c, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00,
+        0x04, 0x5d, 0xb8, 0xd8, 0x22, 0x00, 0x00, 0x29, 0x04, 0xd0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(DnsLayer::NAME, "DNS");
+        assert_eq!(DnsLayer::TYPE, LayerType::DnsLayer);
+        assert_eq!(DnsLayer::OSI_LEVEL, OsiLevel::Application);
+    }
+
+    #[test]
+    fn flags() {
+        let flags = DnsFlags::from_bits(0x8180);
+        assert!(flags.response && flags.recursion_desired && flags.recursion_available);
+        assert_eq!(flags.opcode, DnsOpcode::Query);
+        assert_eq!(flags.bits(), 0x8180);
+        assert_eq!(DnsFlags::from_bits(0x2803).opcode, DnsOpcode::Update);
+        assert_eq!(DnsFlags::from_bits(0x2803).rcode, DnsRcode::NxDomain);
+    }
+
+    #[test]
+    fn parse_and_write() {
+        let (layer, len) = DnsLayer::parse(&RESPONSE).unwrap();
+        assert_eq!(len, 56);
+        assert_eq!(layer.id, 0x1234);
+        assert_eq!(layer.questions, [DnsQuestion::new("example.com", DnsType::A)]);
+        assert_eq!(layer.answers, [DnsRecord::new("example.com", 3600, DnsRecordData::A(Ipv4Addr::new(93, 184, 216, 34)))]);
+        assert_eq!(layer.edns().unwrap().udp_payload_size(), 1232);
+        assert_eq!(layer.rcode(), 0);
+
+        let mut buf = Vec::new();
+        layer.write(&mut buf);
+        assert_eq!(buf, RESPONSE);
+        assert_eq!(layer.header_len(), 56);
+
+        assert_eq!(DnsLayer::parse(&RESPONSE[..11]), Err(LayerError::BufferTooShort("DNS")));
+        assert_eq!(DnsLayer::parse(&RESPONSE[..50]), Err(LayerError::Dns(DnsError::Truncated)));
+    }
+
+    #[test]
+    fn query_and_response() {
+        let query = DnsLayer::query(7, "example.org", DnsType::Aaaa);
+        let bytes = query.to_message();
+        assert_eq!(bytes[2..6], [0x01, 0x00, 0x00, 0x01]);
+
+        let answer = DnsRecord::new("example.org", 60, DnsRecordData::Cname("www.example.org".to_string()));
+        let response = DnsLayer::response(&query, vec![answer]);
+        assert_eq!((response.id, response.flags.bits()), (7, 0x8180));
+
+        // The CNAME target points into the question, which precedes it
+        let bytes = response.to_message();
+        assert_eq!(bytes[bytes.len() - 6..], [3, b'w', b'w', b'w', 0xc0, 12]);
+        assert_eq!(DnsLayer::parse(&bytes).unwrap().0, response);
+
+        let uncompressed = DnsLayer { compression: false, ..response.clone() };
+        assert_eq!(uncompressed.to_message().len(), bytes.len() + 11 + 11);
+        assert_eq!(DnsLayer::parse(&uncompressed.to_message()).unwrap().0.answers, response.answers);
+    }
+
+    #[test]
+    fn stream() {
+        let query = DnsLayer { framing: DnsFraming::Stream, ..DnsLayer::query(1, "example.com", DnsType::Mx) };
+        let mut bytes = Packet::from(query.clone()).to_bytes();
+        assert_eq!(bytes[..2], [0, 29]);
+        assert_eq!(query.header_len(), 31);
+
+        bytes.extend_from_slice(&[0, 12]);
+        assert_eq!(DnsLayer::parse_stream(&bytes).unwrap(), (query, 31));
+        assert_eq!(DnsLayer::parse_stream(&bytes[..30]), Err(LayerError::BufferTooShort("DNS")));
+    }
+
+    #[test]
+    fn reflect() {
+        let mut layer = DnsLayer::parse(&RESPONSE).unwrap().0;
+        layer.set_field("dns.rcode", "NxDomain").unwrap();
+        layer.set_field("dns.aa", "true").unwrap();
+        assert_eq!(layer.flags.bits(), 0x8583);
+        assert_eq!(layer.get_field("dns.ancount"), Some(FieldValue::UInt(1)));
+        assert_eq!(layer.set_field("dns.opcode", "16"), Err(FieldError::InvalidValue("16".to_string())));
+        assert_eq!(layer.set_field("dns.ancount", "2"), Err(FieldError::UnknownField("dns.ancount".to_string())));
+    }
+}
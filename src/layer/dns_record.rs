@@ -0,0 +1,648 @@
+use super::*;
+
+use crate::{Ipv4Addr, Ipv6Addr};
+
+use std::collections::HashMap;
+
+registry_enum! {
+    /// The types of DNS resource records and queries.
+    pub enum DnsType: u16 {
+        A = 1,
+        Ns = 2,
+        Cname = 5,
+        Soa = 6,
+        Ptr = 12,
+        Hinfo = 13,
+        Mx = 15,
+        Txt = 16,
+        Aaaa = 28,
+        Srv = 33,
+        Naptr = 35,
+        Opt = 41,
+        Ds = 43,
+        Rrsig = 46,
+        Nsec = 47,
+        Dnskey = 48,
+        Svcb = 64,
+        Https = 65,
+        Axfr = 252,
+        Any = 255,
+        Caa = 257,
+    }
+}
+
+registry_enum! {
+    /// The classes of DNS resource records and queries.
+    pub enum DnsClass: u16 {
+        In = 1,
+        Cs = 2,
+        Ch = 3,
+        Hs = 4,
+        None = 254,
+        Any = 255,
+    }
+}
+
+registry_enum! {
+    /// The codes of EDNS options.
+    pub enum EdnsOptionCode: u16 {
+        Llq = 1,
+        UpdateLease = 2,
+        Nsid = 3,
+        ClientSubnet = 8,
+        Expire = 9,
+        Cookie = 10,
+        TcpKeepalive = 11,
+        Padding = 12,
+        Chain = 13,
+        KeyTag = 14,
+        ExtendedError = 15,
+    }
+}
+
+/// An option carried by an EDNS OPT pseudo-record (RFC 6891).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EdnsOption {
+    pub code: EdnsOptionCode,
+    pub data: Vec<u8>,
+}
+
+/// An entry of the question section of a DNS message.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DnsQuestion {
+    /// The domain name in presentation format, in which dots and backslashes within labels are escaped (RFC 4343).
+    pub name: String,
+    pub qtype: DnsType,
+    pub qclass: DnsClass,
+}
+
+impl DnsQuestion {
+    /// Constructs a question of the Internet class.
+    pub fn new(name: &str, qtype: DnsType) -> Self {
+        Self {
+            name: name.to_string(),
+            qtype,
+            qclass: DnsClass::In,
+        }
+    }
+}
+
+/// The data of a resource record, typed by the record type.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum DnsRecordData {
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+    Cname(String),
+    Ns(String),
+    Ptr(String),
+    Mx { preference: u16, exchange: String },
+    /// One or more character strings of up to 255 bytes each.
+    Txt(Vec<Vec<u8>>),
+    Soa {
+        mname: String,
+        rname: String,
+        serial: u32,
+        refresh: u32,
+        retry: u32,
+        expire: u32,
+        minimum: u32,
+    },
+    /// A service location (RFC 2782).
+    Srv { priority: u16, weight: u16, port: u16, target: String },
+    /// A certification authority authorization (RFC 8659).
+    Caa { flags: u8, tag: String, value: Vec<u8> },
+    /// The options of an EDNS pseudo-record (RFC 6891).
+    Opt(Vec<EdnsOption>),
+    /// Data of a record type which is either not supported or whose contents do not match the type.
+    Unknown { rtype: DnsType, data: Vec<u8> },
+}
+
+impl DnsRecordData {
+    /// Returns the record type of the data.
+    pub fn rtype(&self) -> DnsType {
+        match self {
+            DnsRecordData::A(_) => DnsType::A,
+            DnsRecordData::Aaaa(_) => DnsType::Aaaa,
+            DnsRecordData::Cname(_) => DnsType::Cname,
+            DnsRecordData::Ns(_) => DnsType::Ns,
+            DnsRecordData::Ptr(_) => DnsType::Ptr,
+            DnsRecordData::Mx { .. } => DnsType::Mx,
+            DnsRecordData::Txt(_) => DnsType::Txt,
+            DnsRecordData::Soa { .. } => DnsType::Soa,
+            DnsRecordData::Srv { .. } => DnsType::Srv,
+            DnsRecordData::Caa { .. } => DnsType::Caa,
+            DnsRecordData::Opt(_) => DnsType::Opt,
+            DnsRecordData::Unknown { rtype, .. } => *rtype,
+        }
+    }
+
+    /// Appends the encoded data to the message. Names are compressed, except for the SRV target (RFC 2782).
+    fn write(&self, message: &mut Vec<u8>, names: &mut NameCompressor) {
+        match self {
+            DnsRecordData::A(addr) => message.extend_from_slice(&addr.octets()),
+            DnsRecordData::Aaaa(addr) => message.extend_from_slice(&addr.octets()),
+            DnsRecordData::Cname(name) | DnsRecordData::Ns(name) | DnsRecordData::Ptr(name) => names.write(message, name),
+            DnsRecordData::Mx { preference, exchange } => {
+                message.extend_from_slice(&preference.to_be_bytes());
+                names.write(message, exchange);
+            }
+            DnsRecordData::Txt(strings) => {
+                for string in strings {
+                    let string = &string[..string.len().min(255)];
+                    message.push(string.len() as u8);
+                    message.extend_from_slice(string);
+                }
+            }
+            DnsRecordData::Soa { mname, rname, serial, refresh, retry, expire, minimum } => {
+                names.write(message, mname);
+                names.write(message, rname);
+                [serial, refresh, retry, expire, minimum].iter().for_each(|value| message.extend_from_slice(&value.to_be_bytes()));
+            }
+            DnsRecordData::Srv { priority, weight, port, target } => {
+                message.extend_from_slice(&priority.to_be_bytes());
+                message.extend_from_slice(&weight.to_be_bytes());
+                message.extend_from_slice(&port.to_be_bytes());
+                write_name(message, target);
+            }
+            DnsRecordData::Caa { flags, tag, value } => {
+                message.extend_from_slice(&[*flags, tag.len() as u8]);
+                message.extend_from_slice(tag.as_bytes());
+                message.extend_from_slice(value);
+            }
+            DnsRecordData::Opt(options) => {
+                for option in options {
+                    message.extend_from_slice(&option.code.value().to_be_bytes());
+                    message.extend_from_slice(&(option.data.len() as u16).to_be_bytes());
+                    message.extend_from_slice(&option.data);
+                }
+            }
+            DnsRecordData::Unknown { data, .. } => message.extend_from_slice(data),
+        }
+    }
+
+    /// Decodes the data of the given type from `message[start..end]`. Names may point anywhere into the message.
+    ///
+    /// Data which does not match its type is returned as [`DnsRecordData::Unknown`], so that it is preserved.
+    fn parse(message: &[u8], rtype: DnsType, start: usize, end: usize) -> Self {
+        let data = &message[start..end];
+        Self::parse_typed(message, rtype, start, end).unwrap_or_else(|| DnsRecordData::Unknown { rtype, data: data.to_vec() })
+    }
+
+    fn parse_typed(message: &[u8], rtype: DnsType, start: usize, end: usize) -> Option<Self> {
+        let data = &message[start..end];
+        let u16_at = |offset: usize| u16::from_be_bytes([data[offset], data[offset + 1]]);
+        let u32_at = |offset: usize| u32::from_be_bytes(data[offset..offset + 4].try_into().unwrap());
+
+        // Reads a name which must end exactly at the end of the data
+        let last_name = |offset: usize| match read_name(message, start + offset) {
+            Ok((name, len)) if start + offset + len == end => Some(name),
+            _ => None,
+        };
+
+        match rtype {
+            DnsType::A if data.len() == 4 => Some(DnsRecordData::A(Ipv4Addr::from(<[u8; 4]>::try_from(data).unwrap()))),
+            DnsType::Aaaa if data.len() == 16 => Some(DnsRecordData::Aaaa(Ipv6Addr::from(<[u8; 16]>::try_from(data).unwrap()))),
+            DnsType::Cname => last_name(0).map(DnsRecordData::Cname),
+            DnsType::Ns => last_name(0).map(DnsRecordData::Ns),
+            DnsType::Ptr => last_name(0).map(DnsRecordData::Ptr),
+            DnsType::Mx if data.len() > 2 => last_name(2).map(|exchange| DnsRecordData::Mx { preference: u16_at(0), exchange }),
+            DnsType::Txt if !data.is_empty() => {
+                let mut strings = Vec::new();
+                let mut rest = data;
+                while let Some((&len, tail)) = rest.split_first() {
+                    strings.push(tail.get(..len as usize)?.to_vec());
+                    rest = &tail[len as usize..];
+                }
+                Some(DnsRecordData::Txt(strings))
+            }
+            DnsType::Soa => {
+                let (mname, mname_len) = read_name(message, start).ok()?;
+                let (rname, rname_len) = read_name(message, start + mname_len).ok()?;
+                let offset = mname_len + rname_len;
+                (offset + 20 == data.len()).then(|| DnsRecordData::Soa {
+                    mname,
+                    rname,
+                    serial: u32_at(offset),
+                    refresh: u32_at(offset + 4),
+                    retry: u32_at(offset + 8),
+                    expire: u32_at(offset + 12),
+                    minimum: u32_at(offset + 16),
+                })
+            }
+            DnsType::Srv if data.len() > 6 => last_name(6).map(|target| DnsRecordData::Srv {
+                priority: u16_at(0),
+                weight: u16_at(2),
+                port: u16_at(4),
+                target,
+            }),
+            DnsType::Caa if data.len() >= 2 => {
+                let tag = data.get(2..2 + data[1] as usize)?;
+                Some(DnsRecordData::Caa {
+                    flags: data[0],
+                    tag: String::from_utf8(tag.to_vec()).ok()?,
+                    value: data[2 + tag.len()..].to_vec(),
+                })
+            }
+            DnsType::Opt => {
+                let mut options = Vec::new();
+                let mut rest = data;
+                while !rest.is_empty() {
+                    let header = rest.get(..4)?;
+                    let len = u16::from_be_bytes([header[2], header[3]]) as usize;
+                    options.push(EdnsOption {
+                        code: EdnsOptionCode::from_value(u16::from_be_bytes([header[0], header[1]])),
+                        data: rest.get(4..4 + len)?.to_vec(),
+                    });
+                    rest = &rest[4 + len..];
+                }
+                Some(DnsRecordData::Opt(options))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A resource record of the answer, authority or additional section of a DNS message.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DnsRecord {
+    /// The domain name in presentation format, in which dots and backslashes within labels are escaped (RFC 4343).
+    pub name: String,
+    /// The class of the record. In OPT pseudo-records, it holds the UDP payload size instead.
+    pub class: DnsClass,
+    /// The time to live in seconds. In OPT pseudo-records, it holds the extended RCODE, the version and the flags instead.
+    pub ttl: u32,
+    pub data: DnsRecordData,
+}
+
+impl DnsRecord {
+    /// Constructs a record of the Internet class.
+    pub fn new(name: &str, ttl: u32, data: DnsRecordData) -> Self {
+        Self {
+            name: name.to_string(),
+            class: DnsClass::In,
+            ttl,
+            data,
+        }
+    }
+
+    /// Constructs an EDNS(0) OPT pseudo-record which advertises the UDP payload size of the sender.
+    pub fn opt(udp_payload_size: u16, dnssec_ok: bool, options: Vec<EdnsOption>) -> Self {
+        Self {
+            name: ".".to_string(),
+            class: DnsClass::from_value(udp_payload_size),
+            ttl: (dnssec_ok as u32) << 15,
+            data: DnsRecordData::Opt(options),
+        }
+    }
+
+    /// Returns the type of the record.
+    pub fn rtype(&self) -> DnsType {
+        self.data.rtype()
+    }
+
+    /// Returns the UDP payload size advertised by an OPT pseudo-record.
+    pub fn udp_payload_size(&self) -> u16 {
+        self.class.value()
+    }
+
+    /// Returns the upper eight bits of the extended RCODE of an OPT pseudo-record.
+    pub fn extended_rcode(&self) -> u8 {
+        (self.ttl >> 24) as u8
+    }
+
+    /// Returns the EDNS version of an OPT pseudo-record.
+    pub fn edns_version(&self) -> u8 {
+        (self.ttl >> 16) as u8
+    }
+
+    /// Checks if an OPT pseudo-record has the DNSSEC OK bit set.
+    pub fn dnssec_ok(&self) -> bool {
+        self.ttl & 0x8000 != 0
+    }
+
+    /// Appends the encoded record to the message.
+    pub(crate) fn write(&self, message: &mut Vec<u8>, names: &mut NameCompressor) {
+        names.write(message, &self.name);
+        message.extend_from_slice(&self.rtype().value().to_be_bytes());
+        message.extend_from_slice(&self.class.value().to_be_bytes());
+        message.extend_from_slice(&self.ttl.to_be_bytes());
+
+        let length_offset = message.len();
+        message.extend_from_slice(&[0; 2]);
+        self.data.write(message, names);
+
+        let rdlength = (message.len() - length_offset - 2) as u16;
+        message[length_offset..length_offset + 2].copy_from_slice(&rdlength.to_be_bytes());
+    }
+
+    /// Parses a record at the offset of the message and returns it together with its length.
+    pub(crate) fn parse(message: &[u8], offset: usize) -> Result<(Self, usize), DnsError> {
+        let (name, name_len) = read_name(message, offset)?;
+        let start = offset + name_len;
+        let fixed = message.get(start..start + 10).ok_or(DnsError::Truncated)?;
+
+        let rtype = DnsType::from_value(u16::from_be_bytes([fixed[0], fixed[1]]));
+        let rdlength = u16::from_be_bytes([fixed[8], fixed[9]]) as usize;
+        let end = start + 10 + rdlength;
+        if end > message.len() {
+            return Err(DnsError::Truncated);
+        }
+
+        let record = DnsRecord {
+            name,
+            class: DnsClass::from_value(u16::from_be_bytes([fixed[2], fixed[3]])),
+            ttl: u32::from_be_bytes(fixed[4..8].try_into().unwrap()),
+            data: DnsRecordData::parse(message, rtype, start + 10, end),
+        };
+
+        Ok((record, end - offset))
+    }
+}
+
+impl DnsQuestion {
+    /// Appends the encoded question to the message.
+    pub(crate) fn write(&self, message: &mut Vec<u8>, names: &mut NameCompressor) {
+        names.write(message, &self.name);
+        message.extend_from_slice(&self.qtype.value().to_be_bytes());
+        message.extend_from_slice(&self.qclass.value().to_be_bytes());
+    }
+
+    /// Parses a question at the offset of the message and returns it together with its length.
+    pub(crate) fn parse(message: &[u8], offset: usize) -> Result<(Self, usize), DnsError> {
+        let (name, name_len) = read_name(message, offset)?;
+        let fixed = message.get(offset + name_len..offset + name_len + 4).ok_or(DnsError::Truncated)?;
+
+        let question = DnsQuestion {
+            name,
+            qtype: DnsType::from_value(u16::from_be_bytes([fixed[0], fixed[1]])),
+            qclass: DnsClass::from_value(u16::from_be_bytes([fixed[2], fixed[3]])),
+        };
+
+        Ok((question, name_len + 4))
+    }
+}
+
+/// The longest encoded domain name in bytes (RFC 1035).
+const MAX_NAME_LEN: usize = 255;
+
+/// The most compression pointers followed while decoding a single name. A valid name never needs more than one pointer
+/// per label, so more than this indicates a loop.
+const MAX_POINTERS: usize = MAX_NAME_LEN / 2;
+
+/// Encodes domain names into a message, replacing suffixes which were already written with pointers to them (RFC 1035
+/// section 4.1.4).
+#[derive(Debug, Default)]
+pub(crate) struct NameCompressor {
+    enabled: bool,
+    /// The offsets of the suffixes written so far, by their lowercase labels.
+    offsets: HashMap<Vec<Vec<u8>>, u16>,
+}
+
+impl NameCompressor {
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self { enabled, offsets: HashMap::new() }
+    }
+
+    /// Appends the name to the message, which must start at the beginning of the buffer.
+    pub(crate) fn write(&mut self, message: &mut Vec<u8>, name: &str) {
+        if !self.enabled {
+            return write_name(message, name);
+        }
+
+        let labels = labels(name);
+        for (i, label) in labels.iter().enumerate() {
+            let suffix = labels[i..].iter().map(|label| label.to_ascii_lowercase()).collect();
+            if let Some(offset) = self.offsets.get(&suffix) {
+                message.extend_from_slice(&(0xc000 | offset).to_be_bytes());
+                return;
+            }
+
+            // Pointers hold 14-bit offsets
+            if message.len() < 0x4000 {
+                self.offsets.insert(suffix, message.len() as u16);
+            }
+            write_label(message, label);
+        }
+        message.push(0);
+    }
+}
+
+/// Splits a name in presentation format into its labels, resolving the escapes `\X` and `\DDD` (RFC 4343). Empty labels
+/// are skipped.
+fn labels(name: &str) -> Vec<Vec<u8>> {
+    let mut labels = Vec::new();
+    let mut label = Vec::new();
+    let mut rest = name.as_bytes();
+
+    while let [byte, tail @ ..] = rest {
+        rest = tail;
+        match (byte, tail) {
+            (b'.', _) => labels.push(std::mem::take(&mut label)),
+            (b'\\', [a @ b'0'..=b'9', b @ b'0'..=b'9', c @ b'0'..=b'9', tail @ ..]) => {
+                let value = (a - b'0') as u16 * 100 + (b - b'0') as u16 * 10 + (c - b'0') as u16;
+                label.push(value.min(0xff) as u8);
+                rest = tail;
+            }
+            (b'\\', [escaped, tail @ ..]) => {
+                label.push(*escaped);
+                rest = tail;
+            }
+            _ => label.push(*byte),
+        }
+    }
+    labels.push(label);
+
+    labels.retain(|label| !label.is_empty());
+    labels
+}
+
+/// Formats a label in presentation format, escaping dots and backslashes with `\X` and other bytes which are not
+/// printable ASCII with `\DDD` (RFC 4343).
+fn escape_label(label: &[u8]) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for &byte in label {
+        match byte {
+            b'.' | b'\\' => {
+                escaped.push('\\');
+                escaped.push(byte as char);
+            }
+            0x21..=0x7e => escaped.push(byte as char),
+            _ => escaped.push_str(&format!("\\{byte:03}")),
+        }
+    }
+    escaped
+}
+
+/// Appends a label, truncated to the longest length of 63 bytes.
+fn write_label(buf: &mut Vec<u8>, label: &[u8]) {
+    let label = &label[..label.len().min(63)];
+    buf.push(label.len() as u8);
+    buf.extend_from_slice(label);
+}
+
+/// Appends a domain name in presentation format without compression. The root is written as either an empty name or `.`.
+pub(crate) fn write_name(buf: &mut Vec<u8>, name: &str) {
+    labels(name).iter().for_each(|label| write_label(buf, label));
+    buf.push(0);
+}
+
+/// Decodes the domain name at the offset of the message, following compression pointers, and returns it in presentation
+/// format together with the number of bytes it occupies at the offset. The root is decoded as `.`.
+pub(crate) fn read_name(message: &[u8], offset: usize) -> Result<(String, usize), DnsError> {
+    let mut labels = Vec::new();
+    let mut position = offset;
+    let mut len = None;
+    let mut name_len = 1;
+    let mut pointers = 0;
+
+    loop {
+        let byte = *message.get(position).ok_or(DnsError::Truncated)?;
+        match byte & 0xc0 {
+            0x00 if byte == 0 => break,
+            0x00 => {
+                let label = message.get(position + 1..position + 1 + byte as usize).ok_or(DnsError::Truncated)?;
+                name_len += label.len() + 1;
+                if name_len > MAX_NAME_LEN {
+                    return Err(DnsError::NameTooLong);
+                }
+
+                labels.push(escape_label(label));
+                position += label.len() + 1;
+            }
+            0xc0 => {
+                let low = *message.get(position + 1).ok_or(DnsError::Truncated)?;
+                len.get_or_insert(position + 2 - offset);
+
+                pointers += 1;
+                if pointers > MAX_POINTERS {
+                    return Err(DnsError::CompressionLoop(offset));
+                }
+                position = ((byte & 0x3f) as usize) << 8 | low as usize;
+            }
+            _ => return Err(DnsError::InvalidLabel(byte)),
+        }
+    }
+
+    let len = len.unwrap_or_else(|| position + 1 - offset);
+    match labels.is_empty() {
+        true => Ok((".".to_string(), len)),
+        false => Ok((labels.join("."), len)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(record: DnsRecord) -> Vec<u8> {
+        let mut message = Vec::new();
+        record.write(&mut message, &mut NameCompressor::new(true));
+        assert_eq!(DnsRecord::parse(&message, 0), Ok((record, message.len())));
+        message
+    }
+
+    #[test]
+    fn records() {
+        let buf = roundtrip(DnsRecord::new("example.com", 300, DnsRecordData::A(Ipv4Addr::new(93, 184, 216, 34))));
+        assert_eq!(buf[13..], [0, 1, 0, 1, 0, 0, 0x01, 0x2c, 0, 4, 93, 184, 216, 34]);
+
+        roundtrip(DnsRecord::new("example.com", 60, DnsRecordData::Aaaa(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1))));
+        roundtrip(DnsRecord::new("www.example.com", 60, DnsRecordData::Cname("example.com".to_string())));
+        roundtrip(DnsRecord::new("1.0.0.127.in-addr.arpa", 60, DnsRecordData::Ptr("localhost".to_string())));
+        roundtrip(DnsRecord::new("example.com", 60, DnsRecordData::Ns("a.iana-servers.net".to_string())));
+        roundtrip(DnsRecord::new("example.com", 60, DnsRecordData::Mx { preference: 10, exchange: "mail.example.com".to_string() }));
+        roundtrip(DnsRecord::new("example.com", 60, DnsRecordData::Txt(vec![b"v=spf1 -all".to_vec(), vec![]])));
+        roundtrip(DnsRecord::new("example.com", 60, DnsRecordData::Soa {
+            mname: "ns.icann.org".to_string(),
+            rname: "noc.dns.icann.org".to_string(),
+            serial: 2024010101,
+            refresh: 7200,
+            retry: 3600,
+            expire: 1209600,
+            minimum: 3600,
+        }));
+        roundtrip(DnsRecord::new("_sip._tcp.example.com", 60, DnsRecordData::Srv {
+            priority: 10,
+            weight: 60,
+            port: 5060,
+            target: "sip.example.com".to_string(),
+        }));
+        roundtrip(DnsRecord::new("example.com", 60, DnsRecordData::Caa {
+            flags: 0,
+            tag: "issue".to_string(),
+            value: b"letsencrypt.org".to_vec(),
+        }));
+        roundtrip(DnsRecord::new("example.com", 60, DnsRecordData::Unknown { rtype: DnsType::Https, data: vec![0, 1, 0] }));
+    }
+
+    #[test]
+    fn opt() {
+        let cookie = EdnsOption { code: EdnsOptionCode::Cookie, data: vec![0x11; 8] };
+        let record = DnsRecord::opt(1232, true, vec![cookie]);
+        assert_eq!((record.udp_payload_size(), record.dnssec_ok(), record.edns_version()), (1232, true, 0));
+
+        let buf = roundtrip(record);
+        assert_eq!(buf[..11], [0, 0, 41, 0x04, 0xd0, 0, 0, 0x80, 0, 0, 12]);
+    }
+
+    #[test]
+    fn mismatched_data() {
+        // An A record with five bytes of data is preserved as unknown
+        let message = [0, 0, 1, 0, 1, 0, 0, 0, 0, 0, 5, 1, 2, 3, 4, 5];
+        let (record, _) = DnsRecord::parse(&message, 0).unwrap();
+        assert_eq!(record.data, DnsRecordData::Unknown { rtype: DnsType::A, data: vec![1, 2, 3, 4, 5] });
+        assert_eq!(DnsRecord::parse(&message[..15], 0), Err(DnsError::Truncated));
+    }
+
+    #[test]
+    fn compression() {
+        let mut names = NameCompressor::new(true);
+        let mut message = vec![0; 12];
+        names.write(&mut message, "www.Example.com");
+        names.write(&mut message, "mail.example.com");
+        names.write(&mut message, "example.com.");
+        assert_eq!(message[29..], [4, b'm', b'a', b'i', b'l', 0xc0, 16, 0xc0, 16]);
+
+        assert_eq!(read_name(&message, 12), Ok(("www.Example.com".to_string(), 17)));
+        assert_eq!(read_name(&message, 29), Ok(("mail.Example.com".to_string(), 7)));
+        assert_eq!(read_name(&message, 36), Ok(("Example.com".to_string(), 2)));
+
+        let mut message = Vec::new();
+        NameCompressor::new(false).write(&mut message, "a.a");
+        NameCompressor::new(false).write(&mut message, ".");
+        assert_eq!(message, [1, b'a', 1, b'a', 0, 0]);
+        assert_eq!(read_name(&message, 5), Ok((".".to_string(), 1)));
+    }
+
+    #[test]
+    fn escaped_names() {
+        // A dot within a label, as used by DNS-SD instance names
+        let message = [&[10][..], b"My.Printer", &[5], b"local", &[0]].concat();
+        assert_eq!(read_name(&message, 0), Ok(("My\\.Printer.local".to_string(), 18)));
+
+        let mut buf = Vec::new();
+        write_name(&mut buf, "My\\.Printer.local");
+        assert_eq!(buf, message);
+
+        // Bytes which are not printable ASCII, including invalid UTF-8
+        let message = [2, 0xff, 0xfe, 3, b'a', b' ', b'\\', 0];
+        assert_eq!(read_name(&message, 0), Ok(("\\255\\254.a\\032\\\\".to_string(), 8)));
+
+        let mut buf = Vec::new();
+        write_name(&mut buf, &read_name(&message, 0).unwrap().0);
+        assert_eq!(buf, message);
+    }
+
+    #[test]
+    fn malformed_names() {
+        // A pointer to itself and two pointers to each other
+        assert_eq!(read_name(&[0xc0, 0], 0), Err(DnsError::CompressionLoop(0)));
+        assert_eq!(read_name(&[1, b'a', 0xc0, 4, 0xc0, 2], 0), Err(DnsError::CompressionLoop(0)));
+
+        // A label followed by a pointer back to it
+        assert_eq!(read_name(&[1, b'a', 0xc0, 0], 0), Err(DnsError::NameTooLong));
+
+        assert_eq!(read_name(&[3, b'a', b'b'], 0), Err(DnsError::Truncated));
+        assert_eq!(read_name(&[0x40], 0), Err(DnsError::InvalidLabel(0x40)));
+    }
+}
@@ -1,6 +1,6 @@
 use super::*;
 
-use crate::{MacAddr, primitives::*};
+use crate::{Discriminator, MacAddr, primitives::*, reflect::*};
 
 use thiserror::Error;
 
@@ -8,8 +8,10 @@ use thiserror::Error;
 pub struct EthLayer {
     pub src_mac: MacAddr,
     pub dst_mac: MacAddr,
-    pub _802_1q_tag: Option<Q802_1Tag>,
-    pub ether_type: EtherType,
+    /// The stack of VLAN tags, outermost first.
+    pub tags: Vec<Q802_1Tag>,
+    /// The EtherType or the IEEE 802.3 length of the payload. If left unset, it is derived from the next layer when the packet is finalized.
+    pub ether_type: Option<TypeOrLength>,
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -20,49 +22,83 @@ pub struct Q802_1Tag {
 
 impl Q802_1Tag {
     /// Tag Protocol Identifier (TPID) constant as specified by the 802.1Q encapsulation standard.
-    const TPID: u16 = 0x8100;
+    pub const TPID: u16 = 0x8100;
+
+    /// TPID of an 802.1ad service tag, used as the outer tag of QinQ frames.
+    pub const QINQ_TPID: u16 = 0x88a8;
+
+    /// Pre-standard TPID which some vendors use for the outer tag of QinQ frames.
+    pub const LEGACY_QINQ_TPID: u16 = 0x9100;
+
+    /// The length of a serialized tag in bytes.
+    pub const LEN: usize = 4;
 
     /// Constructs a new 802.1Q tag with the specified Tag Control Information (TIC).
-    fn new(tic: u16) -> Self {
+    pub fn new(tic: u16) -> Self {
         Self {
             tpid: Self::TPID,
             tic,
         }
     }
 
+    /// Constructs a new tag with the specified TPID and Tag Control Information.
+    pub fn with_tpid(tpid: u16, tic: u16) -> Self {
+        Self { tpid, tic }
+    }
+
+    /// Checks if the value is one of the recognized tag protocol identifiers.
+    pub fn is_tpid(value: u16) -> bool {
+        matches!(value, Self::TPID | Self::QINQ_TPID | Self::LEGACY_QINQ_TPID)
+    }
+
     /// Checks if the tag is valid by comparing its tag protocol identifier (TPID).
-    fn is_valid(self) -> bool {
-        self.tpid == Self::TPID
+    pub fn is_valid(self) -> bool {
+        Self::is_tpid(self.tpid)
+    }
+
+    /// Returns the Tag Protocol Identifier.
+    pub fn tpid(self) -> u16 {
+        self.tpid
+    }
+
+    /// Sets the Tag Protocol Identifier.
+    pub fn set_tpid(&mut self, tpid: u16) {
+        self.tpid = tpid;
+    }
+
+    /// Returns the Tag Control Information.
+    pub fn tic(self) -> u16 {
+        self.tic
     }
 
     /// Checks the Drop Eligible Indicator (DEI) bit.
-    fn is_drop_eligible(self) -> bool {
+    pub fn is_drop_eligible(self) -> bool {
         ((self.tic & 0b0001_0000_0000_0000) >> 12) == 1
     }
 
     /// Sets the Drop Eligible Indicator to the specified value.
-    fn set_drop_eligible(&mut self, value: bool) {
+    pub fn set_drop_eligible(&mut self, value: bool) {
         self.tic = (self.tic & !(1 << 12)) | ((value as u16) << 12);
     }
 
     /// Returns the value of the 3-bit Priority Code Point (PCP) field of the TIC.
-    fn pcp(self) -> U3 {
+    pub fn pcp(self) -> U3 {
         U3::try_from(((self.tic & 0b1110_0000_0000_0000) >> 13) as u8).unwrap()
     }
 
     /// Sets the Priority Code Point field.
-    fn set_pcp(&mut self, pcp: U3) {
+    pub fn set_pcp(&mut self, pcp: U3) {
         self.tic = (self.tic & !(0b111 << 13)) | ((pcp.value() as u16)<< 13);
     }
 
     /// Returns the value of the VLAN Identifier (VID).
-    fn vid(self) -> U12 {
+    pub fn vid(self) -> U12 {
         U12::try_from(self.tic & 0b0000_1111_1111_1111).unwrap()
     }
 
-    /// Sets the VLAN identifier. If the provided VID cannot fit into a 12-bit value, returns an error.
-    fn set_vid(&mut self, vid: U12) {
-        self.tic |= vid.value(); 
+    /// Sets the VLAN identifier. The previous VID is cleared first, while the PCP and DEI are kept.
+    pub fn set_vid(&mut self, vid: U12) {
+        self.tic = (self.tic & !0b0000_1111_1111_1111) | vid.value();
     }
 }
 
@@ -75,26 +111,212 @@ impl Default for Q802_1Tag {
     }
 }
 
+impl Reflect for Q802_1Tag {
+    fn fields(&self) -> Vec<Field> {
+        vec![
+            Field::new("tpid", FieldKind::UInt, 0, 16, FieldValue::UInt(self.tpid as u64)),
+            Field::new("pcp", FieldKind::UInt, 16, 3, FieldValue::UInt(self.pcp().value() as u64)),
+            Field::new("dei", FieldKind::Bool, 19, 1, FieldValue::Bool(self.is_drop_eligible())),
+            Field::new("vid", FieldKind::UInt, 20, 12, FieldValue::UInt(self.vid().value() as u64)),
+        ]
+    }
+
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        let invalid = |_| FieldError::InvalidValue(value.to_string());
+
+        match path {
+            "tpid" => self.tpid = parse_uint_as(value)?,
+            "pcp" => self.set_pcp(U3::try_from(parse_uint_as::<u8>(value)?).map_err(invalid)?),
+            "dei" => self.set_drop_eligible(parse_bool(value)?),
+            "vid" => self.set_vid(U12::try_from(parse_uint_as::<u16>(value)?).map_err(invalid)?),
+            _ => return Err(FieldError::UnknownField(path.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
 impl Layer for EthLayer {
     const NAME: &'static str = "Ethernet";
     const TYPE: LayerType = LayerType::EthLayer;
     const OSI_LEVEL: OsiLevel = OsiLevel::DataLink;
 }
 
+impl DynLayer for EthLayer {
+    fn header_len(&self) -> usize {
+        Self::MIN_LEN + self.tags.len() * Q802_1Tag::LEN
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.dst_mac.octets());
+        buf.extend_from_slice(&self.src_mac.octets());
+
+        for tag in &self.tags {
+            buf.extend_from_slice(&tag.tpid.to_be_bytes());
+            buf.extend_from_slice(&tag.tic.to_be_bytes());
+        }
+
+        buf.extend_from_slice(&self.ether_type.map_or(0, u16::from).to_be_bytes());
+    }
+
+    fn payload_len(&self) -> Option<usize> {
+        match self.ether_type {
+            Some(TypeOrLength::Length(length)) => Some(length as usize),
+            _ => None,
+        }
+    }
+
+    fn finalize(&mut self, ctx: &FinalizeContext) {
+        if self.ether_type.is_some() {
+            return;
+        }
+
+        self.ether_type = match ctx.next() {
+            // LLC frames are carried in IEEE 802.3 frames, which specify the length of the payload without padding
+            Some(next) if next.layer_type() == LayerType::LlcLayer => {
+                Some(TypeOrLength::Length(ctx.unpadded_payload().len() as u16))
+            }
+            next => next.and_then(|next| EtherType::of_layer(next.layer_type())).map(TypeOrLength::Type),
+        };
+    }
+
+    fn next_discriminators(&self) -> Vec<Discriminator> {
+        match self.ether_type {
+            Some(TypeOrLength::Type(ether_type)) => vec![Discriminator::EtherType(ether_type.value())],
+            Some(TypeOrLength::Length(_)) => vec![Discriminator::Ieee8023],
+            Some(TypeOrLength::Undefined(_)) | None => Vec::new(),
+        }
+    }
+
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        let view = EthView::new(bytes)?;
+        Ok((view.to_layer(), view.header_len()))
+    }
+
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+}
+
+impl Reflect for EthLayer {
+    fn fields(&self) -> Vec<Field> {
+        let mut fields = vec![
+            Field::new("eth.dst", FieldKind::Mac, 0, 48, FieldValue::Mac(self.dst_mac)),
+            Field::new("eth.src", FieldKind::Mac, 48, 48, FieldValue::Mac(self.src_mac)),
+        ];
+
+        let mut offset = 96;
+        for (i, tag) in self.tags.iter().enumerate() {
+            let prefix = match i {
+                0 => "eth.vlan".to_string(),
+                i => format!("eth.vlan[{}]", i),
+            };
+
+            fields.extend(tag.fields().into_iter().map(|field| Field {
+                name: format!("{}.{}", prefix, field.name),
+                bit_offset: offset + field.bit_offset,
+                ..field
+            }));
+            offset += Q802_1Tag::LEN * 8;
+        }
+
+        fields.push(match self.ether_type {
+            None => Field::new("eth.type", FieldKind::Enum, offset, 16, FieldValue::Unset).computed(),
+            Some(TypeOrLength::Type(ether_type)) => {
+                Field::new("eth.type", FieldKind::Enum, offset, 16, ether_type.field_value()).computed()
+            }
+            Some(TypeOrLength::Length(length)) => {
+                Field::new("eth.len", FieldKind::UInt, offset, 16, FieldValue::UInt(length as u64)).computed()
+            }
+            Some(TypeOrLength::Undefined(value)) => {
+                Field::new("eth.type", FieldKind::UInt, offset, 16, FieldValue::UInt(value as u64)).computed()
+            }
+        });
+
+        fields
+    }
+
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        match path {
+            "eth.dst" => self.dst_mac = parse_str(value)?,
+            "eth.src" => self.src_mac = parse_str(value)?,
+            "eth.type" | "eth.len" if value.trim() == "auto" => self.ether_type = None,
+            // Numeric values are classified like the parsed field, so that values below 0x0600 are written as lengths
+            "eth.type" => self.ether_type = Some(TypeOrLength::from(EtherType::parse_field(value)?.value())),
+            "eth.len" => self.ether_type = Some(TypeOrLength::from(parse_uint_as::<u16>(value)?)),
+            _ => {
+                let (index, tag_path) = parse_tag_path(path).ok_or_else(|| FieldError::UnknownField(path.to_string()))?;
+
+                // Setting a field of the tag just past the innermost one appends a new tag to the stack, but only if the
+                // value is valid
+                if index == self.tags.len() {
+                    let mut tag = Q802_1Tag::default();
+                    tag.set_field(tag_path, value)?;
+                    self.tags.push(tag);
+                    return Ok(());
+                }
+
+                match self.tags.get_mut(index) {
+                    Some(tag) => tag.set_field(tag_path, value)?,
+                    None => return Err(FieldError::UnknownField(path.to_string())),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits a path such as `eth.vlan.vid` or `eth.vlan[1].vid` into the index of the tag and the path within the tag.
+fn parse_tag_path(path: &str) -> Option<(usize, &str)> {
+    let rest = path.strip_prefix("eth.vlan")?;
+
+    match rest.strip_prefix('.') {
+        Some(tag_path) => Some((0, tag_path)),
+        None => {
+            let (index, tag_path) = rest.strip_prefix('[')?.split_once("].")?;
+            Some((index.parse().ok()?, tag_path))
+        }
+    }
+}
+
 impl EthLayer {
-    fn new(
+    /// The length of an untagged Ethernet header in bytes.
+    pub const MIN_LEN: usize = 14;
+
+    /// Constructs a new Ethernet layer with the specified stack of VLAN tags, outermost first.
+    pub fn new(
         src_mac: MacAddr,
         dst_mac: MacAddr,
         ether_type: EtherType,
-        _802_1q_tag: Option<Q802_1Tag>,
+        tags: Vec<Q802_1Tag>,
     ) -> Self {
         EthLayer {
             src_mac,
             dst_mac,
-            _802_1q_tag,
-            ether_type,
+            tags,
+            ether_type: Some(TypeOrLength::Type(ether_type)),
         }
     }
+
+    /// Returns the outermost VLAN tag, if the frame is tagged.
+    pub fn outer_tag(&self) -> Option<Q802_1Tag> {
+        self.tags.first().copied()
+    }
+
+    /// Pushes a new outermost VLAN tag onto the stack.
+    pub fn push_tag(&mut self, tag: Q802_1Tag) {
+        self.tags.insert(0, tag);
+    }
+
+    /// Removes and returns the outermost VLAN tag, if the frame is tagged.
+    pub fn pop_tag(&mut self) -> Option<Q802_1Tag> {
+        (!self.tags.is_empty()).then(|| self.tags.remove(0))
+    }
 }
 
 impl Default for EthLayer {
@@ -103,40 +325,190 @@ impl Default for EthLayer {
         EthLayer {
             src_mac: MacAddr::default(),
             dst_mac: MacAddr::default(),
-            _802_1q_tag: None,
-            ether_type: EtherType::Empty,
+            tags: Vec::new(),
+            ether_type: None,
         }
     }
 }
 
-#[derive(Error, Debug, PartialEq, Eq, Clone)]
-pub enum EthError {
-    #[error("The specified EtherType represents a length and not an actual type.")]
-    EtherTypeIsLength,
+/// A borrowed, zero-copy view of an Ethernet header at the start of a byte slice.
+///
+/// The length of the slice is validated once on construction and fields are decoded only when accessed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct EthView<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> EthView<'a> {
+    /// Constructs a view of the Ethernet header at the start of the slice.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, LayerError> {
+        if bytes.len() < EthLayer::MIN_LEN {
+            return Err(LayerError::BufferTooShort(EthLayer::NAME));
+        }
+
+        let view = Self { bytes };
+        if bytes.len() < view.header_len() {
+            return Err(LayerError::BufferTooShort(EthLayer::NAME));
+        }
+
+        Ok(view)
+    }
+
+    /// Returns the destination MAC address.
+    pub fn dst_mac(&self) -> MacAddr {
+        MacAddr::from(<[u8; 6]>::try_from(&self.bytes[0..6]).unwrap())
+    }
+
+    /// Returns the source MAC address.
+    pub fn src_mac(&self) -> MacAddr {
+        MacAddr::from(<[u8; 6]>::try_from(&self.bytes[6..12]).unwrap())
+    }
+
+    /// Checks if the frame carries at least one VLAN tag.
+    pub fn is_tagged(&self) -> bool {
+        self.tag_count() > 0
+    }
 
-    #[error("The specified EtherType is unknown.")]
-    UnknownEtherType,
+    /// Returns the number of stacked VLAN tags. Tags are recognized by their TPID, see [`Q802_1Tag::is_tpid`].
+    pub fn tag_count(&self) -> usize {
+        let mut count = 0;
+        let mut offset = 12;
+
+        while offset + 2 <= self.bytes.len()
+            && Q802_1Tag::is_tpid(u16::from_be_bytes([self.bytes[offset], self.bytes[offset + 1]]))
+        {
+            count += 1;
+            offset += Q802_1Tag::LEN;
+        }
+
+        count
+    }
+
+    /// Returns the VLAN tag at the specified position of the stack, where 0 is the outermost tag.
+    pub fn tag_at(&self, index: usize) -> Option<Q802_1Tag> {
+        (index < self.tag_count()).then(|| {
+            let offset = 12 + index * Q802_1Tag::LEN;
+            Q802_1Tag::with_tpid(
+                u16::from_be_bytes([self.bytes[offset], self.bytes[offset + 1]]),
+                u16::from_be_bytes([self.bytes[offset + 2], self.bytes[offset + 3]]),
+            )
+        })
+    }
+
+    /// Returns the outermost VLAN tag, if the frame carries one.
+    pub fn tag(&self) -> Option<Q802_1Tag> {
+        self.tag_at(0)
+    }
+
+    /// Returns the stack of VLAN tags, outermost first.
+    pub fn tags(&self) -> Vec<Q802_1Tag> {
+        (0..self.tag_count()).filter_map(|i| self.tag_at(i)).collect()
+    }
+
+    /// Returns the raw value of the Length/Type field.
+    pub fn ether_type_raw(&self) -> u16 {
+        let offset = self.header_len() - 2;
+        u16::from_be_bytes([self.bytes[offset], self.bytes[offset + 1]])
+    }
+
+    /// Returns the EtherType or the IEEE 802.3 length of the payload.
+    pub fn ether_type(&self) -> TypeOrLength {
+        TypeOrLength::from(self.ether_type_raw())
+    }
+
+    /// Returns the length of the header in bytes.
+    pub fn header_len(&self) -> usize {
+        EthLayer::MIN_LEN + self.tag_count() * Q802_1Tag::LEN
+    }
+
+    /// Returns the bytes following the header.
+    pub fn payload(&self) -> &'a [u8] {
+        &self.bytes[self.header_len()..]
+    }
+
+    /// Converts the view into an owned Ethernet layer.
+    pub fn to_layer(&self) -> EthLayer {
+        EthLayer {
+            src_mac: self.src_mac(),
+            dst_mac: self.dst_mac(),
+            tags: self.tags(),
+            ether_type: Some(self.ether_type()),
+        }
+    }
 }
 
-#[repr(u16)]
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub enum EtherType {
-    IPv4 = 0x0800,
-    Empty = 0x0000,
+/// A mutable, zero-copy view of an Ethernet header at the start of a byte slice, which allows fields to be edited in place.
+#[derive(Debug, PartialEq, Eq)]
+pub struct EthViewMut<'a> {
+    bytes: &'a mut [u8],
 }
 
-impl TryFrom<u16> for EtherType {
-    type Error = EthError;
+impl<'a> EthViewMut<'a> {
+    /// Constructs a mutable view of the Ethernet header at the start of the slice.
+    pub fn new(bytes: &'a mut [u8]) -> Result<Self, LayerError> {
+        EthView::new(bytes)?;
+        Ok(Self { bytes })
+    }
+
+    /// Returns a read-only view of the header.
+    pub fn as_view(&self) -> EthView<'_> {
+        EthView { bytes: self.bytes }
+    }
 
-    fn try_from(value: u16) -> Result<Self, EthError> {
-        match value {
-            _ if value <= 1500 => Err(EthError::EtherTypeIsLength),
-            0x0800 => Ok(EtherType::IPv4),
-            _ => Err(EthError::UnknownEtherType),
+    /// Sets the destination MAC address.
+    pub fn set_dst_mac(&mut self, mac: MacAddr) {
+        self.bytes[0..6].copy_from_slice(&mac.octets());
+    }
+
+    /// Sets the source MAC address.
+    pub fn set_src_mac(&mut self, mac: MacAddr) {
+        self.bytes[6..12].copy_from_slice(&mac.octets());
+    }
+
+    /// Replaces the Tag Control Information of the tag at the specified position of the stack, where 0 is the outermost
+    /// tag. Fails if the frame has no such tag, since a tag cannot be inserted in place.
+    pub fn set_tag(&mut self, index: usize, tag: Q802_1Tag) -> Result<(), EthError> {
+        if index >= self.as_view().tag_count() {
+            return Err(EthError::NotTagged);
         }
+
+        let offset = 12 + index * Q802_1Tag::LEN + 2;
+        self.bytes[offset..offset + 2].copy_from_slice(&tag.tic.to_be_bytes());
+        Ok(())
+    }
+
+    /// Sets the VLAN identifier of the tag at the specified position of the stack. Fails if the frame has no such tag.
+    pub fn set_vid(&mut self, index: usize, vid: U12) -> Result<(), EthError> {
+        let mut tag = self.as_view().tag_at(index).ok_or(EthError::NotTagged)?;
+        tag.set_vid(vid);
+        self.set_tag(index, tag)
+    }
+
+    /// Sets the EtherType or the IEEE 802.3 length of the payload.
+    pub fn set_ether_type(&mut self, ether_type: impl Into<TypeOrLength>) {
+        let offset = self.as_view().header_len() - 2;
+        self.bytes[offset..offset + 2].copy_from_slice(&u16::from(ether_type.into()).to_be_bytes());
+    }
+
+    /// Returns the bytes following the header.
+    pub fn payload_mut(&mut self) -> &mut [u8] {
+        let header_len = self.as_view().header_len();
+        &mut self.bytes[header_len..]
     }
 }
 
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum EthError {
+    #[error("The specified EtherType represents a length and not an actual type.")]
+    EtherTypeIsLength,
+
+    #[error("The value {0:#06x} of the Length/Type field is neither a length nor an EtherType.")]
+    UndefinedTypeOrLength(u16),
+
+    #[error("The frame does not carry the requested VLAN tag.")]
+    NotTagged,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,7 +524,7 @@ mod tests {
     fn create_layer() {
         let src_mac = MacAddr::new(0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff);
         let dst_mac = MacAddr::new(0x11, 0x22, 0x33, 0x44, 0x55, 0x66);
-        let eth_layer = EthLayer::new(src_mac, dst_mac, EtherType::IPv4, None);
+        let eth_layer = EthLayer::new(src_mac, dst_mac, EtherType::IPv4, vec![]);
 
         assert_eq!(
             eth_layer.src_mac,
@@ -162,18 +534,221 @@ mod tests {
             eth_layer.dst_mac,
             MacAddr::new(0x11, 0x22, 0x33, 0x44, 0x55, 0x66)
         );
-        assert_eq!(eth_layer.ether_type, EtherType::IPv4);
+        assert_eq!(eth_layer.ether_type, Some(TypeOrLength::Type(EtherType::IPv4)));
         assert_eq!(
             eth_layer,
             EthLayer {
                 src_mac: MacAddr::new(0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff),
                 dst_mac: MacAddr::new(0x11, 0x22, 0x33, 0x44, 0x55, 0x66),
-                ether_type: EtherType::IPv4,
-                _802_1q_tag: None
+                ether_type: Some(TypeOrLength::Type(EtherType::IPv4)),
+                tags: vec![]
             }
         );
     }
 
+    #[test]
+    fn write_and_parse() {
+        let src_mac = MacAddr::new(0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff);
+        let dst_mac = MacAddr::new(0x11, 0x22, 0x33, 0x44, 0x55, 0x66);
+        let mut tag = Q802_1Tag::default();
+        tag.set_vid(U12::try_from(100).unwrap());
+        let eth_layer = EthLayer::new(src_mac, dst_mac, EtherType::IPv4, vec![tag]);
+
+        let mut buf = Vec::new();
+        eth_layer.write(&mut buf);
+        assert_eq!(buf.len(), eth_layer.header_len());
+        assert_eq!(
+            buf,
+            [
+                0x11, 0x22, 0x33, 0x44, 0x55, 0x66,
+                0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff,
+                0x81, 0x00, 0x00, 0x64,
+                0x08, 0x00
+            ]
+        );
+
+        assert_eq!(EthLayer::parse(&buf), Ok((eth_layer, 18)));
+        assert_eq!(EthLayer::parse(&buf[..10]), Err(LayerError::BufferTooShort("Ethernet")));
+        assert_eq!(EthLayer::parse(&buf[..16]), Err(LayerError::BufferTooShort("Ethernet")));
+    }
+
+    #[test]
+    fn parse_any_ether_type() {
+        let mut buf = vec![0xff; 12];
+        buf.extend_from_slice(&[0x12, 0x34]);
+        let (eth_layer, _) = EthLayer::parse(&buf).unwrap();
+        assert_eq!(eth_layer.ether_type, Some(TypeOrLength::Type(EtherType::Unknown(0x1234))));
+        assert!(eth_layer.next_discriminators().contains(&Discriminator::EtherType(0x1234)));
+
+        // IEEE 802.3 frames carry the length of the payload instead of its type
+        buf[12..14].copy_from_slice(&[0x00, 0x2e]);
+        let (eth_layer, _) = EthLayer::parse(&buf).unwrap();
+        assert_eq!(eth_layer.ether_type, Some(TypeOrLength::Length(46)));
+
+        let mut written = Vec::new();
+        eth_layer.write(&mut written);
+        assert_eq!(written, buf);
+    }
+
+    #[test]
+    fn views() {
+        let mut tag = Q802_1Tag::default();
+        tag.set_vid(U12::try_from(100).unwrap());
+        let eth_layer = EthLayer::new(MacAddr::new(0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff), MacAddr::broadcast(), EtherType::IPv4, vec![tag]);
+        let mut buf = Vec::new();
+        eth_layer.write(&mut buf);
+        buf.extend_from_slice(&[1, 2, 3]);
+
+        let view = EthView::new(&buf).unwrap();
+        assert_eq!(view.dst_mac(), MacAddr::broadcast());
+        assert_eq!(view.src_mac(), MacAddr::new(0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff));
+        assert_eq!(view.tag(), Some(tag));
+        assert_eq!(view.ether_type(), TypeOrLength::Type(EtherType::IPv4));
+        assert_eq!(view.header_len(), 18);
+        assert_eq!(view.payload(), [1, 2, 3]);
+        assert_eq!(view.to_layer(), eth_layer.clone());
+
+        let mut view = EthViewMut::new(&mut buf).unwrap();
+        view.set_src_mac(MacAddr::new(0x11, 0x22, 0x33, 0x44, 0x55, 0x66));
+        view.set_vid(0, U12::try_from(200).unwrap()).unwrap();
+        view.payload_mut()[0] = 9;
+        assert_eq!(view.as_view().tag().unwrap().vid(), U12::try_from(200).unwrap());
+        assert_eq!(&buf[6..12], [0x11, 0x22, 0x33, 0x44, 0x55, 0x66]);
+        assert_eq!(&buf[14..16], [0x00, 0xc8]);
+        assert_eq!(buf[18], 9);
+
+        let mut untagged = buf[..12].to_vec();
+        untagged.extend_from_slice(&[0x08, 0x00]);
+        let mut view = EthViewMut::new(&mut untagged).unwrap();
+        assert_eq!(view.set_vid(0, U12::try_from(1).unwrap()), Err(EthError::NotTagged));
+        assert_eq!(view.as_view().payload(), []);
+
+        assert_eq!(EthView::new(&buf[..13]), Err(LayerError::BufferTooShort("Ethernet")));
+        assert_eq!(EthView::new(&buf[..17]), Err(LayerError::BufferTooShort("Ethernet")));
+    }
+
+    #[test]
+    fn stacked_tags() {
+        let mut eth_layer = EthLayer::new(MacAddr::default(), MacAddr::broadcast(), EtherType::IPv4, vec![Q802_1Tag::new(0x0064)]);
+        eth_layer.push_tag(Q802_1Tag::with_tpid(Q802_1Tag::QINQ_TPID, 0x2005));
+        assert_eq!(eth_layer.outer_tag().unwrap().tpid(), Q802_1Tag::QINQ_TPID);
+        assert_eq!(eth_layer.header_len(), 22);
+
+        let mut buf = Vec::new();
+        eth_layer.write(&mut buf);
+        assert_eq!(&buf[12..], [0x88, 0xa8, 0x20, 0x05, 0x81, 0x00, 0x00, 0x64, 0x08, 0x00]);
+        assert_eq!(EthLayer::parse(&buf), Ok((eth_layer.clone(), 22)));
+
+        let view = EthView::new(&buf).unwrap();
+        assert_eq!(view.tag_count(), 2);
+        assert_eq!(view.tag_at(1), Some(Q802_1Tag::new(0x0064)));
+        assert_eq!(view.tag_at(2), None);
+        assert_eq!(view.ether_type(), TypeOrLength::Type(EtherType::IPv4));
+        assert_eq!(EthView::new(&buf[..20]), Err(LayerError::BufferTooShort("Ethernet")));
+
+        let mut view = EthViewMut::new(&mut buf).unwrap();
+        view.set_vid(1, U12::try_from(200).unwrap()).unwrap();
+        assert_eq!(view.set_vid(2, U12::try_from(1).unwrap()), Err(EthError::NotTagged));
+        assert_eq!(&buf[18..20], [0x00, 0xc8]);
+
+        // Legacy 0x9100 outer tags are recognized as well
+        buf[12..14].copy_from_slice(&Q802_1Tag::LEGACY_QINQ_TPID.to_be_bytes());
+        let (parsed, _) = EthLayer::parse(&buf).unwrap();
+        assert_eq!(parsed.tags.len(), 2);
+
+        assert_eq!(eth_layer.pop_tag(), Some(Q802_1Tag::with_tpid(Q802_1Tag::QINQ_TPID, 0x2005)));
+        assert_eq!(eth_layer.pop_tag(), Some(Q802_1Tag::new(0x0064)));
+        assert_eq!(eth_layer.pop_tag(), None);
+        assert_eq!(eth_layer.header_len(), EthLayer::MIN_LEN);
+    }
+
+    #[test]
+    fn reflect_stacked_tags() {
+        let mut eth_layer = EthLayer::default();
+        eth_layer.set_field("eth.vlan.tpid", "0x88a8").unwrap();
+        eth_layer.set_field("eth.vlan[1].vid", "100").unwrap();
+        assert_eq!(eth_layer.tags.len(), 2);
+        assert_eq!(eth_layer.get_field("eth.vlan.tpid"), Some(FieldValue::UInt(0x88a8)));
+        assert_eq!(eth_layer.get_field("eth.vlan[1].tpid"), Some(FieldValue::UInt(0x8100)));
+
+        let fields = eth_layer.fields();
+        let vid = fields.iter().find(|field| field.name == "eth.vlan[1].vid").unwrap();
+        assert_eq!(vid.bit_offset, 148);
+
+        assert_eq!(
+            eth_layer.set_field("eth.vlan[3].vid", "1"),
+            Err(FieldError::UnknownField("eth.vlan[3].vid".to_string()))
+        );
+    }
+
+    #[test]
+    fn reflect() {
+        let mut eth_layer = EthLayer::new(MacAddr::new(0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff), MacAddr::broadcast(), EtherType::IPv4, vec![]);
+        let names: Vec<String> = eth_layer.fields().into_iter().map(|field| field.name).collect();
+        assert_eq!(names, ["eth.dst", "eth.src", "eth.type"]);
+
+        assert_eq!(eth_layer.get_field("eth.src"), Some(FieldValue::Mac(MacAddr::new(0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff))));
+        assert_eq!(
+            eth_layer.get_field("eth.type"),
+            Some(FieldValue::Enum { type_name: "EtherType", name: "IPv4".to_string(), value: 0x0800 })
+        );
+        assert_eq!(eth_layer.get_field("eth.vlan.vid"), None);
+
+        eth_layer.set_field("eth.dst", "11:22:33:44:55:66").unwrap();
+        eth_layer.set_field("eth.vlan.vid", "100").unwrap();
+        eth_layer.set_field("eth.vlan.pcp", "5").unwrap();
+        eth_layer.set_field("eth.vlan.dei", "true").unwrap();
+        assert_eq!(eth_layer.dst_mac, MacAddr::new(0x11, 0x22, 0x33, 0x44, 0x55, 0x66));
+        assert_eq!(eth_layer.get_field("eth.vlan.vid"), Some(FieldValue::UInt(100)));
+        assert_eq!(eth_layer.get_field("eth.vlan.pcp"), Some(FieldValue::UInt(5)));
+        assert_eq!(eth_layer.get_field("eth.vlan.dei"), Some(FieldValue::Bool(true)));
+
+        let fields = eth_layer.fields();
+        let vid = fields.iter().find(|field| field.name == "eth.vlan.vid").unwrap();
+        assert_eq!((vid.kind, vid.bit_offset, vid.bit_width), (FieldKind::UInt, 116, 12));
+        let ether_type = fields.iter().find(|field| field.name == "eth.type").unwrap();
+        assert_eq!((ether_type.bit_offset, ether_type.bit_width), (128, 16));
+
+        eth_layer.set_field("eth.type", "auto").unwrap();
+        assert_eq!(eth_layer.get_field("eth.type"), Some(FieldValue::Unset));
+        eth_layer.set_field("eth.type", "0x0800").unwrap();
+        assert_eq!(eth_layer.ether_type, Some(TypeOrLength::Type(EtherType::IPv4)));
+        eth_layer.set_field("eth.type", "Lldp").unwrap();
+        assert_eq!(eth_layer.get_field("eth.type"), Some(EtherType::Lldp.field_value()));
+        eth_layer.set_field("eth.len", "46").unwrap();
+        assert_eq!(eth_layer.get_field("eth.len"), Some(FieldValue::UInt(46)));
+        assert_eq!(eth_layer.get_field("eth.type"), None);
+
+        eth_layer.set_field("eth.type", "0x0040").unwrap();
+        assert_eq!(eth_layer.ether_type, Some(TypeOrLength::Length(0x40)));
+        eth_layer.set_field("eth.type", "0x05ff").unwrap();
+        assert_eq!(eth_layer.ether_type, Some(TypeOrLength::Undefined(0x05ff)));
+        eth_layer.set_field("eth.len", "0x0800").unwrap();
+        assert_eq!(eth_layer.ether_type, Some(TypeOrLength::Type(EtherType::IPv4)));
+
+        assert_eq!(eth_layer.set_field("eth.vlan.pcp", "8"), Err(FieldError::InvalidValue("8".to_string())));
+        assert_eq!(eth_layer.set_field("eth.vlan[1].pcp", "8"), Err(FieldError::InvalidValue("8".to_string())));
+        assert_eq!(eth_layer.tags.len(), 1);
+        assert_eq!(eth_layer.set_field("eth.src", "nope"), Err(FieldError::InvalidValue("nope".to_string())));
+        assert_eq!(eth_layer.set_field("eth.ttl", "1"), Err(FieldError::UnknownField("eth.ttl".to_string())));
+    }
+
+    #[test]
+    fn dyn_layer() {
+        let eth_layer = EthLayer::new(MacAddr::default(), MacAddr::broadcast(), EtherType::IPv4, vec![]);
+        let layer: Box<dyn DynLayer> = Box::new(eth_layer.clone());
+
+        assert_eq!(layer.name(), "Ethernet");
+        assert_eq!(layer.layer_type(), LayerType::EthLayer);
+        assert_eq!(layer.osi_level(), OsiLevel::DataLink);
+        assert_eq!(layer.header_len(), EthLayer::MIN_LEN);
+        assert_eq!(layer.payload_len(), None);
+
+        let cloned = layer.clone();
+        assert!(cloned.is::<EthLayer>());
+        assert_eq!(cloned.downcast_ref::<EthLayer>(), Some(&eth_layer));
+    }
+
     #[test]
     fn convert_ether_type() {
         let ether_type = EtherType::try_from(0x0800);
@@ -182,11 +757,29 @@ mod tests {
         let ether_type = EtherType::try_from(0x0010);
         assert_eq!(ether_type, Err(EthError::EtherTypeIsLength));
 
+        let ether_type = EtherType::try_from(0x05dd);
+        assert_eq!(ether_type, Err(EthError::UndefinedTypeOrLength(0x05dd)));
+
         let ether_type = EtherType::try_from(0x9999);
-        assert_eq!(ether_type, Err(EthError::UnknownEtherType));
+        assert_eq!(ether_type, Ok(EtherType::Unknown(0x9999)));
+    }
+
+    #[test]
+    fn overwrite_vid() {
+        // Setting the VID used to OR the new value into the previous one
+        let mut tag = Q802_1Tag::default();
+        tag.set_pcp(0b101.try_into().unwrap());
+        tag.set_drop_eligible(true);
+        tag.set_vid(0x0f0.try_into().unwrap());
+        tag.set_vid(0x00f.try_into().unwrap());
+
+        assert_eq!(tag.vid(), 0x00f.try_into().unwrap());
+        assert_eq!(tag.pcp(), 0b101.try_into().unwrap());
+        assert!(tag.is_drop_eligible());
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_802_1q_tag() {
         
         // Test default tag initialisation
@@ -0,0 +1,171 @@
+use super::*;
+
+registry_enum! {
+    /// The EtherTypes registered by the IEEE Registration Authority which identify the payload of an Ethernet frame.
+    pub enum EtherType: u16 {
+        IPv4 = 0x0800,
+        Arp = 0x0806,
+        WakeOnLan = 0x0842,
+        Trill = 0x22f3,
+        /// Transparent Ethernet Bridging, used to carry Ethernet frames in tunnels such as GRE.
+        TransparentEthernetBridging = 0x6558,
+        Rarp = 0x8035,
+        AppleTalk = 0x809b,
+        Aarp = 0x80f3,
+        /// IEEE 802.1Q customer VLAN tag.
+        Vlan = 0x8100,
+        Ipx = 0x8137,
+        Snmp = 0x814c,
+        IPv6 = 0x86dd,
+        EthernetFlowControl = 0x8808,
+        /// IEEE 802.3 slow protocols, such as LACP.
+        SlowProtocols = 0x8809,
+        CobraNet = 0x8819,
+        Mpls = 0x8847,
+        MplsMulticast = 0x8848,
+        PppoeDiscovery = 0x8863,
+        PppoeSession = 0x8864,
+        HomePlug = 0x887b,
+        /// IEEE 802.1X port-based network access control.
+        Eapol = 0x888e,
+        Profinet = 0x8892,
+        HyperScsi = 0x889a,
+        AtaOverEthernet = 0x88a2,
+        EtherCat = 0x88a4,
+        /// IEEE 802.1ad service VLAN tag.
+        QinQ = 0x88a8,
+        Powerlink = 0x88ab,
+        /// IEEE 802.1 local experimental EtherType 1.
+        LocalExperimental1 = 0x88b5,
+        /// IEEE 802.1 local experimental EtherType 2.
+        LocalExperimental2 = 0x88b6,
+        Goose = 0x88b8,
+        GseManagement = 0x88b9,
+        SampledValues = 0x88ba,
+        Lldp = 0x88cc,
+        Sercos = 0x88cd,
+        Mrp = 0x88e3,
+        /// IEEE 802.1AE MAC security.
+        MacSec = 0x88e5,
+        /// IEEE 802.1ah provider backbone bridging instance tag.
+        Pbb = 0x88e7,
+        /// IEEE 1588 precision time protocol.
+        Ptp = 0x88f7,
+        Prp = 0x88fb,
+        /// IEEE 802.1ag connectivity fault management.
+        Cfm = 0x8902,
+        FCoE = 0x8906,
+        FCoEInitialization = 0x8914,
+        RoCE = 0x8915,
+        TTEthernet = 0x891d,
+        Hsr = 0x892f,
+        Ieee1905 = 0x893a,
+        /// Network service header.
+        Nsh = 0x894f,
+        Loopback = 0x9000,
+        /// Legacy double tagging VLAN tag.
+        VlanDoubleTagged = 0x9100,
+    }
+}
+
+impl EtherType {
+    /// The smallest value of the Length/Type field which is interpreted as an EtherType.
+    pub const MIN_VALUE: u16 = 0x0600;
+
+    /// Returns the EtherType which identifies the specified layer type as a payload, if there is one.
+    pub fn of_layer(layer_type: LayerType) -> Option<Self> {
+        match layer_type {
+            LayerType::Ipv4Layer => Some(EtherType::IPv4),
+            LayerType::Ipv6Layer => Some(EtherType::IPv6),
+            LayerType::ArpLayer => Some(EtherType::Arp),
+            LayerType::EthLayer => Some(EtherType::TransparentEthernetBridging),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<u16> for EtherType {
+    type Error = EthError;
+
+    /// Interprets the value as an EtherType. Values which are not registered are returned as [`EtherType::Unknown`].
+    fn try_from(value: u16) -> Result<Self, EthError> {
+        match value {
+            _ if value <= TypeOrLength::MAX_LENGTH => Err(EthError::EtherTypeIsLength),
+            _ if value < EtherType::MIN_VALUE => Err(EthError::UndefinedTypeOrLength(value)),
+            _ => Ok(EtherType::from_value(value)),
+        }
+    }
+}
+
+/// The interpretation of the Length/Type field of an Ethernet header.
+///
+/// Values up to 1500 give the length of an IEEE 802.3 payload, while values from 0x0600 on identify its type.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum TypeOrLength {
+    Type(EtherType),
+    Length(u16),
+    /// A value between 1501 and 0x05ff, which is neither a length nor a type.
+    Undefined(u16),
+}
+
+impl TypeOrLength {
+    /// The largest value of the Length/Type field which is interpreted as a length.
+    pub const MAX_LENGTH: u16 = 1500;
+}
+
+impl From<u16> for TypeOrLength {
+    fn from(value: u16) -> Self {
+        match EtherType::try_from(value) {
+            Ok(ether_type) => TypeOrLength::Type(ether_type),
+            Err(EthError::UndefinedTypeOrLength(_)) => TypeOrLength::Undefined(value),
+            Err(_) => TypeOrLength::Length(value),
+        }
+    }
+}
+
+impl From<TypeOrLength> for u16 {
+    fn from(value: TypeOrLength) -> Self {
+        match value {
+            TypeOrLength::Type(ether_type) => ether_type.value(),
+            TypeOrLength::Length(length) | TypeOrLength::Undefined(length) => length,
+        }
+    }
+}
+
+impl From<EtherType> for TypeOrLength {
+    fn from(ether_type: EtherType) -> Self {
+        TypeOrLength::Type(ether_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry() {
+        assert_eq!(EtherType::from_value(0x86dd), EtherType::IPv6);
+        assert_eq!(EtherType::from_value(0x88cc), EtherType::Lldp);
+        assert_eq!(EtherType::from_value(0x1234), EtherType::Unknown(0x1234));
+        assert_eq!(EtherType::Arp.value(), 0x0806);
+        assert_eq!(u16::from(EtherType::Unknown(0x1234)), 0x1234);
+        assert_eq!(EtherType::QinQ.name(), "QinQ");
+        assert_eq!(EtherType::Unknown(0x1234).name(), "Unknown");
+        assert_eq!(EtherType::from_name("MacSec"), Some(EtherType::MacSec));
+        assert_eq!(EtherType::from_name("Nope"), None);
+    }
+
+    #[test]
+    fn type_or_length() {
+        assert_eq!(TypeOrLength::from(0x0800), TypeOrLength::Type(EtherType::IPv4));
+        assert_eq!(TypeOrLength::from(0x9999), TypeOrLength::Type(EtherType::Unknown(0x9999)));
+        assert_eq!(TypeOrLength::from(1500), TypeOrLength::Length(1500));
+        assert_eq!(TypeOrLength::from(46), TypeOrLength::Length(46));
+        assert_eq!(TypeOrLength::from(1501), TypeOrLength::Undefined(1501));
+        assert_eq!(TypeOrLength::from(0x05ff), TypeOrLength::Undefined(0x05ff));
+        assert_eq!(TypeOrLength::from(EtherType::MIN_VALUE), TypeOrLength::Type(EtherType::Unknown(0x0600)));
+        assert_eq!(u16::from(TypeOrLength::Undefined(0x0550)), 0x0550);
+        assert_eq!(u16::from(TypeOrLength::Length(46)), 46);
+        assert_eq!(u16::from(TypeOrLength::from(EtherType::Ptp)), 0x88f7);
+    }
+}
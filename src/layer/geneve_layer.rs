@@ -0,0 +1,397 @@
+use super::*;
+
+use crate::{reflect::*, Discriminator};
+
+use thiserror::Error;
+
+/// A Geneve option, which is identified by its class and type.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GeneveOption {
+    pub class: u16,
+    /// The type, whose most significant bit marks the option as critical.
+    pub option_type: u8,
+    /// The 3 reserved bits before the length.
+    pub reserved: u8,
+    /// The data, which is padded with zeros to a multiple of four bytes when written.
+    pub data: Vec<u8>,
+}
+
+impl GeneveOption {
+    /// The length of an option without data in bytes.
+    pub const MIN_LEN: usize = 4;
+
+    /// The bit of the type which marks an option as critical.
+    pub const CRITICAL: u8 = 0x80;
+
+    /// The length of the longest data the 5-bit length field can describe in bytes.
+    pub const MAX_DATA_LEN: usize = 0x1f * 4;
+
+    /// Constructs an option of the specified class and type.
+    pub fn new(class: u16, option_type: u8, data: Vec<u8>) -> Self {
+        Self {
+            class,
+            option_type,
+            reserved: 0,
+            data,
+        }
+    }
+
+    /// Checks if the option is critical, i.e. tunnel endpoints which do not understand it must drop the packet.
+    pub fn is_critical(&self) -> bool {
+        self.option_type & Self::CRITICAL != 0
+    }
+
+    /// Returns the length of the encoded option in bytes.
+    pub fn encoded_len(&self) -> usize {
+        Self::MIN_LEN + self.data.len().next_multiple_of(4)
+    }
+
+    /// Appends the encoded option to the buffer.
+    pub fn write(&self, buf: &mut Vec<u8>) {
+        let words = self.data.len().div_ceil(4);
+        buf.extend_from_slice(&self.class.to_be_bytes());
+        buf.extend_from_slice(&[self.option_type, (self.reserved & 0x07) << 5 | words as u8 & 0x1f]);
+        buf.extend_from_slice(&self.data);
+        buf.resize(buf.len() + words * 4 - self.data.len(), 0);
+    }
+
+    /// Parses an option from the start of the buffer. Returns it with the number of bytes consumed.
+    pub fn parse(bytes: &[u8]) -> Result<(Self, usize), GeneveError> {
+        let class = match bytes {
+            [high, low, ..] => u16::from_be_bytes([*high, *low]),
+            _ => 0,
+        };
+        let malformed = || GeneveError::MalformedOption(class);
+        let header = bytes.get(..Self::MIN_LEN).ok_or_else(malformed)?;
+        let len = Self::MIN_LEN + (header[3] & 0x1f) as usize * 4;
+        let data = bytes.get(Self::MIN_LEN..len).ok_or_else(malformed)?;
+
+        let option = Self {
+            class: u16::from_be_bytes([header[0], header[1]]),
+            option_type: header[2],
+            reserved: header[3] >> 5,
+            data: data.to_vec(),
+        };
+
+        Ok((option, len))
+    }
+}
+
+/// Parses the options which fill the buffer.
+pub fn parse_geneve_options(mut bytes: &[u8]) -> Result<Vec<GeneveOption>, GeneveError> {
+    let mut options = Vec::new();
+    while !bytes.is_empty() {
+        let (option, len) = GeneveOption::parse(bytes)?;
+        options.push(option);
+        bytes = &bytes[len..];
+    }
+
+    Ok(options)
+}
+
+/// A Geneve header (RFC 8926), which carries a frame of a virtual network over UDP together with metadata options.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct GeneveLayer {
+    /// The 2-bit version, which is currently 0.
+    pub version: u8,
+    /// The 6-bit length of the options in 4-byte words. If left unset, it is derived from the options, unless they exceed
+    /// [`GeneveLayer::MAX_OPTIONS_LEN`].
+    pub options_len: Option<u8>,
+    /// The packet contains a control message (O bit).
+    pub oam: bool,
+    /// Critical options are present (C bit). If left unset, it is derived from the options.
+    pub critical: Option<bool>,
+    /// The 6 reserved bits after the flags.
+    pub reserved1: u8,
+    /// The EtherType of the payload. If left unset, it is derived from the next layer.
+    pub protocol_type: Option<EtherType>,
+    /// The 24-bit Virtual Network Identifier.
+    pub vni: u32,
+    /// The 8 reserved bits after the VNI.
+    pub reserved2: u8,
+    pub options: Vec<GeneveOption>,
+}
+
+impl GeneveLayer {
+    /// The length of a header without options in bytes.
+    pub const MIN_LEN: usize = 8;
+
+    /// The UDP port of Geneve.
+    pub const PORT: u16 = 6081;
+
+    /// The largest 24-bit VNI.
+    pub const MAX_VNI: u32 = 0xff_ffff;
+
+    /// The length of the longest options the 6-bit length field can describe in bytes.
+    pub const MAX_OPTIONS_LEN: usize = 0x3f * 4;
+
+    /// Constructs a Geneve header for the virtual network without options. The protocol type is left unset.
+    pub fn new(vni: u32) -> Self {
+        Self {
+            vni: vni & Self::MAX_VNI,
+            ..Self::default()
+        }
+    }
+
+    /// Appends an option. Fails if its data or the options as a whole would be too long for their length fields.
+    pub fn push_option(&mut self, option: GeneveOption) -> Result<(), GeneveError> {
+        if option.data.len() > GeneveOption::MAX_DATA_LEN {
+            return Err(GeneveError::OptionTooLong(option.class));
+        }
+
+        if self.options_bytes_len() + option.encoded_len() > Self::MAX_OPTIONS_LEN {
+            return Err(GeneveError::OptionsTooLong);
+        }
+
+        self.options.push(option);
+        Ok(())
+    }
+
+    /// Checks if any option is critical, which is indicated by the C bit.
+    pub fn has_critical_options(&self) -> bool {
+        self.options.iter().any(GeneveOption::is_critical)
+    }
+
+    /// Checks if the options fit in the header, i.e. if their lengths can be described by the length fields.
+    pub fn options_fit(&self) -> bool {
+        self.options_bytes_len() <= Self::MAX_OPTIONS_LEN
+            && self.options.iter().all(|option| option.data.len() <= GeneveOption::MAX_DATA_LEN)
+    }
+
+    fn options_bytes_len(&self) -> usize {
+        self.options.iter().map(GeneveOption::encoded_len).sum()
+    }
+
+    /// Returns the first option of the specified class and type, ignoring the critical bit.
+    pub fn option(&self, class: u16, option_type: u8) -> Option<&GeneveOption> {
+        let option_type = option_type & !GeneveOption::CRITICAL;
+        self.options.iter().find(|option| option.class == class && option.option_type & !GeneveOption::CRITICAL == option_type)
+    }
+
+    fn options_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.options.iter().for_each(|option| option.write(&mut buf));
+        buf
+    }
+}
+
+impl Layer for GeneveLayer {
+    const NAME: &'static str = "Geneve";
+    const TYPE: LayerType = LayerType::GeneveLayer;
+    const OSI_LEVEL: OsiLevel = OsiLevel::Network;
+}
+
+impl DynLayer for GeneveLayer {
+    fn header_len(&self) -> usize {
+        Self::MIN_LEN + self.options_bytes_len()
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        let options_len = self.options_len.unwrap_or((self.options_bytes_len() / 4) as u8);
+        let critical = self.critical.unwrap_or_else(|| self.has_critical_options());
+        buf.push((self.version & 0x03) << 6 | options_len & 0x3f);
+        buf.push((self.oam as u8) << 7 | (critical as u8) << 6 | self.reserved1 & 0x3f);
+        buf.extend_from_slice(&self.protocol_type.map_or(0, |protocol_type| protocol_type.value()).to_be_bytes());
+        buf.extend_from_slice(&(self.vni << 8 | self.reserved2 as u32).to_be_bytes());
+        self.options.iter().for_each(|option| option.write(buf));
+    }
+
+    fn finalize(&mut self, ctx: &FinalizeContext) {
+        // Options which do not fit are still written, but their length is left unset rather than truncated to 6 bits
+        if self.options_len.is_none() && self.options_fit() {
+            self.options_len = Some((self.options_bytes_len() / 4) as u8);
+        }
+
+        if self.critical.is_none() {
+            self.critical = Some(self.has_critical_options());
+        }
+
+        if self.protocol_type.is_none() {
+            self.protocol_type = ctx.next().and_then(|next| EtherType::of_layer(next.layer_type()));
+        }
+    }
+
+    /// The payload is dispatched like the payload of an Ethernet frame.
+    fn next_discriminators(&self) -> Vec<Discriminator> {
+        self.protocol_type.map(|protocol_type| Discriminator::EtherType(protocol_type.value())).into_iter().collect()
+    }
+
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        if bytes.len() < Self::MIN_LEN {
+            return Err(LayerError::BufferTooShort(Self::NAME));
+        }
+
+        let options_len = bytes[0] & 0x3f;
+        let len = Self::MIN_LEN + options_len as usize * 4;
+        let options = bytes.get(Self::MIN_LEN..len).ok_or(LayerError::BufferTooShort(Self::NAME))?;
+
+        let layer = Self {
+            version: bytes[0] >> 6,
+            options_len: Some(options_len),
+            oam: bytes[1] & 0x80 != 0,
+            critical: Some(bytes[1] & 0x40 != 0),
+            reserved1: bytes[1] & 0x3f,
+            protocol_type: Some(EtherType::from_value(u16::from_be_bytes([bytes[2], bytes[3]]))),
+            vni: u32::from_be_bytes(bytes[4..8].try_into().unwrap()) >> 8,
+            reserved2: bytes[7],
+            options: parse_geneve_options(options)?,
+        };
+
+        Ok((layer, len))
+    }
+
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+}
+
+impl Reflect for GeneveLayer {
+    fn fields(&self) -> Vec<Field> {
+        let uint = |value: u64| FieldValue::UInt(value);
+        let protocol_type = self.protocol_type.map_or(FieldValue::Unset, |protocol_type| protocol_type.field_value());
+        let options = self.options_bytes();
+
+        vec![
+            Field::new("geneve.ver", FieldKind::UInt, 0, 2, uint(self.version as u64)),
+            Field::new("geneve.optlen", FieldKind::UInt, 2, 6, self.options_len.map_or(FieldValue::Unset, |len| uint(len as u64)))
+                .computed(),
+            Field::new("geneve.o", FieldKind::Bool, 8, 1, FieldValue::Bool(self.oam)),
+            Field::new("geneve.c", FieldKind::Bool, 9, 1, self.critical.map_or(FieldValue::Unset, FieldValue::Bool)).computed(),
+            Field::new("geneve.proto", FieldKind::Enum, 16, 16, protocol_type).computed(),
+            Field::new("geneve.vni", FieldKind::UInt, 32, 24, uint(self.vni as u64)),
+            Field::new("geneve.options", FieldKind::Bytes, 64, options.len() * 8, FieldValue::Bytes(options)),
+        ]
+    }
+
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        let auto = value.trim() == "auto";
+        let invalid = || FieldError::InvalidValue(value.to_string());
+
+        match path {
+            "geneve.ver" => match parse_uint_as::<u8>(value)? {
+                version if version <= 3 => self.version = version,
+                _ => return Err(invalid()),
+            },
+            "geneve.optlen" if auto => self.options_len = None,
+            "geneve.optlen" => match parse_uint_as::<u8>(value)? {
+                len if len <= 0x3f => self.options_len = Some(len),
+                _ => return Err(invalid()),
+            },
+            "geneve.o" => self.oam = parse_bool(value)?,
+            "geneve.c" if auto => self.critical = None,
+            "geneve.c" => self.critical = Some(parse_bool(value)?),
+            "geneve.proto" if auto => self.protocol_type = None,
+            "geneve.proto" => self.protocol_type = Some(EtherType::parse_field(value)?),
+            "geneve.vni" => match parse_uint_as::<u32>(value)? {
+                vni if vni <= Self::MAX_VNI => self.vni = vni,
+                _ => return Err(invalid()),
+            },
+            "geneve.options" => {
+                let bytes = hex::decode(value.trim()).map_err(|_| invalid())?;
+                if bytes.len() > Self::MAX_OPTIONS_LEN {
+                    return Err(invalid());
+                }
+                self.options = parse_geneve_options(&bytes).map_err(|_| invalid())?;
+            }
+            _ => return Err(FieldError::UnknownField(path.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum GeneveError {
+    #[error("The Geneve option of class {0:#06x} is malformed.")]
+    MalformedOption(u16),
+
+    #[error("The data of the Geneve option of class {0:#06x} exceeds 124 bytes.")]
+    OptionTooLong(u16),
+
+    #[error("The Geneve options exceed 252 bytes.")]
+    OptionsTooLong,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Ipv4Addr, MacAddr, Packet};
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(GeneveLayer::NAME, "Geneve");
+        assert_eq!(GeneveLayer::TYPE, LayerType::GeneveLayer);
+        assert_eq!(GeneveLayer::OSI_LEVEL, OsiLevel::Network);
+    }
+
+    #[test]
+    fn write_and_parse() {
+        let mut layer = GeneveLayer::new(0xabcdef);
+        layer.push_option(GeneveOption::new(0x0102, 0x03, vec![1, 2, 3, 4, 5])).unwrap();
+        layer.push_option(GeneveOption::new(0x0102, 0x84, vec![])).unwrap();
+        assert!(layer.has_critical_options());
+
+        let frame = EthLayer::new(MacAddr::broadcast(), MacAddr::broadcast(), EtherType::IPv4, vec![]);
+        let bytes = (layer / frame).to_bytes();
+        assert_eq!(bytes[..8], [0x04, 0x40, 0x65, 0x58, 0xab, 0xcd, 0xef, 0x00]);
+        assert_eq!(bytes[8..24], [0x01, 0x02, 0x03, 0x02, 1, 2, 3, 4, 5, 0, 0, 0, 0x01, 0x02, 0x84, 0x00]);
+
+        let (parsed, len) = GeneveLayer::parse(&bytes).unwrap();
+        assert_eq!(len, 24);
+        assert_eq!((parsed.options_len, parsed.critical), (Some(4), Some(true)));
+        assert_eq!(parsed.options[0].data, [1, 2, 3, 4, 5, 0, 0, 0]);
+        assert!(parsed.options[1].is_critical());
+        assert_eq!(parsed.option(0x0102, 0x04), Some(&parsed.options[1]));
+        assert_eq!(parsed.next_discriminators(), [Discriminator::EtherType(0x6558)]);
+        assert_eq!(GeneveLayer::parse(&bytes[..20]), Err(LayerError::BufferTooShort("Geneve")));
+    }
+
+    #[test]
+    fn malformed_option() {
+        let bytes = [0x01, 0x00, 0x08, 0x00, 0, 0, 1, 0, 0x01, 0x02, 0x03, 0x05];
+        assert_eq!(GeneveLayer::parse(&bytes), Err(LayerError::Geneve(GeneveError::MalformedOption(0x0102))));
+    }
+
+    #[test]
+    fn options_too_long() {
+        let mut layer = GeneveLayer::new(1);
+        let long = GeneveOption::new(0x0102, 1, vec![0; 125]);
+        assert_eq!(layer.push_option(long), Err(GeneveError::OptionTooLong(0x0102)));
+
+        for _ in 0..2 {
+            layer.push_option(GeneveOption::new(0x0102, 1, vec![0; 120])).unwrap();
+        }
+        assert_eq!(layer.push_option(GeneveOption::new(0x0102, 2, vec![0; 4])), Err(GeneveError::OptionsTooLong));
+        assert_eq!(layer.options.len(), 2);
+
+        // Options added directly are still written, but their length is not derived
+        layer.options.push(GeneveOption::new(0x0102, 2, vec![0; 4]));
+        assert!(!layer.options_fit());
+        let mut packet = Packet::from(layer);
+        packet.finalize();
+        assert_eq!(packet.get::<GeneveLayer>().unwrap().options_len, None);
+    }
+
+    #[test]
+    fn reflect() {
+        let mut layer = GeneveLayer::new(1);
+        layer.set_field("geneve.options", "0001020100000001").unwrap();
+        layer.set_field("geneve.proto", "IPv4").unwrap();
+        assert_eq!(layer.options, [GeneveOption::new(1, 2, vec![0, 0, 0, 1])]);
+        assert_eq!(layer.get_field("geneve.optlen"), Some(FieldValue::Unset));
+        assert_eq!(layer.get_field("geneve.c"), Some(FieldValue::Unset));
+        layer.set_field("geneve.c", "true").unwrap();
+        assert_eq!(layer.critical, Some(true));
+        layer.set_field("geneve.c", "auto").unwrap();
+        assert_eq!(layer.set_field("geneve.options", &"00".repeat(256)), Err(FieldError::InvalidValue("00".repeat(256))));
+        assert_eq!(layer.set_field("geneve.vni", "0x1000000"), Err(FieldError::InvalidValue("0x1000000".to_string())));
+
+        let ip = Ipv4Layer::new(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2));
+        let bytes = (layer / ip).to_bytes();
+        assert_eq!(bytes[..4], [0x02, 0x00, 0x08, 0x00]);
+    }
+}
@@ -0,0 +1,381 @@
+use super::*;
+
+use crate::{internet_checksum, reflect::*, Discriminator};
+
+/// A GRE header (RFC 2784) with the key and sequence number extensions (RFC 2890).
+///
+/// The key, sequence number and checksum fields are present if they are set, or, for the checksum, if requested by
+/// [`checksum_present`](Self::checksum_present). NVGRE (RFC 7637) reuses the key for a virtual subnet ID and a flow ID.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct GreLayer {
+    /// Whether the checksum and the following reserved field are present (C bit).
+    pub checksum_present: bool,
+    /// The reserved bits of the first 16 bits other than the C, K and S flags, in place (Reserved0).
+    pub reserved0: u16,
+    /// The 3-bit version, which is 0 except for the enhanced GRE of PPTP.
+    pub version: u8,
+    /// The EtherType of the payload. If left unset, it is derived from the next layer.
+    pub protocol_type: Option<EtherType>,
+    /// The checksum of the header and the payload. If left unset while present, it is computed when the packet is
+    /// finalized.
+    pub checksum: Option<u16>,
+    /// The 16 bits which follow the checksum (Reserved1).
+    pub reserved1: u16,
+    pub key: Option<u32>,
+    pub sequence: Option<u32>,
+}
+
+impl GreLayer {
+    /// The length of a header without optional fields in bytes.
+    pub const MIN_LEN: usize = 4;
+
+    /// The flag which indicates that the checksum is present.
+    pub const FLAG_CHECKSUM: u16 = 0x8000;
+
+    /// The flag which indicates that the key is present.
+    pub const FLAG_KEY: u16 = 0x2000;
+
+    /// The flag which indicates that the sequence number is present.
+    pub const FLAG_SEQUENCE: u16 = 0x1000;
+
+    /// The largest 24-bit virtual subnet ID of NVGRE.
+    pub const MAX_VSID: u32 = 0xff_ffff;
+
+    /// Constructs a GRE header without optional fields which carries the specified protocol.
+    pub fn new(protocol_type: EtherType) -> Self {
+        Self {
+            protocol_type: Some(protocol_type),
+            ..Self::default()
+        }
+    }
+
+    /// Constructs an NVGRE header, which carries an Ethernet frame of the virtual subnet in the key field.
+    pub fn nvgre(vsid: u32, flow_id: u8) -> Self {
+        Self {
+            key: Some((vsid & Self::MAX_VSID) << 8 | flow_id as u32),
+            ..Self::new(EtherType::TransparentEthernetBridging)
+        }
+    }
+
+    /// Returns the NVGRE virtual subnet ID, i.e. the upper 24 bits of the key.
+    pub fn vsid(&self) -> Option<u32> {
+        self.key.map(|key| key >> 8)
+    }
+
+    /// Returns the NVGRE flow ID, i.e. the lower 8 bits of the key.
+    pub fn flow_id(&self) -> Option<u8> {
+        self.key.map(|key| key as u8)
+    }
+
+    /// Returns the first 16 bits of the header, which hold the flags and the version.
+    pub fn flags(&self) -> u16 {
+        let mut flags = self.reserved0 & !(Self::FLAG_CHECKSUM | Self::FLAG_KEY | Self::FLAG_SEQUENCE | 0x0007);
+        flags |= if self.checksum_present { Self::FLAG_CHECKSUM } else { 0 };
+        flags |= if self.key.is_some() { Self::FLAG_KEY } else { 0 };
+        flags |= if self.sequence.is_some() { Self::FLAG_SEQUENCE } else { 0 };
+        flags | (self.version & 0x07) as u16
+    }
+
+    /// Computes the checksum of the header followed by the payload, as if the checksum field was zero.
+    pub fn compute_checksum(&self, payload: &[u8]) -> u16 {
+        let mut buf = Vec::with_capacity(self.header_len() + payload.len());
+        Self { checksum_present: true, checksum: Some(0), ..self.clone() }.write(&mut buf);
+        buf.extend_from_slice(payload);
+        internet_checksum(&buf)
+    }
+
+    /// Checks if the checksum is present and matches the header followed by the payload.
+    pub fn is_checksum_valid(&self, payload: &[u8]) -> bool {
+        self.checksum_present && self.checksum == Some(self.compute_checksum(payload))
+    }
+}
+
+impl Layer for GreLayer {
+    const NAME: &'static str = "GRE";
+    const TYPE: LayerType = LayerType::GreLayer;
+    const OSI_LEVEL: OsiLevel = OsiLevel::Network;
+}
+
+impl DynLayer for GreLayer {
+    fn header_len(&self) -> usize {
+        Self::MIN_LEN + 4 * (self.checksum_present as usize + self.key.is_some() as usize + self.sequence.is_some() as usize)
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.flags().to_be_bytes());
+        buf.extend_from_slice(&self.protocol_type.map_or(0, |protocol_type| protocol_type.value()).to_be_bytes());
+
+        if self.checksum_present {
+            buf.extend_from_slice(&self.checksum.unwrap_or(0).to_be_bytes());
+            buf.extend_from_slice(&self.reserved1.to_be_bytes());
+        }
+        if let Some(key) = self.key {
+            buf.extend_from_slice(&key.to_be_bytes());
+        }
+        if let Some(sequence) = self.sequence {
+            buf.extend_from_slice(&sequence.to_be_bytes());
+        }
+    }
+
+    fn finalize(&mut self, ctx: &FinalizeContext) {
+        if self.protocol_type.is_none() {
+            self.protocol_type = ctx.next().and_then(|next| EtherType::of_layer(next.layer_type()));
+        }
+
+        if self.checksum_present && self.checksum.is_none() {
+            self.checksum = Some(self.compute_checksum(ctx.unpadded_payload()));
+        }
+    }
+
+    /// The payload is dispatched like the payload of an Ethernet frame.
+    fn next_discriminators(&self) -> Vec<Discriminator> {
+        self.protocol_type.map(|protocol_type| Discriminator::EtherType(protocol_type.value())).into_iter().collect()
+    }
+
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        let view = GreView::new(bytes)?;
+        Ok((view.to_layer(), view.header_len()))
+    }
+
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+}
+
+impl Reflect for GreLayer {
+    fn fields(&self) -> Vec<Field> {
+        let uint = |value: u64| FieldValue::UInt(value);
+        let protocol_type = self.protocol_type.map_or(FieldValue::Unset, |protocol_type| protocol_type.field_value());
+
+        let mut fields = vec![
+            Field::new("gre.c", FieldKind::Bool, 0, 1, FieldValue::Bool(self.checksum_present)),
+            Field::new("gre.k", FieldKind::Bool, 2, 1, FieldValue::Bool(self.key.is_some())),
+            Field::new("gre.s", FieldKind::Bool, 3, 1, FieldValue::Bool(self.sequence.is_some())),
+            Field::new("gre.ver", FieldKind::UInt, 13, 3, uint(self.version as u64)),
+            Field::new("gre.proto", FieldKind::Enum, 16, 16, protocol_type).computed(),
+        ];
+
+        let mut offset = 32;
+        if self.checksum_present {
+            let checksum = self.checksum.map_or(FieldValue::Unset, |checksum| uint(checksum as u64));
+            fields.push(Field::new("gre.chksum", FieldKind::UInt, offset, 16, checksum).computed());
+            fields.push(Field::new("gre.reserved1", FieldKind::UInt, offset + 16, 16, uint(self.reserved1 as u64)));
+            offset += 32;
+        }
+        if let Some(key) = self.key {
+            fields.push(Field::new("gre.key", FieldKind::UInt, offset, 32, uint(key as u64)));
+            offset += 32;
+        }
+        if let Some(sequence) = self.sequence {
+            fields.push(Field::new("gre.seq", FieldKind::UInt, offset, 32, uint(sequence as u64)));
+        }
+
+        fields
+    }
+
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        let auto = value.trim() == "auto";
+
+        match path {
+            "gre.c" => self.checksum_present = parse_bool(value)?,
+            "gre.k" => self.key = parse_bool(value)?.then(|| self.key.unwrap_or(0)),
+            "gre.s" => self.sequence = parse_bool(value)?.then(|| self.sequence.unwrap_or(0)),
+            "gre.ver" => match parse_uint_as::<u8>(value)? {
+                version if version <= 7 => self.version = version,
+                _ => return Err(FieldError::InvalidValue(value.to_string())),
+            },
+            "gre.proto" if auto => self.protocol_type = None,
+            "gre.proto" => self.protocol_type = Some(EtherType::parse_field(value)?),
+            "gre.chksum" if auto => self.checksum = None,
+            "gre.chksum" => {
+                self.checksum_present = true;
+                self.checksum = Some(parse_uint_as(value)?);
+            }
+            "gre.reserved1" => self.reserved1 = parse_uint_as(value)?,
+            "gre.key" => self.key = Some(parse_uint_as(value)?),
+            "gre.seq" => self.sequence = Some(parse_uint_as(value)?),
+            _ => return Err(FieldError::UnknownField(path.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
+/// A borrowed, zero-copy view of a GRE header at the start of a byte slice.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct GreView<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> GreView<'a> {
+    /// Constructs a view of the GRE header at the start of the slice, including the optional fields its flags announce.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, LayerError> {
+        if bytes.len() < GreLayer::MIN_LEN {
+            return Err(LayerError::BufferTooShort(GreLayer::NAME));
+        }
+
+        let view = Self { bytes };
+        if bytes.len() < view.header_len() {
+            return Err(LayerError::BufferTooShort(GreLayer::NAME));
+        }
+
+        Ok(view)
+    }
+
+    fn u32_at(&self, offset: usize) -> u32 {
+        u32::from_be_bytes(self.bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    /// Returns the first 16 bits of the header, which hold the flags and the version.
+    pub fn flags(&self) -> u16 {
+        u16::from_be_bytes([self.bytes[0], self.bytes[1]])
+    }
+
+    fn has_flag(&self, flag: u16) -> bool {
+        self.flags() & flag != 0
+    }
+
+    /// Returns the 3-bit version.
+    pub fn version(&self) -> u8 {
+        self.bytes[1] & 0x07
+    }
+
+    /// Returns the EtherType of the payload.
+    pub fn protocol_type(&self) -> u16 {
+        u16::from_be_bytes([self.bytes[2], self.bytes[3]])
+    }
+
+    /// Returns the length of the header including the optional fields in bytes.
+    pub fn header_len(&self) -> usize {
+        let optional = [GreLayer::FLAG_CHECKSUM, GreLayer::FLAG_KEY, GreLayer::FLAG_SEQUENCE];
+        GreLayer::MIN_LEN + 4 * optional.iter().filter(|flag| self.has_flag(**flag)).count()
+    }
+
+    /// Returns the checksum, if it is present.
+    pub fn checksum(&self) -> Option<u16> {
+        self.has_flag(GreLayer::FLAG_CHECKSUM).then(|| (self.u32_at(4) >> 16) as u16)
+    }
+
+    /// Returns the key, if it is present.
+    pub fn key(&self) -> Option<u32> {
+        let offset = 4 + 4 * self.has_flag(GreLayer::FLAG_CHECKSUM) as usize;
+        self.has_flag(GreLayer::FLAG_KEY).then(|| self.u32_at(offset))
+    }
+
+    /// Returns the sequence number, if it is present.
+    pub fn sequence(&self) -> Option<u32> {
+        self.has_flag(GreLayer::FLAG_SEQUENCE).then(|| self.u32_at(self.header_len() - 4))
+    }
+
+    /// Returns the bytes following the header, up to the end of the slice.
+    pub fn payload(&self) -> &'a [u8] {
+        &self.bytes[self.header_len()..]
+    }
+
+    /// Converts the view into an owned GRE layer.
+    pub fn to_layer(&self) -> GreLayer {
+        GreLayer {
+            checksum_present: self.has_flag(GreLayer::FLAG_CHECKSUM),
+            reserved0: self.flags() & !(GreLayer::FLAG_CHECKSUM | GreLayer::FLAG_KEY | GreLayer::FLAG_SEQUENCE | 0x0007),
+            version: self.version(),
+            protocol_type: Some(EtherType::from_value(self.protocol_type())),
+            checksum: self.checksum(),
+            reserved1: self.checksum().map_or(0, |_| self.u32_at(4) as u16),
+            key: self.key(),
+            sequence: self.sequence(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Ipv4Addr, MacAddr, Packet};
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(GreLayer::NAME, "GRE");
+        assert_eq!(GreLayer::TYPE, LayerType::GreLayer);
+        assert_eq!(GreLayer::OSI_LEVEL, OsiLevel::Network);
+    }
+
+    #[test]
+    fn write_and_parse() {
+        let layer = GreLayer {
+            checksum_present: true,
+            checksum: Some(0x1234),
+            key: Some(0xdeadbeef),
+            sequence: Some(7),
+            ..GreLayer::new(EtherType::IPv4)
+        };
+        let mut buf = Vec::new();
+        layer.write(&mut buf);
+
+        assert_eq!(buf[..8], [0xb0, 0x00, 0x08, 0x00, 0x12, 0x34, 0x00, 0x00]);
+        assert_eq!(buf[8..], [0xde, 0xad, 0xbe, 0xef, 0, 0, 0, 7]);
+        assert_eq!(GreLayer::parse(&buf), Ok((layer.clone(), 16)));
+        assert_eq!(GreLayer::parse(&buf[..12]), Err(LayerError::BufferTooShort("GRE")));
+        assert_eq!(layer.next_discriminators(), [Discriminator::EtherType(0x0800)]);
+
+        let layer = GreLayer::new(EtherType::IPv6);
+        assert_eq!(Packet::from(layer.clone()).to_bytes(), [0x00, 0x00, 0x86, 0xdd]);
+        assert_eq!(GreLayer::parse(&[0x00, 0x00, 0x86, 0xdd]), Ok((layer, 4)));
+    }
+
+    #[test]
+    fn finalize() {
+        let layer = GreLayer { checksum_present: true, ..GreLayer::default() };
+        let inner = Ipv4Layer::new(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2));
+        let bytes = (layer / inner).to_bytes();
+        assert_eq!(bytes[2..4], [0x08, 0x00]);
+        assert_eq!(internet_checksum(&bytes), 0);
+
+        let (parsed, len) = GreLayer::parse(&bytes).unwrap();
+        assert!(parsed.is_checksum_valid(&bytes[len..]));
+        assert!(!GreLayer::new(EtherType::IPv4).is_checksum_valid(&[]));
+
+        let frame = EthLayer::new(MacAddr::broadcast(), MacAddr::broadcast(), EtherType::IPv4, vec![]);
+        let bytes = (GreLayer::default() / frame).to_bytes();
+        assert_eq!(bytes[2..4], [0x65, 0x58]);
+    }
+
+    #[test]
+    fn nvgre() {
+        let layer = GreLayer::nvgre(0x123456, 0x78);
+        assert_eq!(layer.vsid(), Some(0x123456));
+        assert_eq!(layer.flow_id(), Some(0x78));
+        assert_eq!(Packet::from(layer).to_bytes(), [0x20, 0x00, 0x65, 0x58, 0x12, 0x34, 0x56, 0x78]);
+        assert_eq!(GreLayer::new(EtherType::IPv4).vsid(), None);
+    }
+
+    #[test]
+    fn reflect() {
+        let mut layer = GreLayer::new(EtherType::IPv4);
+        layer.set_field("gre.k", "true").unwrap();
+        layer.set_field("gre.seq", "5").unwrap();
+        assert_eq!(layer.get_field("gre.key"), Some(FieldValue::UInt(0)));
+        assert_eq!(layer.get_field("gre.s"), Some(FieldValue::Bool(true)));
+        assert_eq!(layer.get_field("gre.chksum"), None);
+        assert_eq!(layer.header_len(), 12);
+
+        layer.set_field("gre.c", "true").unwrap();
+        assert_eq!(layer.get_field("gre.chksum"), Some(FieldValue::Unset));
+        assert_eq!(layer.set_field("gre.ver", "8"), Err(FieldError::InvalidValue("8".to_string())));
+        layer.set_field("gre.k", "false").unwrap();
+        assert_eq!(layer.key, None);
+    }
+
+    #[test]
+    fn view() {
+        let buf = [0xa0, 0x01, 0x88, 0x0b, 0xab, 0xcd, 0x00, 0x00, 0x00, 0x00, 0x00, 0x2a, 9];
+        let view = GreView::new(&buf).unwrap();
+        assert_eq!((view.version(), view.protocol_type(), view.header_len()), (1, 0x880b, 12));
+        assert_eq!((view.checksum(), view.key(), view.sequence()), (Some(0xabcd), Some(42), None));
+        assert_eq!(view.payload(), [9]);
+        assert!(GreView::new(&buf[..8]).is_err());
+    }
+}
@@ -0,0 +1,241 @@
+use crate::{internet_checksum, LinkType, Packet};
+
+/// An object of an ICMP extension structure (RFC 4884), such as an MPLS label stack (RFC 4950) or interface information
+/// (RFC 5837).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IcmpExtensionObject {
+    pub class_num: u8,
+    pub c_type: u8,
+    pub data: Vec<u8>,
+}
+
+impl IcmpExtensionObject {
+    /// The class of an MPLS label stack object (RFC 4950).
+    pub const CLASS_MPLS: u8 = 1;
+
+    /// The class of an interface information object (RFC 5837).
+    pub const CLASS_INTERFACE_INFO: u8 = 2;
+
+    /// Constructs an object of the specified class and type.
+    pub fn new(class_num: u8, c_type: u8, data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            class_num,
+            c_type,
+            data: data.into(),
+        }
+    }
+
+    /// Returns the length of the object including its 4-byte header.
+    pub fn encoded_len(&self) -> usize {
+        4 + self.data.len()
+    }
+}
+
+/// The extension structure (RFC 4884) which can follow the original datagram of an ICMP or ICMPv6 error message.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct IcmpExtensions {
+    /// The checksum of the extension structure. If left unset, it is computed when the packet is finalized.
+    pub checksum: Option<u16>,
+    pub objects: Vec<IcmpExtensionObject>,
+}
+
+impl IcmpExtensions {
+    /// The version of the extension structure.
+    pub const VERSION: u8 = 2;
+
+    /// Constructs an extension structure containing the objects.
+    pub fn new(objects: Vec<IcmpExtensionObject>) -> Self {
+        Self { checksum: None, objects }
+    }
+
+    /// Returns the length of the structure including its 4-byte header.
+    pub fn encoded_len(&self) -> usize {
+        4 + self.objects.iter().map(IcmpExtensionObject::encoded_len).sum::<usize>()
+    }
+
+    /// Computes the checksum of the structure as if the checksum field was zero.
+    pub fn compute_checksum(&self) -> u16 {
+        let mut buf = Vec::with_capacity(self.encoded_len());
+        self.write_with_checksum(&mut buf, 0);
+        internet_checksum(&buf)
+    }
+
+    /// Appends the serialized structure to the buffer.
+    pub fn write(&self, buf: &mut Vec<u8>) {
+        self.write_with_checksum(buf, self.checksum.unwrap_or(0));
+    }
+
+    fn write_with_checksum(&self, buf: &mut Vec<u8>, checksum: u16) {
+        buf.extend_from_slice(&[Self::VERSION << 4, 0]);
+        buf.extend_from_slice(&checksum.to_be_bytes());
+
+        for object in &self.objects {
+            buf.extend_from_slice(&(object.encoded_len() as u16).to_be_bytes());
+            buf.extend_from_slice(&[object.class_num, object.c_type]);
+            buf.extend_from_slice(&object.data);
+        }
+    }
+
+    /// Parses an extension structure which spans the whole slice. Returns `None` if it is not a well-formed version 2
+    /// structure.
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 4 || bytes[0] >> 4 != Self::VERSION {
+            return None;
+        }
+
+        let mut objects = Vec::new();
+        let mut rest = &bytes[4..];
+        while !rest.is_empty() {
+            let len = u16::from_be_bytes([rest[0], *rest.get(1)?]) as usize;
+            if len < 4 || len > rest.len() {
+                return None;
+            }
+
+            objects.push(IcmpExtensionObject::new(rest[2], rest[3], &rest[4..len]));
+            rest = &rest[len..];
+        }
+
+        Some(Self {
+            checksum: Some(u16::from_be_bytes([bytes[2], bytes[3]])),
+            objects,
+        })
+    }
+}
+
+/// The leading part of the datagram which triggered an ICMP or ICMPv6 error message, together with the optional
+/// extension structure which follows it (RFC 4884).
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct OriginalDatagram {
+    /// The length of the original datagram field in words, which are 4 bytes long for ICMP and 8 bytes for ICMPv6. Zero
+    /// means that the message has no extensions. If left unset, it is derived from the data and the extensions.
+    pub length: Option<u8>,
+    pub data: Vec<u8>,
+    pub extensions: Option<IcmpExtensions>,
+}
+
+impl OriginalDatagram {
+    /// The minimum length of the original datagram field of a message with extensions (RFC 4884).
+    pub const MIN_EXTENDED_LEN: usize = 128;
+
+    /// Constructs an original datagram field without extensions.
+    pub fn new(data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            length: None,
+            data: data.into(),
+            extensions: None,
+        }
+    }
+
+    /// Returns the length of the original datagram field including the zero padding, for words of the specified size.
+    /// A set length field only pads the data if extensions follow it, so a truncated quote is not extended.
+    pub fn padded_len(&self, word: usize) -> usize {
+        match (self.length, &self.extensions) {
+            (Some(length), Some(_)) => self.data.len().max(length as usize * word),
+            (Some(_), None) => self.data.len(),
+            (None, Some(_)) => self.data.len().max(Self::MIN_EXTENDED_LEN).next_multiple_of(word),
+            (None, None) => self.data.len(),
+        }
+    }
+
+    /// Returns the value of the length field, for words of the specified size.
+    pub fn length_field(&self, word: usize) -> u8 {
+        match (self.length, &self.extensions) {
+            (Some(length), _) => length,
+            (None, Some(_)) => (self.padded_len(word) / word).min(u8::MAX as usize) as u8,
+            (None, None) => 0,
+        }
+    }
+
+    /// Returns the length of the original datagram field and the extensions in bytes.
+    pub fn encoded_len(&self, word: usize) -> usize {
+        self.padded_len(word) + self.extensions.as_ref().map_or(0, IcmpExtensions::encoded_len)
+    }
+
+    /// Fills in the length field and the checksum of the extensions if they were left unset.
+    pub fn finalize(&mut self, word: usize) {
+        if self.length.is_none() {
+            self.length = Some(self.length_field(word));
+        }
+
+        if let Some(extensions) = &mut self.extensions {
+            if extensions.checksum.is_none() {
+                extensions.checksum = Some(extensions.compute_checksum());
+            }
+        }
+    }
+
+    /// Appends the padded original datagram and the extensions to the buffer.
+    pub fn write(&self, buf: &mut Vec<u8>, word: usize) {
+        buf.extend_from_slice(&self.data);
+        buf.resize(buf.len() + self.padded_len(word) - self.data.len(), 0);
+
+        if let Some(extensions) = &self.extensions {
+            extensions.write(buf);
+        }
+    }
+
+    /// Parses the original datagram field described by the length field. Bytes following the field which do not form
+    /// a valid extension structure are left unconsumed. Returns the field together with the number of bytes consumed.
+    pub fn parse(bytes: &[u8], length: u8, word: usize) -> (Self, usize) {
+        if length == 0 {
+            return (Self { length: Some(0), ..Self::new(bytes) }, bytes.len());
+        }
+
+        let end = bytes.len().min(length as usize * word);
+        let extensions = IcmpExtensions::parse(&bytes[end..]);
+        let consumed = if extensions.is_some() { bytes.len() } else { end };
+
+        let datagram = Self {
+            length: Some(length),
+            data: bytes[..end].to_vec(),
+            extensions,
+        };
+        (datagram, consumed)
+    }
+
+    /// Decodes the original datagram as a packet of the specified link type. If its first layer cannot be decoded, the
+    /// packet contains the data as a single [`RawLayer`](crate::RawLayer).
+    pub fn to_packet(&self, link_type: LinkType) -> Packet {
+        Packet::parse(&self.data, link_type).unwrap_or_else(|_| Packet::from(self.data.as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extensions() {
+        let mut datagram = OriginalDatagram::new([0xab; 28]);
+        assert_eq!((datagram.padded_len(4), datagram.length_field(4)), (28, 0));
+
+        datagram.extensions = Some(IcmpExtensions::new(vec![IcmpExtensionObject::new(
+            IcmpExtensionObject::CLASS_MPLS,
+            1,
+            [0x00, 0x01, 0x01, 0x01],
+        )]));
+        assert_eq!((datagram.padded_len(4), datagram.length_field(4)), (128, 32));
+        assert_eq!((datagram.padded_len(8), datagram.length_field(8)), (128, 16));
+        assert_eq!(datagram.encoded_len(4), 140);
+
+        datagram.finalize(4);
+        let mut buf = Vec::new();
+        datagram.write(&mut buf, 4);
+        assert_eq!(buf.len(), 140);
+        assert_eq!(buf[127..130], [0x00, 0x20, 0x00]);
+        assert_eq!(internet_checksum(&buf[128..]), 0);
+
+        let (parsed, len) = OriginalDatagram::parse(&buf, 32, 4);
+        assert_eq!(len, 140);
+        assert_eq!(parsed.data.len(), 128);
+        assert_eq!(parsed.extensions, datagram.extensions);
+
+        // Without a valid extension structure, the trailing bytes are not consumed
+        buf[128] = 0x10;
+        let (parsed, len) = OriginalDatagram::parse(&buf, 32, 4);
+        assert_eq!((len, parsed.extensions), (128, None));
+
+        let (parsed, len) = OriginalDatagram::parse(&buf, 0, 4);
+        assert_eq!((len, parsed.data.len()), (140, 140));
+    }
+}
@@ -0,0 +1,541 @@
+use super::*;
+
+use crate::{internet_checksum, reflect::*, Ipv4Addr, LinkType, Packet};
+
+registry_enum! {
+    /// The ICMP message types assigned by IANA.
+    pub enum IcmpType: u8 {
+        EchoReply = 0,
+        DestinationUnreachable = 3,
+        SourceQuench = 4,
+        Redirect = 5,
+        EchoRequest = 8,
+        RouterAdvertisement = 9,
+        RouterSolicitation = 10,
+        TimeExceeded = 11,
+        ParameterProblem = 12,
+        TimestampRequest = 13,
+        TimestampReply = 14,
+        ExtendedEchoRequest = 42,
+        ExtendedEchoReply = 43,
+    }
+}
+
+registry_enum! {
+    /// The codes of ICMP Destination Unreachable messages.
+    pub enum UnreachableCode: u8 {
+        NetUnreachable = 0,
+        HostUnreachable = 1,
+        ProtocolUnreachable = 2,
+        PortUnreachable = 3,
+        /// The datagram is larger than the next-hop MTU but its Don't Fragment flag is set (RFC 1191).
+        FragmentationNeeded = 4,
+        SourceRouteFailed = 5,
+        NetUnknown = 6,
+        HostUnknown = 7,
+        SourceHostIsolated = 8,
+        NetProhibited = 9,
+        HostProhibited = 10,
+        NetUnreachableForTos = 11,
+        HostUnreachableForTos = 12,
+        CommunicationProhibited = 13,
+        HostPrecedenceViolation = 14,
+        PrecedenceCutoff = 15,
+    }
+}
+
+/// The type-specific part of an ICMP message which follows the type, code and checksum fields.
+///
+/// Error messages carry the leading part of the datagram which triggered them, while the data of echo and unknown
+/// messages is kept in the next layer.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum IcmpMessage {
+    Echo { identifier: u16, sequence: u16 },
+    DestinationUnreachable { next_hop_mtu: u16, original: OriginalDatagram },
+    TimeExceeded { original: OriginalDatagram },
+    ParameterProblem { pointer: u8, original: OriginalDatagram },
+    Redirect { gateway: Ipv4Addr, original: OriginalDatagram },
+    /// A timestamp request or reply, whose timestamps are milliseconds since midnight UT.
+    Timestamp { identifier: u16, sequence: u16, originate: u32, receive: u32, transmit: u32 },
+    Unknown { rest_of_header: [u8; 4] },
+}
+
+impl IcmpMessage {
+    /// Returns the original datagram of an error message.
+    pub fn original(&self) -> Option<&OriginalDatagram> {
+        match self {
+            IcmpMessage::DestinationUnreachable { original, .. }
+            | IcmpMessage::TimeExceeded { original }
+            | IcmpMessage::ParameterProblem { original, .. }
+            | IcmpMessage::Redirect { original, .. } => Some(original),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the original datagram of an error message.
+    pub fn original_mut(&mut self) -> Option<&mut OriginalDatagram> {
+        match self {
+            IcmpMessage::DestinationUnreachable { original, .. }
+            | IcmpMessage::TimeExceeded { original }
+            | IcmpMessage::ParameterProblem { original, .. }
+            | IcmpMessage::Redirect { original, .. } => Some(original),
+            _ => None,
+        }
+    }
+}
+
+/// An ICMP message (RFC 792).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IcmpLayer {
+    pub icmp_type: IcmpType,
+    pub code: u8,
+    /// The checksum of the whole message. If left unset, it is computed when the packet is finalized.
+    pub checksum: Option<u16>,
+    pub message: IcmpMessage,
+}
+
+impl IcmpLayer {
+    /// The length of the fixed part of the header in bytes.
+    pub const MIN_LEN: usize = 8;
+
+    /// The length of the original datagram quoted by new error messages beyond its IPv4 header (RFC 792).
+    pub const QUOTED_PAYLOAD_LEN: usize = 8;
+
+    /// The size of the words in which the length of the original datagram is specified (RFC 4884).
+    const WORD: usize = 4;
+
+    /// Constructs a message of the specified type and code. The checksum is left unset.
+    pub fn new(icmp_type: IcmpType, code: u8, message: IcmpMessage) -> Self {
+        Self {
+            icmp_type,
+            code,
+            checksum: None,
+            message,
+        }
+    }
+
+    /// Constructs an echo request.
+    pub fn echo_request(identifier: u16, sequence: u16) -> Self {
+        Self::new(IcmpType::EchoRequest, 0, IcmpMessage::Echo { identifier, sequence })
+    }
+
+    /// Constructs an echo reply.
+    pub fn echo_reply(identifier: u16, sequence: u16) -> Self {
+        Self::new(IcmpType::EchoReply, 0, IcmpMessage::Echo { identifier, sequence })
+    }
+
+    /// Constructs a destination unreachable message which quotes the original IPv4 datagram.
+    pub fn destination_unreachable(code: UnreachableCode, original: &Packet) -> Self {
+        let message = IcmpMessage::DestinationUnreachable { next_hop_mtu: 0, original: Self::quote(original) };
+        Self::new(IcmpType::DestinationUnreachable, code.value(), message)
+    }
+
+    /// Constructs a fragmentation needed message (RFC 1191) which reports the MTU of the next hop.
+    pub fn fragmentation_needed(next_hop_mtu: u16, original: &Packet) -> Self {
+        let message = IcmpMessage::DestinationUnreachable { next_hop_mtu, original: Self::quote(original) };
+        Self::new(IcmpType::DestinationUnreachable, UnreachableCode::FragmentationNeeded.value(), message)
+    }
+
+    /// Constructs a time exceeded message. Code 0 reports an exceeded TTL and code 1 an exceeded reassembly time.
+    pub fn time_exceeded(code: u8, original: &Packet) -> Self {
+        Self::new(IcmpType::TimeExceeded, code, IcmpMessage::TimeExceeded { original: Self::quote(original) })
+    }
+
+    /// Constructs a parameter problem message which points at the erroneous byte of the original datagram.
+    pub fn parameter_problem(pointer: u8, original: &Packet) -> Self {
+        Self::new(IcmpType::ParameterProblem, 0, IcmpMessage::ParameterProblem { pointer, original: Self::quote(original) })
+    }
+
+    /// Constructs a redirect message which tells the sender to use another gateway.
+    pub fn redirect(code: u8, gateway: Ipv4Addr, original: &Packet) -> Self {
+        Self::new(IcmpType::Redirect, code, IcmpMessage::Redirect { gateway, original: Self::quote(original) })
+    }
+
+    /// Constructs a timestamp request.
+    pub fn timestamp_request(identifier: u16, sequence: u16, originate: u32) -> Self {
+        let message = IcmpMessage::Timestamp { identifier, sequence, originate, receive: 0, transmit: 0 };
+        Self::new(IcmpType::TimestampRequest, 0, message)
+    }
+
+    /// Quotes the IPv4 header and the first bytes of the payload of the serialized packet.
+    fn quote(original: &Packet) -> OriginalDatagram {
+        let mut bytes = original.to_bytes();
+        let header_len = bytes.first().map_or(0, |byte| (byte & 0x0f) as usize * 4);
+        bytes.truncate(header_len + Self::QUOTED_PAYLOAD_LEN);
+        OriginalDatagram::new(bytes)
+    }
+
+    /// Checks if the message reports an error, i.e. carries an original datagram.
+    pub fn is_error(&self) -> bool {
+        self.message.original().is_some()
+    }
+
+    /// Decodes the original datagram of an error message as an IPv4 packet, e.g. to match it with a sent probe.
+    pub fn original_packet(&self) -> Option<Packet> {
+        self.message.original().map(|original| original.to_packet(LinkType::Ipv4))
+    }
+
+    /// Computes the checksum of the message followed by the payload, as if the checksum field was zero.
+    pub fn compute_checksum(&self, payload: &[u8]) -> u16 {
+        let mut buf = Vec::with_capacity(self.header_len() + payload.len());
+        Self { checksum: Some(0), ..self.clone() }.write(&mut buf);
+        buf.extend_from_slice(payload);
+        internet_checksum(&buf)
+    }
+
+    /// Checks if the checksum matches the message followed by the payload.
+    pub fn is_checksum_valid(&self, payload: &[u8]) -> bool {
+        self.checksum == Some(self.compute_checksum(payload))
+    }
+
+    /// Parses the type-specific part of a message of the specified type. Returns it with the number of bytes consumed.
+    fn parse_message(icmp_type: IcmpType, bytes: &[u8]) -> (IcmpMessage, usize) {
+        let u16_at = |offset: usize| u16::from_be_bytes([bytes[offset], bytes[offset + 1]]);
+        let u32_at = |offset: usize| u32::from_be_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        let rest = &bytes[Self::MIN_LEN..];
+
+        match icmp_type {
+            IcmpType::EchoRequest | IcmpType::EchoReply => {
+                (IcmpMessage::Echo { identifier: u16_at(4), sequence: u16_at(6) }, Self::MIN_LEN)
+            }
+            IcmpType::DestinationUnreachable => {
+                let (original, len) = OriginalDatagram::parse(rest, bytes[5], Self::WORD);
+                (IcmpMessage::DestinationUnreachable { next_hop_mtu: u16_at(6), original }, Self::MIN_LEN + len)
+            }
+            IcmpType::TimeExceeded => {
+                let (original, len) = OriginalDatagram::parse(rest, bytes[5], Self::WORD);
+                (IcmpMessage::TimeExceeded { original }, Self::MIN_LEN + len)
+            }
+            IcmpType::ParameterProblem => {
+                let (original, len) = OriginalDatagram::parse(rest, bytes[5], Self::WORD);
+                (IcmpMessage::ParameterProblem { pointer: bytes[4], original }, Self::MIN_LEN + len)
+            }
+            IcmpType::Redirect => {
+                let gateway = Ipv4Addr::new(bytes[4], bytes[5], bytes[6], bytes[7]);
+                (IcmpMessage::Redirect { gateway, original: OriginalDatagram::new(rest) }, bytes.len())
+            }
+            IcmpType::TimestampRequest | IcmpType::TimestampReply if rest.len() >= 12 => {
+                let message = IcmpMessage::Timestamp {
+                    identifier: u16_at(4),
+                    sequence: u16_at(6),
+                    originate: u32_at(8),
+                    receive: u32_at(12),
+                    transmit: u32_at(16),
+                };
+                (message, Self::MIN_LEN + 12)
+            }
+            _ => (IcmpMessage::Unknown { rest_of_header: bytes[4..8].try_into().unwrap() }, Self::MIN_LEN),
+        }
+    }
+}
+
+impl Default for IcmpLayer {
+    fn default() -> Self {
+        Self::echo_request(0, 0)
+    }
+}
+
+impl Layer for IcmpLayer {
+    const NAME: &'static str = "ICMP";
+    const TYPE: LayerType = LayerType::IcmpLayer;
+    const OSI_LEVEL: OsiLevel = OsiLevel::Network;
+}
+
+impl DynLayer for IcmpLayer {
+    fn header_len(&self) -> usize {
+        match &self.message {
+            IcmpMessage::Timestamp { .. } => Self::MIN_LEN + 12,
+            IcmpMessage::Redirect { original, .. } => Self::MIN_LEN + original.data.len(),
+            message => Self::MIN_LEN + message.original().map_or(0, |original| original.encoded_len(Self::WORD)),
+        }
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&[self.icmp_type.value(), self.code]);
+        buf.extend_from_slice(&self.checksum.unwrap_or(0).to_be_bytes());
+
+        match &self.message {
+            IcmpMessage::Echo { identifier, sequence } => {
+                buf.extend_from_slice(&identifier.to_be_bytes());
+                buf.extend_from_slice(&sequence.to_be_bytes());
+            }
+            IcmpMessage::DestinationUnreachable { next_hop_mtu, original } => {
+                buf.extend_from_slice(&[0, original.length_field(Self::WORD)]);
+                buf.extend_from_slice(&next_hop_mtu.to_be_bytes());
+                original.write(buf, Self::WORD);
+            }
+            IcmpMessage::TimeExceeded { original } => {
+                buf.extend_from_slice(&[0, original.length_field(Self::WORD), 0, 0]);
+                original.write(buf, Self::WORD);
+            }
+            IcmpMessage::ParameterProblem { pointer, original } => {
+                buf.extend_from_slice(&[*pointer, original.length_field(Self::WORD), 0, 0]);
+                original.write(buf, Self::WORD);
+            }
+            IcmpMessage::Redirect { gateway, original } => {
+                buf.extend_from_slice(&gateway.octets());
+                buf.extend_from_slice(&original.data);
+            }
+            IcmpMessage::Timestamp { identifier, sequence, originate, receive, transmit } => {
+                buf.extend_from_slice(&identifier.to_be_bytes());
+                buf.extend_from_slice(&sequence.to_be_bytes());
+                buf.extend_from_slice(&originate.to_be_bytes());
+                buf.extend_from_slice(&receive.to_be_bytes());
+                buf.extend_from_slice(&transmit.to_be_bytes());
+            }
+            IcmpMessage::Unknown { rest_of_header } => buf.extend_from_slice(rest_of_header),
+        }
+    }
+
+    fn finalize(&mut self, ctx: &FinalizeContext) {
+        if let Some(original) = self.message.original_mut() {
+            original.finalize(Self::WORD);
+        }
+
+        if self.checksum.is_none() {
+            self.checksum = Some(self.compute_checksum(ctx.unpadded_payload()));
+        }
+    }
+
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        if bytes.len() < Self::MIN_LEN {
+            return Err(LayerError::BufferTooShort(Self::NAME));
+        }
+
+        let icmp_type = IcmpType::from_value(bytes[0]);
+        let (message, len) = Self::parse_message(icmp_type, bytes);
+        let layer = Self {
+            icmp_type,
+            code: bytes[1],
+            checksum: Some(u16::from_be_bytes([bytes[2], bytes[3]])),
+            message,
+        };
+
+        Ok((layer, len))
+    }
+
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+}
+
+impl Reflect for IcmpLayer {
+    fn fields(&self) -> Vec<Field> {
+        let uint = |value: u64| FieldValue::UInt(value);
+
+        let mut fields = vec![
+            Field::new("icmp.type", FieldKind::Enum, 0, 8, self.icmp_type.field_value()),
+            Field::new("icmp.code", FieldKind::UInt, 8, 8, uint(self.code as u64)),
+            Field::new("icmp.chksum", FieldKind::UInt, 16, 16, self.checksum.map_or(FieldValue::Unset, |c| uint(c as u64)))
+                .computed(),
+        ];
+
+        match &self.message {
+            IcmpMessage::Echo { identifier, sequence } => {
+                fields.push(Field::new("icmp.id", FieldKind::UInt, 32, 16, uint(*identifier as u64)));
+                fields.push(Field::new("icmp.seq", FieldKind::UInt, 48, 16, uint(*sequence as u64)));
+            }
+            IcmpMessage::DestinationUnreachable { next_hop_mtu, .. } => {
+                fields.push(Field::new("icmp.mtu", FieldKind::UInt, 48, 16, uint(*next_hop_mtu as u64)));
+            }
+            IcmpMessage::ParameterProblem { pointer, .. } => {
+                fields.push(Field::new("icmp.ptr", FieldKind::UInt, 32, 8, uint(*pointer as u64)));
+            }
+            IcmpMessage::Redirect { gateway, .. } => {
+                fields.push(Field::new("icmp.gw", FieldKind::Ipv4, 32, 32, FieldValue::Ipv4(*gateway)));
+            }
+            IcmpMessage::Timestamp { identifier, sequence, originate, receive, transmit } => {
+                fields.push(Field::new("icmp.id", FieldKind::UInt, 32, 16, uint(*identifier as u64)));
+                fields.push(Field::new("icmp.seq", FieldKind::UInt, 48, 16, uint(*sequence as u64)));
+                fields.push(Field::new("icmp.ts_ori", FieldKind::UInt, 64, 32, uint(*originate as u64)));
+                fields.push(Field::new("icmp.ts_rx", FieldKind::UInt, 96, 32, uint(*receive as u64)));
+                fields.push(Field::new("icmp.ts_tx", FieldKind::UInt, 128, 32, uint(*transmit as u64)));
+            }
+            IcmpMessage::TimeExceeded { .. } | IcmpMessage::Unknown { .. } => {}
+        }
+
+        if let Some(original) = self.message.original() {
+            if !matches!(self.message, IcmpMessage::Redirect { .. }) {
+                let length = original.length.map_or(FieldValue::Unset, |length| uint(length as u64));
+                fields.push(Field::new("icmp.length", FieldKind::UInt, 40, 8, length).computed());
+            }
+
+            let data = FieldValue::Bytes(original.data.clone());
+            fields.push(Field::new("icmp.original", FieldKind::Bytes, 64, original.data.len() * 8, data));
+        }
+
+        fields
+    }
+
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        let auto = value.trim() == "auto";
+        let unknown = || FieldError::UnknownField(path.to_string());
+
+        match (path, &mut self.message) {
+            ("icmp.type", _) => self.icmp_type = IcmpType::parse_field(value)?,
+            ("icmp.code", _) => self.code = parse_uint_as(value)?,
+            ("icmp.chksum", _) if auto => self.checksum = None,
+            ("icmp.chksum", _) => self.checksum = Some(parse_uint_as(value)?),
+            ("icmp.id", IcmpMessage::Echo { identifier, .. } | IcmpMessage::Timestamp { identifier, .. }) => {
+                *identifier = parse_uint_as(value)?
+            }
+            ("icmp.seq", IcmpMessage::Echo { sequence, .. } | IcmpMessage::Timestamp { sequence, .. }) => {
+                *sequence = parse_uint_as(value)?
+            }
+            ("icmp.mtu", IcmpMessage::DestinationUnreachable { next_hop_mtu, .. }) => *next_hop_mtu = parse_uint_as(value)?,
+            ("icmp.ptr", IcmpMessage::ParameterProblem { pointer, .. }) => *pointer = parse_uint_as(value)?,
+            ("icmp.gw", IcmpMessage::Redirect { gateway, .. }) => *gateway = parse_str(value)?,
+            ("icmp.ts_ori", IcmpMessage::Timestamp { originate, .. }) => *originate = parse_uint_as(value)?,
+            ("icmp.ts_rx", IcmpMessage::Timestamp { receive, .. }) => *receive = parse_uint_as(value)?,
+            ("icmp.ts_tx", IcmpMessage::Timestamp { transmit, .. }) => *transmit = parse_uint_as(value)?,
+            ("icmp.length", IcmpMessage::Redirect { .. }) => return Err(unknown()),
+            ("icmp.length", message) => {
+                let original = message.original_mut().ok_or_else(unknown)?;
+                original.length = if auto { None } else { Some(parse_uint_as(value)?) };
+            }
+            ("icmp.original", message) => {
+                let original = message.original_mut().ok_or_else(unknown)?;
+                original.data = hex::decode(value.trim()).map_err(|_| FieldError::InvalidValue(value.to_string()))?;
+            }
+            _ => return Err(unknown()),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn original() -> Packet {
+        let ip = Ipv4Layer {
+            identification: 0x1234,
+            ttl: 1,
+            protocol: Some(IpProtocol::Udp),
+            ..Ipv4Layer::new(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(192, 0, 2, 1))
+        };
+        ip / RawLayer::new([0x82, 0x9b, 0x82, 0x9a, 0x00, 0x14, 0xab, 0xcd, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12])
+    }
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(IcmpLayer::NAME, "ICMP");
+        assert_eq!(IcmpLayer::TYPE, LayerType::IcmpLayer);
+        assert_eq!(IcmpLayer::OSI_LEVEL, OsiLevel::Network);
+    }
+
+    #[test]
+    fn echo() {
+        let packet = IcmpLayer::echo_request(0x1a2b, 7) / RawLayer::new(*b"ping");
+        let bytes = packet.to_bytes();
+        assert_eq!(bytes[..8], [0x08, 0x00, 0xfe, 0xfc, 0x1a, 0x2b, 0x00, 0x07]);
+        assert_eq!(internet_checksum(&bytes), 0);
+
+        let (layer, len) = IcmpLayer::parse(&bytes).unwrap();
+        assert_eq!(len, 8);
+        assert_eq!(layer.message, IcmpMessage::Echo { identifier: 0x1a2b, sequence: 7 });
+        assert!(layer.is_checksum_valid(b"ping"));
+        assert!(!layer.is_error());
+        assert_eq!(IcmpLayer::parse(&bytes[..7]), Err(LayerError::BufferTooShort("ICMP")));
+    }
+
+    #[test]
+    fn time_exceeded() {
+        let layer = IcmpLayer::time_exceeded(0, &original());
+        let bytes = Packet::from(layer).to_bytes();
+        assert_eq!(bytes.len(), 8 + 28);
+        assert_eq!(bytes[4..8], [0, 0, 0, 0]);
+        assert_eq!(internet_checksum(&bytes), 0);
+
+        let (layer, len) = IcmpLayer::parse(&bytes).unwrap();
+        assert_eq!(len, 36);
+        assert!(layer.is_error());
+
+        let quoted = layer.original_packet().unwrap();
+        assert_eq!(quoted.to_string(), "IPv4 / UDP");
+        assert_eq!(quoted.get::<Ipv4Layer>().unwrap().identification, 0x1234);
+        assert_eq!(quoted.get::<Ipv4Layer>().unwrap().total_length, Some(40));
+        assert_eq!(quoted.get::<UdpLayer>().unwrap().ports(), (33435, 33434));
+
+        // A length field which announces more than the captured quote does not pad it
+        let mut truncated = bytes.clone();
+        truncated[5] = 200;
+        let (layer, len) = IcmpLayer::parse(&truncated).unwrap();
+        assert_eq!(len, 36);
+
+        let mut buf = Vec::new();
+        layer.write(&mut buf);
+        assert_eq!(buf, truncated);
+    }
+
+    #[test]
+    fn unreachable_with_extensions() {
+        let mut layer = IcmpLayer::fragmentation_needed(1400, &original());
+        layer.message.original_mut().unwrap().extensions =
+            Some(IcmpExtensions::new(vec![IcmpExtensionObject::new(IcmpExtensionObject::CLASS_MPLS, 1, [0, 0x01, 0x01, 0x01])]));
+
+        let bytes = Packet::from(layer).to_bytes();
+        assert_eq!(bytes.len(), 8 + 128 + 12);
+        assert_eq!(bytes[..2], [3, 4]);
+        assert_eq!(bytes[4..8], [0, 32, 0x05, 0x78]);
+        assert_eq!(internet_checksum(&bytes), 0);
+
+        let (layer, len) = IcmpLayer::parse(&bytes).unwrap();
+        assert_eq!(len, bytes.len());
+        let IcmpMessage::DestinationUnreachable { next_hop_mtu, original } = &layer.message else {
+            panic!("unexpected message {:?}", layer.message);
+        };
+        assert_eq!(*next_hop_mtu, 1400);
+        assert_eq!(original.length, Some(32));
+        assert_eq!(original.extensions.as_ref().unwrap().objects[0].class_num, IcmpExtensionObject::CLASS_MPLS);
+        assert_eq!(layer.original_packet().unwrap().get::<Ipv4Layer>().unwrap().identification, 0x1234);
+
+        let mut buf = Vec::new();
+        layer.write(&mut buf);
+        assert_eq!(buf, bytes);
+    }
+
+    #[test]
+    fn other_messages() {
+        let layer = IcmpLayer::redirect(1, Ipv4Addr::new(10, 0, 0, 254), &original());
+        let bytes = Packet::from(layer.clone()).to_bytes();
+        assert_eq!(bytes[4..8], [10, 0, 0, 254]);
+        assert_eq!(IcmpLayer::parse(&bytes).unwrap().0.message, layer.message);
+
+        let layer = IcmpLayer::parameter_problem(20, &original());
+        let bytes = Packet::from(layer).to_bytes();
+        assert_eq!(bytes[4], 20);
+        assert_eq!(IcmpLayer::parse(&bytes).unwrap().1, 36);
+
+        let layer = IcmpLayer::timestamp_request(1, 2, 3_600_000);
+        let bytes = Packet::from(layer.clone()).to_bytes();
+        assert_eq!(bytes.len(), 20);
+        assert_eq!(IcmpLayer::parse(&bytes).unwrap().0.message, layer.message);
+
+        let bytes = [10, 0, 0xf5, 0xff, 0, 0, 0, 0];
+        let (layer, len) = IcmpLayer::parse(&bytes).unwrap();
+        assert_eq!((layer.icmp_type, len), (IcmpType::RouterSolicitation, 8));
+        assert_eq!(layer.message, IcmpMessage::Unknown { rest_of_header: [0; 4] });
+    }
+
+    #[test]
+    fn reflect() {
+        let mut layer = IcmpLayer::echo_request(1, 1);
+        layer.set_field("icmp.seq", "42").unwrap();
+        assert_eq!(layer.get_field("icmp.seq"), Some(FieldValue::UInt(42)));
+        assert_eq!(layer.get_field("icmp.chksum"), Some(FieldValue::Unset));
+        assert_eq!(layer.set_field("icmp.mtu", "1500"), Err(FieldError::UnknownField("icmp.mtu".to_string())));
+
+        let mut layer = IcmpLayer::destination_unreachable(UnreachableCode::PortUnreachable, &original());
+        layer.set_field("icmp.mtu", "1280").unwrap();
+        layer.set_field("icmp.length", "7").unwrap();
+        assert_eq!(layer.get_field("icmp.mtu"), Some(FieldValue::UInt(1280)));
+        assert_eq!(layer.get_field("icmp.length"), Some(FieldValue::UInt(7)));
+        assert_eq!(layer.get_field("icmp.code"), Some(FieldValue::UInt(3)));
+    }
+}
@@ -0,0 +1,891 @@
+use super::*;
+
+use crate::{internet_checksum, reflect::*, Ipv6Addr, LinkType, MacAddr, Packet};
+
+use thiserror::Error;
+
+registry_enum! {
+    /// The ICMPv6 message types assigned by IANA.
+    pub enum Icmpv6Type: u8 {
+        DestinationUnreachable = 1,
+        PacketTooBig = 2,
+        TimeExceeded = 3,
+        ParameterProblem = 4,
+        EchoRequest = 128,
+        EchoReply = 129,
+        MulticastListenerQuery = 130,
+        MulticastListenerReport = 131,
+        MulticastListenerDone = 132,
+        RouterSolicitation = 133,
+        RouterAdvertisement = 134,
+        NeighborSolicitation = 135,
+        NeighborAdvertisement = 136,
+        Redirect = 137,
+        MulticastListenerReportV2 = 143,
+    }
+}
+
+/// The type-specific part of an ICMPv6 message which follows the type, code and checksum fields.
+///
+/// Error messages carry the leading part of the packet which triggered them, while the data of echo and unknown messages
+/// is kept in the next layer.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Icmpv6Message {
+    Echo { identifier: u16, sequence: u16 },
+    DestinationUnreachable { original: OriginalDatagram },
+    PacketTooBig { mtu: u32, original: OriginalDatagram },
+    TimeExceeded { original: OriginalDatagram },
+    /// A parameter problem, whose pointer is the offset of the erroneous byte in the original packet.
+    ParameterProblem { pointer: u32, original: OriginalDatagram },
+    RouterSolicitation { options: Vec<NdpOption> },
+    RouterAdvertisement(RouterAdvertisement),
+    NeighborSolicitation { target: Ipv6Addr, options: Vec<NdpOption> },
+    NeighborAdvertisement(NeighborAdvertisement),
+    Redirect { target: Ipv6Addr, destination: Ipv6Addr, options: Vec<NdpOption> },
+    /// An MLDv1 query, report or done message (RFC 2710), whose maximum response delay in milliseconds is only used by
+    /// queries.
+    MulticastListener { max_response_delay: u16, group: Ipv6Addr },
+    MulticastListenerQueryV2(MldV2Query),
+    MulticastListenerReportV2 { records: Vec<MldGroupRecord> },
+    Unknown { rest_of_header: [u8; 4] },
+}
+
+/// The body of a Router Advertisement message.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct RouterAdvertisement {
+    /// The hop limit which hosts should use for outgoing packets, or zero if unspecified.
+    pub current_hop_limit: u8,
+    /// Addresses are available via DHCPv6.
+    pub managed: bool,
+    /// Other configuration is available via DHCPv6.
+    pub other: bool,
+    /// The remaining 6 bits of the flags field, such as the router preference (RFC 4191).
+    pub reserved: u8,
+    /// The lifetime of the default router in seconds, or zero if the router is not a default router.
+    pub router_lifetime: u16,
+    /// The time in milliseconds for which a neighbor is reachable after a confirmation, or zero if unspecified.
+    pub reachable_time: u32,
+    /// The time in milliseconds between retransmitted Neighbor Solicitations, or zero if unspecified.
+    pub retrans_timer: u32,
+    pub options: Vec<NdpOption>,
+}
+
+/// The body of a Neighbor Advertisement message.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct NeighborAdvertisement {
+    /// The sender is a router.
+    pub router: bool,
+    /// The advertisement was sent in response to a Neighbor Solicitation.
+    pub solicited: bool,
+    /// The advertisement should override an existing cache entry.
+    pub override_flag: bool,
+    pub target: Ipv6Addr,
+    pub options: Vec<NdpOption>,
+}
+
+impl Icmpv6Message {
+    /// Returns the original packet of an error message.
+    pub fn original(&self) -> Option<&OriginalDatagram> {
+        match self {
+            Icmpv6Message::DestinationUnreachable { original }
+            | Icmpv6Message::PacketTooBig { original, .. }
+            | Icmpv6Message::TimeExceeded { original }
+            | Icmpv6Message::ParameterProblem { original, .. } => Some(original),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the original packet of an error message.
+    pub fn original_mut(&mut self) -> Option<&mut OriginalDatagram> {
+        match self {
+            Icmpv6Message::DestinationUnreachable { original }
+            | Icmpv6Message::PacketTooBig { original, .. }
+            | Icmpv6Message::TimeExceeded { original }
+            | Icmpv6Message::ParameterProblem { original, .. } => Some(original),
+            _ => None,
+        }
+    }
+
+    /// Returns the options of a Neighbor Discovery message.
+    pub fn options(&self) -> Option<&Vec<NdpOption>> {
+        match self {
+            Icmpv6Message::RouterSolicitation { options }
+            | Icmpv6Message::NeighborSolicitation { options, .. }
+            | Icmpv6Message::Redirect { options, .. } => Some(options),
+            Icmpv6Message::RouterAdvertisement(ra) => Some(&ra.options),
+            Icmpv6Message::NeighborAdvertisement(na) => Some(&na.options),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the options of a Neighbor Discovery message.
+    pub fn options_mut(&mut self) -> Option<&mut Vec<NdpOption>> {
+        match self {
+            Icmpv6Message::RouterSolicitation { options }
+            | Icmpv6Message::NeighborSolicitation { options, .. }
+            | Icmpv6Message::Redirect { options, .. } => Some(options),
+            Icmpv6Message::RouterAdvertisement(ra) => Some(&mut ra.options),
+            Icmpv6Message::NeighborAdvertisement(na) => Some(&mut na.options),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the original packet is preceded by an RFC 4884 length field.
+    fn has_length_field(&self) -> bool {
+        matches!(self, Icmpv6Message::DestinationUnreachable { .. } | Icmpv6Message::TimeExceeded { .. })
+    }
+}
+
+/// An ICMPv6 message (RFC 4443), including the Neighbor Discovery (RFC 4861) and Multicast Listener Discovery (RFC 3810)
+/// messages.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Icmpv6Layer {
+    pub icmp_type: Icmpv6Type,
+    pub code: u8,
+    /// The checksum of the pseudo-header and the whole message. If left unset, it is computed when the packet is
+    /// finalized.
+    pub checksum: Option<u16>,
+    pub message: Icmpv6Message,
+}
+
+impl Icmpv6Layer {
+    /// The length of the fixed part of the header in bytes.
+    pub const MIN_LEN: usize = 8;
+
+    /// The length up to which error messages quote the original packet, so that they fit into the minimum IPv6 MTU.
+    pub const MAX_QUOTED_LEN: usize = 1280 - Ipv6Layer::MIN_LEN - Self::MIN_LEN;
+
+    /// The hop limit of Neighbor Discovery messages, which lets receivers check that they were not forwarded.
+    pub const NDP_HOP_LIMIT: u8 = 255;
+
+    /// The hop limit of Multicast Listener Discovery messages, which must not leave the link.
+    pub const MLD_HOP_LIMIT: u8 = 1;
+
+    /// The size of the words in which the length of the original packet is specified (RFC 4884).
+    const WORD: usize = 8;
+
+    /// Constructs a message of the specified type and code. The checksum is left unset.
+    pub fn new(icmp_type: Icmpv6Type, code: u8, message: Icmpv6Message) -> Self {
+        Self {
+            icmp_type,
+            code,
+            checksum: None,
+            message,
+        }
+    }
+
+    /// Constructs an echo request.
+    pub fn echo_request(identifier: u16, sequence: u16) -> Self {
+        Self::new(Icmpv6Type::EchoRequest, 0, Icmpv6Message::Echo { identifier, sequence })
+    }
+
+    /// Constructs an echo reply.
+    pub fn echo_reply(identifier: u16, sequence: u16) -> Self {
+        Self::new(Icmpv6Type::EchoReply, 0, Icmpv6Message::Echo { identifier, sequence })
+    }
+
+    /// Constructs a destination unreachable message which quotes the original IPv6 packet.
+    pub fn destination_unreachable(code: u8, original: &Packet) -> Self {
+        let message = Icmpv6Message::DestinationUnreachable { original: Self::quote(original) };
+        Self::new(Icmpv6Type::DestinationUnreachable, code, message)
+    }
+
+    /// Constructs a packet too big message which reports the MTU of the next hop.
+    pub fn packet_too_big(mtu: u32, original: &Packet) -> Self {
+        Self::new(Icmpv6Type::PacketTooBig, 0, Icmpv6Message::PacketTooBig { mtu, original: Self::quote(original) })
+    }
+
+    /// Constructs a time exceeded message. Code 0 reports an exceeded hop limit and code 1 an exceeded reassembly time.
+    pub fn time_exceeded(code: u8, original: &Packet) -> Self {
+        Self::new(Icmpv6Type::TimeExceeded, code, Icmpv6Message::TimeExceeded { original: Self::quote(original) })
+    }
+
+    /// Constructs a parameter problem message which points at the erroneous byte of the original packet.
+    pub fn parameter_problem(code: u8, pointer: u32, original: &Packet) -> Self {
+        let message = Icmpv6Message::ParameterProblem { pointer, original: Self::quote(original) };
+        Self::new(Icmpv6Type::ParameterProblem, code, message)
+    }
+
+    /// Constructs a Router Solicitation which carries the link-layer address of the sender, if it is known.
+    pub fn router_solicitation(src_mac: Option<MacAddr>) -> Self {
+        let options = src_mac.map(NdpOption::SourceLinkAddress).into_iter().collect();
+        Self::new(Icmpv6Type::RouterSolicitation, 0, Icmpv6Message::RouterSolicitation { options })
+    }
+
+    /// Constructs a Router Advertisement.
+    pub fn router_advertisement(advertisement: RouterAdvertisement) -> Self {
+        Self::new(Icmpv6Type::RouterAdvertisement, 0, Icmpv6Message::RouterAdvertisement(advertisement))
+    }
+
+    /// Constructs a Neighbor Solicitation for the target address which carries the link-layer address of the sender.
+    pub fn neighbor_solicitation(target: Ipv6Addr, src_mac: MacAddr) -> Self {
+        let options = vec![NdpOption::SourceLinkAddress(src_mac)];
+        Self::new(Icmpv6Type::NeighborSolicitation, 0, Icmpv6Message::NeighborSolicitation { target, options })
+    }
+
+    /// Constructs a solicited Neighbor Advertisement which tells that the target address is at the MAC address.
+    pub fn neighbor_advertisement(target: Ipv6Addr, target_mac: MacAddr, router: bool) -> Self {
+        let advertisement = NeighborAdvertisement {
+            router,
+            solicited: true,
+            override_flag: true,
+            target,
+            options: vec![NdpOption::TargetLinkAddress(target_mac)],
+        };
+        Self::new(Icmpv6Type::NeighborAdvertisement, 0, Icmpv6Message::NeighborAdvertisement(advertisement))
+    }
+
+    /// Constructs a Redirect message which tells that the destination is better reached via the target.
+    pub fn redirect(target: Ipv6Addr, destination: Ipv6Addr) -> Self {
+        Self::new(Icmpv6Type::Redirect, 0, Icmpv6Message::Redirect { target, destination, options: Vec::new() })
+    }
+
+    /// Wraps a Neighbor Solicitation for the target address into an Ethernet frame sent to its solicited-node multicast
+    /// address, which resolves the MAC address of the target like an ARP request does for IPv4.
+    pub fn neighbor_solicitation_frame(src_mac: MacAddr, src_ip: Ipv6Addr, target: Ipv6Addr) -> Packet {
+        let dst_ip = target.solicited_node();
+        let ip = Ipv6Layer {
+            hop_limit: Self::NDP_HOP_LIMIT,
+            ..Ipv6Layer::new(src_ip, dst_ip)
+        };
+
+        EthLayer::new(src_mac, MacAddr::ipv6_multicast(&dst_ip), EtherType::IPv6, vec![]) / ip
+            / Self::neighbor_solicitation(target, src_mac)
+    }
+
+    /// Constructs an MLDv1 query for the multicast address, or a general query if it is unspecified.
+    pub fn multicast_listener_query(group: Ipv6Addr) -> Self {
+        let message = Icmpv6Message::MulticastListener { max_response_delay: MldV2Query::DEFAULT_MAX_RESPONSE_CODE, group };
+        Self::new(Icmpv6Type::MulticastListenerQuery, 0, message)
+    }
+
+    /// Constructs an MLDv2 query for the sources of the multicast address, or a general query if it is unspecified.
+    pub fn multicast_listener_query_v2(group: Ipv6Addr, sources: Vec<Ipv6Addr>) -> Self {
+        let message = Icmpv6Message::MulticastListenerQueryV2(MldV2Query::new(group, sources));
+        Self::new(Icmpv6Type::MulticastListenerQuery, 0, message)
+    }
+
+    /// Constructs an MLDv1 report which starts listening to the multicast address.
+    pub fn multicast_listener_report(group: Ipv6Addr) -> Self {
+        let message = Icmpv6Message::MulticastListener { max_response_delay: 0, group };
+        Self::new(Icmpv6Type::MulticastListenerReport, 0, message)
+    }
+
+    /// Constructs an MLDv1 done message which stops listening to the multicast address.
+    pub fn multicast_listener_done(group: Ipv6Addr) -> Self {
+        let message = Icmpv6Message::MulticastListener { max_response_delay: 0, group };
+        Self::new(Icmpv6Type::MulticastListenerDone, 0, message)
+    }
+
+    /// Constructs an MLDv2 report which carries the group records.
+    pub fn multicast_listener_report_v2(records: Vec<MldGroupRecord>) -> Self {
+        Self::new(Icmpv6Type::MulticastListenerReportV2, 0, Icmpv6Message::MulticastListenerReportV2 { records })
+    }
+
+    /// Returns the address an MLD message is sent to: queries go to the queried address or to all nodes, MLDv1 reports
+    /// to the reported address, done messages to all routers and MLDv2 reports to all MLDv2 routers.
+    pub fn mld_destination(&self) -> Option<Ipv6Addr> {
+        let group = match (&self.message, self.icmp_type) {
+            (Icmpv6Message::MulticastListener { .. }, Icmpv6Type::MulticastListenerDone) => return Some(Ipv6Addr::all_routers()),
+            (Icmpv6Message::MulticastListenerReportV2 { .. }, _) => return Some(Ipv6Addr::all_mldv2_routers()),
+            (Icmpv6Message::MulticastListener { group, .. }, _) => *group,
+            (Icmpv6Message::MulticastListenerQueryV2(query), _) => query.group,
+            _ => return None,
+        };
+
+        Some(if group == Ipv6Addr::unspecified() { Ipv6Addr::all_nodes() } else { group })
+    }
+
+    /// Wraps an MLD message into an Ethernet frame sent to its destination address. The IPv6 header carries a Hop-by-Hop
+    /// Router Alert option and a hop limit of 1. Other messages are sent to all nodes.
+    pub fn into_mld_frame(self, src_mac: MacAddr, src_ip: Ipv6Addr) -> Packet {
+        let dst_ip = self.mld_destination().unwrap_or(Ipv6Addr::all_nodes());
+        let mut ip = Ipv6Layer {
+            hop_limit: Self::MLD_HOP_LIMIT,
+            ..Ipv6Layer::new(src_ip, dst_ip)
+        };
+        ip.push_extension(Ipv6Extension::HopByHop(vec![Ipv6Option::router_alert_mld()]));
+
+        EthLayer::new(src_mac, MacAddr::ipv6_multicast(&dst_ip), EtherType::IPv6, vec![]) / ip / self
+    }
+
+    /// Quotes as much of the serialized packet as fits into the minimum IPv6 MTU.
+    fn quote(original: &Packet) -> OriginalDatagram {
+        let mut bytes = original.to_bytes();
+        bytes.truncate(Self::MAX_QUOTED_LEN);
+        OriginalDatagram::new(bytes)
+    }
+
+    /// Checks if the message reports an error, i.e. carries an original packet.
+    pub fn is_error(&self) -> bool {
+        self.message.original().is_some()
+    }
+
+    /// Decodes the original packet of an error message as an IPv6 packet, e.g. to match it with a sent probe.
+    pub fn original_packet(&self) -> Option<Packet> {
+        self.message.original().map(|original| original.to_packet(LinkType::Ipv6))
+    }
+
+    /// Computes the checksum of the message followed by the payload, as if the checksum field was zero. The pseudo-header
+    /// is taken from the IPv6 layer which carries the message.
+    pub fn compute_checksum(&self, ip: &Ipv6Layer, payload: &[u8]) -> u16 {
+        let mut buf = Vec::with_capacity(self.header_len() + payload.len());
+        Self { checksum: Some(0), ..self.clone() }.write(&mut buf);
+        buf.extend_from_slice(payload);
+
+        ip.pseudo_header(IpProtocol::Icmpv6, buf.len() as u32).add_bytes(&buf).finish()
+    }
+
+    /// Checks if the checksum matches the message followed by the payload.
+    pub fn is_checksum_valid(&self, ip: &Ipv6Layer, payload: &[u8]) -> bool {
+        self.checksum == Some(self.compute_checksum(ip, payload))
+    }
+
+    /// Parses the type-specific part of a message of the specified type. Returns it with the number of bytes consumed.
+    fn parse_message(icmp_type: Icmpv6Type, bytes: &[u8]) -> Result<(Icmpv6Message, usize), LayerError> {
+        let u16_at = |offset: usize| u16::from_be_bytes([bytes[offset], bytes[offset + 1]]);
+        let u32_at = |offset: usize| u32::from_be_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        let addr_at = |offset: usize| Ipv6Addr::from(<[u8; 16]>::try_from(&bytes[offset..offset + 16]).unwrap());
+        let too_short = || LayerError::BufferTooShort(Self::NAME);
+        let rest = &bytes[Self::MIN_LEN..];
+
+        let message = match icmp_type {
+            Icmpv6Type::EchoRequest | Icmpv6Type::EchoReply => {
+                return Ok((Icmpv6Message::Echo { identifier: u16_at(4), sequence: u16_at(6) }, Self::MIN_LEN));
+            }
+            Icmpv6Type::DestinationUnreachable | Icmpv6Type::TimeExceeded => {
+                let (original, len) = OriginalDatagram::parse(rest, bytes[4], Self::WORD);
+                let message = match icmp_type {
+                    Icmpv6Type::DestinationUnreachable => Icmpv6Message::DestinationUnreachable { original },
+                    _ => Icmpv6Message::TimeExceeded { original },
+                };
+                return Ok((message, Self::MIN_LEN + len));
+            }
+            Icmpv6Type::PacketTooBig => Icmpv6Message::PacketTooBig { mtu: u32_at(4), original: OriginalDatagram::new(rest) },
+            Icmpv6Type::ParameterProblem => {
+                Icmpv6Message::ParameterProblem { pointer: u32_at(4), original: OriginalDatagram::new(rest) }
+            }
+            Icmpv6Type::RouterSolicitation => Icmpv6Message::RouterSolicitation { options: parse_ndp_options(rest)? },
+            Icmpv6Type::RouterAdvertisement => {
+                let options = parse_ndp_options(bytes.get(16..).ok_or_else(too_short)?)?;
+                Icmpv6Message::RouterAdvertisement(RouterAdvertisement {
+                    current_hop_limit: bytes[4],
+                    managed: bytes[5] & 0x80 != 0,
+                    other: bytes[5] & 0x40 != 0,
+                    reserved: bytes[5] & 0x3f,
+                    router_lifetime: u16_at(6),
+                    reachable_time: u32_at(8),
+                    retrans_timer: u32_at(12),
+                    options,
+                })
+            }
+            Icmpv6Type::NeighborSolicitation => {
+                let options = parse_ndp_options(bytes.get(24..).ok_or_else(too_short)?)?;
+                Icmpv6Message::NeighborSolicitation { target: addr_at(8), options }
+            }
+            Icmpv6Type::NeighborAdvertisement => {
+                let options = parse_ndp_options(bytes.get(24..).ok_or_else(too_short)?)?;
+                Icmpv6Message::NeighborAdvertisement(NeighborAdvertisement {
+                    router: bytes[4] & 0x80 != 0,
+                    solicited: bytes[4] & 0x40 != 0,
+                    override_flag: bytes[4] & 0x20 != 0,
+                    target: addr_at(8),
+                    options,
+                })
+            }
+            Icmpv6Type::Redirect => {
+                let options = parse_ndp_options(bytes.get(40..).ok_or_else(too_short)?)?;
+                Icmpv6Message::Redirect { target: addr_at(8), destination: addr_at(24), options }
+            }
+            // Queries of at least 28 bytes are MLDv2 queries (RFC 3810, section 8.1).
+            Icmpv6Type::MulticastListenerQuery if bytes.len() >= 4 + MldV2Query::MIN_BODY_LEN => {
+                let (query, len) = MldV2Query::parse(&bytes[4..]).ok_or_else(too_short)?;
+                return Ok((Icmpv6Message::MulticastListenerQueryV2(query), 4 + len));
+            }
+            Icmpv6Type::MulticastListenerQuery | Icmpv6Type::MulticastListenerReport | Icmpv6Type::MulticastListenerDone => {
+                bytes.get(..24).ok_or_else(too_short)?;
+                return Ok((Icmpv6Message::MulticastListener { max_response_delay: u16_at(4), group: addr_at(8) }, 24));
+            }
+            Icmpv6Type::MulticastListenerReportV2 => {
+                let mut records = Vec::new();
+                let mut offset = Self::MIN_LEN;
+                for _ in 0..u16_at(6) {
+                    let (record, len) = MldGroupRecord::parse(&bytes[offset..]).ok_or_else(too_short)?;
+                    records.push(record);
+                    offset += len;
+                }
+                return Ok((Icmpv6Message::MulticastListenerReportV2 { records }, offset));
+            }
+            _ => return Ok((Icmpv6Message::Unknown { rest_of_header: bytes[4..8].try_into().unwrap() }, Self::MIN_LEN)),
+        };
+
+        Ok((message, bytes.len()))
+    }
+}
+
+impl Default for Icmpv6Layer {
+    fn default() -> Self {
+        Self::echo_request(0, 0)
+    }
+}
+
+impl Layer for Icmpv6Layer {
+    const NAME: &'static str = "ICMPv6";
+    const TYPE: LayerType = LayerType::Icmpv6Layer;
+    const OSI_LEVEL: OsiLevel = OsiLevel::Network;
+}
+
+impl DynLayer for Icmpv6Layer {
+    fn header_len(&self) -> usize {
+        let options_len = |options: &Vec<NdpOption>| options.iter().map(NdpOption::encoded_len).sum::<usize>();
+
+        Self::MIN_LEN
+            + match &self.message {
+                Icmpv6Message::Echo { .. } | Icmpv6Message::Unknown { .. } => 0,
+                Icmpv6Message::RouterSolicitation { options } => options_len(options),
+                Icmpv6Message::RouterAdvertisement(ra) => 8 + options_len(&ra.options),
+                Icmpv6Message::NeighborSolicitation { options, .. } => 16 + options_len(options),
+                Icmpv6Message::NeighborAdvertisement(na) => 16 + options_len(&na.options),
+                Icmpv6Message::Redirect { options, .. } => 32 + options_len(options),
+                Icmpv6Message::MulticastListener { .. } => 16,
+                Icmpv6Message::MulticastListenerQueryV2(query) => query.encoded_len() - 4,
+                Icmpv6Message::MulticastListenerReportV2 { records } => records.iter().map(MldGroupRecord::encoded_len).sum(),
+                message if message.has_length_field() => message.original().unwrap().encoded_len(Self::WORD),
+                message => message.original().unwrap().data.len(),
+            }
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&[self.icmp_type.value(), self.code]);
+        buf.extend_from_slice(&self.checksum.unwrap_or(0).to_be_bytes());
+
+        let write_options = |buf: &mut Vec<u8>, options: &Vec<NdpOption>| options.iter().for_each(|option| option.write(buf));
+
+        match &self.message {
+            Icmpv6Message::Echo { identifier, sequence } => {
+                buf.extend_from_slice(&identifier.to_be_bytes());
+                buf.extend_from_slice(&sequence.to_be_bytes());
+            }
+            Icmpv6Message::DestinationUnreachable { original } | Icmpv6Message::TimeExceeded { original } => {
+                buf.extend_from_slice(&[original.length_field(Self::WORD), 0, 0, 0]);
+                original.write(buf, Self::WORD);
+            }
+            Icmpv6Message::PacketTooBig { mtu: value, original } | Icmpv6Message::ParameterProblem { pointer: value, original } => {
+                buf.extend_from_slice(&value.to_be_bytes());
+                buf.extend_from_slice(&original.data);
+            }
+            Icmpv6Message::RouterSolicitation { options } => {
+                buf.extend_from_slice(&[0; 4]);
+                write_options(buf, options);
+            }
+            Icmpv6Message::RouterAdvertisement(ra) => {
+                buf.push(ra.current_hop_limit);
+                buf.push((ra.managed as u8) << 7 | (ra.other as u8) << 6 | ra.reserved & 0x3f);
+                buf.extend_from_slice(&ra.router_lifetime.to_be_bytes());
+                buf.extend_from_slice(&ra.reachable_time.to_be_bytes());
+                buf.extend_from_slice(&ra.retrans_timer.to_be_bytes());
+                write_options(buf, &ra.options);
+            }
+            Icmpv6Message::NeighborSolicitation { target, options } => {
+                buf.extend_from_slice(&[0; 4]);
+                buf.extend_from_slice(&target.octets());
+                write_options(buf, options);
+            }
+            Icmpv6Message::NeighborAdvertisement(na) => {
+                buf.extend_from_slice(&[(na.router as u8) << 7 | (na.solicited as u8) << 6 | (na.override_flag as u8) << 5, 0, 0, 0]);
+                buf.extend_from_slice(&na.target.octets());
+                write_options(buf, &na.options);
+            }
+            Icmpv6Message::Redirect { target, destination, options } => {
+                buf.extend_from_slice(&[0; 4]);
+                buf.extend_from_slice(&target.octets());
+                buf.extend_from_slice(&destination.octets());
+                write_options(buf, options);
+            }
+            Icmpv6Message::MulticastListener { max_response_delay, group } => {
+                buf.extend_from_slice(&max_response_delay.to_be_bytes());
+                buf.extend_from_slice(&[0; 2]);
+                buf.extend_from_slice(&group.octets());
+            }
+            Icmpv6Message::MulticastListenerQueryV2(query) => query.write(buf),
+            Icmpv6Message::MulticastListenerReportV2 { records } => {
+                buf.extend_from_slice(&[0; 2]);
+                buf.extend_from_slice(&(records.len() as u16).to_be_bytes());
+                records.iter().for_each(|record| record.write(buf));
+            }
+            Icmpv6Message::Unknown { rest_of_header } => buf.extend_from_slice(rest_of_header),
+        }
+    }
+
+    /// Computes the checksum over the pseudo-header of the IPv6 layer directly below. Without one, the checksum covers
+    /// only the message.
+    fn finalize(&mut self, ctx: &FinalizeContext) {
+        if self.message.has_length_field() {
+            self.message.original_mut().unwrap().finalize(Self::WORD);
+        }
+
+        if self.checksum.is_some() {
+            return;
+        }
+
+        let payload = ctx.unpadded_payload();
+        self.checksum = Some(match ctx.lower.last().and_then(|layer| layer.downcast_ref::<Ipv6Layer>()) {
+            Some(ip) => self.compute_checksum(ip, payload),
+            None => {
+                let mut buf = Vec::new();
+                Self { checksum: Some(0), ..self.clone() }.write(&mut buf);
+                buf.extend_from_slice(payload);
+                internet_checksum(&buf)
+            }
+        });
+    }
+
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        if bytes.len() < Self::MIN_LEN {
+            return Err(LayerError::BufferTooShort(Self::NAME));
+        }
+
+        let icmp_type = Icmpv6Type::from_value(bytes[0]);
+        let (message, len) = Self::parse_message(icmp_type, bytes)?;
+        let layer = Self {
+            icmp_type,
+            code: bytes[1],
+            checksum: Some(u16::from_be_bytes([bytes[2], bytes[3]])),
+            message,
+        };
+
+        Ok((layer, len))
+    }
+
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+}
+
+impl Reflect for Icmpv6Layer {
+    fn fields(&self) -> Vec<Field> {
+        let uint = |value: u64| FieldValue::UInt(value);
+        let bool = |value: bool| FieldValue::Bool(value);
+
+        let mut fields = vec![
+            Field::new("icmpv6.type", FieldKind::Enum, 0, 8, self.icmp_type.field_value()),
+            Field::new("icmpv6.code", FieldKind::UInt, 8, 8, uint(self.code as u64)),
+            Field::new("icmpv6.chksum", FieldKind::UInt, 16, 16, self.checksum.map_or(FieldValue::Unset, |c| uint(c as u64)))
+                .computed(),
+        ];
+
+        match &self.message {
+            Icmpv6Message::Echo { identifier, sequence } => {
+                fields.push(Field::new("icmpv6.id", FieldKind::UInt, 32, 16, uint(*identifier as u64)));
+                fields.push(Field::new("icmpv6.seq", FieldKind::UInt, 48, 16, uint(*sequence as u64)));
+            }
+            Icmpv6Message::DestinationUnreachable { original } | Icmpv6Message::TimeExceeded { original } => {
+                let length = original.length.map_or(FieldValue::Unset, |length| uint(length as u64));
+                fields.push(Field::new("icmpv6.length", FieldKind::UInt, 32, 8, length).computed());
+            }
+            Icmpv6Message::PacketTooBig { mtu, .. } => {
+                fields.push(Field::new("icmpv6.mtu", FieldKind::UInt, 32, 32, uint(*mtu as u64)));
+            }
+            Icmpv6Message::ParameterProblem { pointer, .. } => {
+                fields.push(Field::new("icmpv6.ptr", FieldKind::UInt, 32, 32, uint(*pointer as u64)));
+            }
+            Icmpv6Message::RouterAdvertisement(ra) => {
+                fields.push(Field::new("icmpv6.chlim", FieldKind::UInt, 32, 8, uint(ra.current_hop_limit as u64)));
+                fields.push(Field::new("icmpv6.flags.m", FieldKind::Bool, 40, 1, bool(ra.managed)));
+                fields.push(Field::new("icmpv6.flags.o", FieldKind::Bool, 41, 1, bool(ra.other)));
+                fields.push(Field::new("icmpv6.routerlifetime", FieldKind::UInt, 48, 16, uint(ra.router_lifetime as u64)));
+                fields.push(Field::new("icmpv6.reachabletime", FieldKind::UInt, 64, 32, uint(ra.reachable_time as u64)));
+                fields.push(Field::new("icmpv6.retranstimer", FieldKind::UInt, 96, 32, uint(ra.retrans_timer as u64)));
+            }
+            Icmpv6Message::NeighborSolicitation { target, .. } => {
+                fields.push(Field::new("icmpv6.tgt", FieldKind::Ipv6, 64, 128, FieldValue::Ipv6(*target)));
+            }
+            Icmpv6Message::NeighborAdvertisement(na) => {
+                fields.push(Field::new("icmpv6.flags.r", FieldKind::Bool, 32, 1, bool(na.router)));
+                fields.push(Field::new("icmpv6.flags.s", FieldKind::Bool, 33, 1, bool(na.solicited)));
+                fields.push(Field::new("icmpv6.flags.o", FieldKind::Bool, 34, 1, bool(na.override_flag)));
+                fields.push(Field::new("icmpv6.tgt", FieldKind::Ipv6, 64, 128, FieldValue::Ipv6(na.target)));
+            }
+            Icmpv6Message::Redirect { target, destination, .. } => {
+                fields.push(Field::new("icmpv6.tgt", FieldKind::Ipv6, 64, 128, FieldValue::Ipv6(*target)));
+                fields.push(Field::new("icmpv6.dst", FieldKind::Ipv6, 192, 128, FieldValue::Ipv6(*destination)));
+            }
+            Icmpv6Message::MulticastListener { max_response_delay, group } => {
+                fields.push(Field::new("icmpv6.mrd", FieldKind::UInt, 32, 16, uint(*max_response_delay as u64)));
+                fields.push(Field::new("icmpv6.mcaddr", FieldKind::Ipv6, 64, 128, FieldValue::Ipv6(*group)));
+            }
+            Icmpv6Message::MulticastListenerQueryV2(query) => {
+                fields.push(Field::new("icmpv6.mrd", FieldKind::UInt, 32, 16, uint(query.max_response_code as u64)));
+                fields.push(Field::new("icmpv6.mcaddr", FieldKind::Ipv6, 64, 128, FieldValue::Ipv6(query.group)));
+                fields.push(Field::new("icmpv6.s", FieldKind::Bool, 196, 1, bool(query.suppress_router_processing)));
+                fields.push(Field::new("icmpv6.qrv", FieldKind::UInt, 197, 3, uint(query.robustness as u64)));
+                fields.push(Field::new("icmpv6.qqic", FieldKind::UInt, 200, 8, uint(query.query_interval_code as u64)));
+                fields.push(Field::new("icmpv6.numsrc", FieldKind::UInt, 208, 16, uint(query.sources.len() as u64)).computed());
+            }
+            Icmpv6Message::MulticastListenerReportV2 { records } => {
+                fields.push(Field::new("icmpv6.numrec", FieldKind::UInt, 48, 16, uint(records.len() as u64)).computed());
+            }
+            Icmpv6Message::RouterSolicitation { .. } | Icmpv6Message::Unknown { .. } => {}
+        }
+
+        if let Some(original) = self.message.original() {
+            let data = FieldValue::Bytes(original.data.clone());
+            fields.push(Field::new("icmpv6.original", FieldKind::Bytes, 64, original.data.len() * 8, data));
+        }
+
+        if let Some(options) = self.message.options().filter(|options| !options.is_empty()) {
+            let mut bytes = Vec::new();
+            options.iter().for_each(|option| option.write(&mut bytes));
+
+            let offset = (self.header_len() - bytes.len()) * 8;
+            fields.push(Field::new("icmpv6.options", FieldKind::Bytes, offset, bytes.len() * 8, FieldValue::Bytes(bytes)));
+        }
+
+        fields
+    }
+
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        let auto = value.trim() == "auto";
+        let unknown = || FieldError::UnknownField(path.to_string());
+        let invalid = || FieldError::InvalidValue(value.to_string());
+
+        match (path, &mut self.message) {
+            ("icmpv6.type", _) => self.icmp_type = Icmpv6Type::parse_field(value)?,
+            ("icmpv6.code", _) => self.code = parse_uint_as(value)?,
+            ("icmpv6.chksum", _) if auto => self.checksum = None,
+            ("icmpv6.chksum", _) => self.checksum = Some(parse_uint_as(value)?),
+            ("icmpv6.id", Icmpv6Message::Echo { identifier, .. }) => *identifier = parse_uint_as(value)?,
+            ("icmpv6.seq", Icmpv6Message::Echo { sequence, .. }) => *sequence = parse_uint_as(value)?,
+            ("icmpv6.mtu", Icmpv6Message::PacketTooBig { mtu, .. }) => *mtu = parse_uint_as(value)?,
+            ("icmpv6.ptr", Icmpv6Message::ParameterProblem { pointer, .. }) => *pointer = parse_uint_as(value)?,
+            ("icmpv6.chlim", Icmpv6Message::RouterAdvertisement(ra)) => ra.current_hop_limit = parse_uint_as(value)?,
+            ("icmpv6.flags.m", Icmpv6Message::RouterAdvertisement(ra)) => ra.managed = parse_bool(value)?,
+            ("icmpv6.flags.o", Icmpv6Message::RouterAdvertisement(ra)) => ra.other = parse_bool(value)?,
+            ("icmpv6.routerlifetime", Icmpv6Message::RouterAdvertisement(ra)) => ra.router_lifetime = parse_uint_as(value)?,
+            ("icmpv6.reachabletime", Icmpv6Message::RouterAdvertisement(ra)) => ra.reachable_time = parse_uint_as(value)?,
+            ("icmpv6.retranstimer", Icmpv6Message::RouterAdvertisement(ra)) => ra.retrans_timer = parse_uint_as(value)?,
+            ("icmpv6.flags.r", Icmpv6Message::NeighborAdvertisement(na)) => na.router = parse_bool(value)?,
+            ("icmpv6.flags.s", Icmpv6Message::NeighborAdvertisement(na)) => na.solicited = parse_bool(value)?,
+            ("icmpv6.flags.o", Icmpv6Message::NeighborAdvertisement(na)) => na.override_flag = parse_bool(value)?,
+            ("icmpv6.tgt", Icmpv6Message::NeighborAdvertisement(na)) => na.target = parse_str(value)?,
+            (
+                "icmpv6.tgt",
+                Icmpv6Message::NeighborSolicitation { target, .. } | Icmpv6Message::Redirect { target, .. },
+            ) => *target = parse_str(value)?,
+            ("icmpv6.dst", Icmpv6Message::Redirect { destination, .. }) => *destination = parse_str(value)?,
+            ("icmpv6.mrd", Icmpv6Message::MulticastListener { max_response_delay, .. }) => *max_response_delay = parse_uint_as(value)?,
+            ("icmpv6.mrd", Icmpv6Message::MulticastListenerQueryV2(query)) => query.max_response_code = parse_uint_as(value)?,
+            ("icmpv6.mcaddr", Icmpv6Message::MulticastListener { group, .. }) => *group = parse_str(value)?,
+            ("icmpv6.mcaddr", Icmpv6Message::MulticastListenerQueryV2(query)) => query.group = parse_str(value)?,
+            ("icmpv6.s", Icmpv6Message::MulticastListenerQueryV2(query)) => query.suppress_router_processing = parse_bool(value)?,
+            ("icmpv6.qrv", Icmpv6Message::MulticastListenerQueryV2(query)) => match parse_uint_as::<u8>(value)? {
+                robustness if robustness <= 7 => query.robustness = robustness,
+                _ => return Err(invalid()),
+            },
+            ("icmpv6.qqic", Icmpv6Message::MulticastListenerQueryV2(query)) => query.query_interval_code = parse_uint_as(value)?,
+            ("icmpv6.length", Icmpv6Message::DestinationUnreachable { original } | Icmpv6Message::TimeExceeded { original }) => {
+                original.length = if auto { None } else { Some(parse_uint_as(value)?) };
+            }
+            ("icmpv6.original", message) => {
+                let original = message.original_mut().ok_or_else(unknown)?;
+                original.data = hex::decode(value.trim()).map_err(|_| invalid())?;
+            }
+            ("icmpv6.options", message) => {
+                let options = message.options_mut().ok_or_else(unknown)?;
+                let bytes = hex::decode(value.trim()).map_err(|_| invalid())?;
+                *options = parse_ndp_options(&bytes).map_err(|_| invalid())?;
+            }
+            _ => return Err(unknown()),
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum Icmpv6Error {
+    #[error("The Neighbor Discovery option of type {0} is malformed.")]
+    MalformedNdpOption(u8),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(last: u16) -> Ipv6Addr {
+        Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, last)
+    }
+
+    fn mac() -> MacAddr {
+        MacAddr::new(0x00, 0x11, 0x22, 0x33, 0x44, 0x55)
+    }
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(Icmpv6Layer::NAME, "ICMPv6");
+        assert_eq!(Icmpv6Layer::TYPE, LayerType::Icmpv6Layer);
+        assert_eq!(Icmpv6Layer::OSI_LEVEL, OsiLevel::Network);
+    }
+
+    #[test]
+    fn echo_checksum() {
+        let ip = Ipv6Layer::new(addr(1), addr(2));
+        let packet = ip.clone() / Icmpv6Layer::echo_request(1, 2) / b"abcd";
+        let bytes = packet.to_bytes();
+        assert_eq!(bytes[6], IpProtocol::Icmpv6.value());
+
+        let (layer, len) = Icmpv6Layer::parse(&bytes[40..]).unwrap();
+        assert_eq!(len, 8);
+        assert!(layer.is_checksum_valid(&ip, b"abcd"));
+        assert!(!layer.is_checksum_valid(&Ipv6Layer::new(addr(1), addr(3)), b"abcd"));
+
+        let mut sum = ip.pseudo_header(IpProtocol::Icmpv6, 12);
+        assert_eq!(sum.add_bytes(&bytes[40..]).finish(), 0);
+    }
+
+    #[test]
+    fn neighbor_discovery() {
+        let frame = Icmpv6Layer::neighbor_solicitation_frame(mac(), addr(1), addr(0x2233));
+        let bytes = frame.to_bytes();
+        assert_eq!(bytes[..6], [0x33, 0x33, 0xff, 0x00, 0x22, 0x33]);
+        assert_eq!(bytes[21], 255);
+        assert_eq!(bytes.len(), 14 + 40 + 32);
+
+        let (layer, len) = Icmpv6Layer::parse(&bytes[54..]).unwrap();
+        assert_eq!(len, 32);
+        let options = vec![NdpOption::SourceLinkAddress(mac())];
+        assert_eq!(layer.message, Icmpv6Message::NeighborSolicitation { target: addr(0x2233), options });
+
+        let layer = Icmpv6Layer::neighbor_advertisement(addr(0x2233), mac(), false);
+        let bytes = Packet::from(layer.clone()).to_bytes();
+        assert_eq!(bytes[4], 0x60);
+        assert_eq!(Icmpv6Layer::parse(&bytes).unwrap().0.message, layer.message);
+
+        let advertisement = RouterAdvertisement {
+            current_hop_limit: 64,
+            managed: true,
+            router_lifetime: 1800,
+            options: vec![
+                NdpOption::SourceLinkAddress(mac()),
+                NdpOption::Mtu(1500),
+                NdpOption::Rdnss { lifetime: 600, servers: vec![addr(0x53)] },
+            ],
+            ..RouterAdvertisement::default()
+        };
+        let layer = Icmpv6Layer::router_advertisement(advertisement);
+        let bytes = Packet::from(layer.clone()).to_bytes();
+        assert_eq!(bytes.len(), 16 + 8 + 8 + 24);
+        assert_eq!(bytes[4..8], [64, 0x80, 0x07, 0x08]);
+        assert_eq!(Icmpv6Layer::parse(&bytes).unwrap().0.message, layer.message);
+
+        let layer = Icmpv6Layer::redirect(addr(9), Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+        let bytes = Packet::from(layer.clone()).to_bytes();
+        let checksum = Some(u16::from_be_bytes([bytes[2], bytes[3]]));
+        assert_eq!(Icmpv6Layer::parse(&bytes), Ok((Icmpv6Layer { checksum, ..layer }, 40)));
+
+        assert_eq!(Icmpv6Layer::parse(&bytes[..20]), Err(LayerError::BufferTooShort("ICMPv6")));
+        assert_eq!(
+            Icmpv6Layer::parse(&[135, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0]),
+            Err(LayerError::Icmpv6(Icmpv6Error::MalformedNdpOption(1)))
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let original = Ipv6Layer { hop_limit: 1, ..Ipv6Layer::new(addr(1), addr(2)) } / Icmpv6Layer::echo_request(7, 1);
+
+        let layer = Icmpv6Layer::time_exceeded(0, &original);
+        let bytes = Packet::from(layer).to_bytes();
+        assert_eq!(bytes.len(), 8 + 48);
+
+        let (layer, len) = Icmpv6Layer::parse(&bytes).unwrap();
+        assert_eq!(len, 56);
+        assert!(layer.is_error());
+        let quoted = layer.original_packet().unwrap();
+        assert_eq!(quoted.to_string(), "IPv6 / ICMPv6");
+        assert_eq!(quoted.get::<Icmpv6Layer>().unwrap().message, Icmpv6Message::Echo { identifier: 7, sequence: 1 });
+
+        // A length field which announces more than the captured quote does not pad it
+        let mut truncated = bytes.clone();
+        truncated[4] = 77;
+        let (layer, len) = Icmpv6Layer::parse(&truncated).unwrap();
+        assert_eq!(len, 56);
+
+        let mut buf = Vec::new();
+        layer.write(&mut buf);
+        assert_eq!(buf, truncated);
+
+        let layer = Icmpv6Layer::packet_too_big(1280, &original);
+        let bytes = Packet::from(layer.clone()).to_bytes();
+        assert_eq!(bytes[4..8], [0, 0, 0x05, 0x00]);
+        assert_eq!(Icmpv6Layer::parse(&bytes).unwrap().0.message, Icmpv6Message::PacketTooBig {
+            mtu: 1280,
+            original: OriginalDatagram { length: None, ..OriginalDatagram::new(&bytes[8..]) },
+        });
+    }
+
+    #[test]
+    fn multicast_listener_discovery() {
+        let group = Ipv6Addr::new(0xff05, 0, 0, 0, 0, 0, 0, 0x1234);
+        let bytes = Packet::from(Icmpv6Layer::multicast_listener_report(group)).to_bytes();
+        assert_eq!(bytes.len(), 24);
+        assert_eq!(Icmpv6Layer::parse(&bytes).unwrap().1, 24);
+        assert_eq!(Icmpv6Layer::parse(&bytes).unwrap().0.message, Icmpv6Message::MulticastListener { max_response_delay: 0, group });
+
+        let query = Icmpv6Layer::multicast_listener_query_v2(group, vec![addr(1)]);
+        let bytes = Packet::from(query.clone()).to_bytes();
+        assert_eq!(bytes.len(), 44);
+        let (layer, len) = Icmpv6Layer::parse(&bytes).unwrap();
+        assert_eq!((layer.message, len), (query.message, 44));
+
+        let records = vec![MldGroupRecord::new(GroupRecordType::ChangeToExcludeMode, group, vec![])];
+        let bytes = Packet::from(Icmpv6Layer::multicast_listener_report_v2(records.clone())).to_bytes();
+        assert_eq!(bytes[6..8], [0, 1]);
+        assert_eq!(Icmpv6Layer::parse(&bytes).unwrap().0.message, Icmpv6Message::MulticastListenerReportV2 { records });
+        assert_eq!(Icmpv6Layer::parse(&bytes[..20]), Err(LayerError::BufferTooShort("ICMPv6")));
+
+        assert_eq!(Icmpv6Layer::multicast_listener_query(Ipv6Addr::unspecified()).mld_destination(), Some(Ipv6Addr::all_nodes()));
+        assert_eq!(Icmpv6Layer::multicast_listener_done(group).mld_destination(), Some(Ipv6Addr::all_routers()));
+        assert_eq!(Icmpv6Layer::multicast_listener_report_v2(vec![]).mld_destination(), Some(Ipv6Addr::all_mldv2_routers()));
+        assert_eq!(Icmpv6Layer::echo_request(1, 2).mld_destination(), None);
+    }
+
+    #[test]
+    fn mld_frame() {
+        let group = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 0xfb);
+        let packet = Icmpv6Layer::multicast_listener_report(group).into_mld_frame(mac(), addr(1));
+        assert_eq!(packet.get::<EthLayer>().unwrap().dst_mac, MacAddr::new(0x33, 0x33, 0, 0, 0, 0xfb));
+
+        let ip = packet.get::<Ipv6Layer>().unwrap();
+        assert_eq!((ip.dst, ip.hop_limit), (group, 1));
+        let bytes = packet.to_bytes();
+        assert_eq!(bytes[14 + 6], IpProtocol::HopByHop.value());
+        assert_eq!(bytes[54..62], [IpProtocol::Icmpv6.value(), 0, 0x05, 0x02, 0, 0, 0x01, 0x00]);
+    }
+
+    #[test]
+    fn reflect() {
+        let mut layer = Icmpv6Layer::neighbor_advertisement(addr(1), mac(), true);
+        layer.set_field("icmpv6.flags.s", "false").unwrap();
+        layer.set_field("icmpv6.tgt", "fe80::2").unwrap();
+        assert_eq!(layer.get_field("icmpv6.flags.r"), Some(FieldValue::Bool(true)));
+        assert_eq!(layer.get_field("icmpv6.flags.s"), Some(FieldValue::Bool(false)));
+        assert_eq!(layer.get_field("icmpv6.tgt"), Some(FieldValue::Ipv6(addr(2))));
+        assert_eq!(layer.get_field("icmpv6.options"), Some(FieldValue::Bytes(vec![2, 1, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55])));
+        assert_eq!(layer.set_field("icmpv6.mtu", "1280"), Err(FieldError::UnknownField("icmpv6.mtu".to_string())));
+
+        layer.set_field("icmpv6.options", "0501000000000500").unwrap();
+        assert_eq!(layer.message.options(), Some(&vec![NdpOption::Mtu(1280)]));
+
+        let mut layer = Icmpv6Layer::multicast_listener_query_v2(Ipv6Addr::unspecified(), vec![]);
+        layer.set_field("icmpv6.qrv", "7").unwrap();
+        layer.set_field("icmpv6.mcaddr", "ff02::fb").unwrap();
+        assert_eq!(layer.get_field("icmpv6.qrv"), Some(FieldValue::UInt(7)));
+        assert_eq!(layer.set_field("icmpv6.qrv", "8"), Err(FieldError::InvalidValue("8".to_string())));
+        assert_eq!(Packet::from(layer).to_bytes()[24], 0x07);
+    }
+}
@@ -0,0 +1,511 @@
+use super::*;
+
+use crate::{internet_checksum, reflect::*, Ipv4Addr, MacAddr, Packet};
+
+registry_enum! {
+    /// The IGMP message types assigned by IANA.
+    pub enum IgmpType: u8 {
+        MembershipQuery = 0x11,
+        MembershipReportV1 = 0x12,
+        MembershipReportV2 = 0x16,
+        LeaveGroup = 0x17,
+        MembershipReportV3 = 0x22,
+    }
+}
+
+registry_enum! {
+    /// The types of the group records in IGMPv3 and MLDv2 reports (RFC 3376, RFC 3810).
+    pub enum GroupRecordType: u8 {
+        ModeIsInclude = 1,
+        ModeIsExclude = 2,
+        ChangeToIncludeMode = 3,
+        ChangeToExcludeMode = 4,
+        AllowNewSources = 5,
+        BlockOldSources = 6,
+    }
+}
+
+/// A group record of an IGMPv3 report, which describes the sources a system listens to for a multicast group.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IgmpGroupRecord {
+    pub record_type: GroupRecordType,
+    pub group: Ipv4Addr,
+    pub sources: Vec<Ipv4Addr>,
+    /// The auxiliary data, which is padded with zeros to a multiple of four bytes when written.
+    pub auxiliary_data: Vec<u8>,
+}
+
+impl IgmpGroupRecord {
+    /// The length of a record without sources and auxiliary data in bytes.
+    pub const MIN_LEN: usize = 8;
+
+    /// Constructs a record for the group and sources without auxiliary data.
+    pub fn new(record_type: GroupRecordType, group: Ipv4Addr, sources: Vec<Ipv4Addr>) -> Self {
+        Self {
+            record_type,
+            group,
+            sources,
+            auxiliary_data: Vec::new(),
+        }
+    }
+
+    /// Returns the length of the encoded record in bytes.
+    pub fn encoded_len(&self) -> usize {
+        Self::MIN_LEN + self.sources.len() * 4 + self.auxiliary_data.len().next_multiple_of(4)
+    }
+
+    /// Appends the encoded record to the buffer.
+    pub fn write(&self, buf: &mut Vec<u8>) {
+        let aux_words = self.auxiliary_data.len().div_ceil(4);
+        buf.extend_from_slice(&[self.record_type.value(), aux_words as u8]);
+        buf.extend_from_slice(&(self.sources.len() as u16).to_be_bytes());
+        buf.extend_from_slice(&self.group.octets());
+        self.sources.iter().for_each(|source| buf.extend_from_slice(&source.octets()));
+        buf.extend_from_slice(&self.auxiliary_data);
+        buf.resize(buf.len() + aux_words * 4 - self.auxiliary_data.len(), 0);
+    }
+
+    /// Parses a record from the start of the buffer. Returns it with the number of bytes consumed, or `None` if the buffer
+    /// is too short.
+    pub fn parse(bytes: &[u8]) -> Option<(Self, usize)> {
+        let header = bytes.get(..Self::MIN_LEN)?;
+        let source_count = u16::from_be_bytes([header[2], header[3]]) as usize;
+        let sources_end = Self::MIN_LEN + source_count * 4;
+        let len = sources_end + header[1] as usize * 4;
+        let body = bytes.get(Self::MIN_LEN..len)?;
+
+        let record = Self {
+            record_type: GroupRecordType::from_value(header[0]),
+            group: Ipv4Addr::from(<[u8; 4]>::try_from(&header[4..8]).unwrap()),
+            sources: body[..source_count * 4].chunks(4).map(|chunk| Ipv4Addr::from(<[u8; 4]>::try_from(chunk).unwrap())).collect(),
+            auxiliary_data: body[source_count * 4..].to_vec(),
+        };
+
+        Some((record, len))
+    }
+}
+
+/// The body of an IGMPv3 query (RFC 3376), which may be restricted to specific sources of the group.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IgmpV3Query {
+    /// The group being queried, or the unspecified address for a general query.
+    pub group: Ipv4Addr,
+    /// Routers receiving the query must not update their timers (S flag).
+    pub suppress_router_processing: bool,
+    /// The 3-bit Querier's Robustness Variable, or zero if it exceeds 7.
+    pub robustness: u8,
+    /// The Querier's Query Interval Code, which encodes the query interval in seconds.
+    pub query_interval_code: u8,
+    pub sources: Vec<Ipv4Addr>,
+}
+
+impl IgmpV3Query {
+    /// The default robustness variable (RFC 3376).
+    pub const DEFAULT_ROBUSTNESS: u8 = 2;
+
+    /// The default query interval in seconds (RFC 3376).
+    pub const DEFAULT_QUERY_INTERVAL: u8 = 125;
+}
+
+/// The part of an IGMP message which follows the type, maximum response code and checksum fields.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum IgmpMessage {
+    /// An IGMPv1 or IGMPv2 message about a single group (RFC 1112, RFC 2236).
+    Group { group: Ipv4Addr },
+    QueryV3(IgmpV3Query),
+    ReportV3 { records: Vec<IgmpGroupRecord> },
+    Unknown { data: Vec<u8> },
+}
+
+/// An IGMP message (RFC 3376), which manages the IPv4 multicast group memberships of a link.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IgmpLayer {
+    pub igmp_type: IgmpType,
+    /// The maximum time allowed before sending a report in response to a query, in units of 1/10 second. It is unused by
+    /// IGMPv1 and by reports.
+    pub max_response_code: u8,
+    /// The checksum of the whole message. If left unset, it is computed when the packet is finalized.
+    pub checksum: Option<u16>,
+    pub message: IgmpMessage,
+}
+
+impl IgmpLayer {
+    /// The length of an IGMPv1 or IGMPv2 message in bytes, which is also the minimum length of all messages.
+    pub const MIN_LEN: usize = 8;
+
+    /// The length of an IGMPv3 query without sources in bytes.
+    pub const QUERY_V3_MIN_LEN: usize = 12;
+
+    /// The default maximum response code of queries, i.e. 10 seconds.
+    pub const DEFAULT_MAX_RESPONSE_CODE: u8 = 100;
+
+    /// The Time To Live of IGMP messages, which must not leave the link.
+    pub const TTL: u8 = 1;
+
+    /// Constructs a message of the specified type. The checksum is left unset.
+    pub fn new(igmp_type: IgmpType, max_response_code: u8, message: IgmpMessage) -> Self {
+        Self {
+            igmp_type,
+            max_response_code,
+            checksum: None,
+            message,
+        }
+    }
+
+    /// Constructs an IGMPv2 query for the group, or a general query if the group is unspecified.
+    pub fn query_v2(group: Ipv4Addr) -> Self {
+        Self::new(IgmpType::MembershipQuery, Self::DEFAULT_MAX_RESPONSE_CODE, IgmpMessage::Group { group })
+    }
+
+    /// Constructs an IGMPv3 query for the sources of the group, or a general query if the group is unspecified.
+    pub fn query_v3(group: Ipv4Addr, sources: Vec<Ipv4Addr>) -> Self {
+        let query = IgmpV3Query {
+            group,
+            suppress_router_processing: false,
+            robustness: IgmpV3Query::DEFAULT_ROBUSTNESS,
+            query_interval_code: IgmpV3Query::DEFAULT_QUERY_INTERVAL,
+            sources,
+        };
+        Self::new(IgmpType::MembershipQuery, Self::DEFAULT_MAX_RESPONSE_CODE, IgmpMessage::QueryV3(query))
+    }
+
+    /// Constructs an IGMPv2 report which joins the group.
+    pub fn report_v2(group: Ipv4Addr) -> Self {
+        Self::new(IgmpType::MembershipReportV2, 0, IgmpMessage::Group { group })
+    }
+
+    /// Constructs an IGMPv2 Leave Group message.
+    pub fn leave(group: Ipv4Addr) -> Self {
+        Self::new(IgmpType::LeaveGroup, 0, IgmpMessage::Group { group })
+    }
+
+    /// Constructs an IGMPv3 report which carries the group records.
+    pub fn report_v3(records: Vec<IgmpGroupRecord>) -> Self {
+        Self::new(IgmpType::MembershipReportV3, 0, IgmpMessage::ReportV3 { records })
+    }
+
+    /// Returns the group the message is about, if it concerns a single group.
+    pub fn group(&self) -> Option<Ipv4Addr> {
+        match &self.message {
+            IgmpMessage::Group { group } => Some(*group),
+            IgmpMessage::QueryV3(query) => Some(query.group),
+            _ => None,
+        }
+    }
+
+    /// Returns the address the message is sent to: queries go to the queried group or to all systems, IGMPv1/v2 reports
+    /// to the reported group, leaves to all routers and IGMPv3 reports to all IGMPv3 routers.
+    pub fn destination(&self) -> Ipv4Addr {
+        match (self.igmp_type, self.group()) {
+            (IgmpType::LeaveGroup, _) => Ipv4Addr::all_routers(),
+            (IgmpType::MembershipReportV3, _) => Ipv4Addr::all_igmpv3_routers(),
+            (IgmpType::MembershipQuery, Some(group)) if group == Ipv4Addr::unspecified() => Ipv4Addr::all_systems(),
+            (_, Some(group)) => group,
+            _ => Ipv4Addr::all_systems(),
+        }
+    }
+
+    /// Wraps the message into an Ethernet frame sent to its destination address. The IPv4 header carries the Router Alert
+    /// option and a Time To Live of 1.
+    pub fn into_frame(self, src_mac: MacAddr, src_ip: Ipv4Addr) -> Packet {
+        let dst_ip = self.destination();
+        let ip = Ipv4Layer {
+            ttl: Self::TTL,
+            options: vec![Ipv4Option::router_alert()],
+            ..Ipv4Layer::new(src_ip, dst_ip)
+        };
+
+        EthLayer::new(src_mac, MacAddr::ipv4_multicast(&dst_ip), EtherType::IPv4, vec![]) / ip / self
+    }
+
+    /// Computes the checksum of the message as if the checksum field was zero.
+    pub fn compute_checksum(&self) -> u16 {
+        let mut buf = Vec::with_capacity(self.header_len());
+        Self { checksum: Some(0), ..self.clone() }.write(&mut buf);
+        internet_checksum(&buf)
+    }
+
+    /// Checks if the checksum matches the message.
+    pub fn is_checksum_valid(&self) -> bool {
+        self.checksum == Some(self.compute_checksum())
+    }
+
+    /// Parses the part of a message of the specified type which follows the checksum. Returns it with the number of bytes
+    /// consumed.
+    fn parse_message(igmp_type: IgmpType, bytes: &[u8]) -> Result<(IgmpMessage, usize), LayerError> {
+        let addr_at = |offset: usize| Ipv4Addr::from(<[u8; 4]>::try_from(&bytes[offset..offset + 4]).unwrap());
+        let too_short = || LayerError::BufferTooShort(Self::NAME);
+
+        match igmp_type {
+            // Queries of at least 12 bytes are IGMPv3 queries (RFC 3376, section 7.1).
+            IgmpType::MembershipQuery if bytes.len() >= Self::QUERY_V3_MIN_LEN => {
+                let source_count = u16::from_be_bytes([bytes[10], bytes[11]]) as usize;
+                let len = Self::QUERY_V3_MIN_LEN + source_count * 4;
+                let sources = bytes.get(Self::QUERY_V3_MIN_LEN..len).ok_or_else(too_short)?;
+                let query = IgmpV3Query {
+                    group: addr_at(4),
+                    suppress_router_processing: bytes[8] & 0x08 != 0,
+                    robustness: bytes[8] & 0x07,
+                    query_interval_code: bytes[9],
+                    sources: sources.chunks(4).map(|chunk| Ipv4Addr::from(<[u8; 4]>::try_from(chunk).unwrap())).collect(),
+                };
+                Ok((IgmpMessage::QueryV3(query), len))
+            }
+            IgmpType::MembershipQuery | IgmpType::MembershipReportV1 | IgmpType::MembershipReportV2 | IgmpType::LeaveGroup => {
+                Ok((IgmpMessage::Group { group: addr_at(4) }, Self::MIN_LEN))
+            }
+            IgmpType::MembershipReportV3 => {
+                let record_count = u16::from_be_bytes([bytes[6], bytes[7]]);
+                let mut records = Vec::with_capacity(record_count as usize);
+                let mut offset = Self::MIN_LEN;
+                for _ in 0..record_count {
+                    let (record, len) = IgmpGroupRecord::parse(&bytes[offset..]).ok_or_else(too_short)?;
+                    records.push(record);
+                    offset += len;
+                }
+                Ok((IgmpMessage::ReportV3 { records }, offset))
+            }
+            _ => Ok((IgmpMessage::Unknown { data: bytes[4..].to_vec() }, bytes.len())),
+        }
+    }
+}
+
+impl Default for IgmpLayer {
+    fn default() -> Self {
+        Self::query_v2(Ipv4Addr::unspecified())
+    }
+}
+
+impl Layer for IgmpLayer {
+    const NAME: &'static str = "IGMP";
+    const TYPE: LayerType = LayerType::IgmpLayer;
+    const OSI_LEVEL: OsiLevel = OsiLevel::Network;
+}
+
+impl DynLayer for IgmpLayer {
+    fn header_len(&self) -> usize {
+        match &self.message {
+            IgmpMessage::Group { .. } => Self::MIN_LEN,
+            IgmpMessage::QueryV3(query) => Self::QUERY_V3_MIN_LEN + query.sources.len() * 4,
+            IgmpMessage::ReportV3 { records } => Self::MIN_LEN + records.iter().map(IgmpGroupRecord::encoded_len).sum::<usize>(),
+            IgmpMessage::Unknown { data } => 4 + data.len(),
+        }
+    }
+
+    /// The length of a message is given by the IP layer, so any following bytes are padding.
+    fn payload_len(&self) -> Option<usize> {
+        Some(0)
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&[self.igmp_type.value(), self.max_response_code]);
+        buf.extend_from_slice(&self.checksum.unwrap_or(0).to_be_bytes());
+
+        match &self.message {
+            IgmpMessage::Group { group } => buf.extend_from_slice(&group.octets()),
+            IgmpMessage::QueryV3(query) => {
+                buf.extend_from_slice(&query.group.octets());
+                buf.push((query.suppress_router_processing as u8) << 3 | query.robustness & 0x07);
+                buf.push(query.query_interval_code);
+                buf.extend_from_slice(&(query.sources.len() as u16).to_be_bytes());
+                query.sources.iter().for_each(|source| buf.extend_from_slice(&source.octets()));
+            }
+            IgmpMessage::ReportV3 { records } => {
+                buf.extend_from_slice(&[0; 2]);
+                buf.extend_from_slice(&(records.len() as u16).to_be_bytes());
+                records.iter().for_each(|record| record.write(buf));
+            }
+            IgmpMessage::Unknown { data } => buf.extend_from_slice(data),
+        }
+    }
+
+    fn finalize(&mut self, _ctx: &FinalizeContext) {
+        if self.checksum.is_none() {
+            self.checksum = Some(self.compute_checksum());
+        }
+    }
+
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        if bytes.len() < Self::MIN_LEN {
+            return Err(LayerError::BufferTooShort(Self::NAME));
+        }
+
+        let igmp_type = IgmpType::from_value(bytes[0]);
+        let (message, len) = Self::parse_message(igmp_type, bytes)?;
+        let layer = Self {
+            igmp_type,
+            max_response_code: bytes[1],
+            checksum: Some(u16::from_be_bytes([bytes[2], bytes[3]])),
+            message,
+        };
+
+        Ok((layer, len))
+    }
+
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+}
+
+impl Reflect for IgmpLayer {
+    fn fields(&self) -> Vec<Field> {
+        let uint = |value: u64| FieldValue::UInt(value);
+
+        let mut fields = vec![
+            Field::new("igmp.type", FieldKind::Enum, 0, 8, self.igmp_type.field_value()),
+            Field::new("igmp.mrcode", FieldKind::UInt, 8, 8, uint(self.max_response_code as u64)),
+            Field::new("igmp.chksum", FieldKind::UInt, 16, 16, self.checksum.map_or(FieldValue::Unset, |c| uint(c as u64)))
+                .computed(),
+        ];
+
+        match &self.message {
+            IgmpMessage::Group { group } => {
+                fields.push(Field::new("igmp.gaddr", FieldKind::Ipv4, 32, 32, FieldValue::Ipv4(*group)));
+            }
+            IgmpMessage::QueryV3(query) => {
+                fields.push(Field::new("igmp.gaddr", FieldKind::Ipv4, 32, 32, FieldValue::Ipv4(query.group)));
+                fields.push(Field::new("igmp.s", FieldKind::Bool, 68, 1, FieldValue::Bool(query.suppress_router_processing)));
+                fields.push(Field::new("igmp.qrv", FieldKind::UInt, 69, 3, uint(query.robustness as u64)));
+                fields.push(Field::new("igmp.qqic", FieldKind::UInt, 72, 8, uint(query.query_interval_code as u64)));
+                fields.push(Field::new("igmp.numsrc", FieldKind::UInt, 80, 16, uint(query.sources.len() as u64)).computed());
+            }
+            IgmpMessage::ReportV3 { records } => {
+                fields.push(Field::new("igmp.numgrp", FieldKind::UInt, 48, 16, uint(records.len() as u64)).computed());
+            }
+            IgmpMessage::Unknown { .. } => {}
+        }
+
+        fields
+    }
+
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        match (path, &mut self.message) {
+            ("igmp.type", _) => self.igmp_type = IgmpType::parse_field(value)?,
+            ("igmp.mrcode", _) => self.max_response_code = parse_uint_as(value)?,
+            ("igmp.chksum", _) if value.trim() == "auto" => self.checksum = None,
+            ("igmp.chksum", _) => self.checksum = Some(parse_uint_as(value)?),
+            ("igmp.gaddr", IgmpMessage::Group { group }) => *group = parse_str(value)?,
+            ("igmp.gaddr", IgmpMessage::QueryV3(query)) => query.group = parse_str(value)?,
+            ("igmp.s", IgmpMessage::QueryV3(query)) => query.suppress_router_processing = parse_bool(value)?,
+            ("igmp.qrv", IgmpMessage::QueryV3(query)) => match parse_uint_as::<u8>(value)? {
+                robustness if robustness <= 7 => query.robustness = robustness,
+                _ => return Err(FieldError::InvalidValue(value.to_string())),
+            },
+            ("igmp.qqic", IgmpMessage::QueryV3(query)) => query.query_interval_code = parse_uint_as(value)?,
+            _ => return Err(FieldError::UnknownField(path.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group() -> Ipv4Addr {
+        Ipv4Addr::new(239, 1, 2, 3)
+    }
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(IgmpLayer::NAME, "IGMP");
+        assert_eq!(IgmpLayer::TYPE, LayerType::IgmpLayer);
+        assert_eq!(IgmpLayer::OSI_LEVEL, OsiLevel::Network);
+    }
+
+    #[test]
+    fn report_v2_round_trip() {
+        let bytes = Packet::from(IgmpLayer::report_v2(group())).to_bytes();
+        assert_eq!(bytes[..2], [0x16, 0x00]);
+        assert_eq!(bytes[4..], [239, 1, 2, 3]);
+
+        let (layer, len) = IgmpLayer::parse(&bytes).unwrap();
+        assert_eq!(len, 8);
+        assert!(layer.is_checksum_valid());
+        assert_eq!(layer.igmp_type, IgmpType::MembershipReportV2);
+        assert_eq!(layer.group(), Some(group()));
+    }
+
+    #[test]
+    fn query_versions() {
+        let v2 = Packet::from(IgmpLayer::query_v2(Ipv4Addr::unspecified())).to_bytes();
+        assert_eq!(IgmpLayer::parse(&v2).unwrap().0.message, IgmpMessage::Group { group: Ipv4Addr::unspecified() });
+
+        let sources = vec![Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2)];
+        let layer = IgmpLayer::query_v3(group(), sources.clone());
+        let bytes = Packet::from(layer.clone()).to_bytes();
+        assert_eq!(bytes.len(), 20);
+        assert_eq!(bytes[8..12], [0x02, 125, 0x00, 0x02]);
+
+        let (parsed, len) = IgmpLayer::parse(&bytes).unwrap();
+        assert_eq!(len, 20);
+        assert!(parsed.is_checksum_valid());
+        let IgmpMessage::QueryV3(query) = parsed.message else { panic!("not an IGMPv3 query") };
+        assert_eq!(query.sources, sources);
+        assert_eq!(query.robustness, 2);
+        assert_eq!(IgmpLayer::parse(&bytes[..16]), Err(LayerError::BufferTooShort("IGMP")));
+    }
+
+    #[test]
+    fn report_v3_records() {
+        let mut record = IgmpGroupRecord::new(GroupRecordType::ModeIsInclude, group(), vec![Ipv4Addr::new(10, 0, 0, 1)]);
+        record.auxiliary_data = vec![0xaa, 0xbb];
+        let records = vec![record, IgmpGroupRecord::new(GroupRecordType::ChangeToExcludeMode, Ipv4Addr::new(239, 0, 0, 9), vec![])];
+        let bytes = Packet::from(IgmpLayer::report_v3(records.clone())).to_bytes();
+        assert_eq!(bytes.len(), 8 + 16 + 8);
+        assert_eq!(bytes[6..10], [0x00, 0x02, 0x01, 0x01]);
+
+        let (layer, len) = IgmpLayer::parse(&bytes).unwrap();
+        assert_eq!(len, bytes.len());
+        assert!(layer.is_checksum_valid());
+        let IgmpMessage::ReportV3 { records: parsed } = layer.message else { panic!("not an IGMPv3 report") };
+        assert_eq!(parsed[0].auxiliary_data, [0xaa, 0xbb, 0x00, 0x00]);
+        assert_eq!(parsed[1], records[1]);
+    }
+
+    #[test]
+    fn destinations() {
+        assert_eq!(IgmpLayer::query_v2(Ipv4Addr::unspecified()).destination(), Ipv4Addr::all_systems());
+        assert_eq!(IgmpLayer::query_v3(group(), vec![]).destination(), group());
+        assert_eq!(IgmpLayer::report_v2(group()).destination(), group());
+        assert_eq!(IgmpLayer::leave(group()).destination(), Ipv4Addr::all_routers());
+        assert_eq!(IgmpLayer::report_v3(vec![]).destination(), Ipv4Addr::all_igmpv3_routers());
+    }
+
+    #[test]
+    fn frame() {
+        let mac = MacAddr::new(0x00, 0x11, 0x22, 0x33, 0x44, 0x55);
+        let packet = IgmpLayer::report_v2(group()).into_frame(mac, Ipv4Addr::new(192, 168, 1, 10));
+        let eth = packet.get::<EthLayer>().unwrap();
+        assert_eq!(eth.dst_mac, MacAddr::new(0x01, 0x00, 0x5e, 0x01, 0x02, 0x03));
+
+        let ip = packet.get::<Ipv4Layer>().unwrap();
+        assert_eq!((ip.dst, ip.ttl), (group(), 1));
+        assert_eq!(ip.options, [Ipv4Option::router_alert()]);
+
+        let bytes = packet.to_bytes();
+        assert_eq!(bytes[14 + 9], IpProtocol::Igmp.value());
+        assert_eq!(bytes[14..16], [0x46, 0x00]);
+    }
+
+    #[test]
+    fn reflect_fields() {
+        let mut layer = IgmpLayer::query_v3(group(), vec![]);
+        layer.set_field("igmp.qrv", "5").unwrap();
+        layer.set_field("igmp.s", "true").unwrap();
+        assert_eq!(layer.set_field("igmp.qrv", "8"), Err(FieldError::InvalidValue("8".to_string())));
+
+        let bytes = Packet::from(layer.clone()).to_bytes();
+        assert_eq!(bytes[8], 0x0d);
+        assert!(layer.fields().iter().any(|field| field.name == "igmp.numsrc"));
+        assert_eq!(
+            IgmpLayer::report_v3(vec![]).set_field("igmp.gaddr", "224.0.0.1"),
+            Err(FieldError::UnknownField("igmp.gaddr".to_string()))
+        );
+    }
+}
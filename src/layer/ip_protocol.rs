@@ -0,0 +1,88 @@
+use super::*;
+
+registry_enum! {
+    /// The protocol numbers assigned by IANA which identify the payload of an IPv4 packet or the next header of an IPv6
+    /// packet.
+    pub enum IpProtocol: u8 {
+        /// IPv6 Hop-by-Hop Options extension header.
+        HopByHop = 0,
+        Icmp = 1,
+        Igmp = 2,
+        Ggp = 3,
+        /// IPv4 encapsulated in IP (IP-in-IP).
+        IPv4 = 4,
+        St = 5,
+        Tcp = 6,
+        Egp = 8,
+        Igp = 9,
+        Udp = 17,
+        Rdp = 27,
+        Dccp = 33,
+        /// IPv6 encapsulated in IP.
+        IPv6 = 41,
+        /// IPv6 Routing extension header.
+        IPv6Route = 43,
+        /// IPv6 Fragment extension header.
+        IPv6Frag = 44,
+        Rsvp = 46,
+        Gre = 47,
+        Esp = 50,
+        Ah = 51,
+        Icmpv6 = 58,
+        /// No next header follows an IPv6 header.
+        IPv6NoNxt = 59,
+        /// IPv6 Destination Options extension header.
+        IPv6Opts = 60,
+        Eigrp = 88,
+        Ospf = 89,
+        EtherIp = 97,
+        Pim = 103,
+        Vrrp = 112,
+        L2tp = 115,
+        Sctp = 132,
+        MobilityHeader = 135,
+        UdpLite = 136,
+        MplsInIp = 137,
+        Hip = 139,
+        Shim6 = 140,
+        Ethernet = 143,
+    }
+}
+
+impl IpProtocol {
+    /// Returns the protocol number which identifies a layer of the specified type as the payload of an IP packet.
+    pub fn of_layer(layer_type: LayerType) -> Option<Self> {
+        match layer_type {
+            LayerType::Ipv4Layer => Some(IpProtocol::IPv4),
+            LayerType::Ipv6Layer => Some(IpProtocol::IPv6),
+            LayerType::IcmpLayer => Some(IpProtocol::Icmp),
+            LayerType::Icmpv6Layer => Some(IpProtocol::Icmpv6),
+            LayerType::IgmpLayer => Some(IpProtocol::Igmp),
+            LayerType::GreLayer => Some(IpProtocol::Gre),
+            LayerType::UdpLayer => Some(IpProtocol::Udp),
+            LayerType::TcpLayer => Some(IpProtocol::Tcp),
+            _ => None,
+        }
+    }
+}
+
+impl From<u8> for IpProtocol {
+    fn from(value: u8) -> Self {
+        Self::from_value(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry() {
+        assert_eq!(IpProtocol::from(6), IpProtocol::Tcp);
+        assert_eq!(IpProtocol::from(253), IpProtocol::Unknown(253));
+        assert_eq!(IpProtocol::Icmpv6.value(), 58);
+        assert_eq!(IpProtocol::from_name("Udp"), Some(IpProtocol::Udp));
+        assert_eq!(IpProtocol::of_layer(LayerType::Ipv4Layer), Some(IpProtocol::IPv4));
+        assert_eq!(IpProtocol::of_layer(LayerType::RawLayer), None);
+    }
+}
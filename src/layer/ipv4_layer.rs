@@ -1,11 +1,721 @@
 use super::*;
 
-pub struct Ipv4Layer{
-    
+use crate::{internet_checksum, reflect::*, Checksum, Discriminator, Ipv4Addr};
+
+use thiserror::Error;
+
+/// The 3-bit flags field of an IPv4 header.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Ipv4Flags {
+    /// The reserved bit, also known as the evil bit (RFC 3514).
+    pub reserved: bool,
+    pub dont_fragment: bool,
+    pub more_fragments: bool,
+}
+
+impl Ipv4Flags {
+    /// Constructs the flags from the three most significant bits of the value.
+    pub const fn from_bits(bits: u8) -> Self {
+        Self {
+            reserved: bits & 0b100 != 0,
+            dont_fragment: bits & 0b010 != 0,
+            more_fragments: bits & 0b001 != 0,
+        }
+    }
+
+    /// Returns the flags as a 3-bit value.
+    pub const fn bits(self) -> u8 {
+        (self.reserved as u8) << 2 | (self.dont_fragment as u8) << 1 | self.more_fragments as u8
+    }
+}
+
+/// An IPv4 header (RFC 791).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Ipv4Layer {
+    /// The 4-bit version, which is always 4 for valid packets.
+    pub version: u8,
+    /// The 4-bit length of the header in 32-bit words. If left unset, it is derived from the options and padding, unless
+    /// they exceed [`Ipv4Layer::MAX_OPTIONS_LEN`].
+    pub ihl: Option<u8>,
+    /// The 6-bit Differentiated Services Code Point.
+    pub dscp: u8,
+    /// The 2-bit Explicit Congestion Notification.
+    pub ecn: u8,
+    /// The length of the whole packet in bytes. If left unset, it is derived from the payload.
+    pub total_length: Option<u16>,
+    pub identification: u16,
+    pub flags: Ipv4Flags,
+    /// The 13-bit offset of the fragment in 8-byte units.
+    pub fragment_offset: u16,
+    pub ttl: u8,
+    /// The protocol of the payload. If left unset, it is derived from the next layer.
+    pub protocol: Option<IpProtocol>,
+    /// The header checksum. If left unset, it is computed when the packet is finalized.
+    pub checksum: Option<u16>,
+    pub src: Ipv4Addr,
+    pub dst: Ipv4Addr,
+    pub options: Vec<Ipv4Option>,
+    /// The bytes following [`Ipv4Option::EndOfList`] up to the end of the header, which are written back as they are.
+    /// Zeros are appended when the options and padding do not fill the last word.
+    pub padding: Vec<u8>,
+}
+
+impl Ipv4Layer {
+    /// The length of a header without options in bytes.
+    pub const MIN_LEN: usize = 20;
+
+    /// The length of the longest possible header in bytes.
+    pub const MAX_LEN: usize = 60;
+
+    /// The length of the longest options which can be described by the IHL in bytes.
+    pub const MAX_OPTIONS_LEN: usize = Self::MAX_LEN - Self::MIN_LEN;
+
+    /// The default Time To Live of new packets.
+    pub const DEFAULT_TTL: u8 = 64;
+
+    /// Constructs a new IPv4 header between the specified addresses. The derived fields are left unset.
+    pub fn new(src: Ipv4Addr, dst: Ipv4Addr) -> Self {
+        Self {
+            src,
+            dst,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the length of the options including the padding to a multiple of four bytes.
+    pub fn options_len(&self) -> usize {
+        let len = self.options.iter().map(Ipv4Option::encoded_len).sum::<usize>() + self.padding.len();
+        len.next_multiple_of(4)
+    }
+
+    /// Checks if the options and padding fit in the header, i.e. if they can be described by the IHL.
+    pub fn options_fit(&self) -> bool {
+        self.options_len() <= Self::MAX_OPTIONS_LEN
+    }
+
+    /// Returns the encoded options and padding, padded with zeros to a multiple of four bytes.
+    fn options_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.options_len());
+        self.options.iter().for_each(|option| option.write(&mut buf));
+        buf.extend_from_slice(&self.padding);
+        buf.resize(self.options_len(), 0);
+        buf
+    }
+
+    /// Checks if the packet is a fragment of a larger packet.
+    pub fn is_fragment(&self) -> bool {
+        self.flags.more_fragments || self.fragment_offset != 0
+    }
+
+    /// Computes the checksum of the header as it would be serialized, ignoring the current value of the checksum field.
+    pub fn compute_checksum(&self) -> u16 {
+        let mut buf = Vec::with_capacity(self.header_len());
+        self.write(&mut buf);
+        buf[10..12].fill(0);
+        internet_checksum(&buf)
+    }
+
+    /// Checks if the checksum field matches the header. An unset checksum is never valid.
+    pub fn is_checksum_valid(&self) -> bool {
+        self.checksum == Some(self.compute_checksum())
+    }
+
+    /// Starts an upper-layer checksum with the pseudo-header (RFC 768) of a payload of the specified protocol and length.
+    pub fn pseudo_header(&self, protocol: IpProtocol, length: u16) -> Checksum {
+        let mut checksum = Checksum::new();
+        checksum
+            .add_bytes(&self.src.octets())
+            .add_bytes(&self.dst.octets())
+            .add_u16(protocol.value() as u16)
+            .add_u16(length);
+        checksum
+    }
+}
+
+impl Default for Ipv4Layer {
+    fn default() -> Self {
+        Self {
+            version: 4,
+            ihl: None,
+            dscp: 0,
+            ecn: 0,
+            total_length: None,
+            identification: 0,
+            flags: Ipv4Flags::default(),
+            fragment_offset: 0,
+            ttl: Self::DEFAULT_TTL,
+            protocol: None,
+            checksum: None,
+            src: Ipv4Addr::unspecified(),
+            dst: Ipv4Addr::unspecified(),
+            options: Vec::new(),
+            padding: Vec::new(),
+        }
+    }
 }
 
 impl Layer for Ipv4Layer {
-    const NAME: &'static str = "Ipv4Layer";
+    const NAME: &'static str = "IPv4";
     const TYPE: LayerType = LayerType::Ipv4Layer;
-    const OSI_LEVEL:OsiLevel = OsiLevel::Network;
-}
\ No newline at end of file
+    const OSI_LEVEL: OsiLevel = OsiLevel::Network;
+}
+
+impl DynLayer for Ipv4Layer {
+    fn header_len(&self) -> usize {
+        Self::MIN_LEN + self.options_len()
+    }
+
+    fn payload_len(&self) -> Option<usize> {
+        // A total length shorter than the header is invalid, or zero due to segmentation offload, so it is ignored
+        let total_length = self.total_length? as usize;
+        total_length.checked_sub(self.header_len())
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.push((self.version << 4) | (self.ihl.unwrap_or(0) & 0x0f));
+        buf.push((self.dscp << 2) | (self.ecn & 0b11));
+        buf.extend_from_slice(&self.total_length.unwrap_or(0).to_be_bytes());
+        buf.extend_from_slice(&self.identification.to_be_bytes());
+        buf.extend_from_slice(&((self.flags.bits() as u16) << 13 | (self.fragment_offset & 0x1fff)).to_be_bytes());
+        buf.push(self.ttl);
+        buf.push(self.protocol.map_or(0, IpProtocol::value));
+        buf.extend_from_slice(&self.checksum.unwrap_or(0).to_be_bytes());
+        buf.extend_from_slice(&self.src.octets());
+        buf.extend_from_slice(&self.dst.octets());
+
+        buf.extend_from_slice(&self.options_bytes());
+    }
+
+    fn finalize(&mut self, ctx: &FinalizeContext) {
+        // Options which do not fit are still written, but the IHL is left unset rather than truncated to 4 bits
+        if self.ihl.is_none() && self.options_fit() {
+            self.ihl = Some((self.header_len() / 4) as u8);
+        }
+
+        if self.total_length.is_none() {
+            self.total_length = Some((self.header_len() + ctx.unpadded_payload().len()) as u16);
+        }
+
+        if self.protocol.is_none() {
+            self.protocol = ctx.next().and_then(|next| IpProtocol::of_layer(next.layer_type()));
+        }
+
+        if self.checksum.is_none() {
+            self.checksum = Some(self.compute_checksum());
+        }
+    }
+
+    fn next_discriminators(&self) -> Vec<Discriminator> {
+        // Only the first fragment starts with the header of the next layer
+        match self.protocol {
+            Some(protocol) if self.fragment_offset == 0 => vec![Discriminator::IpProtocol(protocol.value())],
+            _ => Vec::new(),
+        }
+    }
+
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        let view = Ipv4View::new(bytes)?;
+        Ok((view.to_layer()?, view.header_len()))
+    }
+
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+}
+
+impl Reflect for Ipv4Layer {
+    fn fields(&self) -> Vec<Field> {
+        let uint = |value: Option<u64>| value.map_or(FieldValue::Unset, FieldValue::UInt);
+
+        let mut fields = vec![
+            Field::new("ip.version", FieldKind::UInt, 0, 4, FieldValue::UInt(self.version as u64)),
+            Field::new("ip.ihl", FieldKind::UInt, 4, 4, uint(self.ihl.map(u64::from))).computed(),
+            Field::new("ip.dscp", FieldKind::UInt, 8, 6, FieldValue::UInt(self.dscp as u64)),
+            Field::new("ip.ecn", FieldKind::UInt, 14, 2, FieldValue::UInt(self.ecn as u64)),
+            Field::new("ip.len", FieldKind::UInt, 16, 16, uint(self.total_length.map(u64::from))).computed(),
+            Field::new("ip.id", FieldKind::UInt, 32, 16, FieldValue::UInt(self.identification as u64)),
+            Field::new("ip.flags.rf", FieldKind::Bool, 48, 1, FieldValue::Bool(self.flags.reserved)),
+            Field::new("ip.flags.df", FieldKind::Bool, 49, 1, FieldValue::Bool(self.flags.dont_fragment)),
+            Field::new("ip.flags.mf", FieldKind::Bool, 50, 1, FieldValue::Bool(self.flags.more_fragments)),
+            Field::new("ip.frag", FieldKind::UInt, 51, 13, FieldValue::UInt(self.fragment_offset as u64)),
+            Field::new("ip.ttl", FieldKind::UInt, 64, 8, FieldValue::UInt(self.ttl as u64)),
+            Field::new("ip.proto", FieldKind::Enum, 72, 8, self.protocol.map_or(FieldValue::Unset, IpProtocol::field_value))
+                .computed(),
+            Field::new("ip.chksum", FieldKind::UInt, 80, 16, uint(self.checksum.map(u64::from))).computed(),
+            Field::new("ip.src", FieldKind::Ipv4, 96, 32, FieldValue::Ipv4(self.src)),
+            Field::new("ip.dst", FieldKind::Ipv4, 128, 32, FieldValue::Ipv4(self.dst)),
+        ];
+
+        if !self.options.is_empty() || !self.padding.is_empty() {
+            let options = self.options_bytes();
+            fields.push(Field::new("ip.options", FieldKind::Bytes, 160, options.len() * 8, FieldValue::Bytes(options)));
+        }
+
+        fields
+    }
+
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        let bounded = |max: u64| match parse_uint(value)? {
+            parsed if parsed <= max => Ok(parsed),
+            _ => Err(FieldError::InvalidValue(value.to_string())),
+        };
+        let auto = value.trim() == "auto";
+
+        match path {
+            "ip.version" => self.version = bounded(0x0f)? as u8,
+            "ip.ihl" if auto => self.ihl = None,
+            "ip.ihl" => self.ihl = Some(bounded(0x0f)? as u8),
+            "ip.dscp" => self.dscp = bounded(0x3f)? as u8,
+            "ip.ecn" => self.ecn = bounded(0b11)? as u8,
+            "ip.len" if auto => self.total_length = None,
+            "ip.len" => self.total_length = Some(parse_uint_as(value)?),
+            "ip.id" => self.identification = parse_uint_as(value)?,
+            "ip.flags.rf" => self.flags.reserved = parse_bool(value)?,
+            "ip.flags.df" => self.flags.dont_fragment = parse_bool(value)?,
+            "ip.flags.mf" => self.flags.more_fragments = parse_bool(value)?,
+            "ip.frag" => self.fragment_offset = bounded(0x1fff)? as u16,
+            "ip.ttl" => self.ttl = parse_uint_as(value)?,
+            "ip.proto" if auto => self.protocol = None,
+            "ip.proto" => self.protocol = Some(IpProtocol::parse_field(value)?),
+            "ip.chksum" if auto => self.checksum = None,
+            "ip.chksum" => self.checksum = Some(parse_uint_as(value)?),
+            "ip.src" => self.src = parse_str(value)?,
+            "ip.dst" => self.dst = parse_str(value)?,
+            "ip.options" => {
+                let invalid = || FieldError::InvalidValue(value.to_string());
+                let bytes = hex::decode(value.trim()).map_err(|_| invalid())?;
+                if bytes.len() > Self::MAX_OPTIONS_LEN {
+                    return Err(invalid());
+                }
+
+                let (options, padding) = split_ipv4_options(&bytes).map_err(|_| invalid())?;
+                self.padding = padding.to_vec();
+                self.options = options;
+            }
+            _ => return Err(FieldError::UnknownField(path.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
+/// A borrowed, zero-copy view of an IPv4 header at the start of a byte slice.
+///
+/// The version and the lengths are validated once on construction and fields are decoded only when accessed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Ipv4View<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Ipv4View<'a> {
+    /// Constructs a view of the IPv4 header at the start of the slice.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, LayerError> {
+        if bytes.len() < Ipv4Layer::MIN_LEN {
+            return Err(LayerError::BufferTooShort(Ipv4Layer::NAME));
+        }
+
+        let view = Self { bytes };
+        if view.version() != 4 {
+            return Err(Ipv4Error::InvalidVersion(view.version()).into());
+        }
+
+        if view.ihl() < 5 {
+            return Err(Ipv4Error::InvalidHeaderLength(view.ihl()).into());
+        }
+
+        if bytes.len() < view.header_len() {
+            return Err(LayerError::BufferTooShort(Ipv4Layer::NAME));
+        }
+
+        Ok(view)
+    }
+
+    fn u16_at(&self, offset: usize) -> u16 {
+        u16::from_be_bytes([self.bytes[offset], self.bytes[offset + 1]])
+    }
+
+    /// Returns the version.
+    pub fn version(&self) -> u8 {
+        self.bytes[0] >> 4
+    }
+
+    /// Returns the length of the header in 32-bit words.
+    pub fn ihl(&self) -> u8 {
+        self.bytes[0] & 0x0f
+    }
+
+    /// Returns the length of the header in bytes.
+    pub fn header_len(&self) -> usize {
+        self.ihl() as usize * 4
+    }
+
+    /// Returns the Differentiated Services Code Point.
+    pub fn dscp(&self) -> u8 {
+        self.bytes[1] >> 2
+    }
+
+    /// Returns the Explicit Congestion Notification.
+    pub fn ecn(&self) -> u8 {
+        self.bytes[1] & 0b11
+    }
+
+    /// Returns the length of the whole packet in bytes.
+    pub fn total_length(&self) -> u16 {
+        self.u16_at(2)
+    }
+
+    /// Returns the identification of the packet.
+    pub fn identification(&self) -> u16 {
+        self.u16_at(4)
+    }
+
+    /// Returns the flags.
+    pub fn flags(&self) -> Ipv4Flags {
+        Ipv4Flags::from_bits(self.bytes[6] >> 5)
+    }
+
+    /// Returns the offset of the fragment in 8-byte units.
+    pub fn fragment_offset(&self) -> u16 {
+        self.u16_at(6) & 0x1fff
+    }
+
+    /// Returns the Time To Live.
+    pub fn ttl(&self) -> u8 {
+        self.bytes[8]
+    }
+
+    /// Returns the protocol of the payload.
+    pub fn protocol(&self) -> IpProtocol {
+        IpProtocol::from_value(self.bytes[9])
+    }
+
+    /// Returns the header checksum.
+    pub fn checksum(&self) -> u16 {
+        self.u16_at(10)
+    }
+
+    /// Returns the source address.
+    pub fn src(&self) -> Ipv4Addr {
+        Ipv4Addr::new(self.bytes[12], self.bytes[13], self.bytes[14], self.bytes[15])
+    }
+
+    /// Returns the destination address.
+    pub fn dst(&self) -> Ipv4Addr {
+        Ipv4Addr::new(self.bytes[16], self.bytes[17], self.bytes[18], self.bytes[19])
+    }
+
+    /// Returns the raw bytes of the options, including the padding.
+    pub fn options_raw(&self) -> &'a [u8] {
+        &self.bytes[Ipv4Layer::MIN_LEN..self.header_len()]
+    }
+
+    /// Decodes the options.
+    pub fn options(&self) -> Result<Vec<Ipv4Option>, Ipv4Error> {
+        parse_ipv4_options(self.options_raw())
+    }
+
+    /// Checks if the checksum matches the header.
+    pub fn is_checksum_valid(&self) -> bool {
+        internet_checksum(&self.bytes[..self.header_len()]) == 0
+    }
+
+    /// Returns the bytes following the header, up to the end of the slice.
+    pub fn payload(&self) -> &'a [u8] {
+        &self.bytes[self.header_len()..]
+    }
+
+    /// Converts the view into an owned IPv4 layer. Fails if the options are malformed.
+    pub fn to_layer(&self) -> Result<Ipv4Layer, LayerError> {
+        let (options, padding) = split_ipv4_options(self.options_raw())?;
+
+        Ok(Ipv4Layer {
+            version: self.version(),
+            ihl: Some(self.ihl()),
+            dscp: self.dscp(),
+            ecn: self.ecn(),
+            total_length: Some(self.total_length()),
+            identification: self.identification(),
+            flags: self.flags(),
+            fragment_offset: self.fragment_offset(),
+            ttl: self.ttl(),
+            protocol: Some(self.protocol()),
+            checksum: Some(self.checksum()),
+            src: self.src(),
+            dst: self.dst(),
+            options,
+            padding: padding.to_vec(),
+        })
+    }
+}
+
+/// A mutable, zero-copy view of an IPv4 header at the start of a byte slice, which allows fields to be edited in place.
+///
+/// The checksum is not updated automatically. Call [`Ipv4ViewMut::update_checksum`] after editing the header.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Ipv4ViewMut<'a> {
+    bytes: &'a mut [u8],
+}
+
+impl<'a> Ipv4ViewMut<'a> {
+    /// Constructs a mutable view of the IPv4 header at the start of the slice.
+    pub fn new(bytes: &'a mut [u8]) -> Result<Self, LayerError> {
+        Ipv4View::new(bytes)?;
+        Ok(Self { bytes })
+    }
+
+    /// Returns a read-only view of the header.
+    pub fn as_view(&self) -> Ipv4View<'_> {
+        Ipv4View { bytes: self.bytes }
+    }
+
+    /// Sets the Time To Live.
+    pub fn set_ttl(&mut self, ttl: u8) {
+        self.bytes[8] = ttl;
+    }
+
+    /// Sets the source address.
+    pub fn set_src(&mut self, addr: Ipv4Addr) {
+        self.bytes[12..16].copy_from_slice(&addr.octets());
+    }
+
+    /// Sets the destination address.
+    pub fn set_dst(&mut self, addr: Ipv4Addr) {
+        self.bytes[16..20].copy_from_slice(&addr.octets());
+    }
+
+    /// Sets the header checksum.
+    pub fn set_checksum(&mut self, checksum: u16) {
+        self.bytes[10..12].copy_from_slice(&checksum.to_be_bytes());
+    }
+
+    /// Recomputes the header checksum.
+    pub fn update_checksum(&mut self) {
+        self.set_checksum(0);
+        let header_len = self.as_view().header_len();
+        self.set_checksum(internet_checksum(&self.bytes[..header_len]));
+    }
+
+    /// Returns the bytes following the header.
+    pub fn payload_mut(&mut self) -> &mut [u8] {
+        let header_len = self.as_view().header_len();
+        &mut self.bytes[header_len..]
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum Ipv4Error {
+    #[error("The version {0} is not valid for an IPv4 header.")]
+    InvalidVersion(u8),
+
+    #[error("The header length of {0} words is shorter than the minimal IPv4 header.")]
+    InvalidHeaderLength(u8),
+
+    #[error("The IPv4 option of type {0} is malformed.")]
+    MalformedOption(u8),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Packet;
+
+    /// The header of an ICMP echo request from 192.168.0.1 to 192.168.0.199.
+    const HEADER: [u8; 20] = [
+        0x45, 0x00, 0x00, 0x3c, 0x1c, 0x46, 0x40, 0x00, 0x40, 0x01,
+        0x9c, 0x62, 0xc0, 0xa8, 0x00, 0x01, 0xc0, 0xa8, 0x00, 0xc7,
+    ];
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(Ipv4Layer::NAME, "IPv4");
+        assert_eq!(Ipv4Layer::TYPE, LayerType::Ipv4Layer);
+        assert_eq!(Ipv4Layer::OSI_LEVEL, OsiLevel::Network);
+    }
+
+    #[test]
+    fn parse_and_write() {
+        let (layer, len) = Ipv4Layer::parse(&HEADER).unwrap();
+        assert_eq!(len, 20);
+        assert_eq!(layer.total_length, Some(60));
+        assert_eq!(layer.identification, 0x1c46);
+        assert_eq!(layer.flags, Ipv4Flags { reserved: false, dont_fragment: true, more_fragments: false });
+        assert_eq!(layer.ttl, 64);
+        assert_eq!(layer.protocol, Some(IpProtocol::Icmp));
+        assert_eq!(layer.src, Ipv4Addr::new(192, 168, 0, 1));
+        assert_eq!(layer.dst, Ipv4Addr::new(192, 168, 0, 199));
+        assert!(layer.is_checksum_valid());
+        assert_eq!(layer.payload_len(), Some(40));
+        assert_eq!(layer.next_discriminators(), [Discriminator::IpProtocol(1)]);
+
+        let mut buf = Vec::new();
+        layer.write(&mut buf);
+        assert_eq!(buf, HEADER);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let mut buf = HEADER;
+        assert_eq!(Ipv4Layer::parse(&buf[..19]), Err(LayerError::BufferTooShort("IPv4")));
+
+        buf[0] = 0x65;
+        assert_eq!(Ipv4Layer::parse(&buf), Err(LayerError::Ipv4(Ipv4Error::InvalidVersion(6))));
+
+        buf[0] = 0x44;
+        assert_eq!(Ipv4Layer::parse(&buf), Err(LayerError::Ipv4(Ipv4Error::InvalidHeaderLength(4))));
+
+        buf[0] = 0x46;
+        assert_eq!(Ipv4Layer::parse(&buf), Err(LayerError::BufferTooShort("IPv4")));
+    }
+
+    #[test]
+    fn options() {
+        let mut layer = Ipv4Layer::new(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(224, 0, 0, 22));
+        layer.options = vec![Ipv4Option::router_alert(), Ipv4Option::NoOperation];
+        assert_eq!(layer.options_len(), 8);
+
+        let packet = Packet::from(layer) / b"igmp";
+        let bytes = packet.to_bytes();
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(bytes[0], 0x47);
+        assert_eq!(&bytes[20..28], [0x94, 0x04, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00]);
+
+        // The zero padding is decoded as the end of the option list
+        let (parsed, len) = Ipv4Layer::parse(&bytes).unwrap();
+        assert_eq!(len, 28);
+        assert_eq!(parsed.options, [Ipv4Option::router_alert(), Ipv4Option::NoOperation, Ipv4Option::EndOfList]);
+        assert!(parsed.is_checksum_valid());
+
+        let mut written = Vec::new();
+        parsed.write(&mut written);
+        assert_eq!(written, bytes[..28]);
+
+        let mut malformed = bytes.clone();
+        malformed[21] = 0x10;
+        assert_eq!(Ipv4Layer::parse(&malformed), Err(LayerError::Ipv4(Ipv4Error::MalformedOption(0x94))));
+    }
+
+    #[test]
+    fn finalize() {
+        let layer = Ipv4Layer::new(Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 199));
+        let mut packet = Packet::from(layer) / [0u8; 40].as_slice() / PaddingLayer::zeroed(6);
+        packet.finalize();
+
+        let layer = packet.get::<Ipv4Layer>().unwrap();
+        assert_eq!(layer.ihl, Some(5));
+        assert_eq!(layer.total_length, Some(60));
+        assert_eq!(layer.protocol, None);
+        assert!(layer.is_checksum_valid());
+
+        // Nested IP-in-IP packets are identified by the protocol of the outer header
+        let packet = Ipv4Layer::default() / Ipv4Layer::default();
+        let bytes = packet.to_bytes();
+        assert_eq!(bytes[9], IpProtocol::IPv4.value());
+        assert_eq!(&bytes[2..4], [0x00, 0x28]);
+    }
+
+    #[test]
+    fn fragments() {
+        let mut layer = Ipv4Layer::parse(&HEADER).unwrap().0;
+        layer.flags.more_fragments = true;
+        assert!(layer.is_fragment());
+        assert_eq!(layer.next_discriminators(), [Discriminator::IpProtocol(1)]);
+
+        layer.flags.more_fragments = false;
+        layer.fragment_offset = 185;
+        assert!(layer.is_fragment());
+        assert_eq!(layer.next_discriminators(), []);
+
+        let mut buf = Vec::new();
+        layer.write(&mut buf);
+        assert_eq!(&buf[6..8], [0x40, 0xb9]);
+    }
+
+    #[test]
+    fn views() {
+        let mut buf = HEADER.to_vec();
+        buf.extend_from_slice(&[1, 2, 3]);
+
+        let view = Ipv4View::new(&buf).unwrap();
+        assert_eq!(view.header_len(), 20);
+        assert_eq!(view.protocol(), IpProtocol::Icmp);
+        assert_eq!(view.dst(), Ipv4Addr::new(192, 168, 0, 199));
+        assert_eq!(view.options(), Ok(vec![]));
+        assert_eq!(view.payload(), [1, 2, 3]);
+        assert!(view.is_checksum_valid());
+        assert_eq!(view.to_layer(), Ok(Ipv4Layer::parse(&HEADER).unwrap().0));
+
+        let mut view = Ipv4ViewMut::new(&mut buf).unwrap();
+        view.set_ttl(1);
+        view.set_src(Ipv4Addr::new(10, 0, 0, 1));
+        assert!(!view.as_view().is_checksum_valid());
+        view.update_checksum();
+        assert!(view.as_view().is_checksum_valid());
+        view.payload_mut()[0] = 9;
+        assert_eq!(buf[8], 1);
+        assert_eq!(buf[20], 9);
+    }
+
+    #[test]
+    fn padding_after_end_of_list() {
+        // An IHL of 7 with the option list ended by its first byte
+        let mut bytes = vec![0x47, 0x00, 0x00, 0x1e, 0, 0, 0, 0, 0x40, 0x06, 0, 0, 10, 0, 0, 1, 10, 0, 0, 2];
+        bytes.extend_from_slice(&[0; 8]);
+        bytes.extend_from_slice(b"ab");
+
+        let (layer, len) = Ipv4Layer::parse(&bytes).unwrap();
+        assert_eq!(len, 28);
+        assert_eq!(layer.options, [Ipv4Option::EndOfList]);
+        assert_eq!(layer.padding, [0; 7]);
+        assert_eq!(layer.header_len(), 28);
+
+        let mut written = Vec::new();
+        layer.write(&mut written);
+        assert_eq!(written, bytes[..28]);
+        assert_eq!(layer.get_field("ip.options"), Some(FieldValue::Bytes(vec![0; 8])));
+    }
+
+    #[test]
+    fn options_too_long() {
+        let layer = Ipv4Layer { options: vec![Ipv4Option::NoOperation; 41], ..Ipv4Layer::default() };
+        assert!(!layer.options_fit());
+
+        // The IHL cannot describe the header, so it is not derived
+        let mut packet = Packet::from(layer);
+        packet.finalize();
+        assert_eq!(packet.get::<Ipv4Layer>().unwrap().ihl, None);
+
+        let too_long = "01".repeat(41);
+        assert_eq!(Ipv4Layer::default().set_field("ip.options", &too_long), Err(FieldError::InvalidValue(too_long)));
+    }
+
+    #[test]
+    fn reflect() {
+        let mut layer = Ipv4Layer::default();
+        assert_eq!(layer.get_field("ip.chksum"), Some(FieldValue::Unset));
+        assert_eq!(layer.get_field("ip.options"), None);
+
+        layer.set_field("ip.src", "10.0.0.1").unwrap();
+        layer.set_field("ip.proto", "Udp").unwrap();
+        layer.set_field("ip.flags.df", "1").unwrap();
+        layer.set_field("ip.frag", "0x1fff").unwrap();
+        layer.set_field("ip.options", "94040000").unwrap();
+        assert_eq!(layer.src, Ipv4Addr::new(10, 0, 0, 1));
+        assert_eq!(layer.get_field("ip.proto"), Some(IpProtocol::Udp.field_value()));
+        assert!(layer.flags.dont_fragment);
+        assert_eq!(layer.options, [Ipv4Option::router_alert()]);
+        assert_eq!(layer.get_field("ip.options"), Some(FieldValue::Bytes(vec![0x94, 0x04, 0x00, 0x00])));
+
+        let fields = layer.fields();
+        let frag = fields.iter().find(|field| field.name == "ip.frag").unwrap();
+        assert_eq!((frag.bit_offset, frag.bit_width), (51, 13));
+
+        assert_eq!(layer.set_field("ip.dscp", "64"), Err(FieldError::InvalidValue("64".to_string())));
+        assert_eq!(layer.set_field("ip.frag", "0x2000"), Err(FieldError::InvalidValue("0x2000".to_string())));
+        assert_eq!(layer.set_field("ip.options", "07"), Err(FieldError::InvalidValue("07".to_string())));
+        assert_eq!(layer.set_field("ip.tos", "1"), Err(FieldError::UnknownField("ip.tos".to_string())));
+    }
+}
@@ -0,0 +1,320 @@
+use super::*;
+
+use crate::Ipv4Addr;
+
+registry_enum! {
+    /// The format of the data recorded by an IPv4 timestamp option (RFC 791).
+    pub enum TimestampFlag: u8 {
+        /// Only the timestamps are recorded.
+        TimestampsOnly = 0,
+        /// Each timestamp is preceded by the address of the recording router.
+        WithAddresses = 1,
+        /// Only the routers whose addresses are prespecified record their timestamps.
+        Prespecified = 3,
+    }
+}
+
+/// A single entry of an IPv4 timestamp option.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TimestampEntry {
+    /// The address of the recording router, which is absent if the flag is [`TimestampFlag::TimestampsOnly`].
+    pub address: Option<Ipv4Addr>,
+    /// Milliseconds since midnight UT.
+    pub timestamp: u32,
+}
+
+/// The data of the Record Route, Loose Source Route and Strict Source Route options.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RouteOption {
+    /// The one-based offset of the next free slot within the option.
+    pub pointer: u8,
+    /// The slots of the route, including those which were not filled yet.
+    pub route: Vec<Ipv4Addr>,
+}
+
+/// The data of the Internet Timestamp option.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TimestampOption {
+    /// The one-based offset of the next free entry within the option.
+    pub pointer: u8,
+    /// The 4-bit number of routers which could not record a timestamp due to lack of space.
+    pub overflow: u8,
+    pub flag: TimestampFlag,
+    pub entries: Vec<TimestampEntry>,
+}
+
+/// An option in the header of an IPv4 packet.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Ipv4Option {
+    /// Marks the end of the options. The remaining option bytes are padding.
+    EndOfList,
+    NoOperation,
+    RecordRoute(RouteOption),
+    Timestamp(TimestampOption),
+    LooseSourceRoute(RouteOption),
+    StrictSourceRoute(RouteOption),
+    /// Requests routers to examine the packet more closely (RFC 2113). The value 0 is used for IGMP and MLD.
+    RouterAlert(u16),
+    /// An option which is either not supported or whose contents do not match its type.
+    Unknown { kind: u8, data: Vec<u8> },
+}
+
+impl Ipv4Option {
+    pub const END_OF_LIST: u8 = 0;
+    pub const NO_OPERATION: u8 = 1;
+    pub const RECORD_ROUTE: u8 = 7;
+    pub const TIMESTAMP: u8 = 68;
+    pub const LOOSE_SOURCE_ROUTE: u8 = 131;
+    pub const STRICT_SOURCE_ROUTE: u8 = 137;
+    pub const ROUTER_ALERT: u8 = 148;
+
+    /// Constructs an empty Record Route option with the specified number of slots.
+    pub fn record_route(slots: usize) -> Self {
+        Ipv4Option::RecordRoute(RouteOption {
+            pointer: 4,
+            route: vec![Ipv4Addr::unspecified(); slots],
+        })
+    }
+
+    /// Constructs a Router Alert option which requests every router to examine the packet.
+    pub fn router_alert() -> Self {
+        Ipv4Option::RouterAlert(0)
+    }
+
+    /// Returns the option type, which includes the copied flag and the option class.
+    pub fn kind(&self) -> u8 {
+        match self {
+            Ipv4Option::EndOfList => Self::END_OF_LIST,
+            Ipv4Option::NoOperation => Self::NO_OPERATION,
+            Ipv4Option::RecordRoute(_) => Self::RECORD_ROUTE,
+            Ipv4Option::Timestamp(_) => Self::TIMESTAMP,
+            Ipv4Option::LooseSourceRoute(_) => Self::LOOSE_SOURCE_ROUTE,
+            Ipv4Option::StrictSourceRoute(_) => Self::STRICT_SOURCE_ROUTE,
+            Ipv4Option::RouterAlert(_) => Self::ROUTER_ALERT,
+            Ipv4Option::Unknown { kind, .. } => *kind,
+        }
+    }
+
+    /// Checks if the option is copied into every fragment of the packet.
+    pub fn is_copied(&self) -> bool {
+        self.kind() & 0x80 != 0
+    }
+
+    /// Returns the length of the serialized option in bytes.
+    pub fn encoded_len(&self) -> usize {
+        match self {
+            Ipv4Option::EndOfList | Ipv4Option::NoOperation => 1,
+            Ipv4Option::RecordRoute(option)
+            | Ipv4Option::LooseSourceRoute(option)
+            | Ipv4Option::StrictSourceRoute(option) => 3 + option.route.len() * 4,
+            Ipv4Option::Timestamp(option) => 4 + option.entries.len() * timestamp_entry_len(option.flag),
+            Ipv4Option::RouterAlert(_) => 4,
+            Ipv4Option::Unknown { data, .. } => 2 + data.len(),
+        }
+    }
+
+    /// Appends the serialized option to the buffer.
+    pub fn write(&self, buf: &mut Vec<u8>) {
+        buf.push(self.kind());
+        if let Ipv4Option::EndOfList | Ipv4Option::NoOperation = self {
+            return;
+        }
+
+        buf.push(self.encoded_len() as u8);
+
+        match self {
+            Ipv4Option::EndOfList | Ipv4Option::NoOperation => {}
+            Ipv4Option::RecordRoute(option)
+            | Ipv4Option::LooseSourceRoute(option)
+            | Ipv4Option::StrictSourceRoute(option) => {
+                buf.push(option.pointer);
+                option.route.iter().for_each(|addr| buf.extend_from_slice(&addr.octets()));
+            }
+            Ipv4Option::Timestamp(option) => {
+                buf.push(option.pointer);
+                buf.push((option.overflow << 4) | (option.flag.value() & 0x0f));
+
+                for entry in &option.entries {
+                    if option.flag != TimestampFlag::TimestampsOnly {
+                        buf.extend_from_slice(&entry.address.unwrap_or_default().octets());
+                    }
+                    buf.extend_from_slice(&entry.timestamp.to_be_bytes());
+                }
+            }
+            Ipv4Option::RouterAlert(value) => buf.extend_from_slice(&value.to_be_bytes()),
+            Ipv4Option::Unknown { data, .. } => buf.extend_from_slice(data),
+        }
+    }
+
+    /// Parses an option from the start of the buffer and returns it together with the number of bytes consumed.
+    ///
+    /// Options whose contents do not match their type are returned as [`Ipv4Option::Unknown`], so that they are preserved.
+    /// Fails only if the length of the option is invalid.
+    pub fn parse(bytes: &[u8]) -> Result<(Self, usize), Ipv4Error> {
+        let kind = *bytes.first().ok_or(Ipv4Error::MalformedOption(0))?;
+
+        match kind {
+            Self::END_OF_LIST => return Ok((Ipv4Option::EndOfList, 1)),
+            Self::NO_OPERATION => return Ok((Ipv4Option::NoOperation, 1)),
+            _ => {}
+        }
+
+        let len = *bytes.get(1).ok_or(Ipv4Error::MalformedOption(kind))? as usize;
+        if len < 2 || len > bytes.len() {
+            return Err(Ipv4Error::MalformedOption(kind));
+        }
+
+        let data = &bytes[2..len];
+        let option = match kind {
+            Self::RECORD_ROUTE => parse_route(data).map(Ipv4Option::RecordRoute),
+            Self::LOOSE_SOURCE_ROUTE => parse_route(data).map(Ipv4Option::LooseSourceRoute),
+            Self::STRICT_SOURCE_ROUTE => parse_route(data).map(Ipv4Option::StrictSourceRoute),
+            Self::TIMESTAMP => parse_timestamp(data).map(Ipv4Option::Timestamp),
+            Self::ROUTER_ALERT if data.len() == 2 => Some(Ipv4Option::RouterAlert(u16::from_be_bytes([data[0], data[1]]))),
+            _ => None,
+        };
+
+        let option = option.unwrap_or_else(|| Ipv4Option::Unknown { kind, data: data.to_vec() });
+        Ok((option, len))
+    }
+}
+
+/// Parses the options of a header. Parsing stops after [`Ipv4Option::EndOfList`], since the remaining bytes are padding.
+pub fn parse_ipv4_options(bytes: &[u8]) -> Result<Vec<Ipv4Option>, Ipv4Error> {
+    split_ipv4_options(bytes).map(|(options, _)| options)
+}
+
+/// Parses the options of a header like [`parse_ipv4_options`], and also returns the padding after
+/// [`Ipv4Option::EndOfList`].
+pub fn split_ipv4_options(mut bytes: &[u8]) -> Result<(Vec<Ipv4Option>, &[u8]), Ipv4Error> {
+    let mut options = Vec::new();
+
+    while !bytes.is_empty() {
+        let (option, len) = Ipv4Option::parse(bytes)?;
+        bytes = &bytes[len..];
+
+        let end = option == Ipv4Option::EndOfList;
+        options.push(option);
+        if end {
+            break;
+        }
+    }
+
+    Ok((options, bytes))
+}
+
+fn timestamp_entry_len(flag: TimestampFlag) -> usize {
+    match flag {
+        TimestampFlag::TimestampsOnly => 4,
+        _ => 8,
+    }
+}
+
+fn parse_route(data: &[u8]) -> Option<RouteOption> {
+    let (pointer, route) = data.split_first()?;
+    if route.len() % 4 != 0 {
+        return None;
+    }
+
+    Some(RouteOption {
+        pointer: *pointer,
+        route: route.chunks_exact(4).map(|addr| Ipv4Addr::new(addr[0], addr[1], addr[2], addr[3])).collect(),
+    })
+}
+
+fn parse_timestamp(data: &[u8]) -> Option<TimestampOption> {
+    let [pointer, flags, entries @ ..] = data else {
+        return None;
+    };
+
+    let flag = TimestampFlag::from_value(flags & 0x0f);
+    if let TimestampFlag::Unknown(_) = flag {
+        return None;
+    }
+
+    let entry_len = timestamp_entry_len(flag);
+    if entries.len() % entry_len != 0 {
+        return None;
+    }
+
+    let entries = entries
+        .chunks_exact(entry_len)
+        .map(|entry| {
+            let (address, timestamp) = entry.split_at(entry_len - 4);
+            TimestampEntry {
+                address: (!address.is_empty()).then(|| Ipv4Addr::new(address[0], address[1], address[2], address[3])),
+                timestamp: u32::from_be_bytes([timestamp[0], timestamp[1], timestamp[2], timestamp[3]]),
+            }
+        })
+        .collect();
+
+    Some(TimestampOption {
+        pointer: *pointer,
+        overflow: flags >> 4,
+        flag,
+        entries,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(option: Ipv4Option, expected: &[u8]) {
+        let mut buf = Vec::new();
+        option.write(&mut buf);
+        assert_eq!(buf, expected);
+        assert_eq!(option.encoded_len(), expected.len());
+        assert_eq!(Ipv4Option::parse(&buf), Ok((option, expected.len())));
+    }
+
+    #[test]
+    fn write_and_parse() {
+        round_trip(Ipv4Option::NoOperation, &[0x01]);
+        round_trip(Ipv4Option::router_alert(), &[0x94, 0x04, 0x00, 0x00]);
+        round_trip(
+            Ipv4Option::LooseSourceRoute(RouteOption { pointer: 4, route: vec![Ipv4Addr::new(10, 0, 0, 1)] }),
+            &[0x83, 0x07, 0x04, 10, 0, 0, 1],
+        );
+        round_trip(Ipv4Option::record_route(2), &[0x07, 0x0b, 0x04, 0, 0, 0, 0, 0, 0, 0, 0]);
+        round_trip(
+            Ipv4Option::Timestamp(TimestampOption {
+                pointer: 13,
+                overflow: 2,
+                flag: TimestampFlag::WithAddresses,
+                entries: vec![TimestampEntry { address: Some(Ipv4Addr::new(10, 0, 0, 1)), timestamp: 0x0102_0304 }],
+            }),
+            &[0x44, 0x0c, 0x0d, 0x21, 10, 0, 0, 1, 1, 2, 3, 4],
+        );
+        round_trip(
+            Ipv4Option::Timestamp(TimestampOption {
+                pointer: 5,
+                overflow: 0,
+                flag: TimestampFlag::TimestampsOnly,
+                entries: vec![TimestampEntry { address: None, timestamp: 7 }],
+            }),
+            &[0x44, 0x08, 0x05, 0x00, 0, 0, 0, 7],
+        );
+        round_trip(Ipv4Option::Unknown { kind: 0x88, data: vec![0x12, 0x34] }, &[0x88, 0x04, 0x12, 0x34]);
+    }
+
+    #[test]
+    fn parse_malformed() {
+        // A router alert with the wrong length is preserved as an unknown option
+        assert_eq!(
+            Ipv4Option::parse(&[0x94, 0x03, 0x00]),
+            Ok((Ipv4Option::Unknown { kind: 0x94, data: vec![0x00] }, 3))
+        );
+        assert_eq!(Ipv4Option::parse(&[0x07, 0x0b, 0x04]), Err(Ipv4Error::MalformedOption(0x07)));
+        assert_eq!(Ipv4Option::parse(&[0x07, 0x01]), Err(Ipv4Error::MalformedOption(0x07)));
+        assert_eq!(Ipv4Option::parse(&[0x07]), Err(Ipv4Error::MalformedOption(0x07)));
+    }
+
+    #[test]
+    fn parse_list() {
+        let options = parse_ipv4_options(&[0x01, 0x94, 0x04, 0x00, 0x00, 0x00, 0xff, 0xff]).unwrap();
+        assert_eq!(options, [Ipv4Option::NoOperation, Ipv4Option::router_alert(), Ipv4Option::EndOfList]);
+        assert!(Ipv4Option::LooseSourceRoute(RouteOption { pointer: 4, route: vec![] }).is_copied());
+        assert!(!Ipv4Option::record_route(1).is_copied());
+    }
+}
@@ -0,0 +1,412 @@
+use super::*;
+
+use crate::Ipv6Addr;
+
+/// An option in a Hop-by-Hop or Destination Options extension header.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Ipv6Option {
+    /// A single byte of padding.
+    Pad1,
+    /// Two or more bytes of padding. Holds the padding data, which is normally zero.
+    PadN(Vec<u8>),
+    /// Requests routers to examine the packet more closely (RFC 2711). The value 0 is used for MLD.
+    RouterAlert(u16),
+    /// The length of a jumbogram payload (RFC 2675), in which case the payload length of the IPv6 header is zero.
+    Jumbo(u32),
+    /// An option which is either not supported or whose contents do not match its type.
+    Unknown { kind: u8, data: Vec<u8> },
+}
+
+impl Ipv6Option {
+    pub const PAD1: u8 = 0x00;
+    pub const PADN: u8 = 0x01;
+    pub const ROUTER_ALERT: u8 = 0x05;
+    pub const JUMBO: u8 = 0xc2;
+
+    /// Constructs the Router Alert option which is carried by MLD messages.
+    pub fn router_alert_mld() -> Self {
+        Ipv6Option::RouterAlert(0)
+    }
+
+    /// Returns the option type.
+    pub fn kind(&self) -> u8 {
+        match self {
+            Ipv6Option::Pad1 => Self::PAD1,
+            Ipv6Option::PadN(_) => Self::PADN,
+            Ipv6Option::RouterAlert(_) => Self::ROUTER_ALERT,
+            Ipv6Option::Jumbo(_) => Self::JUMBO,
+            Ipv6Option::Unknown { kind, .. } => *kind,
+        }
+    }
+
+    /// Returns the length of the serialized option in bytes.
+    pub fn encoded_len(&self) -> usize {
+        match self {
+            Ipv6Option::Pad1 => 1,
+            Ipv6Option::PadN(data) | Ipv6Option::Unknown { data, .. } => 2 + data.len(),
+            Ipv6Option::RouterAlert(_) => 4,
+            Ipv6Option::Jumbo(_) => 6,
+        }
+    }
+
+    /// Appends the serialized option to the buffer.
+    pub fn write(&self, buf: &mut Vec<u8>) {
+        buf.push(self.kind());
+
+        match self {
+            Ipv6Option::Pad1 => return,
+            _ => buf.push((self.encoded_len() - 2) as u8),
+        }
+
+        match self {
+            Ipv6Option::Pad1 => {}
+            Ipv6Option::PadN(data) | Ipv6Option::Unknown { data, .. } => buf.extend_from_slice(data),
+            Ipv6Option::RouterAlert(value) => buf.extend_from_slice(&value.to_be_bytes()),
+            Ipv6Option::Jumbo(length) => buf.extend_from_slice(&length.to_be_bytes()),
+        }
+    }
+
+    /// Parses an option from the start of the buffer and returns it together with the number of bytes consumed, or
+    /// `None` if its length exceeds the buffer.
+    pub fn parse(bytes: &[u8]) -> Option<(Self, usize)> {
+        let kind = *bytes.first()?;
+        if kind == Self::PAD1 {
+            return Some((Ipv6Option::Pad1, 1));
+        }
+
+        let len = 2 + *bytes.get(1)? as usize;
+        let data = bytes.get(2..len)?;
+
+        let option = match (kind, data) {
+            (Self::PADN, _) => Ipv6Option::PadN(data.to_vec()),
+            (Self::ROUTER_ALERT, [a, b]) => Ipv6Option::RouterAlert(u16::from_be_bytes([*a, *b])),
+            (Self::JUMBO, [a, b, c, d]) => Ipv6Option::Jumbo(u32::from_be_bytes([*a, *b, *c, *d])),
+            _ => Ipv6Option::Unknown { kind, data: data.to_vec() },
+        };
+
+        Some((option, len))
+    }
+}
+
+/// The Segment Routing Header (RFC 8754), a Routing header of type 4 which carries a list of segments.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct SegmentRoutingHeader {
+    /// The index of the next segment to visit.
+    pub segments_left: u8,
+    pub flags: u8,
+    pub tag: u16,
+    /// The segments in reverse order, i.e. the final destination comes first.
+    pub segments: Vec<Ipv6Addr>,
+    /// The raw optional TLVs which follow the segments.
+    pub tlvs: Vec<u8>,
+}
+
+/// The contents of an IPv6 extension header.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Ipv6Extension {
+    HopByHop(Vec<Ipv6Option>),
+    Destination(Vec<Ipv6Option>),
+    /// A Routing header of a type other than Segment Routing, with its type-specific data.
+    Routing { routing_type: u8, segments_left: u8, data: Vec<u8> },
+    SegmentRouting(SegmentRoutingHeader),
+    Fragment {
+        /// The 13-bit offset of the fragment in 8-byte units.
+        fragment_offset: u16,
+        more_fragments: bool,
+        identification: u32,
+        /// The reserved byte which takes the place of the length field, as it is always 8 bytes long.
+        reserved: u8,
+        /// The two reserved bits between the fragment offset and the M flag.
+        reserved_bits: u8,
+    },
+    /// A header which is not supported, such as AH or Mobility, with the bytes following its length field.
+    Unknown { protocol: IpProtocol, data: Vec<u8> },
+}
+
+/// An IPv6 extension header together with the type of the header which follows it.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Ipv6ExtensionHeader {
+    /// The type of the next header. If left unset, it is derived from the following header or layer.
+    pub next_header: Option<IpProtocol>,
+    pub extension: Ipv6Extension,
+}
+
+impl Ipv6ExtensionHeader {
+    /// The routing type of the Segment Routing Header.
+    pub const SEGMENT_ROUTING: u8 = 4;
+
+    /// Constructs an extension header whose next header is left unset.
+    pub fn new(extension: Ipv6Extension) -> Self {
+        Self {
+            next_header: None,
+            extension,
+        }
+    }
+
+    /// Checks if the protocol number identifies an extension header which can be decoded as part of the chain.
+    pub fn is_extension(protocol: IpProtocol) -> bool {
+        matches!(
+            protocol,
+            IpProtocol::HopByHop
+                | IpProtocol::IPv6Route
+                | IpProtocol::IPv6Frag
+                | IpProtocol::IPv6Opts
+                | IpProtocol::Ah
+                | IpProtocol::MobilityHeader
+                | IpProtocol::Hip
+                | IpProtocol::Shim6
+        )
+    }
+
+    /// Returns the protocol number which identifies this header.
+    pub fn protocol(&self) -> IpProtocol {
+        match &self.extension {
+            Ipv6Extension::HopByHop(_) => IpProtocol::HopByHop,
+            Ipv6Extension::Destination(_) => IpProtocol::IPv6Opts,
+            Ipv6Extension::Routing { .. } | Ipv6Extension::SegmentRouting(_) => IpProtocol::IPv6Route,
+            Ipv6Extension::Fragment { .. } => IpProtocol::IPv6Frag,
+            Ipv6Extension::Unknown { protocol, .. } => *protocol,
+        }
+    }
+
+    /// Returns the length of the serialized header in bytes, including the padding to its alignment.
+    pub fn encoded_len(&self) -> usize {
+        match &self.extension {
+            Ipv6Extension::HopByHop(options) | Ipv6Extension::Destination(options) => {
+                (2 + options.iter().map(Ipv6Option::encoded_len).sum::<usize>()).next_multiple_of(8)
+            }
+            Ipv6Extension::Routing { data, .. } => (4 + data.len()).next_multiple_of(8),
+            Ipv6Extension::SegmentRouting(srh) => (8 + srh.segments.len() * 16 + srh.tlvs.len()).next_multiple_of(8),
+            Ipv6Extension::Fragment { .. } => 8,
+            // The length of the Authentication Header is specified in 4-byte units
+            Ipv6Extension::Unknown { protocol: IpProtocol::Ah, data } => (2 + data.len()).next_multiple_of(4),
+            Ipv6Extension::Unknown { data, .. } => (2 + data.len()).next_multiple_of(8),
+        }
+    }
+
+    /// Appends the serialized header to the buffer.
+    pub fn write(&self, buf: &mut Vec<u8>) {
+        let start = buf.len();
+        let len = self.encoded_len();
+
+        buf.push(self.next_header.map_or(0, IpProtocol::value));
+        buf.push(match (&self.extension, self.protocol()) {
+            (Ipv6Extension::Fragment { reserved, .. }, _) => *reserved,
+            (_, IpProtocol::Ah) => (len / 4 - 2) as u8,
+            _ => (len / 8 - 1) as u8,
+        });
+
+        match &self.extension {
+            Ipv6Extension::HopByHop(options) | Ipv6Extension::Destination(options) => {
+                options.iter().for_each(|option| option.write(buf));
+
+                // The options are padded to the length of the header with Pad1 or PadN
+                match start + len - buf.len() {
+                    0 => {}
+                    1 => Ipv6Option::Pad1.write(buf),
+                    padding => Ipv6Option::PadN(vec![0; padding - 2]).write(buf),
+                }
+            }
+            Ipv6Extension::Routing { routing_type, segments_left, data } => {
+                buf.extend_from_slice(&[*routing_type, *segments_left]);
+                buf.extend_from_slice(data);
+            }
+            Ipv6Extension::SegmentRouting(srh) => {
+                buf.extend_from_slice(&[Self::SEGMENT_ROUTING, srh.segments_left]);
+                buf.push(srh.segments.len().saturating_sub(1) as u8);
+                buf.push(srh.flags);
+                buf.extend_from_slice(&srh.tag.to_be_bytes());
+                srh.segments.iter().for_each(|segment| buf.extend_from_slice(&segment.octets()));
+                buf.extend_from_slice(&srh.tlvs);
+            }
+            Ipv6Extension::Fragment { fragment_offset, more_fragments, identification, reserved_bits, .. } => {
+                let offset = (fragment_offset & 0x1fff) << 3 | (*reserved_bits as u16 & 0b11) << 1 | *more_fragments as u16;
+                buf.extend_from_slice(&offset.to_be_bytes());
+                buf.extend_from_slice(&identification.to_be_bytes());
+            }
+            Ipv6Extension::Unknown { data, .. } => buf.extend_from_slice(data),
+        }
+
+        buf.resize(start + len, 0);
+    }
+
+    /// Parses an extension header of the specified type from the start of the buffer and returns it together with the
+    /// number of bytes consumed.
+    pub fn parse(protocol: IpProtocol, bytes: &[u8]) -> Result<(Self, usize), Ipv6Error> {
+        let malformed = Ipv6Error::MalformedExtensionHeader(protocol.value());
+        let [next_header, length, ..] = *bytes else {
+            return Err(malformed);
+        };
+
+        let len = match protocol {
+            // The Fragment header has a fixed length and a reserved byte in place of the length field
+            IpProtocol::IPv6Frag => 8,
+            IpProtocol::Ah => (length as usize + 2) * 4,
+            _ => (length as usize + 1) * 8,
+        };
+        let data = bytes.get(2..len).ok_or(malformed.clone())?;
+
+        let extension = match protocol {
+            IpProtocol::HopByHop => Ipv6Extension::HopByHop(parse_options(data).ok_or(malformed)?),
+            IpProtocol::IPv6Opts => Ipv6Extension::Destination(parse_options(data).ok_or(malformed)?),
+            IpProtocol::IPv6Route => parse_routing(data),
+            IpProtocol::IPv6Frag => {
+                let offset = u16::from_be_bytes([data[0], data[1]]);
+                Ipv6Extension::Fragment {
+                    fragment_offset: offset >> 3,
+                    more_fragments: offset & 1 != 0,
+                    identification: u32::from_be_bytes([data[2], data[3], data[4], data[5]]),
+                    reserved: length,
+                    reserved_bits: (offset >> 1 & 0b11) as u8,
+                }
+            }
+            protocol => Ipv6Extension::Unknown { protocol, data: data.to_vec() },
+        };
+
+        let header = Self {
+            next_header: Some(IpProtocol::from_value(next_header)),
+            extension,
+        };
+        Ok((header, len))
+    }
+}
+
+/// Parses the options of a Hop-by-Hop or Destination Options header.
+fn parse_options(mut bytes: &[u8]) -> Option<Vec<Ipv6Option>> {
+    let mut options = Vec::new();
+
+    while !bytes.is_empty() {
+        let (option, len) = Ipv6Option::parse(bytes)?;
+        options.push(option);
+        bytes = &bytes[len..];
+    }
+
+    Some(options)
+}
+
+/// Parses the data of a Routing header, which is decoded as a Segment Routing Header if its type and length match.
+fn parse_routing(data: &[u8]) -> Ipv6Extension {
+    let (routing_type, segments_left) = (data[0], data[1]);
+    let segment_count = data[2] as usize + 1;
+
+    if routing_type != Ipv6ExtensionHeader::SEGMENT_ROUTING || data.len() < 6 + segment_count * 16 {
+        return Ipv6Extension::Routing {
+            routing_type,
+            segments_left,
+            data: data[2..].to_vec(),
+        };
+    }
+
+    let (segments, tlvs) = data[6..].split_at(segment_count * 16);
+    Ipv6Extension::SegmentRouting(SegmentRoutingHeader {
+        segments_left,
+        flags: data[3],
+        tag: u16::from_be_bytes([data[4], data[5]]),
+        segments: segments.chunks_exact(16).map(|segment| Ipv6Addr::from(<[u8; 16]>::try_from(segment).unwrap())).collect(),
+        tlvs: tlvs.to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(header: Ipv6ExtensionHeader, expected: &[u8]) {
+        let mut buf = Vec::new();
+        header.write(&mut buf);
+        assert_eq!(buf, expected);
+        assert_eq!(header.encoded_len(), expected.len());
+
+        let (parsed, len) = Ipv6ExtensionHeader::parse(header.protocol(), &buf).unwrap();
+        assert_eq!(len, expected.len());
+
+        let mut written = Vec::new();
+        parsed.write(&mut written);
+        assert_eq!(written, expected);
+    }
+
+    #[test]
+    fn options_headers() {
+        let header = Ipv6ExtensionHeader {
+            next_header: Some(IpProtocol::Icmpv6),
+            extension: Ipv6Extension::HopByHop(vec![Ipv6Option::router_alert_mld()]),
+        };
+        round_trip(header, &[58, 0, 0x05, 0x02, 0x00, 0x00, 0x01, 0x00]);
+
+        let header = Ipv6ExtensionHeader::new(Ipv6Extension::Destination(vec![Ipv6Option::Jumbo(0x0001_0000)]));
+        round_trip(header, &[0, 0, 0xc2, 0x04, 0x00, 0x01, 0x00, 0x00]);
+
+        let (parsed, _) = Ipv6ExtensionHeader::parse(IpProtocol::IPv6Opts, &[6, 0, 0x00, 0x3e, 0x01, 0xaa, 0x01, 0x00]).unwrap();
+        assert_eq!(
+            parsed,
+            Ipv6ExtensionHeader {
+                next_header: Some(IpProtocol::Tcp),
+                extension: Ipv6Extension::Destination(vec![
+                    Ipv6Option::Pad1,
+                    Ipv6Option::Unknown { kind: 0x3e, data: vec![0xaa] },
+                    Ipv6Option::PadN(vec![]),
+                ]),
+            }
+        );
+
+        assert_eq!(
+            Ipv6ExtensionHeader::parse(IpProtocol::HopByHop, &[6, 0, 0x05, 0x08, 0, 0, 0, 0]),
+            Err(Ipv6Error::MalformedExtensionHeader(0))
+        );
+    }
+
+    #[test]
+    fn routing_headers() {
+        let srh = SegmentRoutingHeader {
+            segments_left: 1,
+            tag: 7,
+            segments: vec![Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 2), Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)],
+            ..SegmentRoutingHeader::default()
+        };
+        let header = Ipv6ExtensionHeader::new(Ipv6Extension::SegmentRouting(srh.clone()));
+
+        let mut buf = Vec::new();
+        header.write(&mut buf);
+        assert_eq!(buf.len(), 40);
+        assert_eq!(buf[..8], [0, 4, 4, 1, 1, 0, 0, 7]);
+        assert_eq!(Ipv6ExtensionHeader::parse(IpProtocol::IPv6Route, &buf).unwrap().0.extension, Ipv6Extension::SegmentRouting(srh));
+
+        let header = Ipv6ExtensionHeader::new(Ipv6Extension::Routing { routing_type: 2, segments_left: 1, data: vec![0; 20] });
+        round_trip(header, &[&[0, 2, 2, 1][..], &[0; 20]].concat());
+    }
+
+    #[test]
+    fn fragment_and_unknown_headers() {
+        let header = Ipv6ExtensionHeader {
+            next_header: Some(IpProtocol::Udp),
+            extension: Ipv6Extension::Fragment {
+                fragment_offset: 185,
+                more_fragments: true,
+                identification: 0xdeadbeef,
+                reserved: 0,
+                reserved_bits: 0,
+            },
+        };
+        round_trip(header, &[17, 0, 0x05, 0xc9, 0xde, 0xad, 0xbe, 0xef]);
+
+        // The reserved fields are kept, and a non-zero reserved byte is not mistaken for a length
+        let bytes = [17, 0xff, 0x05, 0xcf, 0xde, 0xad, 0xbe, 0xef, 1, 2, 3, 4];
+        let (header, len) = Ipv6ExtensionHeader::parse(IpProtocol::IPv6Frag, &bytes).unwrap();
+        assert_eq!(len, 8);
+        assert!(matches!(header.extension, Ipv6Extension::Fragment { reserved: 0xff, reserved_bits: 0b11, .. }));
+        round_trip(header, &bytes[..8]);
+
+        let header = Ipv6ExtensionHeader::new(Ipv6Extension::Unknown { protocol: IpProtocol::Ah, data: vec![1; 10] });
+        let mut buf = Vec::new();
+        header.write(&mut buf);
+        assert_eq!(buf[..2], [0, 1]);
+        round_trip(header, &buf);
+
+        assert_eq!(
+            Ipv6ExtensionHeader::parse(IpProtocol::IPv6Frag, &[17, 0, 0, 0]),
+            Err(Ipv6Error::MalformedExtensionHeader(44))
+        );
+        assert!(Ipv6ExtensionHeader::is_extension(IpProtocol::HopByHop));
+        assert!(!Ipv6ExtensionHeader::is_extension(IpProtocol::Tcp));
+    }
+}
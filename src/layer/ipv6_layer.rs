@@ -0,0 +1,619 @@
+use super::*;
+
+use crate::{reflect::*, Checksum, Discriminator, Ipv6Addr};
+
+use thiserror::Error;
+
+/// An IPv6 header (RFC 8200) together with its chain of extension headers.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Ipv6Layer {
+    /// The 4-bit version, which is always 6 for valid packets.
+    pub version: u8,
+    pub traffic_class: u8,
+    /// The 20-bit flow label.
+    pub flow_label: u32,
+    /// The length of the payload including the extension headers. If left unset, it is derived from the payload.
+    pub payload_length: Option<u16>,
+    /// The type of the first extension header or of the payload. If left unset, it is derived from the extension
+    /// headers or the next layer, or set to No Next Header if there is no payload.
+    pub next_header: Option<IpProtocol>,
+    pub hop_limit: u8,
+    pub src: Ipv6Addr,
+    pub dst: Ipv6Addr,
+    /// The chain of extension headers in the order in which they are serialized.
+    pub extension_headers: Vec<Ipv6ExtensionHeader>,
+}
+
+impl Ipv6Layer {
+    /// The length of the fixed header in bytes.
+    pub const MIN_LEN: usize = 40;
+
+    /// The default hop limit of new packets.
+    pub const DEFAULT_HOP_LIMIT: u8 = 64;
+
+    /// Constructs a new IPv6 header between the specified addresses. The derived fields are left unset.
+    pub fn new(src: Ipv6Addr, dst: Ipv6Addr) -> Self {
+        Self {
+            src,
+            dst,
+            ..Self::default()
+        }
+    }
+
+    /// Appends an extension header to the end of the chain.
+    pub fn push_extension(&mut self, extension: Ipv6Extension) {
+        self.extension_headers.push(Ipv6ExtensionHeader::new(extension));
+    }
+
+    /// Returns the length of the extension headers in bytes.
+    pub fn extensions_len(&self) -> usize {
+        self.extension_headers.iter().map(Ipv6ExtensionHeader::encoded_len).sum()
+    }
+
+    /// Returns the type of the payload which follows the last extension header.
+    pub fn upper_protocol(&self) -> Option<IpProtocol> {
+        match self.extension_headers.last() {
+            Some(header) => header.next_header,
+            None => self.next_header,
+        }
+    }
+
+    /// Returns the fragment header, if the packet is a fragment.
+    pub fn fragment(&self) -> Option<&Ipv6Extension> {
+        self.extension_headers
+            .iter()
+            .map(|header| &header.extension)
+            .find(|extension| matches!(extension, Ipv6Extension::Fragment { .. }))
+    }
+
+    /// Returns the destination which is used in the pseudo-header of upper-layer checksums.
+    ///
+    /// If the packet carries a routing header, this is the final destination of the route rather than the next one.
+    pub fn final_destination(&self) -> Ipv6Addr {
+        for header in &self.extension_headers {
+            match &header.extension {
+                Ipv6Extension::SegmentRouting(srh) if srh.segments_left > 0 => {
+                    return srh.segments.first().copied().unwrap_or(self.dst)
+                }
+                // Type 0 and type 2 routing headers carry a reserved word followed by addresses
+                Ipv6Extension::Routing { routing_type: 0 | 2, segments_left: 1.., data } if data.len() >= 20 => {
+                    let last = data[4..].chunks_exact(16).last().unwrap();
+                    return Ipv6Addr::from(<[u8; 16]>::try_from(last).unwrap());
+                }
+                _ => {}
+            }
+        }
+
+        self.dst
+    }
+
+    /// Starts an upper-layer checksum with the pseudo-header (RFC 8200) of a payload of the specified protocol and length.
+    pub fn pseudo_header(&self, protocol: IpProtocol, length: u32) -> Checksum {
+        let mut checksum = Checksum::new();
+        checksum
+            .add_bytes(&self.src.octets())
+            .add_bytes(&self.final_destination().octets())
+            .add_u32(length)
+            .add_u32(protocol.value() as u32);
+        checksum
+    }
+
+    /// Returns the Jumbo Payload length from the Hop-by-Hop options, if the packet is a jumbogram.
+    fn jumbo_length(&self) -> Option<u32> {
+        let Some(Ipv6Extension::HopByHop(options)) = self.extension_headers.first().map(|header| &header.extension) else {
+            return None;
+        };
+
+        options.iter().find_map(|option| match option {
+            Ipv6Option::Jumbo(length) => Some(*length),
+            _ => None,
+        })
+    }
+}
+
+impl Default for Ipv6Layer {
+    fn default() -> Self {
+        Self {
+            version: 6,
+            traffic_class: 0,
+            flow_label: 0,
+            payload_length: None,
+            next_header: None,
+            hop_limit: Self::DEFAULT_HOP_LIMIT,
+            src: Ipv6Addr::unspecified(),
+            dst: Ipv6Addr::unspecified(),
+            extension_headers: Vec::new(),
+        }
+    }
+}
+
+impl Layer for Ipv6Layer {
+    const NAME: &'static str = "IPv6";
+    const TYPE: LayerType = LayerType::Ipv6Layer;
+    const OSI_LEVEL: OsiLevel = OsiLevel::Network;
+}
+
+impl DynLayer for Ipv6Layer {
+    fn header_len(&self) -> usize {
+        Self::MIN_LEN + self.extensions_len()
+    }
+
+    fn payload_len(&self) -> Option<usize> {
+        // A zero payload length is used by jumbograms and by packets captured before segmentation offload
+        let payload_length = match self.payload_length? {
+            0 => self.jumbo_length()? as usize,
+            length => length as usize,
+        };
+
+        payload_length.checked_sub(self.extensions_len())
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        let first_word = (self.version as u32) << 28 | (self.traffic_class as u32) << 20 | (self.flow_label & 0xf_ffff);
+
+        buf.extend_from_slice(&first_word.to_be_bytes());
+        buf.extend_from_slice(&self.payload_length.unwrap_or(0).to_be_bytes());
+        buf.push(self.next_header.map_or(0, IpProtocol::value));
+        buf.push(self.hop_limit);
+        buf.extend_from_slice(&self.src.octets());
+        buf.extend_from_slice(&self.dst.octets());
+
+        self.extension_headers.iter().for_each(|header| header.write(buf));
+    }
+
+    fn finalize(&mut self, ctx: &FinalizeContext) {
+        let upper = ctx
+            .next()
+            .and_then(|next| IpProtocol::of_layer(next.layer_type()))
+            .or_else(|| ctx.payload.is_empty().then_some(IpProtocol::IPv6NoNxt));
+
+        // Each header in the chain is followed by the next extension header, and the last one by the payload
+        let mut following = upper;
+        for header in self.extension_headers.iter_mut().rev() {
+            header.next_header = header.next_header.or(following);
+            following = Some(header.protocol());
+        }
+
+        self.next_header = self.next_header.or(following);
+
+        if self.payload_length.is_none() {
+            let length = self.extensions_len() + ctx.unpadded_payload().len();
+            self.payload_length = Some(u16::try_from(length).unwrap_or(0));
+        }
+    }
+
+    fn next_discriminators(&self) -> Vec<Discriminator> {
+        // Only the first fragment starts with the header of the next layer
+        if let Some(Ipv6Extension::Fragment { fragment_offset: 1.., .. }) = self.fragment() {
+            return Vec::new();
+        }
+
+        match self.upper_protocol() {
+            Some(IpProtocol::IPv6NoNxt) | None => Vec::new(),
+            Some(protocol) => vec![Discriminator::IpProtocol(protocol.value())],
+        }
+    }
+
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        let view = Ipv6View::new(bytes)?;
+        let mut layer = view.to_layer();
+        let mut offset = Ipv6Layer::MIN_LEN;
+        let mut next_header = view.next_header();
+
+        while Ipv6ExtensionHeader::is_extension(next_header) {
+            let (header, len) = Ipv6ExtensionHeader::parse(next_header, &bytes[offset..])?;
+            offset += len;
+            next_header = header.next_header.unwrap_or(IpProtocol::IPv6NoNxt);
+
+            // The headers following a non-first fragment are a part of the fragmented data
+            let last = matches!(header.extension, Ipv6Extension::Fragment { fragment_offset: 1.., .. });
+            layer.extension_headers.push(header);
+            if last {
+                break;
+            }
+        }
+
+        Ok((layer, offset))
+    }
+
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+}
+
+impl Reflect for Ipv6Layer {
+    fn fields(&self) -> Vec<Field> {
+        let mut fields = vec![
+            Field::new("ipv6.version", FieldKind::UInt, 0, 4, FieldValue::UInt(self.version as u64)),
+            Field::new("ipv6.tc", FieldKind::UInt, 4, 8, FieldValue::UInt(self.traffic_class as u64)),
+            Field::new("ipv6.fl", FieldKind::UInt, 12, 20, FieldValue::UInt(self.flow_label as u64)),
+            Field::new("ipv6.plen", FieldKind::UInt, 32, 16, self.payload_length.map_or(FieldValue::Unset, |len| FieldValue::UInt(len as u64)))
+                .computed(),
+            Field::new("ipv6.nh", FieldKind::Enum, 48, 8, self.next_header.map_or(FieldValue::Unset, IpProtocol::field_value))
+                .computed(),
+            Field::new("ipv6.hlim", FieldKind::UInt, 56, 8, FieldValue::UInt(self.hop_limit as u64)),
+            Field::new("ipv6.src", FieldKind::Ipv6, 64, 128, FieldValue::Ipv6(self.src)),
+            Field::new("ipv6.dst", FieldKind::Ipv6, 192, 128, FieldValue::Ipv6(self.dst)),
+        ];
+
+        let mut offset = Self::MIN_LEN * 8;
+        for (i, header) in self.extension_headers.iter().enumerate() {
+            let mut bytes = Vec::new();
+            header.write(&mut bytes);
+
+            fields.push(Field::new(format!("ipv6.ext[{}]", i), FieldKind::Bytes, offset, bytes.len() * 8, FieldValue::Bytes(bytes)));
+            offset += header.encoded_len() * 8;
+        }
+
+        fields
+    }
+
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        let invalid = || FieldError::InvalidValue(value.to_string());
+        let auto = value.trim() == "auto";
+
+        match path {
+            "ipv6.version" => self.version = parse_uint_as::<u8>(value).ok().filter(|version| *version <= 0x0f).ok_or_else(invalid)?,
+            "ipv6.tc" => self.traffic_class = parse_uint_as(value)?,
+            "ipv6.fl" => self.flow_label = parse_uint_as::<u32>(value).ok().filter(|label| *label <= 0xf_ffff).ok_or_else(invalid)?,
+            "ipv6.plen" if auto => self.payload_length = None,
+            "ipv6.plen" => self.payload_length = Some(parse_uint_as(value)?),
+            "ipv6.nh" if auto => self.next_header = None,
+            "ipv6.nh" => self.next_header = Some(IpProtocol::parse_field(value)?),
+            "ipv6.hlim" => self.hop_limit = parse_uint_as(value)?,
+            "ipv6.src" => self.src = parse_str(value)?,
+            "ipv6.dst" => self.dst = parse_str(value)?,
+            _ => {
+                // Extension headers are replaced by their serialized form, keeping their type
+                let header = path
+                    .strip_prefix("ipv6.ext[")
+                    .and_then(|rest| rest.strip_suffix(']'))
+                    .and_then(|index| index.parse::<usize>().ok())
+                    .and_then(|index| self.extension_headers.get_mut(index))
+                    .ok_or_else(|| FieldError::UnknownField(path.to_string()))?;
+
+                let bytes = hex::decode(value.trim()).map_err(|_| invalid())?;
+                match Ipv6ExtensionHeader::parse(header.protocol(), &bytes) {
+                    Ok((parsed, len)) if len == bytes.len() => *header = parsed,
+                    _ => return Err(invalid()),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A borrowed, zero-copy view of the fixed IPv6 header at the start of a byte slice.
+///
+/// The version and the length are validated once on construction and fields are decoded only when accessed. Extension
+/// headers are not decoded and belong to the payload.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Ipv6View<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Ipv6View<'a> {
+    /// Constructs a view of the IPv6 header at the start of the slice.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, LayerError> {
+        if bytes.len() < Ipv6Layer::MIN_LEN {
+            return Err(LayerError::BufferTooShort(Ipv6Layer::NAME));
+        }
+
+        let view = Self { bytes };
+        if view.version() != 6 {
+            return Err(Ipv6Error::InvalidVersion(view.version()).into());
+        }
+
+        Ok(view)
+    }
+
+    fn first_word(&self) -> u32 {
+        u32::from_be_bytes([self.bytes[0], self.bytes[1], self.bytes[2], self.bytes[3]])
+    }
+
+    /// Returns the version.
+    pub fn version(&self) -> u8 {
+        self.bytes[0] >> 4
+    }
+
+    /// Returns the traffic class.
+    pub fn traffic_class(&self) -> u8 {
+        (self.first_word() >> 20) as u8
+    }
+
+    /// Returns the flow label.
+    pub fn flow_label(&self) -> u32 {
+        self.first_word() & 0xf_ffff
+    }
+
+    /// Returns the length of the payload including the extension headers.
+    pub fn payload_length(&self) -> u16 {
+        u16::from_be_bytes([self.bytes[4], self.bytes[5]])
+    }
+
+    /// Returns the type of the first extension header or of the payload.
+    pub fn next_header(&self) -> IpProtocol {
+        IpProtocol::from_value(self.bytes[6])
+    }
+
+    /// Returns the hop limit.
+    pub fn hop_limit(&self) -> u8 {
+        self.bytes[7]
+    }
+
+    /// Returns the source address.
+    pub fn src(&self) -> Ipv6Addr {
+        Ipv6Addr::from(<[u8; 16]>::try_from(&self.bytes[8..24]).unwrap())
+    }
+
+    /// Returns the destination address.
+    pub fn dst(&self) -> Ipv6Addr {
+        Ipv6Addr::from(<[u8; 16]>::try_from(&self.bytes[24..40]).unwrap())
+    }
+
+    /// Returns the bytes following the fixed header, including the extension headers.
+    pub fn payload(&self) -> &'a [u8] {
+        &self.bytes[Ipv6Layer::MIN_LEN..]
+    }
+
+    /// Converts the view into an owned IPv6 layer without extension headers.
+    pub fn to_layer(&self) -> Ipv6Layer {
+        Ipv6Layer {
+            version: self.version(),
+            traffic_class: self.traffic_class(),
+            flow_label: self.flow_label(),
+            payload_length: Some(self.payload_length()),
+            next_header: Some(self.next_header()),
+            hop_limit: self.hop_limit(),
+            src: self.src(),
+            dst: self.dst(),
+            extension_headers: Vec::new(),
+        }
+    }
+}
+
+/// A mutable, zero-copy view of the fixed IPv6 header at the start of a byte slice, which allows fields to be edited in
+/// place.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Ipv6ViewMut<'a> {
+    bytes: &'a mut [u8],
+}
+
+impl<'a> Ipv6ViewMut<'a> {
+    /// Constructs a mutable view of the IPv6 header at the start of the slice.
+    pub fn new(bytes: &'a mut [u8]) -> Result<Self, LayerError> {
+        Ipv6View::new(bytes)?;
+        Ok(Self { bytes })
+    }
+
+    /// Returns a read-only view of the header.
+    pub fn as_view(&self) -> Ipv6View<'_> {
+        Ipv6View { bytes: self.bytes }
+    }
+
+    /// Sets the traffic class.
+    pub fn set_traffic_class(&mut self, traffic_class: u8) {
+        let word = self.as_view().first_word() & !(0xff << 20) | (traffic_class as u32) << 20;
+        self.bytes[0..4].copy_from_slice(&word.to_be_bytes());
+    }
+
+    /// Sets the flow label. Only the lower 20 bits are used.
+    pub fn set_flow_label(&mut self, flow_label: u32) {
+        let word = self.as_view().first_word() & !0xf_ffff | (flow_label & 0xf_ffff);
+        self.bytes[0..4].copy_from_slice(&word.to_be_bytes());
+    }
+
+    /// Sets the hop limit.
+    pub fn set_hop_limit(&mut self, hop_limit: u8) {
+        self.bytes[7] = hop_limit;
+    }
+
+    /// Sets the source address.
+    pub fn set_src(&mut self, addr: Ipv6Addr) {
+        self.bytes[8..24].copy_from_slice(&addr.octets());
+    }
+
+    /// Sets the destination address.
+    pub fn set_dst(&mut self, addr: Ipv6Addr) {
+        self.bytes[24..40].copy_from_slice(&addr.octets());
+    }
+
+    /// Returns the bytes following the fixed header.
+    pub fn payload_mut(&mut self) -> &mut [u8] {
+        &mut self.bytes[Ipv6Layer::MIN_LEN..]
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum Ipv6Error {
+    #[error("The version {0} is not valid for an IPv6 header.")]
+    InvalidVersion(u8),
+
+    #[error("The IPv6 extension header of type {0} is malformed.")]
+    MalformedExtensionHeader(u8),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Packet;
+
+    fn addr(last: u16) -> Ipv6Addr {
+        Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, last)
+    }
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(Ipv6Layer::NAME, "IPv6");
+        assert_eq!(Ipv6Layer::TYPE, LayerType::Ipv6Layer);
+        assert_eq!(Ipv6Layer::OSI_LEVEL, OsiLevel::Network);
+    }
+
+    #[test]
+    fn write_and_parse() {
+        let mut layer = Ipv6Layer::new(addr(1), addr(2));
+        layer.traffic_class = 0xb8;
+        layer.flow_label = 0x12345;
+        layer.next_header = Some(IpProtocol::Udp);
+        layer.payload_length = Some(8);
+
+        let mut buf = Vec::new();
+        layer.write(&mut buf);
+        assert_eq!(buf.len(), 40);
+        assert_eq!(buf[..8], [0x6b, 0x81, 0x23, 0x45, 0x00, 0x08, 17, 64]);
+        assert_eq!(buf[8..24], addr(1).octets());
+
+        assert_eq!(Ipv6Layer::parse(&buf), Ok((layer.clone(), 40)));
+        assert_eq!(layer.next_discriminators(), [Discriminator::IpProtocol(17)]);
+        assert_eq!(layer.payload_len(), Some(8));
+
+        assert_eq!(Ipv6Layer::parse(&buf[..39]), Err(LayerError::BufferTooShort("IPv6")));
+        buf[0] = 0x45;
+        assert_eq!(Ipv6Layer::parse(&buf), Err(LayerError::Ipv6(Ipv6Error::InvalidVersion(4))));
+    }
+
+    #[test]
+    fn extension_chain() {
+        let mut layer = Ipv6Layer::new(addr(1), addr(2));
+        layer.push_extension(Ipv6Extension::HopByHop(vec![Ipv6Option::router_alert_mld()]));
+        layer.push_extension(Ipv6Extension::Destination(vec![Ipv6Option::PadN(vec![0; 4])]));
+        layer.push_extension(Ipv6Extension::Fragment {
+            fragment_offset: 0,
+            more_fragments: true,
+            identification: 1,
+            reserved: 0,
+            reserved_bits: 0,
+        });
+
+        layer.extension_headers[2].next_header = Some(IpProtocol::IPv6NoNxt);
+
+        let bytes = (Packet::from(layer) / b"data").to_bytes();
+        assert_eq!(bytes.len(), 40 + 24 + 4);
+        assert_eq!(bytes[4..7], [0x00, 28, 0]);
+        assert_eq!(bytes[40], IpProtocol::IPv6Opts.value());
+        assert_eq!(bytes[48], IpProtocol::IPv6Frag.value());
+        assert_eq!(bytes[56], IpProtocol::IPv6NoNxt.value());
+
+        let (parsed, len) = Ipv6Layer::parse(&bytes).unwrap();
+        assert_eq!(len, 64);
+        assert_eq!(parsed.extension_headers.len(), 3);
+        assert_eq!(parsed.upper_protocol(), Some(IpProtocol::IPv6NoNxt));
+        assert_eq!(parsed.next_discriminators(), []);
+        assert_eq!(parsed.payload_len(), Some(4));
+
+        let mut written = Vec::new();
+        parsed.write(&mut written);
+        assert_eq!(written, bytes[..64]);
+
+        // The reserved byte of the fragment header is not a length, so it does not swallow the payload
+        let mut reserved = bytes.clone();
+        reserved[57] = 0xff;
+        let (parsed, len) = Ipv6Layer::parse(&reserved).unwrap();
+        assert_eq!((len, parsed.payload_len()), (64, Some(4)));
+
+        let mut written = Vec::new();
+        parsed.write(&mut written);
+        assert_eq!(written, reserved[..64]);
+
+        // The chain is not decoded past a fragment which does not start the packet
+        let mut fragment = bytes.clone();
+        fragment[58..60].copy_from_slice(&[0x00, 0x08]);
+        fragment[56] = IpProtocol::HopByHop.value();
+        let (parsed, len) = Ipv6Layer::parse(&fragment).unwrap();
+        assert_eq!((parsed.extension_headers.len(), len), (3, 64));
+
+        assert_eq!(
+            Ipv6Layer::parse(&bytes[..60]),
+            Err(LayerError::Ipv6(Ipv6Error::MalformedExtensionHeader(IpProtocol::IPv6Frag.value())))
+        );
+    }
+
+    #[test]
+    fn finalize() {
+        let packet = Ipv6Layer::new(addr(1), addr(2)) / Ipv6Layer::new(addr(3), addr(4));
+        let bytes = packet.to_bytes();
+        assert_eq!(bytes[6], IpProtocol::IPv6.value());
+        assert_eq!(bytes[4..6], [0x00, 40]);
+        assert_eq!(bytes[46], IpProtocol::IPv6NoNxt.value());
+
+        // A payload of an unknown protocol is not announced as No Next Header
+        let mut packet = Ipv6Layer::new(addr(1), addr(2)) / b"data";
+        packet.finalize();
+        assert_eq!(packet.get::<Ipv6Layer>().unwrap().next_header, None);
+        assert_eq!(packet.to_bytes()[6], 0);
+
+        let mut layer = Ipv6Layer::new(addr(1), addr(2));
+        layer.push_extension(Ipv6Extension::HopByHop(vec![Ipv6Option::Jumbo(70_000)]));
+        layer.payload_length = Some(0);
+        assert_eq!(layer.payload_len(), Some(70_000 - 8));
+    }
+
+    #[test]
+    fn final_destination() {
+        let mut layer = Ipv6Layer::new(addr(1), addr(2));
+        assert_eq!(layer.final_destination(), addr(2));
+
+        layer.push_extension(Ipv6Extension::SegmentRouting(SegmentRoutingHeader {
+            segments_left: 1,
+            segments: vec![addr(3), addr(2)],
+            ..SegmentRoutingHeader::default()
+        }));
+        assert_eq!(layer.final_destination(), addr(3));
+
+        layer.extension_headers[0].extension = Ipv6Extension::Routing {
+            routing_type: 2,
+            segments_left: 1,
+            data: [&[0; 4][..], &addr(5).octets()].concat(),
+        };
+        assert_eq!(layer.final_destination(), addr(5));
+    }
+
+    #[test]
+    fn views() {
+        let mut buf = Vec::new();
+        Ipv6Layer { next_header: Some(IpProtocol::IPv6NoNxt), ..Ipv6Layer::new(addr(1), addr(2)) }.write(&mut buf);
+        buf.extend_from_slice(&[1, 2, 3]);
+
+        let mut view = Ipv6ViewMut::new(&mut buf).unwrap();
+        view.set_traffic_class(0xff);
+        view.set_flow_label(0xabcde);
+        view.set_hop_limit(1);
+        view.set_dst(addr(9));
+        view.payload_mut()[0] = 9;
+
+        let view = Ipv6View::new(&buf).unwrap();
+        assert_eq!((view.version(), view.traffic_class(), view.flow_label()), (6, 0xff, 0xabcde));
+        assert_eq!(view.hop_limit(), 1);
+        assert_eq!(view.src(), addr(1));
+        assert_eq!(view.dst(), addr(9));
+        assert_eq!(view.payload(), [9, 2, 3]);
+        assert_eq!(view.to_layer().next_header, Some(IpProtocol::IPv6NoNxt));
+    }
+
+    #[test]
+    fn reflect() {
+        let mut layer = Ipv6Layer::default();
+        layer.push_extension(Ipv6Extension::HopByHop(vec![]));
+
+        layer.set_field("ipv6.src", "2001:db8::1").unwrap();
+        layer.set_field("ipv6.fl", "0xfffff").unwrap();
+        layer.set_field("ipv6.nh", "HopByHop").unwrap();
+        layer.set_field("ipv6.ext[0]", "3a00050200000100").unwrap();
+        assert_eq!(layer.src, addr(1));
+        assert_eq!(layer.get_field("ipv6.src").unwrap().to_string(), "2001:db8::1");
+        assert_eq!(layer.extension_headers[0].next_header, Some(IpProtocol::Icmpv6));
+
+        let fields = layer.fields();
+        let ext = fields.iter().find(|field| field.name == "ipv6.ext[0]").unwrap();
+        assert_eq!((ext.bit_offset, ext.bit_width), (320, 64));
+
+        assert_eq!(layer.set_field("ipv6.fl", "0x100000"), Err(FieldError::InvalidValue("0x100000".to_string())));
+        assert_eq!(layer.set_field("ipv6.ext[0]", "3a01"), Err(FieldError::InvalidValue("3a01".to_string())));
+        assert_eq!(layer.set_field("ipv6.ext[1]", "00"), Err(FieldError::UnknownField("ipv6.ext[1]".to_string())));
+    }
+}
@@ -0,0 +1,225 @@
+use super::*;
+
+use crate::{reflect::*, Discriminator};
+
+use thiserror::Error;
+
+registry_enum! {
+    /// The IEEE 802.2 Service Access Points which identify the protocol carried in an LLC frame.
+    pub enum Sap: u8 {
+        Null = 0x00,
+        LlcManagement = 0x02,
+        SnaPathControl = 0x04,
+        IPv4 = 0x06,
+        ProWayNm = 0x0e,
+        /// Spanning Tree Protocol (IEEE 802.1D).
+        Stp = 0x42,
+        Iso8208 = 0x7e,
+        Xns = 0x80,
+        Nestar = 0x86,
+        ProWayLan = 0x8e,
+        Arp = 0x98,
+        /// Subnetwork Access Protocol, which is followed by a [`SnapLayer`].
+        Snap = 0xaa,
+        BanyanVines = 0xbc,
+        Ipx = 0xe0,
+        NetBios = 0xf0,
+        LanManager = 0xf4,
+        /// ISO network layer protocols, such as IS-IS and CLNP.
+        Iso = 0xfe,
+        Global = 0xff,
+    }
+}
+
+/// IEEE 802.2 Logical Link Control header, which follows an Ethernet header whose Length/Type field holds a length.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LlcLayer {
+    /// The destination service access point.
+    pub dsap: Sap,
+    /// The source service access point.
+    pub ssap: Sap,
+    /// The control field. Unnumbered (U-format) frames carry a single byte, information and supervisory frames two, so
+    /// an unnumbered control value must not exceed `0xff`.
+    pub control: u16,
+}
+
+impl LlcLayer {
+    /// The control value of an Unnumbered Information frame.
+    pub const UI: u16 = 0x03;
+
+    /// Constructs a new LLC header, failing if an unnumbered control value does not fit in a single byte.
+    pub fn new(dsap: Sap, ssap: Sap, control: u16) -> Result<Self, LlcError> {
+        match Self::is_valid_control(control) {
+            true => Ok(Self { dsap, ssap, control }),
+            false => Err(LlcError::InvalidControl(control)),
+        }
+    }
+
+    /// Constructs the header which precedes a [`SnapLayer`], i.e. `AA-AA-03`.
+    pub fn snap() -> Self {
+        Self { dsap: Sap::Snap, ssap: Sap::Snap, control: Self::UI }
+    }
+
+    /// Checks if the control value can be encoded, i.e. it is either a two byte information or supervisory value or
+    /// an unnumbered value no greater than `0xff`.
+    pub fn is_valid_control(control: u16) -> bool {
+        control & 0b11 != 0b11 || control <= 0xff
+    }
+
+    /// Checks if the control field has the unnumbered format, which is a single byte long.
+    pub fn is_unnumbered(&self) -> bool {
+        self.control & 0b11 == 0b11
+    }
+
+    /// Returns the length of the control field in bytes.
+    fn control_len(&self) -> usize {
+        if self.is_unnumbered() { 1 } else { 2 }
+    }
+}
+
+impl Default for LlcLayer {
+    /// Constructs an Unnumbered Information frame between null SAPs.
+    fn default() -> Self {
+        Self { dsap: Sap::Null, ssap: Sap::Null, control: Self::UI }
+    }
+}
+
+impl Layer for LlcLayer {
+    const NAME: &'static str = "LLC";
+    const TYPE: LayerType = LayerType::LlcLayer;
+    const OSI_LEVEL: OsiLevel = OsiLevel::DataLink;
+}
+
+impl DynLayer for LlcLayer {
+    fn header_len(&self) -> usize {
+        2 + self.control_len()
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.push(self.dsap.value());
+        buf.push(self.ssap.value());
+
+        match self.is_unnumbered() {
+            true => buf.push(self.control as u8),
+            false => buf.extend_from_slice(&self.control.to_le_bytes()),
+        }
+    }
+
+    fn next_discriminators(&self) -> Vec<Discriminator> {
+        vec![Discriminator::LlcSap(self.dsap.value())]
+    }
+
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        if bytes.len() < 3 {
+            return Err(LayerError::BufferTooShort(Self::NAME));
+        }
+
+        let mut layer = Self { dsap: Sap::from_value(bytes[0]), ssap: Sap::from_value(bytes[1]), control: bytes[2] as u16 };
+        if !layer.is_unnumbered() {
+            // The control field of information and supervisory frames is transmitted least significant byte first
+            if bytes.len() < 4 {
+                return Err(LayerError::BufferTooShort(Self::NAME));
+            }
+
+            layer.control = u16::from_le_bytes([bytes[2], bytes[3]]);
+        }
+
+        let len = layer.header_len();
+        Ok((layer, len))
+    }
+
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+}
+
+impl Reflect for LlcLayer {
+    fn fields(&self) -> Vec<Field> {
+        vec![
+            Field::new("llc.dsap", FieldKind::Enum, 0, 8, self.dsap.field_value()),
+            Field::new("llc.ssap", FieldKind::Enum, 8, 8, self.ssap.field_value()),
+            Field::new("llc.ctrl", FieldKind::UInt, 16, self.control_len() * 8, FieldValue::UInt(self.control as u64)),
+        ]
+    }
+
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        match path {
+            "llc.dsap" => self.dsap = Sap::parse_field(value)?,
+            "llc.ssap" => self.ssap = Sap::parse_field(value)?,
+            "llc.ctrl" => {
+                let control = parse_uint_as(value)?;
+                if !Self::is_valid_control(control) {
+                    return Err(FieldError::InvalidValue(value.to_string()));
+                }
+
+                self.control = control;
+            }
+            _ => return Err(FieldError::UnknownField(path.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum LlcError {
+    #[error("The unnumbered LLC control value {0:#06x} does not fit in a single byte.")]
+    InvalidControl(u16),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(LlcLayer::NAME, "LLC");
+        assert_eq!(LlcLayer::TYPE, LayerType::LlcLayer);
+        assert_eq!(LlcLayer::OSI_LEVEL, OsiLevel::DataLink);
+    }
+
+    #[test]
+    fn write_and_parse() {
+        let layer = LlcLayer::new(Sap::Stp, Sap::Stp, LlcLayer::UI).unwrap();
+        let mut buf = Vec::new();
+        layer.write(&mut buf);
+
+        assert_eq!(buf, [0x42, 0x42, 0x03]);
+        assert_eq!(LlcLayer::parse(&buf), Ok((layer.clone(), 3)));
+        assert_eq!(layer.next_discriminators(), [Discriminator::LlcSap(0x42)]);
+
+        // Information frames carry a two byte control field
+        let layer = LlcLayer::new(Sap::NetBios, Sap::NetBios, 0x0204).unwrap();
+        let mut buf = Vec::new();
+        layer.write(&mut buf);
+
+        assert_eq!(buf, [0xf0, 0xf0, 0x04, 0x02]);
+        assert_eq!(LlcLayer::parse(&buf), Ok((layer, 4)));
+        assert_eq!(LlcLayer::parse(&buf[..3]), Err(LayerError::BufferTooShort("LLC")));
+        assert_eq!(LlcLayer::parse(&buf[..2]), Err(LayerError::BufferTooShort("LLC")));
+
+        // Unnumbered control values only have a single byte
+        assert_eq!(LlcLayer::new(Sap::Stp, Sap::Stp, 0x0103), Err(LlcError::InvalidControl(0x0103)));
+        assert!(LlcLayer::new(Sap::Stp, Sap::Stp, 0x0104).is_ok());
+    }
+
+    #[test]
+    fn reflect() {
+        let mut layer = LlcLayer::snap();
+        assert_eq!(layer.get_field("llc.dsap"), Some(Sap::Snap.field_value()));
+
+        layer.set_field("llc.dsap", "Iso").unwrap();
+        layer.set_field("llc.ssap", "0xfe").unwrap();
+        assert_eq!((layer.dsap, layer.ssap), (Sap::Iso, Sap::Iso));
+        assert_eq!(layer.set_field("llc.ctrl", "0x10000"), Err(FieldError::InvalidValue("0x10000".to_string())));
+        assert_eq!(layer.set_field("llc.ctrl", "0x1f3"), Err(FieldError::InvalidValue("0x1f3".to_string())));
+        assert_eq!(layer.control, LlcLayer::UI);
+
+        layer.set_field("llc.ctrl", "0x1f4").unwrap();
+        assert_eq!(layer.header_len(), 4);
+    }
+}
@@ -0,0 +1,181 @@
+use super::*;
+
+use crate::Ipv6Addr;
+
+/// Decodes the IPv6 addresses which fill the buffer.
+fn parse_addresses(bytes: &[u8]) -> Vec<Ipv6Addr> {
+    bytes.chunks(16).map(|chunk| Ipv6Addr::from(<[u8; 16]>::try_from(chunk).unwrap())).collect()
+}
+
+/// A group record of an MLDv2 report, which describes the sources a node listens to for a multicast address.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MldGroupRecord {
+    pub record_type: GroupRecordType,
+    pub group: Ipv6Addr,
+    pub sources: Vec<Ipv6Addr>,
+    /// The auxiliary data, which is padded with zeros to a multiple of four bytes when written.
+    pub auxiliary_data: Vec<u8>,
+}
+
+impl MldGroupRecord {
+    /// The length of a record without sources and auxiliary data in bytes.
+    pub const MIN_LEN: usize = 20;
+
+    /// Constructs a record for the multicast address and sources without auxiliary data.
+    pub fn new(record_type: GroupRecordType, group: Ipv6Addr, sources: Vec<Ipv6Addr>) -> Self {
+        Self {
+            record_type,
+            group,
+            sources,
+            auxiliary_data: Vec::new(),
+        }
+    }
+
+    /// Returns the length of the encoded record in bytes.
+    pub fn encoded_len(&self) -> usize {
+        Self::MIN_LEN + self.sources.len() * 16 + self.auxiliary_data.len().next_multiple_of(4)
+    }
+
+    /// Appends the encoded record to the buffer.
+    pub fn write(&self, buf: &mut Vec<u8>) {
+        let aux_words = self.auxiliary_data.len().div_ceil(4);
+        buf.extend_from_slice(&[self.record_type.value(), aux_words as u8]);
+        buf.extend_from_slice(&(self.sources.len() as u16).to_be_bytes());
+        buf.extend_from_slice(&self.group.octets());
+        self.sources.iter().for_each(|source| buf.extend_from_slice(&source.octets()));
+        buf.extend_from_slice(&self.auxiliary_data);
+        buf.resize(buf.len() + aux_words * 4 - self.auxiliary_data.len(), 0);
+    }
+
+    /// Parses a record from the start of the buffer. Returns it with the number of bytes consumed, or `None` if the buffer
+    /// is too short.
+    pub fn parse(bytes: &[u8]) -> Option<(Self, usize)> {
+        let header = bytes.get(..Self::MIN_LEN)?;
+        let sources_len = u16::from_be_bytes([header[2], header[3]]) as usize * 16;
+        let len = Self::MIN_LEN + sources_len + header[1] as usize * 4;
+        let body = bytes.get(Self::MIN_LEN..len)?;
+
+        let record = Self {
+            record_type: GroupRecordType::from_value(header[0]),
+            group: Ipv6Addr::from(<[u8; 16]>::try_from(&header[4..20]).unwrap()),
+            sources: parse_addresses(&body[..sources_len]),
+            auxiliary_data: body[sources_len..].to_vec(),
+        };
+
+        Some((record, len))
+    }
+}
+
+/// The body of an MLDv2 query (RFC 3810), which may be restricted to specific sources of the multicast address.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MldV2Query {
+    /// The maximum time allowed before sending a report, in milliseconds. Codes from 32768 on use a floating-point format.
+    pub max_response_code: u16,
+    /// The multicast address being queried, or the unspecified address for a general query.
+    pub group: Ipv6Addr,
+    /// Routers receiving the query must not update their timers (S flag).
+    pub suppress_router_processing: bool,
+    /// The 3-bit Querier's Robustness Variable, or zero if it exceeds 7.
+    pub robustness: u8,
+    /// The Querier's Query Interval Code, which encodes the query interval in seconds.
+    pub query_interval_code: u8,
+    pub sources: Vec<Ipv6Addr>,
+}
+
+impl MldV2Query {
+    /// The length of the query without sources, following the type, code and checksum fields.
+    pub(crate) const MIN_BODY_LEN: usize = 24;
+
+    /// The default maximum response code, i.e. 10 seconds.
+    pub const DEFAULT_MAX_RESPONSE_CODE: u16 = 10_000;
+
+    /// The default robustness variable (RFC 3810).
+    pub const DEFAULT_ROBUSTNESS: u8 = 2;
+
+    /// The default query interval in seconds (RFC 3810).
+    pub const DEFAULT_QUERY_INTERVAL: u8 = 125;
+
+    /// Constructs a query for the sources of the multicast address with the default timers.
+    pub fn new(group: Ipv6Addr, sources: Vec<Ipv6Addr>) -> Self {
+        Self {
+            max_response_code: Self::DEFAULT_MAX_RESPONSE_CODE,
+            group,
+            suppress_router_processing: false,
+            robustness: Self::DEFAULT_ROBUSTNESS,
+            query_interval_code: Self::DEFAULT_QUERY_INTERVAL,
+            sources,
+        }
+    }
+
+    /// Returns the length of the encoded query in bytes, excluding the type, code and checksum fields.
+    pub fn encoded_len(&self) -> usize {
+        Self::MIN_BODY_LEN + self.sources.len() * 16
+    }
+
+    /// Appends the encoded query to the buffer, excluding the type, code and checksum fields.
+    pub fn write(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.max_response_code.to_be_bytes());
+        buf.extend_from_slice(&[0; 2]);
+        buf.extend_from_slice(&self.group.octets());
+        buf.push((self.suppress_router_processing as u8) << 3 | self.robustness & 0x07);
+        buf.push(self.query_interval_code);
+        buf.extend_from_slice(&(self.sources.len() as u16).to_be_bytes());
+        self.sources.iter().for_each(|source| buf.extend_from_slice(&source.octets()));
+    }
+
+    /// Parses a query which follows the type, code and checksum fields. Returns it with the number of bytes consumed, or
+    /// `None` if the buffer is too short.
+    pub fn parse(bytes: &[u8]) -> Option<(Self, usize)> {
+        let header = bytes.get(..Self::MIN_BODY_LEN)?;
+        let len = Self::MIN_BODY_LEN + u16::from_be_bytes([header[22], header[23]]) as usize * 16;
+
+        let query = Self {
+            max_response_code: u16::from_be_bytes([header[0], header[1]]),
+            group: Ipv6Addr::from(<[u8; 16]>::try_from(&header[4..20]).unwrap()),
+            suppress_router_processing: header[20] & 0x08 != 0,
+            robustness: header[20] & 0x07,
+            query_interval_code: header[21],
+            sources: parse_addresses(bytes.get(Self::MIN_BODY_LEN..len)?),
+        };
+
+        Some((query, len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(last: u16) -> Ipv6Addr {
+        Ipv6Addr::new(0xff05, 0, 0, 0, 0, 0, 0, last)
+    }
+
+    #[test]
+    fn record_round_trip() {
+        let mut record = MldGroupRecord::new(GroupRecordType::AllowNewSources, addr(1), vec![Ipv6Addr::loopback()]);
+        record.auxiliary_data = vec![1, 2, 3, 4, 5];
+
+        let mut buf = Vec::new();
+        record.write(&mut buf);
+        assert_eq!(buf.len(), record.encoded_len());
+        assert_eq!(buf[..4], [5, 2, 0, 1]);
+
+        let (parsed, len) = MldGroupRecord::parse(&buf).unwrap();
+        assert_eq!(len, 44);
+        assert_eq!(parsed.sources, record.sources);
+        assert_eq!(parsed.auxiliary_data, [1, 2, 3, 4, 5, 0, 0, 0]);
+        assert_eq!(MldGroupRecord::parse(&buf[..43]), None);
+    }
+
+    #[test]
+    fn query_round_trip() {
+        let query = MldV2Query::new(addr(2), vec![Ipv6Addr::loopback(), addr(3)]);
+
+        let mut buf = Vec::new();
+        query.write(&mut buf);
+        assert_eq!(buf.len(), query.encoded_len());
+        assert_eq!(buf[20..24], [0x02, 125, 0, 2]);
+        assert_eq!(MldV2Query::parse(&buf), Some((query, 56)));
+        assert_eq!(MldV2Query::parse(&buf[..40]), None);
+    }
+}
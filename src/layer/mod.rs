@@ -1,10 +1,122 @@
 pub mod eth_layer;
 pub use eth_layer::*;
 
+pub mod ether_type;
+pub use ether_type::*;
+
+pub mod raw_layer;
+pub use raw_layer::*;
+
+pub mod padding_layer;
+pub use padding_layer::*;
+
+pub mod ip_protocol;
+pub use ip_protocol::*;
+
+pub mod ipv4_layer;
+pub use ipv4_layer::*;
+
+pub mod ipv4_option;
+pub use ipv4_option::*;
+
+pub mod ipv6_layer;
+pub use ipv6_layer::*;
+
+pub mod ipv6_extension;
+pub use ipv6_extension::*;
+
+pub mod icmp_layer;
+pub use icmp_layer::*;
+
+pub mod icmp_extension;
+pub use icmp_extension::*;
+
+pub mod icmpv6_layer;
+pub use icmpv6_layer::*;
+
+pub mod ndp_option;
+pub use ndp_option::*;
+
+pub mod mld_message;
+pub use mld_message::*;
+
+pub mod igmp_layer;
+pub use igmp_layer::*;
+
+pub mod gre_layer;
+pub use gre_layer::*;
+
+pub mod vxlan_layer;
+pub use vxlan_layer::*;
+
+pub mod geneve_layer;
+pub use geneve_layer::*;
+
+pub mod udp_layer;
+pub use udp_layer::*;
+
+pub mod tcp_layer;
+pub use tcp_layer::*;
+
+pub mod tcp_option;
+pub use tcp_option::*;
+
+pub mod dns_layer;
+pub use dns_layer::*;
+
+pub mod dns_record;
+pub use dns_record::*;
+
+pub mod dhcp_layer;
+pub use dhcp_layer::*;
+
+pub mod dhcp_option;
+pub use dhcp_option::*;
+
+pub mod dhcpv6_layer;
+pub use dhcpv6_layer::*;
+
+pub mod dhcpv6_option;
+pub use dhcpv6_option::*;
+
+pub mod arp_layer;
+pub use arp_layer::*;
+
+pub mod llc_layer;
+pub use llc_layer::*;
+
+pub mod snap_layer;
+pub use snap_layer::*;
+
+use std::{any::Any, fmt};
+
+use thiserror::Error;
+
+use crate::{Checksum, Discriminator, Reflect};
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum LayerType {
     EthLayer,
-    Ipv4Layer
+    Ipv4Layer,
+    Ipv6Layer,
+    ArpLayer,
+    IcmpLayer,
+    Icmpv6Layer,
+    IgmpLayer,
+    GreLayer,
+    VxlanLayer,
+    GeneveLayer,
+    UdpLayer,
+    TcpLayer,
+    DnsLayer,
+    DhcpLayer,
+    Dhcpv6Layer,
+    RawLayer,
+    PaddingLayer,
+    LlcLayer,
+    SnapLayer,
+    /// A layer defined outside of this crate.
+    Custom
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -15,11 +127,218 @@ pub enum OsiLevel {
     Transport,
     Session,
     Presentation,
-    Application
+    Application,
+    /// Bytes which were not decoded into a protocol, such as raw payloads and padding.
+    Unspecified
 }
 
 pub trait Layer {
     const NAME: &'static str;
     const TYPE: LayerType;
     const OSI_LEVEL: OsiLevel;
-}
\ No newline at end of file
+}
+
+/// Object-safe counterpart of [`Layer`], which allows layers of different types to be stored and processed together.
+pub trait DynLayer: LayerObject + fmt::Debug {
+    /// Returns the length of the serialized header in bytes.
+    fn header_len(&self) -> usize;
+
+    /// Returns the length of the payload carried by the layer if the header specifies it.
+    fn payload_len(&self) -> Option<usize> {
+        None
+    }
+
+    /// Appends the serialized header to the buffer.
+    fn write(&self, buf: &mut Vec<u8>);
+
+    /// Fills in the fields which are derived from the neighbouring layers, such as types, lengths and checksums.
+    ///
+    /// Only fields which were left unset are computed, so explicitly set values (even wrong ones) are preserved.
+    fn finalize(&mut self, _ctx: &FinalizeContext) {}
+
+    /// Returns the discriminators which identify the next layer, in order of preference.
+    fn next_discriminators(&self) -> Vec<Discriminator> {
+        Vec::new()
+    }
+
+    /// Returns the layer as [`Reflect`] if it supports generic access to its fields.
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        None
+    }
+
+    /// Returns the layer as mutable [`Reflect`] if it supports generic access to its fields.
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        None
+    }
+
+    /// Parses the layer from the start of the buffer and returns it together with the number of bytes consumed.
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError>
+    where
+        Self: Sized;
+}
+
+/// Type-erased accessors shared by all layers.
+///
+/// It is implemented automatically for every type which implements both [`Layer`] and [`DynLayer`].
+pub trait LayerObject {
+    /// Returns the name of the layer.
+    fn name(&self) -> &'static str;
+
+    /// Returns the type of the layer.
+    fn layer_type(&self) -> LayerType;
+
+    /// Returns the OSI level the layer belongs to.
+    fn osi_level(&self) -> OsiLevel;
+
+    /// Returns the layer as [`Any`] so that it can be downcast to its concrete type.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Returns the layer as mutable [`Any`] so that it can be downcast to its concrete type.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// Clones the layer into a new box.
+    fn clone_box(&self) -> Box<dyn DynLayer>;
+}
+
+impl<T: Layer + DynLayer + Clone + 'static> LayerObject for T {
+    fn name(&self) -> &'static str {
+        T::NAME
+    }
+
+    fn layer_type(&self) -> LayerType {
+        T::TYPE
+    }
+
+    fn osi_level(&self) -> OsiLevel {
+        T::OSI_LEVEL
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn DynLayer> {
+        Box::new(self.clone())
+    }
+}
+
+/// The position of a layer within a packet, passed to [`DynLayer::finalize`].
+pub struct FinalizeContext<'a> {
+    /// The layers below the finalized one, starting with the lowest.
+    pub lower: &'a [Box<dyn DynLayer>],
+
+    /// The layers above the finalized one. These have already been finalized.
+    pub upper: &'a [Box<dyn DynLayer>],
+
+    /// The serialized layers above the finalized one.
+    pub payload: &'a [u8],
+}
+
+impl<'a> FinalizeContext<'a> {
+    /// Returns the layer directly below the finalized one.
+    pub fn previous(&self) -> Option<&'a dyn DynLayer> {
+        self.lower.last().map(|layer| layer.as_ref())
+    }
+
+    /// Returns the layer directly above the finalized one.
+    pub fn next(&self) -> Option<&'a dyn DynLayer> {
+        self.upper.first().map(|layer| layer.as_ref())
+    }
+
+    /// Returns the length of the [`PaddingLayer`]s at the end of the payload.
+    pub fn padding_len(&self) -> usize {
+        self.upper
+            .iter()
+            .rev()
+            .take_while(|layer| layer.layer_type() == LayerType::PaddingLayer)
+            .map(|layer| layer.header_len())
+            .sum()
+    }
+
+    /// Returns the payload without the trailing padding.
+    pub fn unpadded_payload(&self) -> &'a [u8] {
+        &self.payload[..self.payload.len() - self.padding_len()]
+    }
+
+    /// Starts an upper-layer checksum with the pseudo-header of the layer directly below, see
+    /// [`pseudo_header`](dyn DynLayer::pseudo_header).
+    pub fn pseudo_header(&self, protocol: IpProtocol, length: usize) -> Option<Checksum> {
+        self.lower.last().and_then(|layer| layer.pseudo_header(protocol, length))
+    }
+}
+
+impl Clone for Box<dyn DynLayer> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+impl dyn DynLayer {
+    /// Checks whether the layer is of the concrete type `T`.
+    pub fn is<T: DynLayer + 'static>(&self) -> bool {
+        self.as_any().is::<T>()
+    }
+
+    /// Attempts to downcast the layer to a reference to the concrete type `T`.
+    pub fn downcast_ref<T: DynLayer + 'static>(&self) -> Option<&T> {
+        self.as_any().downcast_ref::<T>()
+    }
+
+    /// Attempts to downcast the layer to a mutable reference to the concrete type `T`.
+    pub fn downcast_mut<T: DynLayer + 'static>(&mut self) -> Option<&mut T> {
+        self.as_any_mut().downcast_mut::<T>()
+    }
+
+    /// Starts an upper-layer checksum with the pseudo-header of a payload of the specified protocol and length, if the
+    /// layer is an IPv4 or IPv6 header.
+    pub fn pseudo_header(&self, protocol: IpProtocol, length: usize) -> Option<Checksum> {
+        if let Some(ip) = self.downcast_ref::<Ipv4Layer>() {
+            return Some(ip.pseudo_header(protocol, length as u16));
+        }
+
+        self.downcast_ref::<Ipv6Layer>().map(|ip| ip.pseudo_header(protocol, length as u32))
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum LayerError {
+    #[error("The buffer is too short to contain the {0} header.")]
+    BufferTooShort(&'static str),
+
+    #[error(transparent)]
+    Eth(#[from] EthError),
+
+    #[error(transparent)]
+    Llc(#[from] LlcError),
+
+    #[error(transparent)]
+    Ipv4(#[from] Ipv4Error),
+
+    #[error(transparent)]
+    Ipv6(#[from] Ipv6Error),
+
+    #[error(transparent)]
+    Arp(#[from] ArpError),
+
+    #[error(transparent)]
+    Icmpv6(#[from] Icmpv6Error),
+
+    #[error(transparent)]
+    Tcp(#[from] TcpError),
+
+    #[error(transparent)]
+    Dns(#[from] DnsError),
+
+    #[error(transparent)]
+    Dhcp(#[from] DhcpError),
+
+    #[error(transparent)]
+    Dhcpv6(#[from] Dhcpv6Error),
+
+    #[error(transparent)]
+    Geneve(#[from] GeneveError),
+}
@@ -0,0 +1,246 @@
+use super::*;
+
+use crate::{Ipv6Addr, MacAddr};
+
+/// The data of the Prefix Information option, which advertises an on-link prefix or one used for address
+/// autoconfiguration.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct PrefixInformation {
+    pub prefix_length: u8,
+    /// The prefix can be used for on-link determination.
+    pub on_link: bool,
+    /// The prefix can be used for stateless address autoconfiguration (RFC 4862).
+    pub autonomous: bool,
+    /// The lifetime of the prefix in seconds, where `u32::MAX` means infinity.
+    pub valid_lifetime: u32,
+    /// The lifetime of addresses generated from the prefix in seconds, where `u32::MAX` means infinity.
+    pub preferred_lifetime: u32,
+    pub prefix: Ipv6Addr,
+}
+
+/// An option of a Neighbor Discovery message (RFC 4861).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum NdpOption {
+    SourceLinkAddress(MacAddr),
+    TargetLinkAddress(MacAddr),
+    PrefixInformation(PrefixInformation),
+    Mtu(u32),
+    /// Recursive DNS servers (RFC 8106) together with their lifetime in seconds.
+    Rdnss { lifetime: u32, servers: Vec<Ipv6Addr> },
+    /// A DNS search list (RFC 8106) together with its lifetime in seconds.
+    Dnssl { lifetime: u32, domains: Vec<String> },
+    /// An option which is either not supported or whose contents do not match its type. The data is padded to a multiple
+    /// of eight bytes including the 2-byte header.
+    Unknown { kind: u8, data: Vec<u8> },
+}
+
+impl NdpOption {
+    pub const SOURCE_LINK_ADDRESS: u8 = 1;
+    pub const TARGET_LINK_ADDRESS: u8 = 2;
+    pub const PREFIX_INFORMATION: u8 = 3;
+    pub const REDIRECTED_HEADER: u8 = 4;
+    pub const MTU: u8 = 5;
+    pub const RDNSS: u8 = 25;
+    pub const DNSSL: u8 = 31;
+
+    /// Returns the type of the option.
+    pub fn kind(&self) -> u8 {
+        match self {
+            NdpOption::SourceLinkAddress(_) => Self::SOURCE_LINK_ADDRESS,
+            NdpOption::TargetLinkAddress(_) => Self::TARGET_LINK_ADDRESS,
+            NdpOption::PrefixInformation(_) => Self::PREFIX_INFORMATION,
+            NdpOption::Mtu(_) => Self::MTU,
+            NdpOption::Rdnss { .. } => Self::RDNSS,
+            NdpOption::Dnssl { .. } => Self::DNSSL,
+            NdpOption::Unknown { kind, .. } => *kind,
+        }
+    }
+
+    /// Returns the length of the encoded option in bytes, which is a multiple of eight.
+    pub fn encoded_len(&self) -> usize {
+        let data_len = match self {
+            NdpOption::SourceLinkAddress(_) | NdpOption::TargetLinkAddress(_) => 6,
+            NdpOption::PrefixInformation(_) => 30,
+            NdpOption::Mtu(_) => 6,
+            NdpOption::Rdnss { servers, .. } => 6 + servers.len() * 16,
+            NdpOption::Dnssl { domains, .. } => 6 + domains.iter().map(|domain| encoded_name_len(domain)).sum::<usize>(),
+            NdpOption::Unknown { data, .. } => data.len(),
+        };
+
+        (2 + data_len).next_multiple_of(8)
+    }
+
+    /// Appends the encoded option, including the zero padding, to the buffer.
+    pub fn write(&self, buf: &mut Vec<u8>) {
+        let start = buf.len();
+        let len = self.encoded_len();
+        buf.extend_from_slice(&[self.kind(), (len / 8) as u8]);
+
+        match self {
+            NdpOption::SourceLinkAddress(mac) | NdpOption::TargetLinkAddress(mac) => buf.extend_from_slice(&mac.octets()),
+            NdpOption::PrefixInformation(info) => {
+                buf.push(info.prefix_length);
+                buf.push((info.on_link as u8) << 7 | (info.autonomous as u8) << 6);
+                buf.extend_from_slice(&info.valid_lifetime.to_be_bytes());
+                buf.extend_from_slice(&info.preferred_lifetime.to_be_bytes());
+                buf.extend_from_slice(&[0; 4]);
+                buf.extend_from_slice(&info.prefix.octets());
+            }
+            NdpOption::Mtu(mtu) => {
+                buf.extend_from_slice(&[0; 2]);
+                buf.extend_from_slice(&mtu.to_be_bytes());
+            }
+            NdpOption::Rdnss { lifetime, servers } => {
+                buf.extend_from_slice(&[0; 2]);
+                buf.extend_from_slice(&lifetime.to_be_bytes());
+                servers.iter().for_each(|server| buf.extend_from_slice(&server.octets()));
+            }
+            NdpOption::Dnssl { lifetime, domains } => {
+                buf.extend_from_slice(&[0; 2]);
+                buf.extend_from_slice(&lifetime.to_be_bytes());
+                domains.iter().for_each(|domain| write_name(buf, domain));
+            }
+            NdpOption::Unknown { data, .. } => buf.extend_from_slice(data),
+        }
+
+        buf.resize(start + len, 0);
+    }
+
+    /// Parses an option from the start of the buffer and returns it together with its length.
+    pub fn parse(bytes: &[u8]) -> Result<(Self, usize), Icmpv6Error> {
+        let kind = *bytes.first().ok_or(Icmpv6Error::MalformedNdpOption(0))?;
+        let len = *bytes.get(1).ok_or(Icmpv6Error::MalformedNdpOption(kind))? as usize * 8;
+        if len == 0 || len > bytes.len() {
+            return Err(Icmpv6Error::MalformedNdpOption(kind));
+        }
+
+        let data = &bytes[2..len];
+        let u32_at = |offset: usize| u32::from_be_bytes(data[offset..offset + 4].try_into().unwrap());
+        let mac = || MacAddr::from(<[u8; 6]>::try_from(data).unwrap());
+
+        let option = match kind {
+            Self::SOURCE_LINK_ADDRESS if data.len() == 6 => Some(NdpOption::SourceLinkAddress(mac())),
+            Self::TARGET_LINK_ADDRESS if data.len() == 6 => Some(NdpOption::TargetLinkAddress(mac())),
+            Self::PREFIX_INFORMATION if data.len() == 30 => Some(NdpOption::PrefixInformation(PrefixInformation {
+                prefix_length: data[0],
+                on_link: data[1] & 0x80 != 0,
+                autonomous: data[1] & 0x40 != 0,
+                valid_lifetime: u32_at(2),
+                preferred_lifetime: u32_at(6),
+                prefix: Ipv6Addr::from(<[u8; 16]>::try_from(&data[14..30]).unwrap()),
+            })),
+            Self::MTU if data.len() == 6 => Some(NdpOption::Mtu(u32_at(2))),
+            Self::RDNSS if data.len() > 6 => Some(NdpOption::Rdnss {
+                lifetime: u32_at(2),
+                servers: data[6..].chunks_exact(16).map(|chunk| Ipv6Addr::from(<[u8; 16]>::try_from(chunk).unwrap())).collect(),
+            }),
+            Self::DNSSL if data.len() > 6 => parse_names(&data[6..]).map(|domains| NdpOption::Dnssl { lifetime: u32_at(2), domains }),
+            _ => None,
+        };
+
+        let option = option.unwrap_or_else(|| NdpOption::Unknown { kind, data: data.to_vec() });
+        Ok((option, len))
+    }
+}
+
+/// Returns the length of a domain name encoded as a sequence of labels.
+fn encoded_name_len(name: &str) -> usize {
+    name.split('.').filter(|label| !label.is_empty()).map(|label| label.len() + 1).sum::<usize>() + 1
+}
+
+/// Appends a domain name encoded as a sequence of labels (RFC 1035) without compression.
+fn write_name(buf: &mut Vec<u8>, name: &str) {
+    for label in name.split('.').filter(|label| !label.is_empty()) {
+        buf.push(label.len() as u8);
+        buf.extend_from_slice(label.as_bytes());
+    }
+    buf.push(0);
+}
+
+/// Parses the domain names of a DNS search list, which is followed by zero padding.
+fn parse_names(mut bytes: &[u8]) -> Option<Vec<String>> {
+    let mut names = Vec::new();
+
+    while bytes.first().is_some_and(|len| *len != 0) {
+        let mut labels = Vec::new();
+        loop {
+            let (&len, rest) = bytes.split_first()?;
+            bytes = rest;
+            if len == 0 {
+                break;
+            }
+
+            let label = bytes.get(..len as usize)?;
+            labels.push(String::from_utf8(label.to_vec()).ok()?);
+            bytes = &bytes[len as usize..];
+        }
+        names.push(labels.join("."));
+    }
+
+    bytes.iter().all(|byte| *byte == 0).then_some(names)
+}
+
+/// Parses the options of a Neighbor Discovery message.
+pub fn parse_ndp_options(mut bytes: &[u8]) -> Result<Vec<NdpOption>, Icmpv6Error> {
+    let mut options = Vec::new();
+
+    while !bytes.is_empty() {
+        let (option, len) = NdpOption::parse(bytes)?;
+        options.push(option);
+        bytes = &bytes[len..];
+    }
+
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(option: NdpOption) -> Vec<u8> {
+        let mut buf = Vec::new();
+        option.write(&mut buf);
+        assert_eq!(buf.len(), option.encoded_len());
+        assert_eq!(NdpOption::parse(&buf), Ok((option, buf.len())));
+        buf
+    }
+
+    #[test]
+    fn options() {
+        let mac = MacAddr::new(0x00, 0x11, 0x22, 0x33, 0x44, 0x55);
+        assert_eq!(roundtrip(NdpOption::SourceLinkAddress(mac)), [1, 1, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        assert_eq!(roundtrip(NdpOption::Mtu(1500)), [5, 1, 0, 0, 0x00, 0x00, 0x05, 0xdc]);
+
+        let info = PrefixInformation {
+            prefix_length: 64,
+            on_link: true,
+            autonomous: true,
+            valid_lifetime: 2592000,
+            preferred_lifetime: 604800,
+            prefix: Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0),
+        };
+        let buf = roundtrip(NdpOption::PrefixInformation(info));
+        assert_eq!(buf[..4], [3, 4, 64, 0xc0]);
+
+        let servers = vec![Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0x53)];
+        assert_eq!(roundtrip(NdpOption::Rdnss { lifetime: 600, servers }).len(), 24);
+
+        let domains = vec!["example.com".to_string(), "lab.example.org".to_string()];
+        let buf = roundtrip(NdpOption::Dnssl { lifetime: 600, domains });
+        assert_eq!(buf.len(), 40);
+        assert_eq!(buf[8..13], [7, b'e', b'x', b'a', b'm']);
+
+        assert_eq!(roundtrip(NdpOption::Unknown { kind: 14, data: vec![1, 2, 3, 4, 5, 6] }), [14, 1, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn malformed() {
+        // A link-layer address option with a 14-byte address is kept as unknown
+        let bytes = [1, 2, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13];
+        assert_eq!(NdpOption::parse(&bytes).unwrap().0, NdpOption::Unknown { kind: 1, data: bytes[2..].to_vec() });
+
+        assert_eq!(NdpOption::parse(&[1, 0, 0, 0, 0, 0, 0, 0]), Err(Icmpv6Error::MalformedNdpOption(1)));
+        assert_eq!(NdpOption::parse(&[5, 2, 0, 0, 0, 0, 0, 0]), Err(Icmpv6Error::MalformedNdpOption(5)));
+        assert_eq!(parse_ndp_options(&[]), Ok(vec![]));
+    }
+}
@@ -0,0 +1,86 @@
+use super::*;
+
+use crate::reflect::*;
+
+/// Trailing bytes which follow the payload of a layer without belonging to it, such as Ethernet padding up to the minimum frame size.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct PaddingLayer {
+    pub data: Vec<u8>,
+}
+
+impl PaddingLayer {
+    /// Constructs a padding layer holding the specified bytes.
+    pub fn new(data: impl Into<Vec<u8>>) -> Self {
+        Self { data: data.into() }
+    }
+
+    /// Constructs a padding layer of `len` zero bytes.
+    pub fn zeroed(len: usize) -> Self {
+        Self { data: vec![0; len] }
+    }
+}
+
+impl Layer for PaddingLayer {
+    const NAME: &'static str = "Padding";
+    const TYPE: LayerType = LayerType::PaddingLayer;
+    const OSI_LEVEL: OsiLevel = OsiLevel::Unspecified;
+}
+
+impl DynLayer for PaddingLayer {
+    fn header_len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.data);
+    }
+
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        Ok((Self::new(bytes), bytes.len()))
+    }
+
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+}
+
+impl Reflect for PaddingLayer {
+    fn fields(&self) -> Vec<Field> {
+        vec![Field::new("padding.load", FieldKind::Bytes, 0, self.data.len() * 8, FieldValue::Bytes(self.data.clone()))]
+    }
+
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        match path {
+            "padding.load" => self.data = hex::decode(value.trim()).map_err(|_| FieldError::InvalidValue(value.to_string()))?,
+            _ => return Err(FieldError::UnknownField(path.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(PaddingLayer::NAME, "Padding");
+        assert_eq!(PaddingLayer::TYPE, LayerType::PaddingLayer);
+        assert_eq!(PaddingLayer::OSI_LEVEL, OsiLevel::Unspecified);
+    }
+
+    #[test]
+    fn write_and_parse() {
+        let layer = PaddingLayer::zeroed(4);
+        let mut buf = Vec::new();
+        layer.write(&mut buf);
+
+        assert_eq!(buf, [0, 0, 0, 0]);
+        assert_eq!(PaddingLayer::parse(&buf), Ok((layer, 4)));
+    }
+}
@@ -0,0 +1,101 @@
+use super::*;
+
+use crate::reflect::*;
+
+/// Opaque payload bytes which were not decoded into a protocol layer.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct RawLayer {
+    pub data: Vec<u8>,
+}
+
+impl RawLayer {
+    /// Constructs a raw layer holding the specified bytes.
+    pub fn new(data: impl Into<Vec<u8>>) -> Self {
+        Self { data: data.into() }
+    }
+}
+
+impl Layer for RawLayer {
+    const NAME: &'static str = "Raw";
+    const TYPE: LayerType = LayerType::RawLayer;
+    const OSI_LEVEL: OsiLevel = OsiLevel::Unspecified;
+}
+
+impl DynLayer for RawLayer {
+    fn header_len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.data);
+    }
+
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        Ok((Self::new(bytes), bytes.len()))
+    }
+
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+}
+
+impl Reflect for RawLayer {
+    fn fields(&self) -> Vec<Field> {
+        vec![Field::new("raw.load", FieldKind::Bytes, 0, self.data.len() * 8, FieldValue::Bytes(self.data.clone()))]
+    }
+
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        match path {
+            "raw.load" => self.data = hex::decode(value.trim()).map_err(|_| FieldError::InvalidValue(value.to_string()))?,
+            _ => return Err(FieldError::UnknownField(path.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Vec<u8>> for RawLayer {
+    fn from(data: Vec<u8>) -> Self {
+        Self { data }
+    }
+}
+
+impl From<&[u8]> for RawLayer {
+    fn from(data: &[u8]) -> Self {
+        Self::new(data)
+    }
+}
+
+impl<const N: usize> From<&[u8; N]> for RawLayer {
+    fn from(data: &[u8; N]) -> Self {
+        Self::new(data.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(RawLayer::NAME, "Raw");
+        assert_eq!(RawLayer::TYPE, LayerType::RawLayer);
+        assert_eq!(RawLayer::OSI_LEVEL, OsiLevel::Unspecified);
+    }
+
+    #[test]
+    fn write_and_parse() {
+        let layer = RawLayer::from(b"surgeon");
+        let mut buf = Vec::new();
+        layer.write(&mut buf);
+
+        assert_eq!(buf, b"surgeon");
+        assert_eq!(layer.header_len(), 7);
+        assert_eq!(RawLayer::parse(&buf), Ok((layer, 7)));
+        assert_eq!(RawLayer::parse(&[]), Ok((RawLayer::default(), 0)));
+    }
+}
@@ -0,0 +1,164 @@
+use super::*;
+
+use crate::{reflect::*, Discriminator};
+
+/// Subnetwork Access Protocol header, which follows an [`LlcLayer`] addressed to [`Sap::Snap`].
+///
+/// The protocol ID is interpreted within the organization identified by the OUI. For the zero OUI (RFC 1042) it is an
+/// EtherType.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct SnapLayer {
+    /// The 24-bit Organizationally Unique Identifier.
+    pub oui: u32,
+    /// The protocol ID. If left unset and the OUI is zero, it is derived from the next layer when the packet is finalized.
+    pub protocol_id: Option<u16>,
+}
+
+impl SnapLayer {
+    /// The length of the header in bytes.
+    pub const LEN: usize = 5;
+
+    /// The OUI under which the protocol ID is an EtherType, as specified by RFC 1042.
+    pub const OUI_ETHERNET: u32 = 0x000000;
+
+    /// The OUI of Cisco, used by protocols such as CDP, VTP and DTP.
+    pub const OUI_CISCO: u32 = 0x00000c;
+
+    /// The largest valid OUI.
+    pub const MAX_OUI: u32 = 0xff_ffff;
+
+    /// Constructs a new SNAP header.
+    pub fn new(oui: u32, protocol_id: u16) -> Self {
+        Self {
+            oui,
+            protocol_id: Some(protocol_id),
+        }
+    }
+
+    /// Constructs a header which carries a protocol identified by its EtherType.
+    pub fn ether_type(ether_type: EtherType) -> Self {
+        Self::new(Self::OUI_ETHERNET, ether_type.value())
+    }
+}
+
+impl Layer for SnapLayer {
+    const NAME: &'static str = "SNAP";
+    const TYPE: LayerType = LayerType::SnapLayer;
+    const OSI_LEVEL: OsiLevel = OsiLevel::DataLink;
+}
+
+impl DynLayer for SnapLayer {
+    fn header_len(&self) -> usize {
+        Self::LEN
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.oui.to_be_bytes()[1..]);
+        buf.extend_from_slice(&self.protocol_id.unwrap_or(0).to_be_bytes());
+    }
+
+    fn finalize(&mut self, ctx: &FinalizeContext) {
+        if self.protocol_id.is_none() && self.oui == Self::OUI_ETHERNET {
+            self.protocol_id = ctx
+                .next()
+                .and_then(|next| EtherType::of_layer(next.layer_type()))
+                .map(EtherType::value);
+        }
+    }
+
+    fn next_discriminators(&self) -> Vec<Discriminator> {
+        match (self.oui, self.protocol_id) {
+            (Self::OUI_ETHERNET, Some(protocol_id)) => vec![Discriminator::EtherType(protocol_id)],
+            (oui, Some(protocol_id)) => vec![Discriminator::SnapProtocol(oui, protocol_id)],
+            (_, None) => Vec::new(),
+        }
+    }
+
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        if bytes.len() < Self::LEN {
+            return Err(LayerError::BufferTooShort(Self::NAME));
+        }
+
+        let oui = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        Ok((Self::new(oui, u16::from_be_bytes([bytes[3], bytes[4]])), Self::LEN))
+    }
+
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+}
+
+impl Reflect for SnapLayer {
+    fn fields(&self) -> Vec<Field> {
+        let protocol_id = match (self.oui, self.protocol_id) {
+            (_, None) => Field::new("snap.pid", FieldKind::UInt, 24, 16, FieldValue::Unset),
+            (Self::OUI_ETHERNET, Some(protocol_id)) => {
+                Field::new("snap.pid", FieldKind::Enum, 24, 16, EtherType::from_value(protocol_id).field_value())
+            }
+            (_, Some(protocol_id)) => Field::new("snap.pid", FieldKind::UInt, 24, 16, FieldValue::UInt(protocol_id as u64)),
+        };
+
+        vec![
+            Field::new("snap.oui", FieldKind::UInt, 0, 24, FieldValue::UInt(self.oui as u64)),
+            protocol_id.computed(),
+        ]
+    }
+
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        match path {
+            "snap.oui" => match parse_uint_as(value)? {
+                oui if oui <= Self::MAX_OUI => self.oui = oui,
+                _ => return Err(FieldError::InvalidValue(value.to_string())),
+            },
+            "snap.pid" if value.trim() == "auto" => self.protocol_id = None,
+            "snap.pid" => self.protocol_id = Some(EtherType::parse_field(value)?.value()),
+            _ => return Err(FieldError::UnknownField(path.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(SnapLayer::NAME, "SNAP");
+        assert_eq!(SnapLayer::TYPE, LayerType::SnapLayer);
+        assert_eq!(SnapLayer::OSI_LEVEL, OsiLevel::DataLink);
+    }
+
+    #[test]
+    fn write_and_parse() {
+        let layer = SnapLayer::new(SnapLayer::OUI_CISCO, 0x2000);
+        let mut buf = Vec::new();
+        layer.write(&mut buf);
+
+        assert_eq!(buf, [0x00, 0x00, 0x0c, 0x20, 0x00]);
+        assert_eq!(SnapLayer::parse(&buf), Ok((layer.clone(), 5)));
+        assert_eq!(SnapLayer::parse(&buf[..4]), Err(LayerError::BufferTooShort("SNAP")));
+        assert_eq!(layer.next_discriminators(), [Discriminator::SnapProtocol(0x00000c, 0x2000)]);
+        assert_eq!(SnapLayer::ether_type(EtherType::IPv4).next_discriminators(), [Discriminator::EtherType(0x0800)]);
+    }
+
+    #[test]
+    fn reflect() {
+        let mut layer = SnapLayer::default();
+        assert_eq!(layer.get_field("snap.pid"), Some(FieldValue::Unset));
+
+        layer.set_field("snap.pid", "Arp").unwrap();
+        assert_eq!(layer.get_field("snap.pid"), Some(EtherType::Arp.field_value()));
+
+        layer.set_field("snap.oui", "0x00000c").unwrap();
+        layer.set_field("snap.pid", "0x2000").unwrap();
+        assert_eq!(layer, SnapLayer::new(SnapLayer::OUI_CISCO, 0x2000));
+        assert_eq!(layer.get_field("snap.pid"), Some(FieldValue::UInt(0x2000)));
+        assert_eq!(layer.set_field("snap.oui", "0x1000000"), Err(FieldError::InvalidValue("0x1000000".to_string())));
+    }
+}
@@ -0,0 +1,664 @@
+use super::*;
+
+use crate::{reflect::*, Discriminator};
+
+use thiserror::Error;
+
+/// The control bits of a TCP header, including the ECN bits (RFC 3168) and the Accurate ECN bit (RFC 9768).
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct TcpFlags {
+    pub fin: bool,
+    pub syn: bool,
+    pub rst: bool,
+    pub psh: bool,
+    pub ack: bool,
+    pub urg: bool,
+    /// ECN-Echo.
+    pub ece: bool,
+    /// Congestion Window Reduced.
+    pub cwr: bool,
+    /// Accurate ECN, formerly the ECN-nonce bit.
+    pub ae: bool,
+    /// The 3 reserved bits.
+    pub reserved: u8,
+}
+
+impl TcpFlags {
+    pub const SYN: Self = Self::from_bits(0x002);
+    pub const SYN_ACK: Self = Self::from_bits(0x012);
+    pub const ACK: Self = Self::from_bits(0x010);
+    pub const PSH_ACK: Self = Self::from_bits(0x018);
+    pub const FIN_ACK: Self = Self::from_bits(0x011);
+    pub const RST: Self = Self::from_bits(0x004);
+
+    /// Constructs the flags from the 12 least significant bits of the value.
+    pub const fn from_bits(bits: u16) -> Self {
+        Self {
+            fin: bits & 0x001 != 0,
+            syn: bits & 0x002 != 0,
+            rst: bits & 0x004 != 0,
+            psh: bits & 0x008 != 0,
+            ack: bits & 0x010 != 0,
+            urg: bits & 0x020 != 0,
+            ece: bits & 0x040 != 0,
+            cwr: bits & 0x080 != 0,
+            ae: bits & 0x100 != 0,
+            reserved: (bits >> 9) as u8 & 0b111,
+        }
+    }
+
+    /// Returns the flags as a 12-bit value.
+    pub const fn bits(self) -> u16 {
+        (self.reserved as u16 & 0b111) << 9
+            | (self.ae as u16) << 8
+            | (self.cwr as u16) << 7
+            | (self.ece as u16) << 6
+            | (self.urg as u16) << 5
+            | (self.ack as u16) << 4
+            | (self.psh as u16) << 3
+            | (self.rst as u16) << 2
+            | (self.syn as u16) << 1
+            | self.fin as u16
+    }
+}
+
+/// A TCP header (RFC 9293).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TcpLayer {
+    pub src_port: u16,
+    pub dst_port: u16,
+    pub sequence: u32,
+    pub acknowledgment: u32,
+    /// The 4-bit length of the header in 32-bit words. If left unset, it is derived from the options and padding, unless
+    /// they exceed [`TcpLayer::MAX_OPTIONS_LEN`].
+    pub data_offset: Option<u8>,
+    pub flags: TcpFlags,
+    pub window: u16,
+    /// The checksum over the pseudo-header of the enclosing IP layer, the header and the payload. If left unset, it is
+    /// computed when the packet is finalized.
+    pub checksum: Option<u16>,
+    pub urgent_pointer: u16,
+    pub options: Vec<TcpOption>,
+    /// The bytes following [`TcpOption::EndOfList`] up to the end of the header, which are written back as they are.
+    /// Zeros are appended when the options and padding do not fill the last word.
+    pub padding: Vec<u8>,
+}
+
+impl TcpLayer {
+    /// The length of a header without options in bytes.
+    pub const MIN_LEN: usize = 20;
+
+    /// The length of the longest possible header in bytes.
+    pub const MAX_LEN: usize = 60;
+
+    /// The length of the longest options which can be described by the data offset in bytes.
+    pub const MAX_OPTIONS_LEN: usize = Self::MAX_LEN - Self::MIN_LEN;
+
+    /// The default receive window of new segments.
+    pub const DEFAULT_WINDOW: u16 = 8192;
+
+    /// Constructs a TCP header between the specified ports. The derived fields are left unset.
+    pub fn new(src_port: u16, dst_port: u16, flags: TcpFlags) -> Self {
+        Self {
+            src_port,
+            dst_port,
+            flags,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the source and destination ports.
+    pub fn ports(&self) -> (u16, u16) {
+        (self.src_port, self.dst_port)
+    }
+
+    /// Returns the length of the options including the padding to a multiple of four bytes.
+    pub fn options_len(&self) -> usize {
+        let len = self.options.iter().map(TcpOption::encoded_len).sum::<usize>() + self.padding.len();
+        len.next_multiple_of(4)
+    }
+
+    /// Checks if the options and padding fit in the header, i.e. if they can be described by the data offset.
+    pub fn options_fit(&self) -> bool {
+        self.options_len() <= Self::MAX_OPTIONS_LEN
+    }
+
+    /// Returns the encoded options and padding, padded with zeros to a multiple of four bytes.
+    fn options_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.options_len());
+        self.options.iter().for_each(|option| option.write(&mut buf));
+        buf.extend_from_slice(&self.padding);
+        buf.resize(self.options_len(), 0);
+        buf
+    }
+
+    /// Returns the data offset derived from the length of the header, or `None` if the options do not fit.
+    fn derived_data_offset(&self) -> Option<u8> {
+        self.options_fit().then(|| (self.header_len() / 4) as u8)
+    }
+
+    /// Computes the checksum of the segment over the pseudo-header of the IP layer, as if the checksum field was zero.
+    /// Returns `None` if the layer is neither IPv4 nor IPv6.
+    pub fn compute_checksum(&self, ip: &(dyn DynLayer + 'static), payload: &[u8]) -> Option<u16> {
+        let mut buf = Vec::with_capacity(self.header_len() + payload.len());
+        Self { checksum: Some(0), ..self.clone() }.write(&mut buf);
+        buf.extend_from_slice(payload);
+
+        Some(ip.pseudo_header(IpProtocol::Tcp, buf.len())?.add_bytes(&buf).finish())
+    }
+
+    /// Checks if the checksum matches the segment carried by the IP layer.
+    pub fn is_checksum_valid(&self, ip: &(dyn DynLayer + 'static), payload: &[u8]) -> bool {
+        self.checksum.is_some() && self.checksum == self.compute_checksum(ip, payload)
+    }
+}
+
+impl Default for TcpLayer {
+    fn default() -> Self {
+        Self {
+            src_port: 0,
+            dst_port: 0,
+            sequence: 0,
+            acknowledgment: 0,
+            data_offset: None,
+            flags: TcpFlags::SYN,
+            window: Self::DEFAULT_WINDOW,
+            checksum: None,
+            urgent_pointer: 0,
+            options: Vec::new(),
+            padding: Vec::new(),
+        }
+    }
+}
+
+impl Layer for TcpLayer {
+    const NAME: &'static str = "TCP";
+    const TYPE: LayerType = LayerType::TcpLayer;
+    const OSI_LEVEL: OsiLevel = OsiLevel::Transport;
+}
+
+impl DynLayer for TcpLayer {
+    fn header_len(&self) -> usize {
+        Self::MIN_LEN + self.options_len()
+    }
+
+    /// Writes the options and padding, padded with zeros, i.e. End of Option List, to a multiple of four bytes.
+    fn write(&self, buf: &mut Vec<u8>) {
+        let data_offset = self.data_offset.or_else(|| self.derived_data_offset()).unwrap_or(0);
+
+        buf.extend_from_slice(&self.src_port.to_be_bytes());
+        buf.extend_from_slice(&self.dst_port.to_be_bytes());
+        buf.extend_from_slice(&self.sequence.to_be_bytes());
+        buf.extend_from_slice(&self.acknowledgment.to_be_bytes());
+        buf.extend_from_slice(&((data_offset as u16 & 0x0f) << 12 | self.flags.bits()).to_be_bytes());
+        buf.extend_from_slice(&self.window.to_be_bytes());
+        buf.extend_from_slice(&self.checksum.unwrap_or(0).to_be_bytes());
+        buf.extend_from_slice(&self.urgent_pointer.to_be_bytes());
+
+        buf.extend_from_slice(&self.options_bytes());
+    }
+
+    /// Computes the checksum over the pseudo-header of the IP layer directly below. Without one, the checksum is zero.
+    fn finalize(&mut self, ctx: &FinalizeContext) {
+        // Options which do not fit are still written, but the data offset is left unset rather than truncated to 4 bits
+        if self.data_offset.is_none() {
+            self.data_offset = self.derived_data_offset();
+        }
+
+        if self.checksum.is_none() {
+            let checksum = match ctx.lower.last() {
+                Some(ip) => self.compute_checksum(ip.as_ref(), ctx.unpadded_payload()),
+                None => None,
+            };
+            self.checksum = Some(checksum.unwrap_or(0));
+        }
+    }
+
+    /// Application protocols are identified by the lower port first, since it is usually the well-known one.
+    fn next_discriminators(&self) -> Vec<Discriminator> {
+        let (low, high) = match self.src_port < self.dst_port {
+            true => (self.src_port, self.dst_port),
+            false => (self.dst_port, self.src_port),
+        };
+
+        vec![Discriminator::TcpPort(low), Discriminator::TcpPort(high)]
+    }
+
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        let view = TcpView::new(bytes)?;
+        Ok((view.to_layer()?, view.header_len()))
+    }
+
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+}
+
+impl Reflect for TcpLayer {
+    fn fields(&self) -> Vec<Field> {
+        let uint = |value: u64| FieldValue::UInt(value);
+        let bool = |value: bool| FieldValue::Bool(value);
+
+        let mut fields = vec![
+            Field::new("tcp.sport", FieldKind::UInt, 0, 16, uint(self.src_port as u64)),
+            Field::new("tcp.dport", FieldKind::UInt, 16, 16, uint(self.dst_port as u64)),
+            Field::new("tcp.seq", FieldKind::UInt, 32, 32, uint(self.sequence as u64)),
+            Field::new("tcp.ack", FieldKind::UInt, 64, 32, uint(self.acknowledgment as u64)),
+            Field::new("tcp.dataofs", FieldKind::UInt, 96, 4, self.data_offset.map_or(FieldValue::Unset, |d| uint(d as u64)))
+                .computed(),
+            Field::new("tcp.reserved", FieldKind::UInt, 100, 3, uint(self.flags.reserved as u64)),
+            Field::new("tcp.flags.ae", FieldKind::Bool, 103, 1, bool(self.flags.ae)),
+            Field::new("tcp.flags.cwr", FieldKind::Bool, 104, 1, bool(self.flags.cwr)),
+            Field::new("tcp.flags.ece", FieldKind::Bool, 105, 1, bool(self.flags.ece)),
+            Field::new("tcp.flags.urg", FieldKind::Bool, 106, 1, bool(self.flags.urg)),
+            Field::new("tcp.flags.ack", FieldKind::Bool, 107, 1, bool(self.flags.ack)),
+            Field::new("tcp.flags.psh", FieldKind::Bool, 108, 1, bool(self.flags.psh)),
+            Field::new("tcp.flags.rst", FieldKind::Bool, 109, 1, bool(self.flags.rst)),
+            Field::new("tcp.flags.syn", FieldKind::Bool, 110, 1, bool(self.flags.syn)),
+            Field::new("tcp.flags.fin", FieldKind::Bool, 111, 1, bool(self.flags.fin)),
+            Field::new("tcp.window", FieldKind::UInt, 112, 16, uint(self.window as u64)),
+            Field::new("tcp.chksum", FieldKind::UInt, 128, 16, self.checksum.map_or(FieldValue::Unset, |c| uint(c as u64)))
+                .computed(),
+            Field::new("tcp.urgptr", FieldKind::UInt, 144, 16, uint(self.urgent_pointer as u64)),
+        ];
+
+        if !self.options.is_empty() || !self.padding.is_empty() {
+            let options = self.options_bytes();
+            fields.push(Field::new("tcp.options", FieldKind::Bytes, 160, options.len() * 8, FieldValue::Bytes(options)));
+        }
+
+        fields
+    }
+
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        let bounded = |max: u64| match parse_uint(value)? {
+            parsed if parsed <= max => Ok(parsed),
+            _ => Err(FieldError::InvalidValue(value.to_string())),
+        };
+        let auto = value.trim() == "auto";
+
+        match path {
+            "tcp.sport" => self.src_port = parse_uint_as(value)?,
+            "tcp.dport" => self.dst_port = parse_uint_as(value)?,
+            "tcp.seq" => self.sequence = parse_uint_as(value)?,
+            "tcp.ack" => self.acknowledgment = parse_uint_as(value)?,
+            "tcp.dataofs" if auto => self.data_offset = None,
+            "tcp.dataofs" => self.data_offset = Some(bounded(0x0f)? as u8),
+            "tcp.reserved" => self.flags.reserved = bounded(0b111)? as u8,
+            "tcp.flags.ae" => self.flags.ae = parse_bool(value)?,
+            "tcp.flags.cwr" => self.flags.cwr = parse_bool(value)?,
+            "tcp.flags.ece" => self.flags.ece = parse_bool(value)?,
+            "tcp.flags.urg" => self.flags.urg = parse_bool(value)?,
+            "tcp.flags.ack" => self.flags.ack = parse_bool(value)?,
+            "tcp.flags.psh" => self.flags.psh = parse_bool(value)?,
+            "tcp.flags.rst" => self.flags.rst = parse_bool(value)?,
+            "tcp.flags.syn" => self.flags.syn = parse_bool(value)?,
+            "tcp.flags.fin" => self.flags.fin = parse_bool(value)?,
+            "tcp.window" => self.window = parse_uint_as(value)?,
+            "tcp.chksum" if auto => self.checksum = None,
+            "tcp.chksum" => self.checksum = Some(parse_uint_as(value)?),
+            "tcp.urgptr" => self.urgent_pointer = parse_uint_as(value)?,
+            "tcp.options" => {
+                let invalid = || FieldError::InvalidValue(value.to_string());
+                let bytes = hex::decode(value.trim()).map_err(|_| invalid())?;
+                if bytes.len() > Self::MAX_OPTIONS_LEN {
+                    return Err(invalid());
+                }
+
+                let (options, padding) = split_tcp_options(&bytes).map_err(|_| invalid())?;
+                self.padding = padding.to_vec();
+                self.options = options;
+            }
+            _ => return Err(FieldError::UnknownField(path.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
+/// A borrowed, zero-copy view of a TCP header at the start of a byte slice.
+///
+/// The data offset is validated once on construction and fields are decoded only when accessed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TcpView<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> TcpView<'a> {
+    /// Constructs a view of the TCP header at the start of the slice.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, LayerError> {
+        if bytes.len() < TcpLayer::MIN_LEN {
+            return Err(LayerError::BufferTooShort(TcpLayer::NAME));
+        }
+
+        let view = Self { bytes };
+        if view.data_offset() < 5 {
+            return Err(TcpError::InvalidDataOffset(view.data_offset()).into());
+        }
+
+        if bytes.len() < view.header_len() {
+            return Err(LayerError::BufferTooShort(TcpLayer::NAME));
+        }
+
+        Ok(view)
+    }
+
+    fn u16_at(&self, offset: usize) -> u16 {
+        u16::from_be_bytes([self.bytes[offset], self.bytes[offset + 1]])
+    }
+
+    fn u32_at(&self, offset: usize) -> u32 {
+        u32::from_be_bytes(self.bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    /// Returns the source port.
+    pub fn src_port(&self) -> u16 {
+        self.u16_at(0)
+    }
+
+    /// Returns the destination port.
+    pub fn dst_port(&self) -> u16 {
+        self.u16_at(2)
+    }
+
+    /// Returns the sequence number.
+    pub fn sequence(&self) -> u32 {
+        self.u32_at(4)
+    }
+
+    /// Returns the acknowledgment number.
+    pub fn acknowledgment(&self) -> u32 {
+        self.u32_at(8)
+    }
+
+    /// Returns the length of the header in 32-bit words.
+    pub fn data_offset(&self) -> u8 {
+        self.bytes[12] >> 4
+    }
+
+    /// Returns the length of the header in bytes.
+    pub fn header_len(&self) -> usize {
+        self.data_offset() as usize * 4
+    }
+
+    /// Returns the flags.
+    pub fn flags(&self) -> TcpFlags {
+        TcpFlags::from_bits(self.u16_at(12) & 0x0fff)
+    }
+
+    /// Returns the receive window.
+    pub fn window(&self) -> u16 {
+        self.u16_at(14)
+    }
+
+    /// Returns the checksum.
+    pub fn checksum(&self) -> u16 {
+        self.u16_at(16)
+    }
+
+    /// Returns the urgent pointer.
+    pub fn urgent_pointer(&self) -> u16 {
+        self.u16_at(18)
+    }
+
+    /// Returns the raw bytes of the options, including the padding.
+    pub fn options_raw(&self) -> &'a [u8] {
+        &self.bytes[TcpLayer::MIN_LEN..self.header_len()]
+    }
+
+    /// Decodes the options.
+    pub fn options(&self) -> Result<Vec<TcpOption>, TcpError> {
+        parse_tcp_options(self.options_raw())
+    }
+
+    /// Returns the bytes following the header, up to the end of the slice.
+    pub fn payload(&self) -> &'a [u8] {
+        &self.bytes[self.header_len()..]
+    }
+
+    /// Converts the view into an owned TCP layer. Fails if the options are malformed.
+    pub fn to_layer(&self) -> Result<TcpLayer, LayerError> {
+        let (options, padding) = split_tcp_options(self.options_raw())?;
+
+        Ok(TcpLayer {
+            src_port: self.src_port(),
+            dst_port: self.dst_port(),
+            sequence: self.sequence(),
+            acknowledgment: self.acknowledgment(),
+            data_offset: Some(self.data_offset()),
+            flags: self.flags(),
+            window: self.window(),
+            checksum: Some(self.checksum()),
+            urgent_pointer: self.urgent_pointer(),
+            options,
+            padding: padding.to_vec(),
+        })
+    }
+}
+
+/// A mutable, zero-copy view of a TCP header at the start of a byte slice, which allows fields to be edited in place.
+///
+/// The checksum is not updated automatically, since it depends on the pseudo-header of the enclosing IP layer.
+#[derive(Debug, PartialEq, Eq)]
+pub struct TcpViewMut<'a> {
+    bytes: &'a mut [u8],
+}
+
+impl<'a> TcpViewMut<'a> {
+    /// Constructs a mutable view of the TCP header at the start of the slice.
+    pub fn new(bytes: &'a mut [u8]) -> Result<Self, LayerError> {
+        TcpView::new(bytes)?;
+        Ok(Self { bytes })
+    }
+
+    /// Returns a read-only view of the header.
+    pub fn as_view(&self) -> TcpView<'_> {
+        TcpView { bytes: self.bytes }
+    }
+
+    /// Sets the sequence number.
+    pub fn set_sequence(&mut self, sequence: u32) {
+        self.bytes[4..8].copy_from_slice(&sequence.to_be_bytes());
+    }
+
+    /// Sets the acknowledgment number.
+    pub fn set_acknowledgment(&mut self, acknowledgment: u32) {
+        self.bytes[8..12].copy_from_slice(&acknowledgment.to_be_bytes());
+    }
+
+    /// Sets the flags, preserving the data offset.
+    pub fn set_flags(&mut self, flags: TcpFlags) {
+        let value = (self.bytes[12] as u16 & 0xf0) << 8 | flags.bits();
+        self.bytes[12..14].copy_from_slice(&value.to_be_bytes());
+    }
+
+    /// Sets the receive window.
+    pub fn set_window(&mut self, window: u16) {
+        self.bytes[14..16].copy_from_slice(&window.to_be_bytes());
+    }
+
+    /// Sets the checksum.
+    pub fn set_checksum(&mut self, checksum: u16) {
+        self.bytes[16..18].copy_from_slice(&checksum.to_be_bytes());
+    }
+
+    /// Returns the bytes following the header.
+    pub fn payload_mut(&mut self) -> &mut [u8] {
+        let header_len = self.as_view().header_len();
+        &mut self.bytes[header_len..]
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum TcpError {
+    #[error("The data offset of {0} words is shorter than the minimal TCP header.")]
+    InvalidDataOffset(u8),
+
+    #[error("The TCP option of kind {0} is malformed.")]
+    MalformedOption(u8),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Ipv4Addr, Ipv6Addr, Packet};
+
+    /// A SYN with MSS, SACK-permitted, timestamps, NOP and window scale options, as sent by Linux.
+    const SYN: [u8; 40] = [
+        0xc3, 0x50, 0x00, 0x50, 0x12, 0x34, 0x56, 0x78, 0x00, 0x00, 0x00, 0x00, 0xa0, 0x02, 0xfa, 0xf0, 0x00, 0x00, 0x00, 0x00,
+        0x02, 0x04, 0x05, 0xb4, 0x04, 0x02, 0x08, 0x0a, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03, 0x03, 0x07,
+    ];
+
+    fn ipv4() -> Ipv4Layer {
+        Ipv4Layer::new(Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 199))
+    }
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(TcpLayer::NAME, "TCP");
+        assert_eq!(TcpLayer::TYPE, LayerType::TcpLayer);
+        assert_eq!(TcpLayer::OSI_LEVEL, OsiLevel::Transport);
+    }
+
+    #[test]
+    fn flags() {
+        assert_eq!(TcpFlags::SYN_ACK, TcpFlags { syn: true, ack: true, ..TcpFlags::default() });
+        assert_eq!(TcpFlags::from_bits(0x1c2).bits(), 0x1c2);
+        assert!(TcpFlags::from_bits(0x1c2).ae);
+        assert!(TcpFlags::from_bits(0x1c2).cwr);
+        assert!(TcpFlags::from_bits(0x1c2).ece);
+        assert_eq!(TcpFlags::from_bits(0xe00).reserved, 0b111);
+    }
+
+    #[test]
+    fn parse_and_write() {
+        let (layer, len) = TcpLayer::parse(&SYN).unwrap();
+        assert_eq!(len, 40);
+        assert_eq!(layer.ports(), (50000, 80));
+        assert_eq!(layer.sequence, 0x12345678);
+        assert_eq!(layer.flags, TcpFlags::SYN);
+        assert_eq!(layer.window, 64240);
+        assert_eq!(
+            layer.options,
+            [
+                TcpOption::Mss(1460),
+                TcpOption::SackPermitted,
+                TcpOption::Timestamps { value: 1, echo_reply: 0 },
+                TcpOption::NoOperation,
+                TcpOption::WindowScale(7),
+            ]
+        );
+
+        let mut buf = Vec::new();
+        layer.write(&mut buf);
+        assert_eq!(buf, SYN);
+
+        assert_eq!(TcpLayer::parse(&SYN[..39]), Err(LayerError::BufferTooShort("TCP")));
+        let mut bytes = SYN;
+        bytes[12] = 0x40;
+        assert_eq!(TcpLayer::parse(&bytes), Err(LayerError::Tcp(TcpError::InvalidDataOffset(4))));
+    }
+
+    #[test]
+    fn padding() {
+        let layer = TcpLayer {
+            options: vec![TcpOption::Mss(1460), TcpOption::WindowScale(7)],
+            ..TcpLayer::new(1, 2, TcpFlags::SYN)
+        };
+        assert_eq!(layer.header_len(), 28);
+
+        let bytes = Packet::from(layer).to_bytes();
+        assert_eq!(bytes.len(), 28);
+        assert_eq!(bytes[12], 0x70);
+        assert_eq!(bytes[20..], [2, 4, 0x05, 0xb4, 3, 3, 7, 0]);
+        assert_eq!(TcpLayer::parse(&bytes).unwrap().0.options.last(), Some(&TcpOption::EndOfList));
+    }
+
+    #[test]
+    fn padding_after_end_of_list() {
+        // A SYN with a data offset of 8 whose options end after the MSS
+        let layer = TcpLayer {
+            options: vec![TcpOption::Mss(1460), TcpOption::EndOfList],
+            padding: vec![0; 7],
+            ..TcpLayer::new(1, 2, TcpFlags::SYN)
+        };
+        let bytes = Packet::from(layer.clone()).to_bytes();
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(bytes[12], 0x80);
+
+        let (parsed, len) = TcpLayer::parse(&bytes).unwrap();
+        assert_eq!(len, 32);
+        assert_eq!((&parsed.options, &parsed.padding), (&layer.options, &layer.padding));
+
+        let mut written = Vec::new();
+        parsed.write(&mut written);
+        assert_eq!(written, bytes);
+    }
+
+    #[test]
+    fn options_too_long() {
+        let layer = TcpLayer { options: vec![TcpOption::NoOperation; 41], ..TcpLayer::default() };
+        assert!(!layer.options_fit());
+
+        // The data offset cannot describe the header, so it is not derived
+        let mut packet = Packet::from(layer);
+        packet.finalize();
+        assert_eq!(packet.get::<TcpLayer>().unwrap().data_offset, None);
+
+        let too_long = "01".repeat(41);
+        assert_eq!(TcpLayer::default().set_field("tcp.options", &too_long), Err(FieldError::InvalidValue(too_long)));
+    }
+
+    #[test]
+    fn checksum() {
+        let layer = TcpLayer::new(50000, 80, TcpFlags::PSH_ACK);
+        let bytes = (ipv4() / layer / b"GET /").to_bytes();
+
+        let (layer, _) = TcpLayer::parse(&bytes[20..]).unwrap();
+        assert!(layer.is_checksum_valid(&ipv4(), b"GET /"));
+        assert!(!layer.is_checksum_valid(&ipv4(), b"GET ."));
+
+        let mut sum = ipv4().pseudo_header(IpProtocol::Tcp, 25);
+        assert_eq!(sum.add_bytes(&bytes[20..]).finish(), 0);
+
+        let ipv6 = Ipv6Layer::new(Ipv6Addr::loopback(), Ipv6Addr::loopback());
+        let bytes = (ipv6.clone() / TcpLayer::default()).to_bytes();
+        assert!(TcpLayer::parse(&bytes[40..]).unwrap().0.is_checksum_valid(&ipv6, &[]));
+    }
+
+    #[test]
+    fn reflect() {
+        let mut layer = TcpLayer::default();
+        layer.set_field("tcp.flags.ack", "true").unwrap();
+        layer.set_field("tcp.flags.ae", "1").unwrap();
+        layer.set_field("tcp.options", "020405b4").unwrap();
+        assert_eq!(layer.flags.bits(), 0x112);
+        assert_eq!(layer.options, [TcpOption::Mss(1460)]);
+        assert_eq!(layer.get_field("tcp.options"), Some(FieldValue::Bytes(vec![2, 4, 5, 0xb4])));
+        assert_eq!(layer.get_field("tcp.dataofs"), Some(FieldValue::Unset));
+        assert_eq!(layer.set_field("tcp.reserved", "8"), Err(FieldError::InvalidValue("8".to_string())));
+    }
+
+    #[test]
+    fn views() {
+        let mut buf = SYN.to_vec();
+        buf.extend_from_slice(b"data");
+
+        let view = TcpView::new(&buf).unwrap();
+        assert_eq!((view.data_offset(), view.header_len()), (10, 40));
+        assert_eq!(view.options_raw().len(), 20);
+        assert_eq!(view.payload(), b"data");
+
+        let mut view = TcpViewMut::new(&mut buf).unwrap();
+        view.set_flags(TcpFlags::SYN_ACK);
+        view.set_acknowledgment(1);
+        view.payload_mut()[0] = b'D';
+        assert_eq!(view.as_view().flags(), TcpFlags::SYN_ACK);
+        assert_eq!(view.as_view().data_offset(), 10);
+        assert_eq!(&buf[40..], b"Data");
+    }
+}
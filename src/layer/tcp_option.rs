@@ -0,0 +1,243 @@
+use super::*;
+
+registry_enum! {
+    /// The subtypes of Multipath TCP options (RFC 8684).
+    pub enum MptcpSubtype: u8 {
+        MpCapable = 0,
+        MpJoin = 1,
+        Dss = 2,
+        AddAddr = 3,
+        RemoveAddr = 4,
+        MpPrio = 5,
+        MpFail = 6,
+        MpFastclose = 7,
+        MpTcprst = 8,
+    }
+}
+
+/// A block of received data reported by a SACK option, from its first sequence number up to the one following its end.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct SackBlock {
+    pub left_edge: u32,
+    pub right_edge: u32,
+}
+
+/// An option in the header of a TCP segment.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum TcpOption {
+    /// Marks the end of the options. The remaining option bytes are padding.
+    EndOfList,
+    NoOperation,
+    /// The Maximum Segment Size the sender can receive.
+    Mss(u16),
+    /// The shift count of the receive window (RFC 7323).
+    WindowScale(u8),
+    /// Selective acknowledgments are supported (RFC 2018).
+    SackPermitted,
+    /// Selectively acknowledged blocks of data (RFC 2018).
+    Sack(Vec<SackBlock>),
+    /// Timestamps for round-trip time measurement and protection against wrapped sequence numbers (RFC 7323).
+    Timestamps { value: u32, echo_reply: u32 },
+    /// A TCP Fast Open cookie (RFC 7413). An empty cookie requests one from the server.
+    FastOpen(Vec<u8>),
+    /// A Multipath TCP option (RFC 8684). The data starts with the byte which holds the subtype in its upper four bits.
+    Mptcp { subtype: MptcpSubtype, data: Vec<u8> },
+    /// An option which is either not supported or whose contents do not match its kind.
+    Unknown { kind: u8, data: Vec<u8> },
+}
+
+impl TcpOption {
+    pub const END_OF_LIST: u8 = 0;
+    pub const NO_OPERATION: u8 = 1;
+    pub const MSS: u8 = 2;
+    pub const WINDOW_SCALE: u8 = 3;
+    pub const SACK_PERMITTED: u8 = 4;
+    pub const SACK: u8 = 5;
+    pub const TIMESTAMPS: u8 = 8;
+    pub const MPTCP: u8 = 30;
+    pub const FAST_OPEN: u8 = 34;
+
+    /// Returns the option kind.
+    pub fn kind(&self) -> u8 {
+        match self {
+            TcpOption::EndOfList => Self::END_OF_LIST,
+            TcpOption::NoOperation => Self::NO_OPERATION,
+            TcpOption::Mss(_) => Self::MSS,
+            TcpOption::WindowScale(_) => Self::WINDOW_SCALE,
+            TcpOption::SackPermitted => Self::SACK_PERMITTED,
+            TcpOption::Sack(_) => Self::SACK,
+            TcpOption::Timestamps { .. } => Self::TIMESTAMPS,
+            TcpOption::FastOpen(_) => Self::FAST_OPEN,
+            TcpOption::Mptcp { .. } => Self::MPTCP,
+            TcpOption::Unknown { kind, .. } => *kind,
+        }
+    }
+
+    /// Returns the length of the serialized option in bytes.
+    pub fn encoded_len(&self) -> usize {
+        match self {
+            TcpOption::EndOfList | TcpOption::NoOperation => 1,
+            TcpOption::Mss(_) => 4,
+            TcpOption::WindowScale(_) => 3,
+            TcpOption::SackPermitted => 2,
+            TcpOption::Sack(blocks) => 2 + blocks.len() * 8,
+            TcpOption::Timestamps { .. } => 10,
+            TcpOption::FastOpen(cookie) => 2 + cookie.len(),
+            TcpOption::Mptcp { data, .. } => 2 + data.len().max(1),
+            TcpOption::Unknown { data, .. } => 2 + data.len(),
+        }
+    }
+
+    /// Appends the serialized option to the buffer.
+    pub fn write(&self, buf: &mut Vec<u8>) {
+        buf.push(self.kind());
+        if let TcpOption::EndOfList | TcpOption::NoOperation = self {
+            return;
+        }
+
+        buf.push(self.encoded_len() as u8);
+
+        match self {
+            TcpOption::EndOfList | TcpOption::NoOperation | TcpOption::SackPermitted => {}
+            TcpOption::Mss(mss) => buf.extend_from_slice(&mss.to_be_bytes()),
+            TcpOption::WindowScale(shift) => buf.push(*shift),
+            TcpOption::Sack(blocks) => {
+                for block in blocks {
+                    buf.extend_from_slice(&block.left_edge.to_be_bytes());
+                    buf.extend_from_slice(&block.right_edge.to_be_bytes());
+                }
+            }
+            TcpOption::Timestamps { value, echo_reply } => {
+                buf.extend_from_slice(&value.to_be_bytes());
+                buf.extend_from_slice(&echo_reply.to_be_bytes());
+            }
+            TcpOption::FastOpen(cookie) => buf.extend_from_slice(cookie),
+            TcpOption::Mptcp { subtype, data } => match data.split_first() {
+                Some((first, rest)) => {
+                    buf.push(subtype.value() << 4 | first & 0x0f);
+                    buf.extend_from_slice(rest);
+                }
+                None => buf.push(subtype.value() << 4),
+            },
+            TcpOption::Unknown { data, .. } => buf.extend_from_slice(data),
+        }
+    }
+
+    /// Parses an option from the start of the buffer and returns it together with the number of bytes consumed.
+    ///
+    /// Options whose contents do not match their kind are returned as [`TcpOption::Unknown`], so that they are preserved.
+    /// Fails only if the length of the option is invalid.
+    pub fn parse(bytes: &[u8]) -> Result<(Self, usize), TcpError> {
+        let kind = *bytes.first().ok_or(TcpError::MalformedOption(0))?;
+
+        match kind {
+            Self::END_OF_LIST => return Ok((TcpOption::EndOfList, 1)),
+            Self::NO_OPERATION => return Ok((TcpOption::NoOperation, 1)),
+            _ => {}
+        }
+
+        let len = *bytes.get(1).ok_or(TcpError::MalformedOption(kind))? as usize;
+        if len < 2 || len > bytes.len() {
+            return Err(TcpError::MalformedOption(kind));
+        }
+
+        let data = &bytes[2..len];
+        let u32_at = |offset: usize| u32::from_be_bytes(data[offset..offset + 4].try_into().unwrap());
+
+        let option = match kind {
+            Self::MSS if data.len() == 2 => Some(TcpOption::Mss(u16::from_be_bytes([data[0], data[1]]))),
+            Self::WINDOW_SCALE if data.len() == 1 => Some(TcpOption::WindowScale(data[0])),
+            Self::SACK_PERMITTED if data.is_empty() => Some(TcpOption::SackPermitted),
+            Self::SACK if data.len().is_multiple_of(8) => Some(TcpOption::Sack(
+                data.chunks_exact(8)
+                    .map(|block| SackBlock {
+                        left_edge: u32::from_be_bytes(block[..4].try_into().unwrap()),
+                        right_edge: u32::from_be_bytes(block[4..].try_into().unwrap()),
+                    })
+                    .collect(),
+            )),
+            Self::TIMESTAMPS if data.len() == 8 => Some(TcpOption::Timestamps { value: u32_at(0), echo_reply: u32_at(4) }),
+            Self::FAST_OPEN => Some(TcpOption::FastOpen(data.to_vec())),
+            Self::MPTCP if !data.is_empty() => {
+                let data = [&[data[0] & 0x0f][..], &data[1..]].concat();
+                Some(TcpOption::Mptcp { subtype: MptcpSubtype::from_value(bytes[2] >> 4), data })
+            }
+            _ => None,
+        };
+
+        let option = option.unwrap_or_else(|| TcpOption::Unknown { kind, data: data.to_vec() });
+        Ok((option, len))
+    }
+}
+
+/// Parses the options of a header. Parsing stops after [`TcpOption::EndOfList`], since the remaining bytes are padding.
+pub fn parse_tcp_options(bytes: &[u8]) -> Result<Vec<TcpOption>, TcpError> {
+    split_tcp_options(bytes).map(|(options, _)| options)
+}
+
+/// Parses the options of a header like [`parse_tcp_options`], and also returns the padding after
+/// [`TcpOption::EndOfList`].
+pub fn split_tcp_options(mut bytes: &[u8]) -> Result<(Vec<TcpOption>, &[u8]), TcpError> {
+    let mut options = Vec::new();
+
+    while !bytes.is_empty() {
+        let (option, len) = TcpOption::parse(bytes)?;
+        bytes = &bytes[len..];
+
+        let end = option == TcpOption::EndOfList;
+        options.push(option);
+        if end {
+            break;
+        }
+    }
+
+    Ok((options, bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(option: TcpOption) -> Vec<u8> {
+        let mut buf = Vec::new();
+        option.write(&mut buf);
+        assert_eq!(buf.len(), option.encoded_len());
+        assert_eq!(TcpOption::parse(&buf), Ok((option, buf.len())));
+        buf
+    }
+
+    #[test]
+    fn options() {
+        assert_eq!(roundtrip(TcpOption::Mss(1460)), [2, 4, 0x05, 0xb4]);
+        assert_eq!(roundtrip(TcpOption::WindowScale(7)), [3, 3, 7]);
+        assert_eq!(roundtrip(TcpOption::SackPermitted), [4, 2]);
+        assert_eq!(roundtrip(TcpOption::NoOperation), [1]);
+
+        let blocks = vec![SackBlock { left_edge: 1000, right_edge: 2000 }, SackBlock { left_edge: 3000, right_edge: 4000 }];
+        assert_eq!(roundtrip(TcpOption::Sack(blocks)).len(), 18);
+
+        let buf = roundtrip(TcpOption::Timestamps { value: 0x01020304, echo_reply: 0 });
+        assert_eq!(buf[..6], [8, 10, 1, 2, 3, 4]);
+
+        assert_eq!(roundtrip(TcpOption::FastOpen(vec![])), [34, 2]);
+        assert_eq!(roundtrip(TcpOption::FastOpen(vec![0xaa; 8])).len(), 10);
+
+        let option = TcpOption::Mptcp { subtype: MptcpSubtype::MpCapable, data: vec![0x01, 0x81] };
+        assert_eq!(roundtrip(option), [30, 4, 0x01, 0x81]);
+        let option = TcpOption::Mptcp { subtype: MptcpSubtype::Dss, data: vec![0x00, 0x01] };
+        assert_eq!(roundtrip(option), [30, 4, 0x20, 0x01]);
+
+        assert_eq!(roundtrip(TcpOption::Unknown { kind: 254, data: vec![0xf9, 0x89] }), [254, 4, 0xf9, 0x89]);
+    }
+
+    #[test]
+    fn malformed() {
+        // An MSS option of the wrong length is preserved as unknown
+        assert_eq!(TcpOption::parse(&[2, 3, 5]), Ok((TcpOption::Unknown { kind: 2, data: vec![5] }, 3)));
+        assert_eq!(TcpOption::parse(&[2, 4, 5]), Err(TcpError::MalformedOption(2)));
+        assert_eq!(TcpOption::parse(&[8, 1]), Err(TcpError::MalformedOption(8)));
+
+        let options = parse_tcp_options(&[1, 1, 4, 2, 0, 0xff, 0xff]).unwrap();
+        assert_eq!(options, [TcpOption::NoOperation, TcpOption::NoOperation, TcpOption::SackPermitted, TcpOption::EndOfList]);
+    }
+}
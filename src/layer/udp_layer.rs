@@ -0,0 +1,352 @@
+use super::*;
+
+use crate::{reflect::*, Discriminator};
+
+/// A UDP header (RFC 768).
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct UdpLayer {
+    pub src_port: u16,
+    pub dst_port: u16,
+    /// The length of the header and the payload in bytes. If left unset, it is derived from the payload.
+    pub length: Option<u16>,
+    /// The checksum over the pseudo-header of the enclosing IP layer, the header and the payload. If left unset, it is
+    /// computed when the packet is finalized. Over IPv4, the value 0 means that no checksum was computed.
+    pub checksum: Option<u16>,
+}
+
+impl UdpLayer {
+    /// The length of the header in bytes.
+    pub const LEN: usize = 8;
+
+    /// The checksum value which means that no checksum was computed. It is only allowed over IPv4.
+    pub const NO_CHECKSUM: u16 = 0;
+
+    /// Constructs a UDP header between the specified ports. The length and the checksum are left unset.
+    pub fn new(src_port: u16, dst_port: u16) -> Self {
+        Self {
+            src_port,
+            dst_port,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the source and destination ports.
+    pub fn ports(&self) -> (u16, u16) {
+        (self.src_port, self.dst_port)
+    }
+
+    /// Computes the checksum of the datagram over the pseudo-header of the IP layer, as if the checksum field was zero.
+    ///
+    /// A computed value of zero is transmitted as `0xffff`, since zero means no checksum. Returns `None` if the layer is
+    /// neither IPv4 nor IPv6.
+    pub fn compute_checksum(&self, ip: &(dyn DynLayer + 'static), payload: &[u8]) -> Option<u16> {
+        let length = Self::LEN + payload.len();
+        let mut checksum = ip.pseudo_header(IpProtocol::Udp, length)?;
+
+        let mut buf = Vec::with_capacity(Self::LEN);
+        Self { checksum: Some(0), ..self.clone() }.write(&mut buf);
+        let checksum = checksum.add_bytes(&buf).add_bytes(payload).finish();
+
+        Some(if checksum == 0 { 0xffff } else { checksum })
+    }
+
+    /// Checks if the checksum matches the datagram carried by the IP layer.
+    ///
+    /// A missing checksum is valid over IPv4 but not over IPv6, where it is mandatory (RFC 8200).
+    pub fn is_checksum_valid(&self, ip: &(dyn DynLayer + 'static), payload: &[u8]) -> bool {
+        match self.checksum {
+            Some(Self::NO_CHECKSUM) => ip.is::<Ipv4Layer>(),
+            checksum => checksum.is_some() && checksum == self.compute_checksum(ip, payload),
+        }
+    }
+}
+
+impl Layer for UdpLayer {
+    const NAME: &'static str = "UDP";
+    const TYPE: LayerType = LayerType::UdpLayer;
+    const OSI_LEVEL: OsiLevel = OsiLevel::Transport;
+}
+
+impl DynLayer for UdpLayer {
+    fn header_len(&self) -> usize {
+        Self::LEN
+    }
+
+    /// The length field of jumbograms (RFC 2675) is zero, in which case the payload extends to the end of the IP packet.
+    fn payload_len(&self) -> Option<usize> {
+        (self.length? as usize).checked_sub(Self::LEN)
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.src_port.to_be_bytes());
+        buf.extend_from_slice(&self.dst_port.to_be_bytes());
+        buf.extend_from_slice(&self.length.unwrap_or(0).to_be_bytes());
+        buf.extend_from_slice(&self.checksum.unwrap_or(0).to_be_bytes());
+    }
+
+    /// Computes the checksum over the pseudo-header of the IP layer directly below. Without one, no checksum is set.
+    fn finalize(&mut self, ctx: &FinalizeContext) {
+        let payload = ctx.unpadded_payload();
+
+        if self.length.is_none() {
+            self.length = Some(u16::try_from(Self::LEN + payload.len()).unwrap_or(0));
+        }
+
+        if self.checksum.is_none() {
+            let checksum = match ctx.lower.last() {
+                Some(ip) => self.compute_checksum(ip.as_ref(), payload),
+                None => None,
+            };
+            self.checksum = Some(checksum.unwrap_or(Self::NO_CHECKSUM));
+        }
+    }
+
+    /// Application protocols are identified by the lower port first, since it is usually the well-known one.
+    fn next_discriminators(&self) -> Vec<Discriminator> {
+        let (low, high) = match self.src_port < self.dst_port {
+            true => (self.src_port, self.dst_port),
+            false => (self.dst_port, self.src_port),
+        };
+
+        vec![Discriminator::UdpPort(low), Discriminator::UdpPort(high)]
+    }
+
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        let view = UdpView::new(bytes)?;
+        Ok((view.to_layer(), Self::LEN))
+    }
+
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+}
+
+impl Reflect for UdpLayer {
+    fn fields(&self) -> Vec<Field> {
+        let uint = |value: Option<u16>| value.map_or(FieldValue::Unset, |value| FieldValue::UInt(value as u64));
+
+        vec![
+            Field::new("udp.sport", FieldKind::UInt, 0, 16, FieldValue::UInt(self.src_port as u64)),
+            Field::new("udp.dport", FieldKind::UInt, 16, 16, FieldValue::UInt(self.dst_port as u64)),
+            Field::new("udp.len", FieldKind::UInt, 32, 16, uint(self.length)).computed(),
+            Field::new("udp.chksum", FieldKind::UInt, 48, 16, uint(self.checksum)).computed(),
+        ]
+    }
+
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        let auto = value.trim() == "auto";
+
+        match path {
+            "udp.sport" => self.src_port = parse_uint_as(value)?,
+            "udp.dport" => self.dst_port = parse_uint_as(value)?,
+            "udp.len" if auto => self.length = None,
+            "udp.len" => self.length = Some(parse_uint_as(value)?),
+            "udp.chksum" if auto => self.checksum = None,
+            "udp.chksum" => self.checksum = Some(parse_uint_as(value)?),
+            _ => return Err(FieldError::UnknownField(path.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
+/// A borrowed, zero-copy view of a UDP header at the start of a byte slice.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct UdpView<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> UdpView<'a> {
+    /// Constructs a view of the UDP header at the start of the slice.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, LayerError> {
+        if bytes.len() < UdpLayer::LEN {
+            return Err(LayerError::BufferTooShort(UdpLayer::NAME));
+        }
+
+        Ok(Self { bytes })
+    }
+
+    fn u16_at(&self, offset: usize) -> u16 {
+        u16::from_be_bytes([self.bytes[offset], self.bytes[offset + 1]])
+    }
+
+    /// Returns the source port.
+    pub fn src_port(&self) -> u16 {
+        self.u16_at(0)
+    }
+
+    /// Returns the destination port.
+    pub fn dst_port(&self) -> u16 {
+        self.u16_at(2)
+    }
+
+    /// Returns the length of the header and the payload in bytes.
+    pub fn length(&self) -> u16 {
+        self.u16_at(4)
+    }
+
+    /// Returns the checksum.
+    pub fn checksum(&self) -> u16 {
+        self.u16_at(6)
+    }
+
+    /// Returns the bytes following the header, up to the end of the slice.
+    pub fn payload(&self) -> &'a [u8] {
+        &self.bytes[UdpLayer::LEN..]
+    }
+
+    /// Converts the view into an owned UDP layer.
+    pub fn to_layer(&self) -> UdpLayer {
+        UdpLayer {
+            src_port: self.src_port(),
+            dst_port: self.dst_port(),
+            length: Some(self.length()),
+            checksum: Some(self.checksum()),
+        }
+    }
+}
+
+/// A mutable, zero-copy view of a UDP header at the start of a byte slice, which allows fields to be edited in place.
+#[derive(Debug, PartialEq, Eq)]
+pub struct UdpViewMut<'a> {
+    bytes: &'a mut [u8],
+}
+
+impl<'a> UdpViewMut<'a> {
+    /// Constructs a mutable view of the UDP header at the start of the slice.
+    pub fn new(bytes: &'a mut [u8]) -> Result<Self, LayerError> {
+        UdpView::new(bytes)?;
+        Ok(Self { bytes })
+    }
+
+    /// Returns a read-only view of the header.
+    pub fn as_view(&self) -> UdpView<'_> {
+        UdpView { bytes: self.bytes }
+    }
+
+    /// Sets the source port.
+    pub fn set_src_port(&mut self, port: u16) {
+        self.bytes[0..2].copy_from_slice(&port.to_be_bytes());
+    }
+
+    /// Sets the destination port.
+    pub fn set_dst_port(&mut self, port: u16) {
+        self.bytes[2..4].copy_from_slice(&port.to_be_bytes());
+    }
+
+    /// Sets the checksum.
+    pub fn set_checksum(&mut self, checksum: u16) {
+        self.bytes[6..8].copy_from_slice(&checksum.to_be_bytes());
+    }
+
+    /// Returns the bytes following the header.
+    pub fn payload_mut(&mut self) -> &mut [u8] {
+        &mut self.bytes[UdpLayer::LEN..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Ipv4Addr, Ipv6Addr, Packet};
+
+    fn ipv4() -> Ipv4Layer {
+        Ipv4Layer::new(Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 0, 199))
+    }
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(UdpLayer::NAME, "UDP");
+        assert_eq!(UdpLayer::TYPE, LayerType::UdpLayer);
+        assert_eq!(UdpLayer::OSI_LEVEL, OsiLevel::Transport);
+    }
+
+    #[test]
+    fn write_and_parse() {
+        let layer = UdpLayer {
+            length: Some(12),
+            checksum: Some(0xabcd),
+            ..UdpLayer::new(1234, 53)
+        };
+        let mut buf = Vec::new();
+        layer.write(&mut buf);
+
+        assert_eq!(buf, [0x04, 0xd2, 0x00, 0x35, 0x00, 0x0c, 0xab, 0xcd]);
+        assert_eq!(UdpLayer::parse(&buf), Ok((layer.clone(), 8)));
+        assert_eq!(UdpLayer::parse(&buf[..7]), Err(LayerError::BufferTooShort("UDP")));
+        assert_eq!(layer.payload_len(), Some(4));
+        assert_eq!(layer.ports(), (1234, 53));
+        assert_eq!(layer.next_discriminators(), [Discriminator::UdpPort(53), Discriminator::UdpPort(1234)]);
+    }
+
+    #[test]
+    fn checksum_ipv4() {
+        let packet = ipv4() / UdpLayer::new(1234, 53) / b"abcd" / PaddingLayer::zeroed(2);
+        let bytes = packet.to_bytes();
+        assert_eq!(bytes[24..26], [0x00, 0x0c]);
+
+        let mut sum = ipv4().pseudo_header(IpProtocol::Udp, 12);
+        assert_eq!(sum.add_bytes(&bytes[20..32]).finish(), 0);
+
+        let (layer, _) = UdpLayer::parse(&bytes[20..]).unwrap();
+        assert!(layer.is_checksum_valid(&ipv4(), b"abcd"));
+        assert!(!layer.is_checksum_valid(&ipv4(), b"abce"));
+
+        // Zero means that no checksum was computed, which is only allowed over IPv4
+        let layer = UdpLayer { checksum: Some(0), ..layer };
+        assert!(layer.is_checksum_valid(&ipv4(), b"abce"));
+        let ipv6 = Ipv6Layer::new(Ipv6Addr::loopback(), Ipv6Addr::loopback());
+        assert!(!layer.is_checksum_valid(&ipv6, b"abce"));
+
+        let packet = ipv4() / UdpLayer { checksum: Some(0), ..UdpLayer::new(1, 2) };
+        assert_eq!(packet.to_bytes()[26..28], [0, 0]);
+
+        // Without an IP layer there is no pseudo-header
+        assert_eq!(Packet::from(UdpLayer::new(1, 2)).to_bytes()[6..], [0, 0]);
+    }
+
+    #[test]
+    fn checksum_ipv6() {
+        let mut ip = Ipv6Layer::new(Ipv6Addr::loopback(), Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+        ip.push_extension(Ipv6Extension::Destination(vec![]));
+        let packet = ip.clone() / UdpLayer::new(5353, 5353) / b"xyz";
+        let bytes = packet.to_bytes();
+        assert_eq!(bytes[48 + 4..48 + 6], [0x00, 0x0b]);
+
+        let (layer, _) = UdpLayer::parse(&bytes[48..]).unwrap();
+        assert!(layer.is_checksum_valid(&ip, b"xyz"));
+        assert_ne!(layer.checksum, Some(0));
+    }
+
+    #[test]
+    fn reflect() {
+        let mut layer = UdpLayer::new(1, 2);
+        layer.set_field("udp.dport", "0x35").unwrap();
+        layer.set_field("udp.chksum", "0").unwrap();
+        assert_eq!(layer.get_field("udp.dport"), Some(FieldValue::UInt(53)));
+        assert_eq!(layer.get_field("udp.len"), Some(FieldValue::Unset));
+        assert_eq!(layer.checksum, Some(0));
+        assert_eq!(layer.set_field("udp.sport", "65536"), Err(FieldError::InvalidValue("65536".to_string())));
+    }
+
+    #[test]
+    fn views() {
+        let mut buf = [0x04, 0xd2, 0x00, 0x35, 0x00, 0x0a, 0x00, 0x00, 1, 2];
+
+        let view = UdpView::new(&buf).unwrap();
+        assert_eq!((view.src_port(), view.dst_port(), view.length()), (1234, 53, 10));
+        assert_eq!(view.payload(), [1, 2]);
+
+        let mut view = UdpViewMut::new(&mut buf).unwrap();
+        view.set_dst_port(5353);
+        view.set_checksum(0xffff);
+        view.payload_mut()[0] = 9;
+        assert_eq!(view.as_view().to_layer().dst_port, 5353);
+        assert_eq!(buf[6..], [0xff, 0xff, 9, 2]);
+        assert!(UdpView::new(&buf[..4]).is_err());
+    }
+}
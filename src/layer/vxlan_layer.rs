@@ -0,0 +1,260 @@
+use super::*;
+
+use crate::{reflect::*, Discriminator};
+
+registry_enum! {
+    /// The payload protocols of VXLAN-GPE.
+    pub enum VxlanNextProtocol: u8 {
+        IPv4 = 1,
+        IPv6 = 2,
+        Ethernet = 3,
+        /// Network service header.
+        Nsh = 4,
+        Mpls = 5,
+    }
+}
+
+impl VxlanNextProtocol {
+    /// Returns the protocol which identifies the specified layer type as a payload, if there is one.
+    pub fn of_layer(layer_type: LayerType) -> Option<Self> {
+        match layer_type {
+            LayerType::Ipv4Layer => Some(VxlanNextProtocol::IPv4),
+            LayerType::Ipv6Layer => Some(VxlanNextProtocol::IPv6),
+            LayerType::EthLayer => Some(VxlanNextProtocol::Ethernet),
+            _ => None,
+        }
+    }
+
+    /// Returns the EtherType of the same protocol, which is used to dispatch the payload.
+    pub fn ether_type(self) -> Option<EtherType> {
+        match self {
+            VxlanNextProtocol::IPv4 => Some(EtherType::IPv4),
+            VxlanNextProtocol::IPv6 => Some(EtherType::IPv6),
+            VxlanNextProtocol::Ethernet => Some(EtherType::TransparentEthernetBridging),
+            VxlanNextProtocol::Nsh => Some(EtherType::Nsh),
+            VxlanNextProtocol::Mpls => Some(EtherType::Mpls),
+            VxlanNextProtocol::Unknown(_) => None,
+        }
+    }
+}
+
+/// A VXLAN header (RFC 7348), which carries an Ethernet frame of a virtual network over UDP.
+///
+/// With the P flag set, the header is a Generic Protocol Extension (VXLAN-GPE) header, which announces the protocol of
+/// its payload.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct VxlanLayer {
+    /// The flags, of which the I flag must be set for the VNI to be valid.
+    pub flags: u8,
+    /// The 16 reserved bits after the flags.
+    pub reserved1: u16,
+    /// The protocol of the payload in VXLAN-GPE, which is a reserved byte in plain VXLAN. If left unset in VXLAN-GPE, it
+    /// is derived from the next layer.
+    pub next_protocol: Option<VxlanNextProtocol>,
+    /// The 24-bit VXLAN Network Identifier.
+    pub vni: u32,
+    /// The 8 reserved bits after the VNI.
+    pub reserved2: u8,
+}
+
+impl VxlanLayer {
+    /// The length of the header in bytes.
+    pub const LEN: usize = 8;
+
+    /// The UDP port of VXLAN.
+    pub const PORT: u16 = 4789;
+
+    /// The UDP port of VXLAN-GPE.
+    pub const GPE_PORT: u16 = 4790;
+
+    /// The largest 24-bit VNI.
+    pub const MAX_VNI: u32 = 0xff_ffff;
+
+    /// The flag which indicates that the VNI is valid.
+    pub const FLAG_VNI: u8 = 0x08;
+
+    /// The VXLAN-GPE flag which indicates that the next protocol field is present.
+    pub const FLAG_NEXT_PROTOCOL: u8 = 0x04;
+
+    /// The VXLAN-GPE flag which indicates a Broadcast, Unknown unicast or Multicast packet.
+    pub const FLAG_BUM: u8 = 0x02;
+
+    /// The VXLAN-GPE flag which indicates an Operations, Administration and Maintenance packet.
+    pub const FLAG_OAM: u8 = 0x01;
+
+    /// Constructs a VXLAN header for the virtual network.
+    pub fn new(vni: u32) -> Self {
+        Self {
+            flags: Self::FLAG_VNI,
+            reserved1: 0,
+            next_protocol: None,
+            vni: vni & Self::MAX_VNI,
+            reserved2: 0,
+        }
+    }
+
+    /// Constructs a VXLAN-GPE header for the virtual network. The next protocol is left unset.
+    pub fn gpe(vni: u32) -> Self {
+        Self {
+            flags: Self::FLAG_VNI | Self::FLAG_NEXT_PROTOCOL,
+            ..Self::new(vni)
+        }
+    }
+
+    /// Checks if the header is a VXLAN-GPE header, i.e. the P flag is set.
+    pub fn is_gpe(&self) -> bool {
+        self.flags & Self::FLAG_NEXT_PROTOCOL != 0
+    }
+
+    /// Returns the 2-bit VXLAN-GPE version.
+    pub fn gpe_version(&self) -> u8 {
+        (self.flags >> 4) & 0x03
+    }
+}
+
+impl Default for VxlanLayer {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl Layer for VxlanLayer {
+    const NAME: &'static str = "VXLAN";
+    const TYPE: LayerType = LayerType::VxlanLayer;
+    const OSI_LEVEL: OsiLevel = OsiLevel::Network;
+}
+
+impl DynLayer for VxlanLayer {
+    fn header_len(&self) -> usize {
+        Self::LEN
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.push(self.flags);
+        buf.extend_from_slice(&self.reserved1.to_be_bytes());
+        buf.push(self.next_protocol.map_or(0, |protocol| protocol.value()));
+        buf.extend_from_slice(&(self.vni << 8 | self.reserved2 as u32).to_be_bytes());
+    }
+
+    fn finalize(&mut self, ctx: &FinalizeContext) {
+        if self.is_gpe() && self.next_protocol.is_none() {
+            self.next_protocol = ctx.next().and_then(|next| VxlanNextProtocol::of_layer(next.layer_type()));
+        }
+    }
+
+    /// The payload of plain VXLAN is an Ethernet frame, while VXLAN-GPE payloads are dispatched by their EtherType.
+    fn next_discriminators(&self) -> Vec<Discriminator> {
+        let ether_type = match self.is_gpe() {
+            true => self.next_protocol.and_then(VxlanNextProtocol::ether_type),
+            false => Some(EtherType::TransparentEthernetBridging),
+        };
+
+        ether_type.map(|ether_type| Discriminator::EtherType(ether_type.value())).into_iter().collect()
+    }
+
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+        if bytes.len() < Self::LEN {
+            return Err(LayerError::BufferTooShort(Self::NAME));
+        }
+
+        let layer = Self {
+            flags: bytes[0],
+            reserved1: u16::from_be_bytes([bytes[1], bytes[2]]),
+            next_protocol: (bytes[0] & Self::FLAG_NEXT_PROTOCOL != 0 || bytes[3] != 0)
+                .then(|| VxlanNextProtocol::from_value(bytes[3])),
+            vni: u32::from_be_bytes(bytes[4..8].try_into().unwrap()) >> 8,
+            reserved2: bytes[7],
+        };
+
+        Ok((layer, Self::LEN))
+    }
+
+    fn as_reflect(&self) -> Option<&dyn Reflect> {
+        Some(self)
+    }
+
+    fn as_reflect_mut(&mut self) -> Option<&mut dyn Reflect> {
+        Some(self)
+    }
+}
+
+impl Reflect for VxlanLayer {
+    fn fields(&self) -> Vec<Field> {
+        let uint = |value: u64| FieldValue::UInt(value);
+        let next_protocol = self.next_protocol.map_or(FieldValue::Unset, |protocol| protocol.field_value());
+
+        vec![
+            Field::new("vxlan.flags", FieldKind::UInt, 0, 8, uint(self.flags as u64)),
+            Field::new("vxlan.reserved1", FieldKind::UInt, 8, 16, uint(self.reserved1 as u64)),
+            Field::new("vxlan.nextproto", FieldKind::Enum, 24, 8, next_protocol).computed(),
+            Field::new("vxlan.vni", FieldKind::UInt, 32, 24, uint(self.vni as u64)),
+            Field::new("vxlan.reserved2", FieldKind::UInt, 56, 8, uint(self.reserved2 as u64)),
+        ]
+    }
+
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        match path {
+            "vxlan.flags" => self.flags = parse_uint_as(value)?,
+            "vxlan.reserved1" => self.reserved1 = parse_uint_as(value)?,
+            "vxlan.nextproto" if value.trim() == "auto" => self.next_protocol = None,
+            "vxlan.nextproto" => self.next_protocol = Some(VxlanNextProtocol::parse_field(value)?),
+            "vxlan.vni" => match parse_uint_as::<u32>(value)? {
+                vni if vni <= Self::MAX_VNI => self.vni = vni,
+                _ => return Err(FieldError::InvalidValue(value.to_string())),
+            },
+            "vxlan.reserved2" => self.reserved2 = parse_uint_as(value)?,
+            _ => return Err(FieldError::UnknownField(path.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Ipv6Addr, Packet};
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(VxlanLayer::NAME, "VXLAN");
+        assert_eq!(VxlanLayer::TYPE, LayerType::VxlanLayer);
+        assert_eq!(VxlanLayer::OSI_LEVEL, OsiLevel::Network);
+    }
+
+    #[test]
+    fn write_and_parse() {
+        let layer = VxlanLayer::new(0x123456);
+        let bytes = Packet::from(layer.clone()).to_bytes();
+        assert_eq!(bytes, [0x08, 0, 0, 0, 0x12, 0x34, 0x56, 0x00]);
+        assert_eq!(VxlanLayer::parse(&bytes), Ok((layer.clone(), 8)));
+        assert_eq!(VxlanLayer::parse(&bytes[..7]), Err(LayerError::BufferTooShort("VXLAN")));
+        assert!(!layer.is_gpe());
+        assert_eq!(layer.next_discriminators(), [Discriminator::EtherType(0x6558)]);
+    }
+
+    #[test]
+    fn gpe() {
+        let bytes = (VxlanLayer::gpe(7) / Ipv6Layer::new(Ipv6Addr::loopback(), Ipv6Addr::loopback())).to_bytes();
+        assert_eq!(bytes[..8], [0x0c, 0, 0, 0x02, 0, 0, 0x07, 0]);
+
+        let (layer, _) = VxlanLayer::parse(&bytes).unwrap();
+        assert!(layer.is_gpe());
+        assert_eq!(layer.gpe_version(), 0);
+        assert_eq!(layer.next_protocol, Some(VxlanNextProtocol::IPv6));
+        assert_eq!(layer.next_discriminators(), [Discriminator::EtherType(0x86dd)]);
+
+        let layer = VxlanLayer { next_protocol: Some(VxlanNextProtocol::Unknown(0x7f)), ..VxlanLayer::gpe(7) };
+        assert_eq!(layer.next_discriminators(), []);
+    }
+
+    #[test]
+    fn reflect() {
+        let mut layer = VxlanLayer::new(1);
+        layer.set_field("vxlan.vni", "0xffffff").unwrap();
+        layer.set_field("vxlan.nextproto", "Nsh").unwrap();
+        assert_eq!(layer.get_field("vxlan.vni"), Some(FieldValue::UInt(0xffffff)));
+        assert_eq!(layer.next_protocol, Some(VxlanNextProtocol::Nsh));
+        assert_eq!(layer.set_field("vxlan.vni", "0x1000000"), Err(FieldError::InvalidValue("0x1000000".to_string())));
+    }
+}
@@ -1,3 +1,6 @@
+#[macro_use]
+mod macros;
+
 pub mod ip_address;
 pub use ip_address::*;
 
@@ -7,18 +10,25 @@ pub use mac_address::*;
 pub mod layer;
 pub use layer::*;
 
+pub mod packet;
+pub use packet::*;
+
+pub mod dissector;
+pub use dissector::*;
+
+pub mod reflect;
+pub use reflect::*;
+
+pub mod show;
+
+pub mod diff;
+pub use diff::*;
+
+pub mod checksum;
+pub use checksum::*;
+
 pub mod interface;
 pub use interface::*;
 
 pub mod primitives;
 pub use primitives::*;
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test() {
-        
-    }
-}
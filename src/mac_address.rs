@@ -1,7 +1,10 @@
 use std::fmt;
+use std::str::FromStr;
 
 use thiserror::Error;
 
+use crate::{Ipv4Addr, Ipv6Addr};
+
 #[derive(Error, Copy, Debug, PartialEq, Eq, Clone)]
 pub enum MacAddressError
 {
@@ -20,6 +23,7 @@ impl MacAddr {
     }
 
     /// Attempts to construct a MAC address from a string.
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(string: &str) -> Result<Self, MacAddressError> {
         let mut instance = MacAddr::default();
 
@@ -37,10 +41,10 @@ impl MacAddr {
             instance.4 = bytes[4];
             instance.5 = bytes[5];
 
-            return Ok(instance);
+            Ok(instance)
         }
         else {
-            return Err(MacAddressError::InvalidAddress);
+            Err(MacAddressError::InvalidAddress)
         }
     }
 
@@ -49,6 +53,44 @@ impl MacAddr {
         MacAddr(0xff, 0xff, 0xff, 0xff, 0xff, 0xff)
     }
 
+    /// Returns the six octets of the MAC address in transmission order.
+    pub const fn octets(&self) -> [u8; 6] {
+        [self.0, self.1, self.2, self.3, self.4, self.5]
+    }
+
+    /// Constructs the Ethernet multicast address to which packets for the IPv4 multicast address are sent, which carries
+    /// the low 23 bits of the address (RFC 1112).
+    pub const fn ipv4_multicast(addr: &Ipv4Addr) -> Self {
+        let octets = addr.octets();
+        MacAddr(0x01, 0x00, 0x5e, octets[1] & 0x7f, octets[2], octets[3])
+    }
+
+    /// Constructs the Ethernet multicast address to which packets for the IPv6 multicast address are sent (RFC 2464).
+    pub const fn ipv6_multicast(addr: &Ipv6Addr) -> Self {
+        let segments = addr.segments();
+        MacAddr(0x33, 0x33, (segments[6] >> 8) as u8, segments[6] as u8, (segments[7] >> 8) as u8, segments[7] as u8)
+    }
+
+    /// Checks if the address is a group address, i.e. the least significant bit of its first octet is set.
+    pub const fn is_multicast(&self) -> bool {
+        self.0 & 0x01 != 0
+    }
+
+}
+
+impl From<[u8; 6]> for MacAddr {
+    fn from(octets: [u8; 6]) -> Self {
+        Self(octets[0], octets[1], octets[2], octets[3], octets[4], octets[5])
+    }
+}
+
+impl FromStr for MacAddr {
+    type Err = MacAddressError;
+
+    /// Attempts to construct a MAC address from a string.
+    fn from_str(string: &str) -> Result<Self, MacAddressError> {
+        MacAddr::from_str(string)
+    }
 }
 
 impl From<pnet_datalink::MacAddr> for MacAddr {
@@ -76,6 +118,17 @@ mod tests {
         assert_eq!(MacAddr::from_str("FF:FF:FF:FF:FF:FF"), Ok(MacAddr::broadcast()));
         assert_eq!(MacAddr::from_str("00:12:FF:E3:A4:78"), Ok(MacAddr::new(0x00, 0x12, 0xff, 0xe3, 0xa4,0x78)));
         assert_eq!(MacAddr::from_str("AX:BR:13:FA:98:KO"), Err(MacAddressError::InvalidAddress));
+    }
+
+    #[test]
+    fn multicast() {
+        let addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 1, 0xff33, 0x4455);
+        assert_eq!(MacAddr::ipv6_multicast(&addr), MacAddr::new(0x33, 0x33, 0xff, 0x33, 0x44, 0x55));
+        assert!(MacAddr::ipv6_multicast(&addr).is_multicast());
+        let addr = Ipv4Addr::new(239, 129, 2, 3);
+        assert_eq!(MacAddr::ipv4_multicast(&addr), MacAddr::new(0x01, 0x00, 0x5e, 0x01, 0x02, 0x03));
+        assert!(MacAddr::broadcast().is_multicast());
+        assert!(!MacAddr::new(0x00, 0x12, 0xff, 0xe3, 0xa4, 0x78).is_multicast());
 
     }
 }
\ No newline at end of file
@@ -0,0 +1,85 @@
+/// Defines an enumeration of the registered values of a protocol field, such as EtherTypes or IP protocol numbers.
+///
+/// Every value which is not in the registry is represented by the generated `Unknown` variant, so that converting from
+/// the underlying integer never fails.
+macro_rules! registry_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident: $repr:ty {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident = $value:expr,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+        $vis enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )*
+            /// A value which is not in the registry.
+            Unknown($repr),
+        }
+
+        impl $name {
+            /// Returns the underlying value.
+            pub const fn value(self) -> $repr {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Unknown(value) => value,
+                }
+            }
+
+            /// Looks the value up in the registry.
+            pub const fn from_value(value: $repr) -> Self {
+                match value {
+                    $(v if v == $value => $name::$variant,)*
+                    value => $name::Unknown(value),
+                }
+            }
+
+            /// Returns the name of the value, or `Unknown` if it is not in the registry.
+            pub const fn name(self) -> &'static str {
+                match self {
+                    $($name::$variant => stringify!($variant),)*
+                    $name::Unknown(_) => "Unknown",
+                }
+            }
+
+            /// Looks a registered value up by its name.
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $(stringify!($variant) => Some($name::$variant),)*
+                    _ => None,
+                }
+            }
+
+            /// Returns the value as a reflected enumeration field.
+            #[allow(dead_code)]
+            pub(crate) fn field_value(self) -> $crate::FieldValue {
+                $crate::FieldValue::Enum {
+                    type_name: stringify!($name),
+                    name: self.name().to_string(),
+                    value: self.value() as u64,
+                }
+            }
+
+            /// Parses a value given either by its name or as a number.
+            #[allow(dead_code)]
+            pub(crate) fn parse_field(value: &str) -> Result<Self, $crate::FieldError> {
+                match Self::from_name(value.trim()) {
+                    Some(parsed) => Ok(parsed),
+                    None => Ok(Self::from_value($crate::parse_uint_as::<$repr>(value)?)),
+                }
+            }
+        }
+
+        impl From<$name> for $repr {
+            fn from(value: $name) -> Self {
+                value.value()
+            }
+        }
+    };
+}
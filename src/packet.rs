@@ -0,0 +1,358 @@
+use std::{fmt, ops::Div, sync::PoisonError};
+
+use crate::{layer::*, DissectorRegistry, FieldError, FieldValue, LinkType};
+
+/// A packet composed of an ordered stack of layers, starting with the lowest one.
+///
+/// Packets can be built Scapy-style by dividing layers, e.g. `EthLayer::default() / other_layer`.
+#[derive(Debug, Clone, Default)]
+pub struct Packet {
+    layers: Vec<Box<dyn DynLayer>>,
+}
+
+impl Packet {
+    /// Constructs an empty packet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decodes a packet from raw bytes using the global [`DissectorRegistry`].
+    pub fn parse(bytes: &[u8], link_type: LinkType) -> Result<Self, LayerError> {
+        DissectorRegistry::global().read().unwrap_or_else(PoisonError::into_inner).dissect(bytes, link_type)
+    }
+
+    /// Decodes a packet from raw bytes using the specified registry.
+    pub fn parse_with(bytes: &[u8], link_type: LinkType, registry: &DissectorRegistry) -> Result<Self, LayerError> {
+        registry.dissect(bytes, link_type)
+    }
+
+    /// Returns the layers of the packet, starting with the lowest one.
+    pub fn layers(&self) -> &[Box<dyn DynLayer>] {
+        &self.layers
+    }
+
+    /// Returns the number of layers in the packet.
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Checks if the packet contains no layers.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Returns the first layer of type `T`, if the packet contains one.
+    pub fn get<T: DynLayer + 'static>(&self) -> Option<&T> {
+        self.layers.iter().find_map(|layer| layer.downcast_ref::<T>())
+    }
+
+    /// Returns a mutable reference to the first layer of type `T`, if the packet contains one.
+    pub fn get_mut<T: DynLayer + 'static>(&mut self) -> Option<&mut T> {
+        self.layers.iter_mut().find_map(|layer| layer.downcast_mut::<T>())
+    }
+
+    /// Appends a layer on top of the stack.
+    pub fn push<L: DynLayer + 'static>(&mut self, layer: L) {
+        self.layers.push(Box::new(layer));
+    }
+
+    /// Appends an already boxed layer on top of the stack.
+    pub fn push_boxed(&mut self, layer: Box<dyn DynLayer>) {
+        self.layers.push(layer);
+    }
+
+    /// Inserts a layer at the specified position in the stack.
+    ///
+    /// Panics if `index > len`.
+    pub fn insert<L: DynLayer + 'static>(&mut self, index: usize, layer: L) {
+        self.layers.insert(index, Box::new(layer));
+    }
+
+    /// Removes and returns the layer at the specified position in the stack.
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> Box<dyn DynLayer> {
+        self.layers.remove(index)
+    }
+
+    /// Returns the value of the field with the specified path, e.g. `eth.vlan.vid`, from the first layer which has it.
+    pub fn get_field(&self, path: &str) -> Option<FieldValue> {
+        self.layers
+            .iter()
+            .filter_map(|layer| layer.as_reflect())
+            .find_map(|layer| layer.get_field(path))
+    }
+
+    /// Parses the string and assigns it to the field with the specified path in the first layer which has it.
+    pub fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError> {
+        for layer in self.layers.iter_mut().filter_map(|layer| layer.as_reflect_mut()) {
+            match layer.set_field(path, value) {
+                Err(FieldError::UnknownField(_)) => continue,
+                result => return result,
+            }
+        }
+
+        Err(FieldError::UnknownField(path.to_string()))
+    }
+
+    /// Fills in the derived fields of every layer which were left unset, such as types, lengths and checksums.
+    ///
+    /// Layers are finalized from the top of the stack downwards, so that each layer sees its payload in its final form.
+    pub fn finalize(&mut self) {
+        self.build();
+    }
+
+    /// Serializes every layer of the packet, starting with the lowest one.
+    ///
+    /// The derived fields are computed on a copy of the packet, see [`Packet::finalize`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.clone().build()
+    }
+
+    /// Finalizes the layers of the packet and returns their serialized form.
+    pub(crate) fn build(&mut self) -> Vec<u8> {
+        let mut payload = Vec::new();
+
+        for i in (0..self.layers.len()).rev() {
+            let (lower, rest) = self.layers.split_at_mut(i);
+            let (layer, upper) = rest.split_first_mut().unwrap();
+
+            layer.finalize(&FinalizeContext {
+                lower,
+                upper,
+                payload: &payload,
+            });
+
+            let mut buf = Vec::with_capacity(layer.header_len() + payload.len());
+            layer.write(&mut buf);
+            buf.extend_from_slice(&payload);
+            payload = buf;
+        }
+
+        payload
+    }
+}
+
+impl<L: DynLayer + 'static> From<L> for Packet {
+    fn from(layer: L) -> Self {
+        Self {
+            layers: vec![Box::new(layer)],
+        }
+    }
+}
+
+impl From<Box<dyn DynLayer>> for Packet {
+    fn from(layer: Box<dyn DynLayer>) -> Self {
+        Self { layers: vec![layer] }
+    }
+}
+
+impl From<Vec<u8>> for Packet {
+    fn from(data: Vec<u8>) -> Self {
+        Packet::from(RawLayer::from(data))
+    }
+}
+
+impl From<&[u8]> for Packet {
+    fn from(data: &[u8]) -> Self {
+        Packet::from(RawLayer::from(data))
+    }
+}
+
+impl<const N: usize> From<&[u8; N]> for Packet {
+    fn from(data: &[u8; N]) -> Self {
+        Packet::from(RawLayer::from(data))
+    }
+}
+
+impl<R: Into<Packet>> Div<R> for Packet {
+    type Output = Packet;
+
+    /// Stacks the right-hand side on top of the packet.
+    fn div(mut self, rhs: R) -> Packet {
+        self.layers.extend(rhs.into().layers);
+        self
+    }
+}
+
+/// Implements `/` composition for layer types, so that `lower / upper` produces a [`Packet`].
+macro_rules! impl_layer_div {
+    ($($layer:ty),* $(,)?) => {
+        $(
+            impl<R: Into<Packet>> Div<R> for $layer {
+                type Output = Packet;
+
+                fn div(self, rhs: R) -> Packet {
+                    Packet::from(self) / rhs
+                }
+            }
+        )*
+    };
+}
+
+impl_layer_div!(EthLayer, RawLayer, PaddingLayer, LlcLayer, SnapLayer, Ipv4Layer, Ipv6Layer, ArpLayer, IcmpLayer, Icmpv6Layer, IgmpLayer, GreLayer, VxlanLayer, GeneveLayer, UdpLayer, TcpLayer, DnsLayer, DhcpLayer, Dhcpv6Layer);
+
+impl fmt::Display for Packet {
+
+    /// Display the packet as the names of its layers separated by '/'.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, layer) in self.layers.iter().enumerate() {
+            if i > 0 {
+                write!(f, " / ")?;
+            }
+
+            write!(f, "{}", layer.name())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MacAddr;
+
+    fn eth(ether_type: Option<EtherType>) -> EthLayer {
+        EthLayer {
+            ether_type: ether_type.map(TypeOrLength::Type),
+            ..EthLayer::new(MacAddr::new(0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff), MacAddr::broadcast(), EtherType::IPv4, vec![])
+        }
+    }
+
+    #[test]
+    fn compose() {
+        let packet = eth(Some(EtherType::IPv4)) / eth(None);
+        assert_eq!(packet.len(), 2);
+        assert_eq!(packet.layers()[0].downcast_ref::<EthLayer>(), Some(&eth(Some(EtherType::IPv4))));
+        assert_eq!(packet.layers()[1].downcast_ref::<EthLayer>(), Some(&eth(None)));
+
+        let packet = packet / (eth(None) / eth(Some(EtherType::IPv4)));
+        assert_eq!(packet.len(), 4);
+        assert_eq!(packet.to_string(), "Ethernet / Ethernet / Ethernet / Ethernet");
+        assert_eq!(Packet::new().to_string(), "");
+    }
+
+    #[test]
+    fn access_layers() {
+        let mut packet = eth(Some(EtherType::IPv4)) / eth(None);
+        assert_eq!(packet.get::<EthLayer>(), Some(&eth(Some(EtherType::IPv4))));
+
+        packet.get_mut::<EthLayer>().unwrap().ether_type = None;
+        assert_eq!(packet.get::<EthLayer>().unwrap().ether_type, None);
+
+        packet.insert(0, eth(Some(EtherType::IPv4)));
+        assert_eq!(packet.len(), 3);
+        assert_eq!(packet.get::<EthLayer>(), Some(&eth(Some(EtherType::IPv4))));
+
+        let removed = packet.remove(0);
+        assert_eq!(removed.downcast_ref::<EthLayer>(), Some(&eth(Some(EtherType::IPv4))));
+        assert_eq!(packet.len(), 2);
+        assert!(!packet.is_empty());
+        assert!(Packet::new().is_empty());
+        assert_eq!(Packet::new().get::<EthLayer>(), None);
+    }
+
+    /// A layer which stores the length of its payload, used to test finalization.
+    #[derive(Debug, Clone, Default)]
+    struct LengthLayer {
+        length: Option<u16>,
+    }
+
+    impl Layer for LengthLayer {
+        const NAME: &'static str = "Length";
+        const TYPE: LayerType = LayerType::Custom;
+        const OSI_LEVEL: OsiLevel = OsiLevel::Network;
+    }
+
+    impl DynLayer for LengthLayer {
+        fn header_len(&self) -> usize {
+            2
+        }
+
+        fn write(&self, buf: &mut Vec<u8>) {
+            buf.extend_from_slice(&self.length.unwrap_or(0).to_be_bytes());
+        }
+
+        fn finalize(&mut self, ctx: &FinalizeContext) {
+            self.length.get_or_insert(ctx.unpadded_payload().len() as u16);
+        }
+
+        fn parse(bytes: &[u8]) -> Result<(Self, usize), LayerError> {
+            let length = u16::from_be_bytes([bytes[0], bytes[1]]);
+            Ok((Self { length: Some(length) }, 2))
+        }
+    }
+
+    #[test]
+    fn finalize() {
+        let mut packet = eth(None) / LengthLayer::default() / LengthLayer::default() / eth(None);
+        let bytes = packet.to_bytes();
+
+        // The packet itself is left untouched by serialization
+        assert_eq!(packet.get::<EthLayer>().unwrap().ether_type, None);
+        assert_eq!(packet.get::<LengthLayer>().unwrap().length, None);
+
+        // A custom layer has no EtherType, so the type of the Ethernet header preceding it stays unset
+        assert_eq!(&bytes[12..14], &[0x00, 0x00]);
+        assert_eq!(&bytes[14..16], &[0x00, 0x10]);
+        assert_eq!(&bytes[16..18], &[0x00, 0x0e]);
+        assert_eq!(&bytes[30..32], &[0x00, 0x00]);
+
+        packet.finalize();
+        assert_eq!(packet.get::<EthLayer>().unwrap().ether_type, None);
+        assert_eq!(packet.get::<LengthLayer>().unwrap().length, Some(16));
+        assert_eq!(packet.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn finalize_excludes_padding() {
+        let packet = Packet::from(LengthLayer::default()) / b"abc" / PaddingLayer::zeroed(5);
+        assert_eq!(packet.to_bytes()[..2], [0x00, 0x03]);
+    }
+
+    #[test]
+    fn finalize_preserves_explicit_values() {
+        let packet = eth(Some(EtherType::IPv4)) / LengthLayer { length: Some(0xdead) } / eth(Some(EtherType::IPv4));
+        let bytes = packet.to_bytes();
+
+        assert_eq!(&bytes[12..14], &[0x08, 0x00]);
+        assert_eq!(&bytes[14..16], &[0xde, 0xad]);
+    }
+
+    #[test]
+    fn compose_raw() {
+        let packet = eth(Some(EtherType::IPv4)) / b"hello" / PaddingLayer::zeroed(2);
+        assert_eq!(packet.to_string(), "Ethernet / Raw / Padding");
+        assert_eq!(packet.get::<RawLayer>().unwrap().data, b"hello");
+        assert_eq!(packet.to_bytes()[14..], [b'h', b'e', b'l', b'l', b'o', 0, 0]);
+
+        let packet = RawLayer::new([1, 2]) / vec![3] / &[4u8][..];
+        assert_eq!(packet.to_bytes(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn reflect() {
+        let mut packet = eth(Some(EtherType::IPv4)) / b"abc";
+        assert_eq!(packet.get_field("eth.src"), Some(FieldValue::Mac(MacAddr::new(0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff))));
+        assert_eq!(packet.get_field("raw.load"), Some(FieldValue::Bytes(b"abc".to_vec())));
+        assert_eq!(packet.get_field("ip.ttl"), None);
+
+        packet.set_field("eth.vlan.vid", "42").unwrap();
+        packet.set_field("raw.load", "dead").unwrap();
+        assert_eq!(packet.get_field("eth.vlan.vid"), Some(FieldValue::UInt(42)));
+        assert_eq!(packet.get::<RawLayer>().unwrap().data, [0xde, 0xad]);
+        assert_eq!(packet.set_field("ip.ttl", "1"), Err(FieldError::UnknownField("ip.ttl".to_string())));
+    }
+
+    #[test]
+    fn to_bytes() {
+        let packet = eth(Some(EtherType::IPv4)) / eth(Some(EtherType::IPv4));
+        let mut expected = Vec::new();
+        eth(Some(EtherType::IPv4)).write(&mut expected);
+        eth(Some(EtherType::IPv4)).write(&mut expected);
+
+        assert_eq!(packet.to_bytes(), expected);
+        assert_eq!(packet.to_bytes().len(), 2 * EthLayer::MIN_LEN);
+    }
+}
@@ -1,5 +1,3 @@
-use std::fmt;
-
 use thiserror::Error;
 
 pub trait BitPrimitive: Copy + Default + TryFrom<Self::RustPrimitive> {
@@ -40,7 +38,7 @@ impl BitPrimitive for Bit {
     const MAX_VALUE: Self::RustPrimitive = 2u8.pow(Self::BIT_WIDTH - 1);
 
     fn is_valid(self) -> bool {
-        self.value >= Self::MIN_VALUE && self.value <= Self::MAX_VALUE
+        (Self::MIN_VALUE..=Self::MAX_VALUE).contains(&self.value)
     }
 
     fn value(self) -> Self::RustPrimitive {
@@ -48,7 +46,7 @@ impl BitPrimitive for Bit {
     }
 
     fn set(&mut self, value: Self::RustPrimitive) -> Result<(), BitPrimitiveError> {
-        if value >= Self::MIN_VALUE && value <= Self::MAX_VALUE {
+        if (Self::MIN_VALUE..=Self::MAX_VALUE).contains(&value) {
             self.value = value;
             Ok(())
         }
@@ -63,11 +61,11 @@ impl TryFrom<u8> for Bit {
     type Error = BitPrimitiveError;
     
     fn try_from(value: u8) -> Result<Self, BitPrimitiveError> {
-        if value >= Self::MIN_VALUE && value <= Self::MAX_VALUE {
-            return Ok(Self {value});
+        if (Self::MIN_VALUE..=Self::MAX_VALUE).contains(&value) {
+            Ok(Self {value})
         }
         else {
-            return Err(BitPrimitiveError::ValueOutOfRange);
+            Err(BitPrimitiveError::ValueOutOfRange)
         }
     }
 }
@@ -84,7 +82,7 @@ impl BitPrimitive for Crumb {
     const MAX_VALUE: Self::RustPrimitive = 2u8.pow(Self::BIT_WIDTH) - 1;
 
     fn is_valid(self) -> bool {
-        self.value >= Self::MIN_VALUE && self.value <= Self::MAX_VALUE
+        (Self::MIN_VALUE..=Self::MAX_VALUE).contains(&self.value)
     }
 
     fn value(self) -> Self::RustPrimitive {
@@ -92,7 +90,7 @@ impl BitPrimitive for Crumb {
     }
 
     fn set(&mut self, value: Self::RustPrimitive) -> Result<(), BitPrimitiveError> {
-        if value >= Self::MIN_VALUE && value <= Self::MAX_VALUE {
+        if (Self::MIN_VALUE..=Self::MAX_VALUE).contains(&value) {
             self.value = value;
             Ok(())
         }
@@ -106,11 +104,11 @@ impl TryFrom<u8> for Crumb {
     type Error = BitPrimitiveError;
 
     fn try_from(value: u8) -> Result<Self, BitPrimitiveError> {
-        if value >= Self::MIN_VALUE && value <= Self::MAX_VALUE {
-            return Ok(Self {value});
+        if (Self::MIN_VALUE..=Self::MAX_VALUE).contains(&value) {
+            Ok(Self {value})
         }
         else {
-            return Err(BitPrimitiveError::ValueOutOfRange);
+            Err(BitPrimitiveError::ValueOutOfRange)
         }
     }
 }
@@ -127,7 +125,7 @@ impl BitPrimitive for U3 {
     const MAX_VALUE: Self::RustPrimitive = 2u8.pow(Self::BIT_WIDTH) - 1;
 
     fn is_valid(self) -> bool {
-        self.value >= Self::MIN_VALUE && self.value <= Self::MAX_VALUE
+        (Self::MIN_VALUE..=Self::MAX_VALUE).contains(&self.value)
     }
 
     fn value(self) -> Self::RustPrimitive {
@@ -135,7 +133,7 @@ impl BitPrimitive for U3 {
     }
 
     fn set(&mut self, value: Self::RustPrimitive) -> Result<(), BitPrimitiveError> {
-        if value >= Self::MIN_VALUE && value <= Self::MAX_VALUE {
+        if (Self::MIN_VALUE..=Self::MAX_VALUE).contains(&value) {
             self.value = value;
             Ok(())
         }
@@ -149,11 +147,11 @@ impl TryFrom<u8> for U3 {
     type Error = BitPrimitiveError;
 
     fn try_from(value: u8) -> Result<Self, BitPrimitiveError> {
-        if value >= Self::MIN_VALUE && value <= Self::MAX_VALUE {
-            return Ok(Self {value});
+        if (Self::MIN_VALUE..=Self::MAX_VALUE).contains(&value) {
+            Ok(Self {value})
         }
         else {
-            return Err(BitPrimitiveError::ValueOutOfRange);
+            Err(BitPrimitiveError::ValueOutOfRange)
         }
     }
 }
@@ -170,7 +168,7 @@ impl BitPrimitive for Nibble {
     const MAX_VALUE: Self::RustPrimitive = 2u8.pow(Self::BIT_WIDTH) - 1;
 
     fn is_valid(self) -> bool {
-        self.value >= Self::MIN_VALUE && self.value <= Self::MAX_VALUE
+        (Self::MIN_VALUE..=Self::MAX_VALUE).contains(&self.value)
     }
 
     fn value(self) -> Self::RustPrimitive {
@@ -178,7 +176,7 @@ impl BitPrimitive for Nibble {
     }
 
     fn set(&mut self, value: Self::RustPrimitive) -> Result<(), BitPrimitiveError> {
-        if value >= Self::MIN_VALUE && value <= Self::MAX_VALUE {
+        if (Self::MIN_VALUE..=Self::MAX_VALUE).contains(&value) {
             self.value = value;
             Ok(())
         }
@@ -192,17 +190,15 @@ impl TryFrom<u8> for Nibble {
     type Error = BitPrimitiveError;
 
     fn try_from(value: u8) -> Result<Self, BitPrimitiveError> {
-        if value >= Self::MIN_VALUE && value <= Self::MAX_VALUE {
-            return Ok(Self {value});
+        if (Self::MIN_VALUE..=Self::MAX_VALUE).contains(&value) {
+            Ok(Self {value})
         }
         else {
-            return Err(BitPrimitiveError::ValueOutOfRange);
+            Err(BitPrimitiveError::ValueOutOfRange)
         }
     }
 }
 
-type Byte = u8;
-
 #[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub struct U12 {
     value: u16
@@ -215,7 +211,7 @@ impl BitPrimitive for U12 {
     const MAX_VALUE: Self::RustPrimitive = 2u16.pow(Self::BIT_WIDTH) - 1;
 
     fn is_valid(self) -> bool {
-        self.value >= Self::MIN_VALUE && self.value <= Self::MAX_VALUE
+        (Self::MIN_VALUE..=Self::MAX_VALUE).contains(&self.value)
     }
 
     fn value(self) -> Self::RustPrimitive {
@@ -223,7 +219,7 @@ impl BitPrimitive for U12 {
     }
 
     fn set(&mut self, value: Self::RustPrimitive) -> Result<(), BitPrimitiveError> {
-        if value >= Self::MIN_VALUE && value <= Self::MAX_VALUE {
+        if (Self::MIN_VALUE..=Self::MAX_VALUE).contains(&value) {
             self.value = value;
             Ok(())
         }
@@ -237,19 +233,17 @@ impl TryFrom<u16> for U12 {
     type Error = BitPrimitiveError;
 
     fn try_from(value: u16) -> Result<Self, BitPrimitiveError> {
-        if value >= Self::MIN_VALUE && value <= Self::MAX_VALUE {
-            return Ok(Self {value});
+        if (Self::MIN_VALUE..=Self::MAX_VALUE).contains(&value) {
+            Ok(Self {value})
         }
         else {
-            return Err(BitPrimitiveError::ValueOutOfRange);
+            Err(BitPrimitiveError::ValueOutOfRange)
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::primitive;
-
     use super::*;
 
     #[test]
@@ -0,0 +1,172 @@
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::{Ipv4Addr, Ipv6Addr, MacAddr};
+
+/// The type of a layer field.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FieldKind {
+    Bool,
+    UInt,
+    Enum,
+    Mac,
+    Ipv4,
+    Ipv6,
+    Bytes,
+}
+
+/// The value of a layer field.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum FieldValue {
+    Bool(bool),
+    UInt(u64),
+    /// A value of an enumeration, such as `EtherType::IPv4`.
+    Enum {
+        type_name: &'static str,
+        name: String,
+        value: u64,
+    },
+    Mac(MacAddr),
+    Ipv4(Ipv4Addr),
+    Ipv6(Ipv6Addr),
+    Bytes(Vec<u8>),
+    /// A field which is left unset and will be computed when the packet is finalized.
+    Unset,
+}
+
+impl fmt::Display for FieldValue {
+
+    /// Display the value in a human-readable form, e.g. `EtherType::IPv4 (0x0800)`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FieldValue::Bool(value) => write!(f, "{}", value),
+            FieldValue::UInt(value) => write!(f, "{}", value),
+            FieldValue::Enum { type_name, name, value } => write!(f, "{}::{} ({:#06x})", type_name, name, value),
+            FieldValue::Mac(mac) => write!(f, "{}", mac),
+            FieldValue::Ipv4(ip) => write!(f, "{}", ip),
+            FieldValue::Ipv6(ip) => write!(f, "{}", ip),
+            FieldValue::Bytes(bytes) => write!(f, "{}", hex::encode(bytes)),
+            FieldValue::Unset => write!(f, "auto"),
+        }
+    }
+}
+
+/// A description of a layer field together with its current value.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Field {
+    /// The path of the field, e.g. `eth.vlan.vid`.
+    pub name: String,
+    pub kind: FieldKind,
+    /// The offset of the field in bits from the start of the layer.
+    pub bit_offset: usize,
+    pub bit_width: usize,
+    pub value: FieldValue,
+    /// Whether the field can be derived from the other layers when the packet is finalized, such as lengths and checksums.
+    pub computed: bool,
+}
+
+impl Field {
+    /// Constructs a field description.
+    pub fn new(name: impl Into<String>, kind: FieldKind, bit_offset: usize, bit_width: usize, value: FieldValue) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            bit_offset,
+            bit_width,
+            value,
+            computed: false,
+        }
+    }
+
+    /// Marks the field as derived from the other layers when the packet is finalized.
+    pub fn computed(mut self) -> Self {
+        self.computed = true;
+        self
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum FieldError {
+    #[error("The field {0} does not exist.")]
+    UnknownField(String),
+
+    #[error("The value {0} is not valid for the field.")]
+    InvalidValue(String),
+}
+
+/// Generic access to the fields of a layer by their path, e.g. `eth.vlan.pcp`.
+pub trait Reflect {
+    /// Describes every field together with its current value.
+    fn fields(&self) -> Vec<Field>;
+
+    /// Returns the value of the field with the specified path.
+    fn get_field(&self, path: &str) -> Option<FieldValue> {
+        self.fields().into_iter().find(|field| field.name == path).map(|field| field.value)
+    }
+
+    /// Parses the string and assigns it to the field with the specified path.
+    fn set_field(&mut self, path: &str, value: &str) -> Result<(), FieldError>;
+}
+
+/// Parses an unsigned integer written in decimal, or in hexadecimal with a `0x` prefix.
+pub fn parse_uint(value: &str) -> Result<u64, FieldError> {
+    let trimmed = value.trim();
+    let parsed = match trimmed.strip_prefix("0x").or_else(|| trimmed.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => trimmed.parse(),
+    };
+
+    parsed.map_err(|_| FieldError::InvalidValue(value.to_string()))
+}
+
+/// Parses an unsigned integer which must fit into the type `T`.
+pub fn parse_uint_as<T: TryFrom<u64>>(value: &str) -> Result<T, FieldError> {
+    T::try_from(parse_uint(value)?).map_err(|_| FieldError::InvalidValue(value.to_string()))
+}
+
+/// Parses a boolean written as `true`/`false` or `1`/`0`.
+pub fn parse_bool(value: &str) -> Result<bool, FieldError> {
+    match value.trim() {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(FieldError::InvalidValue(value.to_string())),
+    }
+}
+
+/// Parses a value using its [`std::str::FromStr`] implementation.
+pub fn parse_str<T: std::str::FromStr>(value: &str) -> Result<T, FieldError> {
+    value.trim().parse().map_err(|_| FieldError::InvalidValue(value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_values() {
+        assert_eq!(parse_uint("42"), Ok(42));
+        assert_eq!(parse_uint("0x0800"), Ok(0x0800));
+        assert_eq!(parse_uint("nope"), Err(FieldError::InvalidValue("nope".to_string())));
+        assert_eq!(parse_uint_as::<u8>("255"), Ok(255));
+        assert_eq!(parse_uint_as::<u8>("256"), Err(FieldError::InvalidValue("256".to_string())));
+        assert_eq!(parse_bool("1"), Ok(true));
+        assert_eq!(parse_bool("false"), Ok(false));
+        assert!(parse_bool("maybe").is_err());
+        assert_eq!(parse_str::<MacAddr>("ff:ff:ff:ff:ff:ff"), Ok(MacAddr::broadcast()));
+    }
+
+    #[test]
+    fn display_values() {
+        let value = FieldValue::Enum {
+            type_name: "EtherType",
+            name: "IPv4".to_string(),
+            value: 0x0800,
+        };
+
+        assert_eq!(value.to_string(), "EtherType::IPv4 (0x0800)");
+        assert_eq!(FieldValue::Bytes(vec![0xde, 0xad]).to_string(), "dead");
+        assert_eq!(FieldValue::Mac(MacAddr::broadcast()).to_string(), "ff:ff:ff:ff:ff:ff");
+        assert_eq!(FieldValue::Unset.to_string(), "auto");
+    }
+}
@@ -0,0 +1,182 @@
+use std::fmt::Write;
+
+use crate::{DynLayer, Field, Packet};
+
+/// The number of bytes printed on a single hexdump line.
+const BYTES_PER_LINE: usize = 16;
+
+/// ANSI colours which are cycled through for consecutive layers in a hexdump.
+const COLOURS: [&str; 6] = ["\x1b[31m", "\x1b[32m", "\x1b[33m", "\x1b[34m", "\x1b[35m", "\x1b[36m"];
+
+const RESET: &str = "\x1b[0m";
+
+/// A range of bytes in a hexdump together with the fields which produced it.
+struct Segment {
+    layer: usize,
+    start: usize,
+    end: usize,
+    label: String,
+}
+
+impl Packet {
+    /// Returns a multi-line dump of every layer and its fields, e.g.
+    ///
+    /// ```text
+    /// ###[ Ethernet ]###
+    ///   eth.dst  = ff:ff:ff:ff:ff:ff
+    ///   eth.src  = aa:bb:cc:dd:ee:ff
+    ///   eth.type = EtherType::IPv4 (0x0800)
+    /// ```
+    ///
+    /// Fields which are left unset are shown as `auto`. Call [`Packet::finalize`] first to see their computed values.
+    pub fn show(&self) -> String {
+        let mut output = String::new();
+
+        for layer in self.layers() {
+            writeln!(output, "###[ {} ]###", layer.name()).unwrap();
+
+            let fields = layer_fields(layer.as_ref());
+            let width = fields.iter().map(|field| field.name.len()).max().unwrap_or(0);
+
+            for field in fields {
+                writeln!(output, "  {:width$} = {}", field.name, field.value, width = width).unwrap();
+            }
+        }
+
+        output
+    }
+
+    /// Returns a hexdump of the serialized packet, in which every range of bytes is annotated with the fields which produced it.
+    ///
+    /// Fields which share bytes, such as the subfields of an 802.1Q tag, are annotated together. If `colour` is set, the
+    /// bytes of each layer are highlighted with ANSI colours.
+    pub fn hexdump(&self, colour: bool) -> String {
+        let mut packet = self.clone();
+        let bytes = packet.build();
+        let mut output = String::new();
+
+        for segment in segments(&packet, bytes.len()) {
+            let (start_colour, end_colour) = match colour {
+                true => (COLOURS[segment.layer % COLOURS.len()], RESET),
+                false => ("", ""),
+            };
+
+            for (i, line) in bytes[segment.start..segment.end].chunks(BYTES_PER_LINE).enumerate() {
+                let hex: Vec<String> = line.iter().map(|byte| format!("{:02x}", byte)).collect();
+                let label = if i == 0 { segment.label.as_str() } else { "" };
+
+                let line = format!(
+                    "{:04x}  {}{:width$}{}  {}",
+                    segment.start + i * BYTES_PER_LINE,
+                    start_colour,
+                    hex.join(" "),
+                    end_colour,
+                    label,
+                    width = BYTES_PER_LINE * 3 - 1
+                );
+                writeln!(output, "{}", line.trim_end()).unwrap();
+            }
+        }
+
+        output
+    }
+}
+
+/// Returns the fields of the layer, or none if it does not support reflection.
+fn layer_fields(layer: &dyn DynLayer) -> Vec<Field> {
+    layer.as_reflect().map(|layer| layer.fields()).unwrap_or_default()
+}
+
+/// Splits the serialized packet into ranges of bytes attributed to the fields which produced them.
+fn segments(packet: &Packet, len: usize) -> Vec<Segment> {
+    let mut segments: Vec<Segment> = Vec::new();
+    let mut offset = 0;
+
+    for (i, layer) in packet.layers().iter().enumerate() {
+        let header_end = (offset + layer.header_len()).min(len);
+        let mut covered = offset;
+
+        for field in layer_fields(layer.as_ref()) {
+            let start = offset + field.bit_offset / 8;
+            let end = (offset + (field.bit_offset + field.bit_width).div_ceil(8)).min(header_end);
+
+            if start >= end {
+                continue;
+            }
+
+            match segments.last_mut() {
+                Some(last) if last.layer == i && start < last.end => {
+                    last.end = last.end.max(end);
+                    last.label = format!("{}, {}", last.label, field.name);
+                }
+                _ => {
+                    if start > covered {
+                        segments.push(Segment { layer: i, start: covered, end: start, label: layer.name().to_string() });
+                    }
+
+                    segments.push(Segment { layer: i, start, end, label: field.name });
+                }
+            }
+
+            covered = covered.max(end);
+        }
+
+        if header_end > covered {
+            segments.push(Segment { layer: i, start: covered, end: header_end, label: layer.name().to_string() });
+        }
+
+        offset = header_end;
+    }
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn eth() -> EthLayer {
+        let mut tag = Q802_1Tag::default();
+        tag.set_vid(U12::try_from(100).unwrap());
+        EthLayer::new(MacAddr::new(0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff), MacAddr::broadcast(), EtherType::IPv4, vec![tag])
+    }
+
+    #[test]
+    fn show() {
+        let packet = eth() / b"abc";
+
+        assert_eq!(
+            packet.show(),
+            "###[ Ethernet ]###\n\
+             \x20 eth.dst       = ff:ff:ff:ff:ff:ff\n\
+             \x20 eth.src       = aa:bb:cc:dd:ee:ff\n\
+             \x20 eth.vlan.tpid = 33024\n\
+             \x20 eth.vlan.pcp  = 0\n\
+             \x20 eth.vlan.dei  = false\n\
+             \x20 eth.vlan.vid  = 100\n\
+             \x20 eth.type      = EtherType::IPv4 (0x0800)\n\
+             ###[ Raw ]###\n\
+             \x20 raw.load = 616263\n"
+        );
+    }
+
+    #[test]
+    fn hexdump() {
+        let packet = eth() / [0x61u8; 20].as_slice();
+        let dump = packet.hexdump(false);
+        let lines: Vec<&str> = dump.lines().collect();
+
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[0], format!("0000  {:47}  eth.dst", "ff ff ff ff ff ff"));
+        assert_eq!(lines[1], format!("0006  {:47}  eth.src", "aa bb cc dd ee ff"));
+        assert_eq!(lines[2], format!("000c  {:47}  eth.vlan.tpid", "81 00"));
+        assert_eq!(lines[3], format!("000e  {:47}  eth.vlan.pcp, eth.vlan.dei, eth.vlan.vid", "00 64"));
+        assert_eq!(lines[4], format!("0010  {:47}  eth.type", "08 00"));
+        assert_eq!(lines[5], format!("0012  {}  raw.load", ["61"; 16].join(" ")));
+        assert_eq!(lines[6], "0022  61 61 61 61");
+
+        let dump = packet.hexdump(true);
+        assert!(dump.starts_with("0000  \x1b[31mff ff ff ff ff ff"));
+        assert!(dump.contains("\x1b[32m61 61"));
+    }
+}